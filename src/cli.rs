@@ -0,0 +1,813 @@
+//! Command-line interface for headless use.
+//!
+//! When the application is started with a subcommand it runs without opening
+//! a window, so the same analysis shown in the GUI can be used from scripts
+//! and CI jobs.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::core::error::AppError;
+use crate::core::format::{self, WriteOptions};
+use crate::core::log::InputLog;
+use crate::core::normalize::{HoldEncoding, normalize};
+use crate::core::parser;
+use crate::core::splice::{self, SpliceError};
+use crate::core::stats::{StatsConfig, compute_stats};
+use crate::core::stream::{self, Endpoint, StreamSender};
+use crate::core::validate::{Diagnostic, Severity, max_severity, validate};
+use crate::core::writer;
+
+/// Exit code for a successful run.
+const EXIT_OK: i32 = 0;
+/// Exit code when the input could not be loaded or processed.
+const EXIT_FAILURE: i32 = 1;
+/// Exit code for invalid command-line usage.
+const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "\
+Usage: input-log-viewer [COMMAND]
+
+Without a command the viewer window is opened.
+
+Commands:
+  stats <FILE> [--range START-END] [--threshold VALUE] [--bins N] [--lenient]
+        Print per-input statistics as JSON
+  validate <FILE> [--format text|json] [--fail-on error|warning] [--lenient]
+        Check a log for consistency problems; exits with 1 if any
+        diagnostic reaches the --fail-on severity (default: error)
+  send <FILE> [--to ENDPOINT] [--speed X] [--lenient]
+        Replay a log to a viewer listening for a live stream, paced at the
+        log's target FPS times --speed. ENDPOINT is PORT, HOST:PORT or
+        unix:PATH (default: 127.0.0.1:7878)
+  bench <FILE>... [--iterations N]
+        Compare size and encode/decode throughput of uncompressed (v1) and
+        compressed binary logs (default: 100 iterations)
+  slice <FILE> --range START-END --out OUT [--compress]
+        [--no-checksum] [--lenient]
+        Cut frames START-END out of a log; frames are rebased to 0 and
+        inputs held at the cut are pressed on the first frame
+  concat <FILE> <FILE>... --out OUT [--compress] [--no-checksum] [--lenient]
+        Append logs one after another
+  merge <FILE> <FILE>... --out OUT [--compress] [--no-checksum] [--lenient]
+        Combine logs that record different input IDs over the same frames
+  normalize <FILE> [--holds dense|sparse] [--dedup-axes] --out OUT
+            [--compress] [--no-checksum] [--lenient]
+        Encode held buttons with a Held event on every frame (dense) or
+        only Pressed/Released edges (sparse), and/or drop axis samples
+        that repeat the previous value
+
+Options:
+  --lenient      Skip or repair bad JSON entries instead of rejecting the file
+  --compress     Write .ilb output in the compressed binary format instead of
+                 uncompressed v1
+  --no-checksum  Write .ilb output without the CRC-32 trailer
+  help  Show this message";
+
+/// Run a subcommand if one was given.
+///
+/// # Arguments
+/// * `args` - Command-line arguments without the program name
+///
+/// # Returns
+/// * `None` - No subcommand was given; the GUI should be started
+/// * `Some(code)` - A subcommand ran; the process should exit with `code`
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+
+    let result = match command.as_str() {
+        "stats" => run_stats(rest),
+        "validate" => run_validate(rest),
+        "send" => run_send(rest),
+        "bench" => run_bench(rest),
+        "slice" => run_slice(rest),
+        "concat" => run_combine(rest, splice::concat),
+        "merge" => run_combine(rest, splice::merge),
+        "normalize" => run_normalize(rest),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(EXIT_OK)
+        }
+        other => Err(CliError::Usage(format!("Unknown command '{}'", other))),
+    };
+
+    Some(match result {
+        Ok(code) => code,
+        Err(CliError::Usage(message)) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            EXIT_USAGE
+        }
+        Err(CliError::Failed(message)) => {
+            eprintln!("error: {}", message);
+            EXIT_FAILURE
+        }
+    })
+}
+
+/// Errors reported by subcommands.
+#[derive(Debug, PartialEq)]
+enum CliError {
+    /// The arguments were invalid
+    Usage(String),
+    /// The command itself failed
+    Failed(String),
+}
+
+/// Parsed arguments: positional values, `--name value` options and `--name` flags.
+#[derive(Debug, Default)]
+struct ParsedArgs {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl ParsedArgs {
+    /// Split arguments into positionals, options and flags, rejecting unknown ones.
+    fn parse(args: &[String], known: &[&str], known_flags: &[&str]) -> Result<Self, CliError> {
+        let mut parsed = ParsedArgs::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                if known_flags.contains(&name) {
+                    parsed.flags.push(name.to_string());
+                    continue;
+                }
+                if !known.contains(&name) {
+                    return Err(CliError::Usage(format!("Unknown option '{}'", arg)));
+                }
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CliError::Usage(format!("Missing value for '{}'", arg)))?;
+                parsed.options.push((name.to_string(), value.clone()));
+            } else {
+                parsed.positional.push(arg.clone());
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// Get the last value given for an option.
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Check whether a flag was given.
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    /// Get the single input file argument.
+    fn file(&self) -> Result<PathBuf, CliError> {
+        match self.positional.as_slice() {
+            [file] => Ok(PathBuf::from(file)),
+            [] => Err(CliError::Usage("Missing input file".to_string())),
+            _ => Err(CliError::Usage("Expected a single input file".to_string())),
+        }
+    }
+
+    /// Parse an option value, returning `None` if the option was not given.
+    fn parsed_option<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, CliError> {
+        self.option(name)
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    CliError::Usage(format!("Invalid value for --{}: '{}'", name, value))
+                })
+            })
+            .transpose()
+    }
+}
+
+/// Parse a frame range in the form `START-END` (inclusive).
+fn parse_range(value: &str) -> Result<(u64, u64), CliError> {
+    let invalid = || CliError::Usage(format!("Invalid range '{}': expected START-END", value));
+    let (start, end) = value.split_once('-').ok_or_else(invalid)?;
+    let start: u64 = start.trim().parse().map_err(|_| invalid())?;
+    let end: u64 = end.trim().parse().map_err(|_| invalid())?;
+    if start > end {
+        return Err(invalid());
+    }
+    Ok((start, end))
+}
+
+/// `stats` subcommand: print per-input statistics as JSON.
+fn run_stats(args: &[String]) -> Result<i32, CliError> {
+    let args = ParsedArgs::parse(args, &["range", "threshold", "bins"], &["lenient"])?;
+    let path = args.file()?;
+    let range = args.option("range").map(parse_range).transpose()?;
+
+    let mut config = StatsConfig::default();
+    if let Some(threshold) = args.parsed_option("threshold")? {
+        config.axis_threshold = threshold;
+    }
+    if let Some(bins) = args.parsed_option::<usize>("bins")? {
+        config.histogram_bins = bins.max(1);
+    }
+
+    let (log, _) = load(&args, &path)?;
+
+    print_output(&compute_stats(&log, range, &config).to_json());
+    Ok(EXIT_OK)
+}
+
+/// `validate` subcommand: print diagnostics and fail on serious problems.
+fn run_validate(args: &[String]) -> Result<i32, CliError> {
+    let args = ParsedArgs::parse(args, &["format", "fail-on"], &["lenient"])?;
+    let path = args.file()?;
+
+    let json = match args.option("format").unwrap_or("text") {
+        "text" => false,
+        "json" => true,
+        other => {
+            return Err(CliError::Usage(format!(
+                "Invalid value for --format: '{}'",
+                other
+            )));
+        }
+    };
+    let fail_on = match args.option("fail-on").unwrap_or("error") {
+        "error" => Severity::Error,
+        "warning" => Severity::Warning,
+        other => {
+            return Err(CliError::Usage(format!(
+                "Invalid value for --fail-on: '{}'",
+                other
+            )));
+        }
+    };
+
+    let (log, load_warnings) = load(&args, &path)?;
+    let mut diagnostics: Vec<Diagnostic> = load_warnings
+        .iter()
+        .map(Diagnostic::from_load_warning)
+        .collect();
+    diagnostics.extend(validate(&log));
+
+    if json {
+        print_output(&serde_json::to_string_pretty(&diagnostics).unwrap_or_default());
+    } else if diagnostics.is_empty() {
+        print_output(&format!("{}: no problems found", path.display()));
+    } else {
+        let lines: Vec<String> = diagnostics
+            .iter()
+            .map(|d| format!("{}: {}", path.display(), d))
+            .collect();
+        print_output(&lines.join("\n"));
+    }
+
+    if max_severity(&diagnostics) >= Some(fail_on) {
+        Ok(EXIT_FAILURE)
+    } else {
+        Ok(EXIT_OK)
+    }
+}
+
+/// `send` subcommand: replay a log to a listening viewer.
+fn run_send(args: &[String]) -> Result<i32, CliError> {
+    let args = ParsedArgs::parse(args, &["to", "speed"], &["lenient"])?;
+    let path = args.file()?;
+    let endpoint = Endpoint::parse(args.option("to").unwrap_or(stream::DEFAULT_ENDPOINT))
+        .map_err(|e| CliError::Usage(e.to_string()))?;
+    let speed: f64 = args.parsed_option("speed")?.unwrap_or(1.0);
+    if !(speed > 0.0 && speed.is_finite()) {
+        return Err(CliError::Usage(format!(
+            "Invalid value for --speed: '{}'",
+            speed
+        )));
+    }
+
+    let (log, _) = load(&args, &path)?;
+
+    let failed = |e: stream::StreamError| CliError::Failed(format!("{}: {}", endpoint, e));
+    let mut sender = StreamSender::connect(&endpoint).map_err(failed)?;
+    let sent = stream::replay(&log, &mut sender, speed).map_err(failed)?;
+
+    print_output(&format!("Sent {} events to {}", sent, endpoint));
+    Ok(EXIT_OK)
+}
+
+/// `bench` subcommand: compare the v1 and compressed binary encodings.
+fn run_bench(args: &[String]) -> Result<i32, CliError> {
+    let args = ParsedArgs::parse(args, &["iterations"], &[])?;
+    let iterations: u32 = args.parsed_option("iterations")?.unwrap_or(100);
+    if iterations == 0 {
+        return Err(CliError::Usage(
+            "Invalid value for --iterations: '0'".to_string(),
+        ));
+    }
+
+    if args.positional.is_empty() {
+        return Err(CliError::Usage(
+            "Missing input file: give one or more logs to benchmark".to_string(),
+        ));
+    }
+    let files: Vec<PathBuf> = args.positional.iter().map(PathBuf::from).collect();
+
+    let encodings: [(&str, Encoder); 2] = [
+        ("v1", |log| writer::write_binary(log, true)),
+        ("compressed", |log| {
+            writer::write_binary_compressed(log, true)
+        }),
+    ];
+
+    let mut lines = Vec::new();
+    for path in &files {
+        let (log, _) = load(&args, path)?;
+        lines.push(format!(
+            "{}: {} events, {} frames",
+            path.display(),
+            log.events.len(),
+            log.metadata.frame_count
+        ));
+        lines.push(format!(
+            "  {:<12}{:>10}{:>8}{:>16}{:>16}",
+            "encoding", "bytes", "ratio", "encode ev/s", "decode ev/s"
+        ));
+
+        let mut baseline = None;
+        for (name, encode) in encodings {
+            let result = bench_encoding(&log, encode, iterations)
+                .map_err(|e| CliError::Failed(format!("{}: {}", path.display(), e)))?;
+            let baseline_size = *baseline.get_or_insert(result.size);
+            lines.push(format!(
+                "  {:<12}{:>10}{:>7.1}%{:>16}{:>16}",
+                name,
+                result.size,
+                result.size as f64 * 100.0 / baseline_size as f64,
+                events_per_second(log.events.len(), result.encode),
+                events_per_second(log.events.len(), result.decode),
+            ));
+        }
+    }
+
+    print_output(&lines.join("\n"));
+    Ok(EXIT_OK)
+}
+
+/// `slice` subcommand: write a frame range of a log to a new file.
+fn run_slice(args: &[String]) -> Result<i32, CliError> {
+    let args = ParsedArgs::parse(
+        args,
+        &["range", "out"],
+        &["compress", "no-checksum", "lenient"],
+    )?;
+    let path = args.file()?;
+    let (start, end) = args
+        .option("range")
+        .map(parse_range)
+        .transpose()?
+        .ok_or_else(|| CliError::Usage("Missing --range".to_string()))?;
+    let out = output_path(&args)?;
+
+    let (log, _) = load(&args, &path)?;
+    let sliced = splice::slice(&log, start, end).map_err(splice_failed)?;
+
+    save(&sliced, &out, &args)
+}
+
+/// Operation combining two logs into one.
+type Combine = fn(&InputLog, &InputLog) -> Result<InputLog, SpliceError>;
+
+/// `concat` and `merge` subcommands: combine several logs into a new file.
+fn run_combine(args: &[String], combine: Combine) -> Result<i32, CliError> {
+    let args = ParsedArgs::parse(args, &["out"], &["compress", "no-checksum", "lenient"])?;
+    if args.positional.len() < 2 {
+        return Err(CliError::Usage(
+            "Expected at least two input files".to_string(),
+        ));
+    }
+    let out = output_path(&args)?;
+
+    let mut combined: Option<InputLog> = None;
+    for file in &args.positional {
+        let (log, _) = load(&args, Path::new(file))?;
+        combined = Some(match combined {
+            Some(so_far) => combine(&so_far, &log).map_err(splice_failed)?,
+            None => log,
+        });
+    }
+
+    match combined {
+        Some(log) => save(&log, &out, &args),
+        None => Ok(EXIT_FAILURE),
+    }
+}
+
+/// `normalize` subcommand: re-encode held buttons and drop repeated axis samples.
+fn run_normalize(args: &[String]) -> Result<i32, CliError> {
+    let args = ParsedArgs::parse(
+        args,
+        &["holds", "out"],
+        &["compress", "no-checksum", "dedup-axes", "lenient"],
+    )?;
+    let path = args.file()?;
+    let holds = args
+        .option("holds")
+        .map(|name| {
+            HoldEncoding::from_name(name).ok_or_else(|| {
+                CliError::Usage(format!(
+                    "Invalid --holds '{}': expected dense or sparse",
+                    name
+                ))
+            })
+        })
+        .transpose()?;
+    let dedup_axes = args.flag("dedup-axes");
+    if holds.is_none() && !dedup_axes {
+        return Err(CliError::Usage(
+            "Nothing to do: give --holds and/or --dedup-axes".to_string(),
+        ));
+    }
+    let out = output_path(&args)?;
+
+    let (mut log, _) = load(&args, &path)?;
+    for change in normalize(&log, holds, dedup_axes) {
+        change.apply(&mut log);
+    }
+
+    save(&log, &out, &args)
+}
+
+/// Get the `--out` file, which must have an extension of a writable format
+/// that can also be compressed if `--compress` was given.
+fn output_path(args: &ParsedArgs) -> Result<PathBuf, CliError> {
+    let out = args
+        .option("out")
+        .map(PathBuf::from)
+        .ok_or_else(|| CliError::Usage("Missing --out".to_string()))?;
+    let writable = format::registry()
+        .by_extension(&out)
+        .is_some_and(|format| format.can_write());
+    if !writable {
+        return Err(CliError::Usage(format!(
+            "Cannot write '{}': unsupported file extension",
+            out.display()
+        )));
+    }
+    let compressible = format::registry()
+        .by_extension(&out)
+        .is_some_and(|format| format.can_compress());
+    if args.flag("compress") && !compressible {
+        return Err(CliError::Usage(format!(
+            "Cannot compress '{}': --compress is only supported for .ilb output",
+            out.display()
+        )));
+    }
+    Ok(out)
+}
+
+fn splice_failed(error: SpliceError) -> CliError {
+    CliError::Failed(error.to_string())
+}
+
+/// Write a log to `path` with the `--compress` and `--no-checksum` options
+/// and report what was written.
+fn save(log: &InputLog, path: &Path, args: &ParsedArgs) -> Result<i32, CliError> {
+    let options = WriteOptions {
+        compress: args.flag("compress"),
+        checksum: !args.flag("no-checksum"),
+    };
+    format::registry()
+        .save(log, path, &options)
+        .map_err(|e| CliError::Failed(format!("{}: {}", path.display(), e.brief_description())))?;
+    print_output(&format!(
+        "Wrote {} frames, {} events to {}",
+        log.metadata.frame_count,
+        log.events.len(),
+        path.display()
+    ));
+    Ok(EXIT_OK)
+}
+
+/// Function serializing a log into one binary encoding.
+type Encoder = fn(&InputLog) -> Vec<u8>;
+
+/// Size and average timings of one binary encoding.
+struct BenchResult {
+    size: usize,
+    encode: Duration,
+    decode: Duration,
+}
+
+/// Encode and decode a log `iterations` times and average the timings.
+fn bench_encoding(
+    log: &InputLog,
+    encode: Encoder,
+    iterations: u32,
+) -> Result<BenchResult, parser::ParseError> {
+    let start = Instant::now();
+    let mut data = Vec::new();
+    for _ in 0..iterations {
+        data = std::hint::black_box(encode(std::hint::black_box(log)));
+    }
+    let encode_time = start.elapsed() / iterations;
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        std::hint::black_box(parser::parse_binary(std::hint::black_box(&data))?);
+    }
+    let decode_time = start.elapsed() / iterations;
+
+    Ok(BenchResult {
+        size: data.len(),
+        encode: encode_time,
+        decode: decode_time,
+    })
+}
+
+/// Format a throughput as events per second with a metric suffix.
+fn events_per_second(events: usize, elapsed: Duration) -> String {
+    let rate = events as f64 / elapsed.as_secs_f64().max(1e-9);
+    if rate >= 1e6 {
+        format!("{:.1}M", rate / 1e6)
+    } else if rate >= 1e3 {
+        format!("{:.1}k", rate / 1e3)
+    } else {
+        format!("{:.0}", rate)
+    }
+}
+
+/// Load the input file, leniently if `--lenient` was given.
+fn load(args: &ParsedArgs, path: &Path) -> Result<(InputLog, Vec<AppError>), CliError> {
+    let registry = format::registry();
+    let result = if args.flag("lenient") {
+        registry.load_lenient(path)
+    } else {
+        registry.load(path).map(|log| (log, Vec::new()))
+    };
+    result.map_err(|e| CliError::Failed(format!("{}: {}", path.display(), e.brief_description())))
+}
+
+/// Write command output to stdout.
+///
+/// Write errors are ignored so piping into tools like `head` doesn't panic.
+fn print_output(text: &str) {
+    let _ = writeln!(std::io::stdout().lock(), "{}", text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::BinaryHeader;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_no_command_starts_gui() {
+        assert_eq!(run(&[]), None);
+    }
+
+    #[test]
+    fn test_unknown_command_is_usage_error() {
+        assert_eq!(run(&args(&["frobnicate"])), Some(EXIT_USAGE));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("10-20"), Ok((10, 20)));
+        assert_eq!(parse_range("5-5"), Ok((5, 5)));
+        assert!(parse_range("20-10").is_err());
+        assert!(parse_range("10").is_err());
+        assert!(parse_range("a-b").is_err());
+    }
+
+    #[test]
+    fn test_parsed_args() {
+        let parsed =
+            ParsedArgs::parse(&args(&["log.ilj", "--range", "1-2"]), &["range"], &[]).unwrap();
+        assert_eq!(parsed.file().unwrap(), PathBuf::from("log.ilj"));
+        assert_eq!(parsed.option("range"), Some("1-2"));
+        assert!(!parsed.flag("lenient"));
+
+        let parsed =
+            ParsedArgs::parse(&args(&["--lenient", "log.ilj"]), &[], &["lenient"]).unwrap();
+        assert!(parsed.flag("lenient"));
+        assert_eq!(parsed.file().unwrap(), PathBuf::from("log.ilj"));
+
+        assert!(ParsedArgs::parse(&args(&["--bogus", "1"]), &["range"], &[]).is_err());
+        assert!(ParsedArgs::parse(&args(&["--range"]), &["range"], &[]).is_err());
+    }
+
+    #[test]
+    fn test_validate_command() {
+        let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/sample.ilb");
+        assert_eq!(run_validate(&args(&[sample])), Ok(EXIT_OK));
+        assert_eq!(
+            run_validate(&args(&[sample, "--format", "json"])),
+            Ok(EXIT_OK)
+        );
+        assert!(matches!(
+            run_validate(&args(&[sample, "--fail-on", "never"])),
+            Err(CliError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_send_command_arguments() {
+        let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/sample.ilb");
+        assert!(matches!(
+            run_send(&args(&[sample, "--speed", "0"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            run_send(&args(&[sample, "--to", "10.1.2.3:7878"])),
+            Err(CliError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_bench_command() {
+        let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/sample.ilb");
+        assert_eq!(
+            run_bench(&args(&[sample, "--iterations", "2"])),
+            Ok(EXIT_OK)
+        );
+        assert!(matches!(
+            run_bench(&args(&[sample, "--iterations", "0"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(run_bench(&args(&[])), Err(CliError::Usage(_))));
+        assert!(matches!(
+            run_bench(&args(&[sample, "--lenient"])),
+            Err(CliError::Usage(_))
+        ));
+        assert_eq!(events_per_second(2_500_000, Duration::from_secs(1)), "2.5M");
+        assert_eq!(events_per_second(1_500, Duration::from_secs(1)), "1.5k");
+    }
+
+    #[test]
+    fn test_slice_and_combine_commands() {
+        let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/sample.ilj");
+        let dir = std::env::temp_dir().join(format!("ilv-cli-splice-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let sliced = dir.join("slice.ilj");
+        let joined = dir.join("joined.ilb");
+        let sliced_arg = sliced.to_string_lossy().to_string();
+        let joined_arg = joined.to_string_lossy().to_string();
+
+        assert_eq!(
+            run_slice(&args(&[sample, "--range", "10-29", "--out", &sliced_arg])),
+            Ok(EXIT_OK)
+        );
+        let log = format::registry().load(&sliced).unwrap();
+        assert_eq!(log.metadata.frame_count, 20);
+
+        assert_eq!(
+            run_combine(
+                &args(&[sample, &sliced_arg, "--out", &joined_arg]),
+                splice::concat
+            ),
+            Ok(EXIT_OK)
+        );
+        let original = format::registry().load(Path::new(sample)).unwrap();
+        let log = format::registry().load(&joined).unwrap();
+        assert_eq!(log.metadata.frame_count, original.metadata.frame_count + 20);
+
+        // A log can't be merged with itself, since all input IDs overlap
+        assert!(matches!(
+            run_combine(
+                &args(&[sample, sample, "--out", &joined_arg]),
+                splice::merge
+            ),
+            Err(CliError::Failed(_))
+        ));
+        assert!(matches!(
+            run_slice(&args(&[sample, "--range", "0-9"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            run_slice(&args(&[sample, "--range", "0-9", "--out", "out.txt"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            run_combine(&args(&[sample, "--out", &joined_arg]), splice::concat),
+            Err(CliError::Usage(_))
+        ));
+
+        // Binary output is uncompressed unless --compress is given
+        let header = |path: &Path| {
+            let data = std::fs::read(path).unwrap();
+            bytemuck::pod_read_unaligned::<BinaryHeader>(&data[..BinaryHeader::SIZE])
+        };
+        assert!(!header(&joined).is_compressed());
+        assert_eq!(
+            run_slice(&args(&[
+                sample,
+                "--range",
+                "10-29",
+                "--out",
+                &joined_arg,
+                "--compress"
+            ])),
+            Ok(EXIT_OK)
+        );
+        assert!(header(&joined).is_compressed());
+        assert!(header(&joined).has_checksum());
+        assert_eq!(
+            format::registry()
+                .load(&joined)
+                .unwrap()
+                .metadata
+                .frame_count,
+            20
+        );
+        assert!(matches!(
+            run_slice(&args(&[
+                sample,
+                "--range",
+                "10-29",
+                "--out",
+                &sliced_arg,
+                "--compress"
+            ])),
+            Err(CliError::Usage(_))
+        ));
+
+        // --no-checksum leaves out the trailer
+        assert_eq!(
+            run_slice(&args(&[
+                sample,
+                "--range",
+                "10-29",
+                "--out",
+                &joined_arg,
+                "--no-checksum"
+            ])),
+            Ok(EXIT_OK)
+        );
+        assert!(!header(&joined).has_checksum());
+        assert_eq!(
+            format::registry()
+                .load(&joined)
+                .unwrap()
+                .metadata
+                .frame_count,
+            20
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_normalize_command() {
+        let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/sample.ilj");
+        let dir = std::env::temp_dir().join(format!("ilv-cli-normalize-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dense = dir.join("dense.ilj");
+        let sparse = dir.join("sparse.ilj");
+        let dense_arg = dense.to_string_lossy().to_string();
+        let sparse_arg = sparse.to_string_lossy().to_string();
+
+        assert_eq!(
+            run_normalize(&args(&[sample, "--holds", "dense", "--out", &dense_arg])),
+            Ok(EXIT_OK)
+        );
+        let dense_log = format::registry().load(&dense).unwrap();
+        assert_eq!(HoldEncoding::detect(&dense_log), HoldEncoding::Dense);
+
+        assert_eq!(
+            run_normalize(&args(&[
+                &dense_arg,
+                "--holds",
+                "sparse",
+                "--dedup-axes",
+                "--out",
+                &sparse_arg
+            ])),
+            Ok(EXIT_OK)
+        );
+        let sparse_log = format::registry().load(&sparse).unwrap();
+        assert_eq!(HoldEncoding::detect(&sparse_log), HoldEncoding::Sparse);
+        assert!(sparse_log.events.len() < dense_log.events.len());
+
+        assert!(matches!(
+            run_normalize(&args(&[sample, "--out", &sparse_arg])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            run_normalize(&args(&[sample, "--holds", "edges", "--out", &sparse_arg])),
+            Err(CliError::Usage(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stats_command() {
+        let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/sample.ilj");
+        assert_eq!(run_stats(&args(&[sample, "--range", "0-59"])), Ok(EXIT_OK));
+        assert!(matches!(
+            run_stats(&args(&["/nonexistent/log.ilj"])),
+            Err(CliError::Failed(_))
+        ));
+        assert!(matches!(run_stats(&args(&[])), Err(CliError::Usage(_))));
+    }
+}
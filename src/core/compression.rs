@@ -0,0 +1,575 @@
+//! Compact event encoding used by compressed binary logs.
+//!
+//! Compressed `.ilb` files (header flag `BINARY_FLAG_COMPRESSED`) store their
+//! events as variable-length records instead of fixed 24-byte `BinaryEvent`s:
+//!
+//! | Field       | Encoding                                                   |
+//! |-------------|------------------------------------------------------------|
+//! | frame delta | zigzag varint, relative to the previous record's frame     |
+//! | id          | varint                                                     |
+//! | tag         | u8: kind (bits 0-2), state (bits 3-4), run (bit 5), value mode (bits 6-7) |
+//! | contact     | varint, only for `Touch` events                            |
+//! | run length  | varint, only when the run bit is set                       |
+//! | value       | two little-endian f32, only for `VALUE_EXPLICIT`           |
+//!
+//! A run record stands for its event plus `run length` copies on the
+//! following frames, which covers held buttons and axes resting at one value.
+//! Decoded events come back in frame order and, within a frame, in the order
+//! of the records they came from. The encoder only extends runs where this
+//! keeps the order of events on the same frame, so a round trip of a log
+//! sorted by frame returns its events unchanged.
+//!
+//! Per-frame timestamps (header flag `BINARY_FLAG_TIMESTAMPS`) follow the
+//! records as a varint count and one varint delta per frame.
+
+use std::collections::HashMap;
+
+use super::log::{InputEvent, InputKind};
+use super::parser::{BinaryEvent, ParseError};
+
+/// Value mode: the value is `[0.0, 0.0]`.
+const VALUE_ZERO: u8 = 0;
+/// Value mode: the value equals the previous record of the same input.
+const VALUE_REPEAT: u8 = 1;
+/// Value mode: the value follows the tag as two f32.
+const VALUE_EXPLICIT: u8 = 2;
+
+/// Tag bit marking a run record.
+const TAG_RUN: u8 = 1 << 5;
+
+/// Longest valid LEB128 encoding of a u64.
+const MAX_VARINT_LEN: usize = 10;
+
+/// Shortest record: a frame delta, an input ID and a tag of one byte each.
+const MIN_RECORD_LEN: usize = 3;
+
+/// One encoded record: an event and how many copies follow on the next frames.
+struct Record {
+    event: InputEvent,
+    run: u64,
+}
+
+/// Encode events into the compressed record stream.
+///
+/// # Arguments
+/// * `events` - The events in log order
+///
+/// # Returns
+/// The encoded records, without header or checksum.
+pub fn encode_events(events: &[InputEvent]) -> Vec<u8> {
+    let mut records: Vec<Record> = Vec::new();
+    // Index of the last record of each input, which a run may continue
+    let mut open_runs: HashMap<u32, usize> = HashMap::new();
+    // Frame being encoded, and the highest record any of its events came from
+    let mut current_frame = None;
+    let mut frame_last_record: Option<usize> = None;
+
+    for event in events {
+        if current_frame != Some(event.frame) {
+            current_frame = Some(event.frame);
+            frame_last_record = None;
+        }
+
+        // Extend the input's run if this event repeats it on the next frame and
+        // the decoder's (frame, record) ordering would place it at this position.
+        // New records always sort last, so after one no run can continue.
+        let continues = open_runs.get(&event.id).copied().filter(|&index| {
+            let record = &records[index];
+            let last_frame = record.event.frame + record.run;
+            last_frame.checked_add(1) == Some(event.frame)
+                && same_event(&record.event, event)
+                && frame_last_record.is_none_or(|last| last < index)
+        });
+
+        let index = match continues {
+            Some(index) => {
+                records[index].run += 1;
+                index
+            }
+            None => {
+                records.push(Record {
+                    event: event.clone(),
+                    run: 0,
+                });
+                records.len() - 1
+            }
+        };
+        open_runs.insert(event.id, index);
+        frame_last_record = Some(index);
+    }
+
+    write_records(&records)
+}
+
+/// Serialize records, using deltas and value repetition.
+fn write_records(records: &[Record]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(records.len() * 4);
+    let mut previous_frame = 0u64;
+    let mut last_values: HashMap<u32, [f32; 2]> = HashMap::new();
+
+    for record in records {
+        let event = &record.event;
+        let binary = BinaryEvent::from_input_event(event);
+
+        // Compare bit patterns so that -0.0 and NaN payloads survive
+        let bits = |value: &[f32; 2]| value.map(f32::to_bits);
+        let value_mode = if last_values.get(&event.id).map(bits) == Some(bits(&event.value)) {
+            VALUE_REPEAT
+        } else if bits(&event.value) == [0, 0] {
+            VALUE_ZERO
+        } else {
+            VALUE_EXPLICIT
+        };
+        let mut tag = (binary.kind & 0x07) | ((binary.state & 0x03) << 3) | (value_mode << 6);
+        if record.run > 0 {
+            tag |= TAG_RUN;
+        }
+
+        let delta = event.frame.wrapping_sub(previous_frame) as i64;
+        write_varint(&mut data, zigzag_encode(delta));
+        write_varint(&mut data, event.id as u64);
+        data.push(tag);
+        if event.kind == InputKind::Touch {
+            write_varint(&mut data, u64::from(event.contact));
+        }
+        if record.run > 0 {
+            write_varint(&mut data, record.run);
+        }
+        if value_mode == VALUE_EXPLICIT {
+            data.extend_from_slice(&event.value[0].to_le_bytes());
+            data.extend_from_slice(&event.value[1].to_le_bytes());
+        }
+
+        previous_frame = event.frame;
+        last_values.insert(event.id, event.value);
+    }
+
+    data
+}
+
+/// Whether two events are identical apart from their frame.
+fn same_event(a: &InputEvent, b: &InputEvent) -> bool {
+    a.id == b.id
+        && a.kind == b.kind
+        && a.state == b.state
+        && a.contact == b.contact
+        && a.value[0].to_bits() == b.value[0].to_bits()
+        && a.value[1].to_bits() == b.value[1].to_bits()
+}
+
+/// Events decoded from a compressed record stream.
+#[derive(Debug)]
+pub struct DecodedEvents {
+    /// Decoded events in log order
+    pub events: Vec<InputEvent>,
+    /// Number of bytes of the stream that were used
+    pub consumed: usize,
+    /// Whether all declared events were decoded; `false` if the data ended
+    /// in the middle of the stream
+    pub complete: bool,
+}
+
+/// Decode a compressed record stream.
+///
+/// Decoding stops once `event_count` events were produced; the remaining
+/// bytes (e.g. a checksum trailer) are left to the caller. If the data ends
+/// early, the events of every complete record are returned with `complete`
+/// set to `false`.
+///
+/// The header values are not trusted: a run may not repeat an event on more
+/// frames than the log has, and `event_count` may not exceed what `data`
+/// can hold under that limit, so a small file cannot claim billions of
+/// events.
+///
+/// # Arguments
+/// * `data` - The record stream, possibly followed by other data
+/// * `event_count` - Number of events declared in the header
+/// * `frame_count` - Number of frames declared in the header
+///
+/// # Returns
+/// * `Ok(DecodedEvents)` - The decoded events
+/// * `Err(ParseError)` - A record is invalid or produces too many events
+pub fn decode_events(
+    data: &[u8],
+    event_count: u64,
+    frame_count: u64,
+) -> Result<DecodedEvents, ParseError> {
+    let max_events = ((data.len() / MIN_RECORD_LEN) as u64).saturating_mul(frame_count.max(1));
+    if event_count > max_events {
+        return Err(ParseError::InvalidBinaryEvent {
+            index: 0,
+            reason: format!(
+                "{} declared events do not fit in {} bytes of records",
+                event_count,
+                data.len()
+            ),
+        });
+    }
+
+    let mut reader = Reader { data, pos: 0 };
+    let mut events: Vec<InputEvent> = Vec::new();
+    let mut previous_frame = 0u64;
+    let mut last_values: HashMap<u32, [f32; 2]> = HashMap::new();
+    let mut complete = true;
+
+    while (events.len() as u64) < event_count {
+        let start = reader.pos;
+        let Some(record) = reader.read_record(previous_frame, &last_values, events.len())? else {
+            reader.pos = start;
+            complete = false;
+            break;
+        };
+
+        let (binary, run) = record;
+        let event = binary.to_input_event(events.len())?;
+        if run >= frame_count.max(1) {
+            return Err(ParseError::InvalidBinaryEvent {
+                index: events.len(),
+                reason: format!(
+                    "run of {} events is longer than the log ({} frames)",
+                    run.saturating_add(1),
+                    frame_count
+                ),
+            });
+        }
+        let remaining = event_count - events.len() as u64;
+        if run.saturating_add(1) > remaining || event.frame.checked_add(run).is_none() {
+            return Err(ParseError::InvalidBinaryEvent {
+                index: events.len(),
+                reason: format!(
+                    "run of {} events exceeds the declared event count",
+                    run.saturating_add(1)
+                ),
+            });
+        }
+
+        previous_frame = event.frame;
+        last_values.insert(event.id, event.value);
+        for offset in 0..=run {
+            events.push(InputEvent {
+                frame: event.frame + offset,
+                ..event
+            });
+        }
+    }
+
+    // Records are in order of their first frame; interleave the runs
+    events.sort_by_key(|event| event.frame);
+
+    Ok(DecodedEvents {
+        events,
+        consumed: reader.pos,
+        complete,
+    })
+}
+
+/// Encode per-frame timestamps as a count followed by deltas.
+///
+/// Timestamps are expected not to decrease; a decrease is stored as a zero delta.
+pub fn encode_timestamps(timestamps: &[u64]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(timestamps.len() + MAX_VARINT_LEN);
+    write_varint(&mut data, timestamps.len() as u64);
+    let mut previous = 0u64;
+    for &timestamp in timestamps {
+        write_varint(&mut data, timestamp.saturating_sub(previous));
+        previous = previous.max(timestamp);
+    }
+    data
+}
+
+/// Decode per-frame timestamps written by `encode_timestamps`.
+///
+/// # Returns
+/// * `Ok(Some((timestamps, consumed)))` - The timestamps and their size in bytes
+/// * `Ok(None)` - The data ends before the last timestamp
+/// * `Err(ParseError)` - A delta is malformed or overflows
+pub fn decode_timestamps(data: &[u8]) -> Result<Option<(Vec<u64>, usize)>, ParseError> {
+    let mut reader = Reader { data, pos: 0 };
+    let invalid = |frame: usize| ParseError::InvalidTimestamps { frame };
+
+    let Some(count) = reader.read_varint().map_err(|_| invalid(0))? else {
+        return Ok(None);
+    };
+    let mut timestamps = Vec::new();
+    let mut previous = 0u64;
+    for frame in 0..count {
+        let frame = frame as usize;
+        let Some(delta) = reader.read_varint().map_err(|_| invalid(frame))? else {
+            return Ok(None);
+        };
+        previous = previous.checked_add(delta).ok_or_else(|| invalid(frame))?;
+        timestamps.push(previous);
+    }
+    Ok(Some((timestamps, reader.pos)))
+}
+
+/// Cursor over a compressed record stream.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    /// Read one record, or `None` if the data ends before it is complete.
+    fn read_record(
+        &mut self,
+        previous_frame: u64,
+        last_values: &HashMap<u32, [f32; 2]>,
+        index: usize,
+    ) -> Result<Option<(BinaryEvent, u64)>, ParseError> {
+        let invalid = |reason: &str| ParseError::InvalidBinaryEvent {
+            index,
+            reason: reason.to_string(),
+        };
+
+        let Some(delta) = self
+            .read_varint()
+            .map_err(|_| invalid("invalid frame delta"))?
+        else {
+            return Ok(None);
+        };
+        let Some(id) = self
+            .read_varint()
+            .map_err(|_| invalid("invalid input id"))?
+        else {
+            return Ok(None);
+        };
+        let id = u32::try_from(id).map_err(|_| invalid("input id out of range"))?;
+        let Some(&tag) = self.data.get(self.pos) else {
+            return Ok(None);
+        };
+        self.pos += 1;
+
+        let contact = if tag & 0x07 == InputKind::Touch as u8 {
+            let Some(contact) = self
+                .read_varint()
+                .map_err(|_| invalid("invalid contact id"))?
+            else {
+                return Ok(None);
+            };
+            u16::try_from(contact).map_err(|_| invalid("contact id out of range"))?
+        } else {
+            0
+        };
+
+        let run = if tag & TAG_RUN != 0 {
+            match self
+                .read_varint()
+                .map_err(|_| invalid("invalid run length"))?
+            {
+                Some(run) => run,
+                None => return Ok(None),
+            }
+        } else {
+            0
+        };
+
+        let value = match tag >> 6 {
+            VALUE_ZERO => [0.0, 0.0],
+            VALUE_REPEAT => *last_values
+                .get(&id)
+                .ok_or_else(|| invalid("repeated value without a previous event"))?,
+            VALUE_EXPLICIT => {
+                let Some(bytes) = self.data.get(self.pos..self.pos + 8) else {
+                    return Ok(None);
+                };
+                self.pos += 8;
+                [
+                    f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+                    f32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+                ]
+            }
+            _ => return Err(invalid("invalid value mode")),
+        };
+
+        let frame = previous_frame.wrapping_add(zigzag_decode(delta) as u64);
+        let binary = BinaryEvent {
+            frame,
+            id,
+            kind: tag & 0x07,
+            state: (tag >> 3) & 0x03,
+            contact: contact.to_le_bytes(),
+            value,
+        };
+        Ok(Some((binary, run)))
+    }
+
+    /// Read a LEB128 varint.
+    ///
+    /// Returns `Ok(None)` if the data ends inside the varint and `Err(())` if
+    /// it is longer than any u64 encoding.
+    fn read_varint(&mut self) -> Result<Option<u64>, ()> {
+        let mut value = 0u64;
+        for i in 0..MAX_VARINT_LEN {
+            let Some(&byte) = self.data.get(self.pos + i) else {
+                return Ok(None);
+            };
+            value |= ((byte & 0x7F) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                self.pos += i + 1;
+                return Ok(Some(value));
+            }
+        }
+        Err(())
+    }
+}
+
+/// Append a LEB128 varint.
+fn write_varint(data: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        data.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+/// Map a signed value to an unsigned one so small magnitudes stay small.
+fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Inverse of `zigzag_encode`.
+fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log::ButtonState;
+    use crate::core::test_util::{axis, button};
+
+    /// Frame count used for decoding; longer than any test log.
+    const FRAMES: u64 = 10_000;
+
+    fn round_trip(events: &[InputEvent]) -> Vec<InputEvent> {
+        let data = encode_events(events);
+        let decoded = decode_events(&data, events.len() as u64, FRAMES).unwrap();
+        assert!(decoded.complete);
+        assert_eq!(decoded.consumed, data.len());
+        decoded.events
+    }
+
+    #[test]
+    fn test_varint_and_zigzag() {
+        for value in [0u64, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut data = Vec::new();
+            write_varint(&mut data, value);
+            let mut reader = Reader {
+                data: &data,
+                pos: 0,
+            };
+            assert_eq!(reader.read_varint(), Ok(Some(value)));
+            assert_eq!(reader.pos, data.len());
+        }
+        for value in [0i64, -1, 1, -64, 64, i64::MIN, i64::MAX] {
+            assert_eq!(zigzag_decode(zigzag_encode(value)), value);
+        }
+        assert_eq!(zigzag_encode(-1), 1);
+    }
+
+    #[test]
+    fn test_held_runs_compress() {
+        let mut events = vec![button(10, 0, ButtonState::Pressed)];
+        events.extend((11..1000).map(|f| button(f, 0, ButtonState::Held)));
+        events.push(button(1000, 0, ButtonState::Released));
+
+        let data = encode_events(&events);
+        // Pressed, one Held run and Released
+        assert!(data.len() < 16, "encoded to {} bytes", data.len());
+        assert_eq!(round_trip(&events), events);
+    }
+
+    #[test]
+    fn test_interleaved_runs_keep_order() {
+        let mut events = Vec::new();
+        for frame in 0..50 {
+            events.push(button(frame, 0, ButtonState::Held));
+            events.push(axis(frame, 1, 0.25));
+            if frame % 7 == 0 {
+                events.push(button(frame, 2, ButtonState::Pressed));
+            }
+            events.push(button(frame, 3, ButtonState::Held));
+        }
+        // A new record between run continuations on the same frame
+        events.push(button(50, 0, ButtonState::Held));
+        events.push(button(50, 5, ButtonState::Pressed));
+        events.push(axis(50, 1, 0.25));
+
+        assert_eq!(round_trip(&events), events);
+    }
+
+    #[test]
+    fn test_values_round_trip_exactly() {
+        let events = vec![
+            axis(0, 1, 0.5),
+            axis(3, 1, 0.5),
+            axis(4, 1, -0.0),
+            axis(5, 1, f32::MIN_POSITIVE),
+            InputEvent {
+                frame: 6,
+                id: u32::MAX,
+                kind: InputKind::Axis2D,
+                state: ButtonState::Released,
+                value: [1.0, -1.0],
+                contact: 0,
+            },
+            // Unsorted frames use a negative delta
+            axis(2, 1, 0.5),
+        ];
+        let decoded = round_trip(&events);
+        assert_eq!(decoded.len(), events.len());
+        assert_eq!(decoded[3].value[0].to_bits(), (-0.0f32).to_bits());
+        let mut sorted = events.clone();
+        sorted.sort_by_key(|e| e.frame);
+        assert_eq!(decoded, sorted);
+    }
+
+    #[test]
+    fn test_decode_truncated_stream() {
+        let mut events = vec![button(0, 0, ButtonState::Pressed)];
+        events.extend((1..10).map(|f| button(f, 0, ButtonState::Held)));
+        events.push(axis(12, 1, 0.75));
+
+        let data = encode_events(&events);
+        let decoded = decode_events(&data[..data.len() - 3], events.len() as u64, FRAMES).unwrap();
+        assert!(!decoded.complete);
+        assert_eq!(decoded.events.len(), 10);
+
+        // A run longer than the declared count is rejected
+        assert!(decode_events(&data, 5, FRAMES).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_runs_longer_than_the_log() {
+        // One record repeated 2^34 - 1 times, claiming 2^34 events
+        let event_count = 1u64 << 34;
+        let mut data = vec![0, 0, InputKind::Button as u8 | TAG_RUN];
+        write_varint(&mut data, event_count - 1);
+        assert_eq!(data.len(), 8);
+
+        // The declared count cannot fit in 8 bytes of records
+        assert!(matches!(
+            decode_events(&data, event_count, 120),
+            Err(ParseError::InvalidBinaryEvent { .. })
+        ));
+        // Nor can a run be longer than the log, whatever count is declared
+        let result = decode_events(&data, 2_000, 1_000);
+        assert!(matches!(
+            result,
+            Err(ParseError::InvalidBinaryEvent { ref reason, .. }) if reason.contains("longer than the log")
+        ));
+    }
+
+    #[test]
+    fn test_timestamps_round_trip() {
+        let timestamps = vec![5, 16_672, 33_339, 83_000, 83_000];
+        let data = encode_timestamps(&timestamps);
+        assert_eq!(data.len(), 1 + 1 + 3 + 3 + 3 + 1);
+        assert_eq!(
+            decode_timestamps(&data).unwrap(),
+            Some((timestamps, data.len()))
+        );
+        assert_eq!(decode_timestamps(&data[..data.len() - 1]).unwrap(), None);
+    }
+}
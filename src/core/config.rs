@@ -7,6 +7,8 @@ use eframe::egui::Color32;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use super::format::WriteOptions;
+
 /// Settings filename for persistence.
 const SETTINGS_FILENAME: &str = "config.json";
 
@@ -16,7 +18,9 @@ const MAX_RECENT_FILES: usize = 10;
 /// Color settings for the application UI.
 ///
 /// All colors can be customized by the user and are persisted to disk.
+/// Colors missing from an older config file fall back to their defaults.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ColorSettings {
     // Button state colors
     /// Color for pressed button state (default: green)
@@ -87,6 +91,12 @@ pub struct ColorSettings {
     pub auto_scroll_enabled: [u8; 3],
     /// Loop selection enabled indicator color
     pub loop_enabled: [u8; 3],
+
+    // Analysis colors
+    /// Idle span shading color
+    pub idle_span: [u8; 3],
+    /// Event gap (no events) shading color
+    pub event_gap: [u8; 3],
 }
 
 impl Default for ColorSettings {
@@ -136,6 +146,10 @@ impl Default for ColorSettings {
             // Control colors
             auto_scroll_enabled: [100, 200, 100], // Light green
             loop_enabled: [180, 100, 220],        // Purple
+
+            // Analysis colors
+            idle_span: [120, 120, 140], // Slate gray
+            event_gap: [255, 140, 60],  // Orange
         }
     }
 }
@@ -241,11 +255,13 @@ impl ColorSettings {
     }
 
     /// Get axis1d color as Color32.
+    #[allow(dead_code)] // Reserved for unmapped axis rows
     pub fn axis1d_color(&self) -> Color32 {
         Self::to_color32(self.axis1d)
     }
 
     /// Get axis2d color as Color32.
+    #[allow(dead_code)] // Reserved for unmapped axis rows
     pub fn axis2d_color(&self) -> Color32 {
         Self::to_color32(self.axis2d)
     }
@@ -299,6 +315,101 @@ impl ColorSettings {
     pub fn loop_enabled_color(&self) -> Color32 {
         Self::to_color32(self.loop_enabled)
     }
+
+    /// Get idle span color with alpha.
+    pub fn idle_span_color_alpha(&self, alpha: u8) -> Color32 {
+        Self::to_color32_alpha(self.idle_span, alpha)
+    }
+
+    /// Get event gap color with alpha.
+    pub fn event_gap_color_alpha(&self, alpha: u8) -> Color32 {
+        Self::to_color32_alpha(self.event_gap, alpha)
+    }
+}
+
+/// Default height of timeline input rows in pixels.
+pub const DEFAULT_ROW_HEIGHT: f32 = 32.0;
+
+/// Height of timeline input rows in compact mode.
+pub const COMPACT_ROW_HEIGHT: f32 = 16.0;
+
+/// Smallest row height, also the limit when fitting rows to the window.
+pub const MIN_ROW_HEIGHT: f32 = 8.0;
+
+/// Largest row height.
+pub const MAX_ROW_HEIGHT: f32 = 96.0;
+
+/// How the timeline sizes its input rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowDensity {
+    /// Rows use the configured row height
+    #[default]
+    Normal,
+    /// Short rows with smaller headers and padding
+    Compact,
+    /// Rows grow or shrink so all of them fit in the window
+    FitAll,
+}
+
+impl RowDensity {
+    /// All densities, in the order they are offered.
+    pub const ALL: [RowDensity; 3] = [Self::Normal, Self::Compact, Self::FitAll];
+
+    /// Name shown in the settings.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::Compact => "Compact",
+            Self::FitAll => "Fit all rows",
+        }
+    }
+}
+
+/// Row height settings of the timeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RowSettings {
+    /// How rows are sized
+    pub density: RowDensity,
+    /// Height of input rows in pixels in normal density
+    pub row_height: f32,
+}
+
+impl Default for RowSettings {
+    fn default() -> Self {
+        Self {
+            density: RowDensity::default(),
+            row_height: DEFAULT_ROW_HEIGHT,
+        }
+    }
+}
+
+impl RowSettings {
+    /// Height of an input row at a scale of 1 in normal or compact density.
+    ///
+    /// Fit-all density starts from the configured height as well; the
+    /// timeline replaces it once it knows the size of the window.
+    pub fn base_height(&self) -> f32 {
+        match self.density {
+            RowDensity::Compact => COMPACT_ROW_HEIGHT,
+            RowDensity::Normal | RowDensity::FitAll => {
+                self.row_height.clamp(MIN_ROW_HEIGHT, MAX_ROW_HEIGHT)
+            }
+        }
+    }
+
+    /// Row height at which input rows fill the given space.
+    ///
+    /// # Arguments
+    /// * `total_scale` - Sum of the height multipliers of all input rows
+    /// * `available` - Height left for the input rows in pixels
+    pub fn fit_height(total_scale: f32, available: f32) -> f32 {
+        if total_scale <= 0.0 {
+            return MAX_ROW_HEIGHT;
+        }
+        (available / total_scale).clamp(MIN_ROW_HEIGHT, MAX_ROW_HEIGHT)
+    }
 }
 
 /// Application settings including color customization and user preferences.
@@ -322,6 +433,37 @@ pub struct AppSettings {
     /// Window size to restore on startup (width, height).
     #[serde(default)]
     pub window_size: Option<(f32, f32)>,
+
+    /// Whether logs are loaded leniently, skipping or repairing bad JSON entries
+    /// and recovering the complete events of truncated binary files.
+    #[serde(default)]
+    pub lenient_json: bool,
+
+    /// Whether `.ilb` files are saved in the compressed binary format instead
+    /// of uncompressed v1, which older readers and follow mode expect.
+    #[serde(default)]
+    pub compress_binary: bool,
+
+    /// Whether `.ilb` files are saved with a CRC-32 trailer that detects
+    /// corruption on load.
+    #[serde(default = "default_checksum_binary")]
+    pub checksum_binary: bool,
+
+    /// Frame time in milliseconds above which a frame is highlighted as a hitch.
+    #[serde(default = "default_hitch_threshold_ms")]
+    pub hitch_threshold_ms: f32,
+
+    /// Whether the mapping profile matching a log's source is applied on load.
+    #[serde(default = "default_apply_profiles")]
+    pub apply_profiles_on_load: bool,
+
+    /// Whether the minimap of the whole log is shown above the timeline.
+    #[serde(default = "default_show_minimap")]
+    pub show_minimap: bool,
+
+    /// Height of the timeline rows.
+    #[serde(default)]
+    pub rows: RowSettings,
 }
 
 /// Default playback speed.
@@ -329,6 +471,26 @@ fn default_speed() -> f32 {
     1.0
 }
 
+/// Binary files are saved with a checksum unless turned off.
+fn default_checksum_binary() -> bool {
+    true
+}
+
+/// Default hitch threshold: a frame that takes longer than 1.5 frames at 60 FPS.
+fn default_hitch_threshold_ms() -> f32 {
+    25.0
+}
+
+/// Matching mapping profiles are applied on load unless turned off.
+fn default_apply_profiles() -> bool {
+    true
+}
+
+/// The minimap is shown unless turned off.
+fn default_show_minimap() -> bool {
+    true
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
@@ -337,6 +499,13 @@ impl Default for AppSettings {
             loop_enabled: false,
             recent_files: Vec::new(),
             window_size: None,
+            lenient_json: false,
+            compress_binary: false,
+            checksum_binary: default_checksum_binary(),
+            hitch_threshold_ms: default_hitch_threshold_ms(),
+            apply_profiles_on_load: default_apply_profiles(),
+            show_minimap: default_show_minimap(),
+            rows: RowSettings::default(),
         }
     }
 }
@@ -416,12 +585,32 @@ impl AppSettings {
         self.default_speed = speed.clamp(0.1, 10.0);
     }
 
+    /// Set the hitch threshold in milliseconds, clamped to 1-1000 ms.
+    pub fn set_hitch_threshold_ms(&mut self, threshold_ms: f32) {
+        self.hitch_threshold_ms = threshold_ms.clamp(1.0, 1000.0);
+    }
+
+    /// Hitch threshold in microseconds, the unit of frame timestamps.
+    pub fn hitch_threshold_us(&self) -> u64 {
+        (self.hitch_threshold_ms * 1000.0) as u64
+    }
+
+    /// Options for saving log files.
+    pub fn write_options(&self) -> WriteOptions {
+        WriteOptions {
+            compress: self.compress_binary,
+            checksum: self.checksum_binary,
+        }
+    }
+
     /// Set the window size.
+    #[allow(dead_code)] // Will be used when restoring window size on startup
     pub fn set_window_size(&mut self, width: f32, height: f32) {
         self.window_size = Some((width, height));
     }
 
     /// Get the config file path for display purposes.
+    #[allow(dead_code)] // Will be used to show the config location in settings
     pub fn get_config_path() -> Option<PathBuf> {
         Self::get_settings_path()
     }
@@ -477,6 +666,50 @@ mod tests {
         assert!(!settings.loop_enabled);
         assert!(settings.recent_files.is_empty());
         assert!(settings.window_size.is_none());
+        assert!(!settings.lenient_json);
+        assert!(!settings.compress_binary);
+        assert!(settings.checksum_binary);
+        assert_eq!(settings.write_options(), WriteOptions::default());
+        assert_eq!(settings.hitch_threshold_ms, 25.0);
+    }
+
+    #[test]
+    fn test_hitch_threshold() {
+        let mut settings = AppSettings::default();
+        assert_eq!(settings.hitch_threshold_us(), 25_000);
+
+        settings.set_hitch_threshold_ms(0.0);
+        assert_eq!(settings.hitch_threshold_ms, 1.0);
+
+        // Settings saved before the threshold existed get the default
+        let restored: AppSettings =
+            serde_json::from_str(r#"{"colors": {}, "default_speed": 1.0}"#).unwrap();
+        assert_eq!(restored.hitch_threshold_ms, 25.0);
+    }
+
+    #[test]
+    fn test_row_settings() {
+        let mut rows = RowSettings::default();
+        assert_eq!(rows.base_height(), DEFAULT_ROW_HEIGHT);
+        rows.row_height = 500.0;
+        assert_eq!(rows.base_height(), MAX_ROW_HEIGHT);
+        rows.density = RowDensity::Compact;
+        assert_eq!(rows.base_height(), COMPACT_ROW_HEIGHT);
+
+        // Fitting is limited to the row height range
+        assert_eq!(RowSettings::fit_height(4.0, 100.0), 25.0);
+        assert_eq!(RowSettings::fit_height(100.0, 100.0), MIN_ROW_HEIGHT);
+        assert_eq!(RowSettings::fit_height(0.0, 100.0), MAX_ROW_HEIGHT);
+
+        rows.density = RowDensity::FitAll;
+        let settings = AppSettings {
+            rows: rows.clone(),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(json.contains(r#""density":"fit_all""#));
+        let restored: AppSettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.rows, rows);
     }
 
     #[test]
@@ -498,12 +731,14 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_settings_serialization() {
         let mut settings = AppSettings::default();
         settings.default_speed = 2.5;
         settings.loop_enabled = true;
         settings.recent_files.push(PathBuf::from("/test/file.ilj"));
         settings.window_size = Some((1024.0, 768.0));
+        settings.lenient_json = true;
 
         let json = serde_json::to_string(&settings).unwrap();
         let restored: AppSettings = serde_json::from_str(&json).unwrap();
@@ -513,6 +748,7 @@ mod tests {
         assert!(restored.loop_enabled);
         assert_eq!(restored.recent_files.len(), 1);
         assert_eq!(restored.window_size, Some((1024.0, 768.0)));
+        assert!(restored.lenient_json);
     }
 
     #[test]
@@ -597,9 +833,15 @@ mod tests {
         let settings: AppSettings = serde_json::from_str(old_json).unwrap();
 
         // New fields should have defaults
+        assert_eq!(
+            settings.colors.idle_span,
+            ColorSettings::default().idle_span
+        );
         assert_eq!(settings.default_speed, 1.0);
         assert!(!settings.loop_enabled);
         assert!(settings.recent_files.is_empty());
         assert!(settings.window_size.is_none());
+        assert!(settings.checksum_binary);
+        assert_eq!(settings.rows, RowSettings::default());
     }
 }
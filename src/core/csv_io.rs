@@ -0,0 +1,535 @@
+//! CSV import and export of input events.
+//!
+//! Exported files have one row per event with the columns listed in
+//! `CSV_COLUMNS`, which loads directly into spreadsheets and pandas.
+//! Importing goes the other way: the columns of an arbitrary CSV file are
+//! mapped onto event fields (see `CsvColumnMapping`) so test inputs can be
+//! authored by hand.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+use super::filter::FilterState;
+use super::log::{ButtonState, InputEvent, InputKind, InputLog, InputMapping, LogMetadata};
+use super::parser::{self, ParseError};
+use super::writer::{button_state_name, input_kind_name};
+
+/// Column headers of exported CSV files, in order.
+pub const CSV_COLUMNS: [&str; 8] = ["frame", "time_s", "id", "name", "kind", "state", "x", "y"];
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Export the visible events of a log as CSV.
+///
+/// # Arguments
+/// * `log` - The log to export
+/// * `filter` - Only events of inputs visible in this filter are written
+/// * `range` - Optional inclusive frame range (e.g. the current selection)
+///
+/// # Returns
+/// The CSV text, with a header row followed by one row per event.
+pub fn export_csv(log: &InputLog, filter: &FilterState, range: Option<(u64, u64)>) -> String {
+    let mut output = Vec::new();
+    // Writing to memory cannot fail
+    let _ = write_csv(&mut output, log, filter, range);
+    String::from_utf8(output).unwrap_or_default()
+}
+
+/// Write the visible events of a log as CSV to `writer`.
+fn write_csv<W: Write>(
+    writer: W,
+    log: &InputLog,
+    filter: &FilterState,
+    range: Option<(u64, u64)>,
+) -> csv::Result<()> {
+    let (start, end) = match range {
+        Some((a, b)) => (a.min(b), a.max(b)),
+        None => (0, u64::MAX),
+    };
+    let names: HashMap<u32, String> = log
+        .get_effective_mappings()
+        .into_iter()
+        .map(|m| (m.id, m.qualified_name()))
+        .collect();
+
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    csv_writer.write_record(CSV_COLUMNS)?;
+
+    for event in &log.events {
+        if event.frame < start || event.frame > end || !filter.is_visible(event.id, event.kind) {
+            continue;
+        }
+        let name = names.get(&event.id).map(String::as_str).unwrap_or("");
+        csv_writer.write_record([
+            event.frame.to_string(),
+            format!("{:.6}", log.frame_time_secs(event.frame)),
+            event.id.to_string(),
+            name.to_string(),
+            input_kind_name(event.kind).to_string(),
+            button_state_name(event.state).to_string(),
+            event.value[0].to_string(),
+            event.value[1].to_string(),
+        ])?;
+    }
+
+    csv_writer.flush()?;
+    Ok(())
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// A CSV file read into memory: the header row and the data rows.
+#[derive(Debug, Clone, Default)]
+pub struct CsvTable {
+    /// Column headers
+    pub headers: Vec<String>,
+    /// Data rows
+    pub rows: Vec<CsvRow>,
+}
+
+/// A single data row of a CSV file.
+#[derive(Debug, Clone)]
+pub struct CsvRow {
+    /// Line number in the file (1-indexed)
+    pub line: usize,
+    /// Field values, trimmed
+    pub fields: Vec<String>,
+}
+
+/// Read CSV text into a table.
+///
+/// The first row is taken as the header. Rows may have differing numbers of
+/// fields; missing fields are treated as empty.
+pub fn read_csv(content: &str) -> Result<CsvTable, ParseError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(csv_error)?
+        .iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        rows.push(CsvRow {
+            line: record.position().map(|p| p.line() as usize).unwrap_or(0),
+            fields: record.iter().map(str::to_string).collect(),
+        });
+    }
+
+    Ok(CsvTable { headers, rows })
+}
+
+/// Convert a CSV reader error into a parse error with its line.
+fn csv_error(error: csv::Error) -> ParseError {
+    ParseError::InvalidLine {
+        line: error.position().map(|p| p.line() as usize).unwrap_or(0),
+        column: None,
+        message: error.to_string(),
+    }
+}
+
+/// Event field that a CSV column can be mapped onto.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvField {
+    Frame,
+    Time,
+    Id,
+    Name,
+    Kind,
+    State,
+    X,
+    Y,
+}
+
+impl CsvField {
+    /// All fields, in export column order.
+    pub const ALL: [CsvField; 8] = [
+        Self::Frame,
+        Self::Time,
+        Self::Id,
+        Self::Name,
+        Self::Kind,
+        Self::State,
+        Self::X,
+        Self::Y,
+    ];
+
+    /// Column header used for this field on export.
+    pub fn column_name(self) -> &'static str {
+        CSV_COLUMNS[self as usize]
+    }
+
+    /// Additional header names recognized when guessing the mapping.
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Self::Frame => &["frame_number", "f"],
+            Self::Time => &["time", "seconds", "t"],
+            Self::Id => &["input_id", "input"],
+            Self::Name => &["input_name", "label"],
+            Self::Kind => &["type", "input_kind"],
+            Self::State => &["button_state"],
+            Self::X => &["value_x", "value", "value0"],
+            Self::Y => &["value_y", "value1"],
+        }
+    }
+}
+
+/// Which CSV column (by index) provides each event field.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsvColumnMapping {
+    columns: [Option<usize>; 8],
+}
+
+impl CsvColumnMapping {
+    /// Guess the mapping from the header names (case-insensitive).
+    pub fn guess(headers: &[String]) -> Self {
+        let mut mapping = Self::default();
+        for field in CsvField::ALL {
+            mapping.columns[field as usize] = headers.iter().position(|header| {
+                let header = header.trim().to_lowercase();
+                header == field.column_name() || field.aliases().contains(&header.as_str())
+            });
+        }
+        mapping
+    }
+
+    /// Column mapped onto a field.
+    pub fn get(&self, field: CsvField) -> Option<usize> {
+        self.columns[field as usize]
+    }
+
+    /// Map a column onto a field (None leaves the field unmapped).
+    pub fn set(&mut self, field: CsvField, column: Option<usize>) {
+        self.columns[field as usize] = column;
+    }
+
+    /// Check that the fields needed to build events are mapped.
+    ///
+    /// A frame needs either the `frame` or the `time_s` column, and an input
+    /// needs either an `id` or a `name` column. Everything else is optional.
+    pub fn validate(&self) -> Result<(), ParseError> {
+        if self.get(CsvField::Frame).is_none() && self.get(CsvField::Time).is_none() {
+            return Err(ParseError::MissingField {
+                field: "frame or time_s column",
+            });
+        }
+        if self.get(CsvField::Id).is_none() && self.get(CsvField::Name).is_none() {
+            return Err(ParseError::MissingField {
+                field: "id or name column",
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Build an `InputLog` from a CSV table.
+///
+/// Rows are validated with the same kind and state parsers as `.ilj` files.
+/// When only a `time_s` column is mapped, frames are computed from
+/// `target_fps`; when only a `name` column is mapped, IDs are assigned to
+/// names in order of first appearance. Missing `kind` defaults to Button and
+/// missing values to 0.
+///
+/// # Arguments
+/// * `table` - The CSV content
+/// * `mapping` - Which column provides each field
+/// * `target_fps` - Frame rate of the resulting log
+///
+/// # Returns
+/// * `Ok(InputLog)` - The log, with events sorted by frame
+/// * `Err(ParseError)` - Unmapped required fields or an invalid cell
+pub fn import_csv(
+    table: &CsvTable,
+    mapping: &CsvColumnMapping,
+    target_fps: u32,
+) -> Result<InputLog, ParseError> {
+    mapping.validate()?;
+    let fps = target_fps.max(1);
+
+    let mut events = Vec::with_capacity(table.rows.len());
+    let mut names: BTreeMap<u32, String> = BTreeMap::new();
+    let mut name_ids: HashMap<String, u32> = HashMap::new();
+
+    for row in &table.rows {
+        if row.fields.iter().all(|f| f.is_empty()) {
+            continue;
+        }
+
+        let cell = |field: CsvField| {
+            mapping.get(field).and_then(|column| {
+                row.fields
+                    .get(column)
+                    .map(String::as_str)
+                    .filter(|value| !value.is_empty())
+                    .map(|value| (column, value))
+            })
+        };
+        let invalid = |column: usize, message: String| ParseError::InvalidLine {
+            line: row.line,
+            column: Some(column + 1),
+            message,
+        };
+        let missing = |field: CsvField| ParseError::InvalidLine {
+            line: row.line,
+            column: mapping.get(field).map(|c| c + 1),
+            message: format!("missing {}", field.column_name()),
+        };
+
+        let frame = match (cell(CsvField::Frame), cell(CsvField::Time)) {
+            (Some((column, value)), _) => value
+                .parse::<u64>()
+                .map_err(|_| invalid(column, format!("invalid frame '{}'", value)))?,
+            (None, Some((column, value))) => {
+                let seconds = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|s| s.is_finite() && *s >= 0.0)
+                    .ok_or_else(|| invalid(column, format!("invalid time '{}'", value)))?;
+                (seconds * f64::from(fps)).round() as u64
+            }
+            (None, None) => return Err(missing(CsvField::Frame)),
+        };
+
+        let name = cell(CsvField::Name).map(|(_, value)| value.to_string());
+        let id = match (cell(CsvField::Id), &name) {
+            (Some((column, value)), _) => value
+                .parse::<u32>()
+                .map_err(|_| invalid(column, format!("invalid id '{}'", value)))?,
+            (None, Some(name)) => {
+                let next_id = name_ids.len() as u32;
+                *name_ids.entry(name.clone()).or_insert(next_id)
+            }
+            (None, None) => return Err(missing(CsvField::Id)),
+        };
+        if let Some(name) = name {
+            names.entry(id).or_insert(name);
+        }
+
+        let kind = match cell(CsvField::Kind) {
+            Some((column, value)) => {
+                parser::parse_input_kind(value).map_err(|e| invalid(column, e.to_string()))?
+            }
+            None => InputKind::Button,
+        };
+
+        // As in .ilj files, Buttons need a state while axes default to Released
+        let state = match cell(CsvField::State) {
+            Some((column, value)) => {
+                parser::parse_button_state(value).map_err(|e| invalid(column, e.to_string()))?
+            }
+            None if kind == InputKind::Button => return Err(missing(CsvField::State)),
+            None => ButtonState::Released,
+        };
+
+        let mut value = [0.0f32; 2];
+        for (index, field) in [CsvField::X, CsvField::Y].into_iter().enumerate() {
+            if let Some((column, text)) = cell(field) {
+                value[index] = text.parse().map_err(|_| {
+                    invalid(
+                        column,
+                        format!("invalid {} value '{}'", field.column_name(), text),
+                    )
+                })?;
+            }
+        }
+
+        events.push(InputEvent {
+            frame,
+            id,
+            kind,
+            state,
+            value,
+            contact: 0,
+        });
+    }
+
+    events.sort_by_key(|e| e.frame);
+    let frame_count = events.iter().map(|e| e.frame + 1).max().unwrap_or(0);
+
+    Ok(InputLog {
+        metadata: LogMetadata {
+            target_fps: fps,
+            frame_count,
+            ..Default::default()
+        },
+        mappings: names
+            .into_iter()
+            .map(|(id, name)| InputMapping {
+                id,
+                name,
+                color: None,
+                player: None,
+                device: None,
+                group: None,
+            })
+            .collect(),
+        events,
+        frame_timestamps: Vec::new(),
+        annotations: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_log() -> InputLog {
+        InputLog {
+            metadata: LogMetadata {
+                target_fps: 60,
+                frame_count: 120,
+                ..Default::default()
+            },
+            mappings: vec![InputMapping {
+                id: 0,
+                name: "Jump, high".to_string(),
+                color: None,
+                player: None,
+                device: None,
+                group: None,
+            }],
+            events: vec![
+                InputEvent {
+                    frame: 30,
+                    id: 0,
+                    kind: InputKind::Button,
+                    state: ButtonState::Pressed,
+                    value: [0.0, 0.0],
+                    contact: 0,
+                },
+                InputEvent {
+                    frame: 60,
+                    id: 1,
+                    kind: InputKind::Axis2D,
+                    state: ButtonState::Released,
+                    value: [0.25, -1.0],
+                    contact: 0,
+                },
+                InputEvent {
+                    frame: 90,
+                    id: 0,
+                    kind: InputKind::Button,
+                    state: ButtonState::Released,
+                    value: [0.0, 0.0],
+                    contact: 0,
+                },
+            ],
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
+        }
+    }
+
+    fn all_visible(log: &InputLog) -> FilterState {
+        let mut filter = FilterState::new();
+        filter.initialize_from_log(log);
+        filter
+    }
+
+    #[test]
+    fn test_export_columns_and_quoting() {
+        let log = create_test_log();
+        let csv = export_csv(&log, &all_visible(&log), None);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "frame,time_s,id,name,kind,state,x,y");
+        assert_eq!(lines[1], "30,0.500000,0,\"Jump, high\",Button,Pressed,0,0");
+        assert_eq!(lines[2], "60,1.000000,1,Input #1,Axis2D,Released,0.25,-1");
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn test_export_uses_frame_timestamps() {
+        let mut log = create_test_log();
+        log.frame_timestamps = (0..=60).map(|f| 500 + f * 20_000).collect();
+        let csv = export_csv(&log, &all_visible(&log), None);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert!(lines[1].starts_with("30,0.600000,"));
+        assert!(lines[2].starts_with("60,1.200000,"));
+    }
+
+    #[test]
+    fn test_export_respects_filter_and_range() {
+        let log = create_test_log();
+        let mut filter = all_visible(&log);
+        filter.set_id_visible(1, false);
+
+        let csv = export_csv(&log, &filter, Some((0, 60)));
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.lines().nth(1).unwrap().starts_with("30,"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let log = create_test_log();
+        let table = read_csv(&export_csv(&log, &all_visible(&log), None)).unwrap();
+        let mapping = CsvColumnMapping::guess(&table.headers);
+        assert_eq!(mapping.get(CsvField::Y), Some(7));
+
+        let imported = import_csv(&table, &mapping, 60).unwrap();
+        assert_eq!(imported.events.len(), 3);
+        assert_eq!(imported.metadata.frame_count, 91);
+        assert_eq!(imported.events[1].kind, InputKind::Axis2D);
+        assert_eq!(imported.events[1].value, [0.25, -1.0]);
+        assert_eq!(imported.get_input_name(0), "Jump, high");
+    }
+
+    #[test]
+    fn test_import_by_time_and_name() {
+        let table = read_csv(
+            "Seconds,Label,State\n0.5,Jump,Pressed\n1.0,Fire,Pressed\n1.5,Jump,Released\n",
+        )
+        .unwrap();
+        let mapping = CsvColumnMapping::guess(&table.headers);
+
+        let log = import_csv(&table, &mapping, 30).unwrap();
+        let frames: Vec<u64> = log.events.iter().map(|e| e.frame).collect();
+        assert_eq!(frames, vec![15, 30, 45]);
+        // IDs are assigned to names in order of appearance
+        assert_eq!(log.events[2].id, 0);
+        assert_eq!(log.get_input_name(1), "Fire");
+    }
+
+    #[test]
+    fn test_import_reports_invalid_cells() {
+        let table =
+            read_csv("frame,id,kind,state\n1,0,Button,Pressed\n2,0,Lever,Pressed\n").unwrap();
+        let mapping = CsvColumnMapping::guess(&table.headers);
+
+        match import_csv(&table, &mapping, 60) {
+            Err(ParseError::InvalidLine {
+                line,
+                column,
+                message,
+            }) => {
+                assert_eq!(line, 3);
+                assert_eq!(column, Some(3));
+                assert!(message.contains("Lever"));
+            }
+            other => panic!("expected InvalidLine, got {:?}", other),
+        }
+
+        // Buttons need a state
+        let table = read_csv("frame,id\n1,0\n").unwrap();
+        let mapping = CsvColumnMapping::guess(&table.headers);
+        assert!(import_csv(&table, &mapping, 60).is_err());
+
+        // Required columns must be mapped
+        let mut mapping = CsvColumnMapping::guess(&table.headers);
+        mapping.set(CsvField::Frame, None);
+        assert!(matches!(
+            import_csv(&table, &mapping, 60),
+            Err(ParseError::MissingField { .. })
+        ));
+    }
+}
@@ -0,0 +1,173 @@
+//! Event density over a whole log, for the timeline minimap.
+//!
+//! The frames of the log are split into equally wide buckets, and for each
+//! input the fraction of frames in a bucket on which it is active is
+//! recorded. An input with button states is active while it is down, however
+//! its holds are encoded; other inputs are active on frames with an event.
+//!
+//! The map is computed once per log (and again after edits or appends) so
+//! drawing the minimap stays cheap on huge logs.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+use super::edit::down_ranges;
+use super::log::{InputEvent, InputLog};
+
+/// Default maximum number of buckets across the log.
+pub const DEFAULT_MAX_BUCKETS: usize = 512;
+
+/// Activity of every input across the whole log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityMap {
+    /// Number of frames in the log
+    frame_count: u64,
+    /// Number of frames covered by each bucket (the last one may be shorter)
+    frames_per_bucket: u64,
+    /// Fraction of active frames per bucket (0.0 to 1.0), per input ID
+    rows: HashMap<u32, Vec<f32>>,
+}
+
+impl DensityMap {
+    /// Compute the density map of a log.
+    ///
+    /// # Arguments
+    /// * `log` - The log to summarize
+    /// * `max_buckets` - Maximum number of buckets; short logs get one per frame
+    pub fn compute(log: &InputLog, max_buckets: usize) -> Self {
+        let frame_count = log.metadata.frame_count;
+        let frames_per_bucket = frame_count.div_ceil(max_buckets.max(1) as u64).max(1);
+        let bucket_count = frame_count.div_ceil(frames_per_bucket) as usize;
+
+        let mut counts: HashMap<u32, Vec<u64>> = HashMap::new();
+        let mut presses: BTreeMap<(u32, u16), Vec<InputEvent>> = BTreeMap::new();
+        for event in &log.events {
+            let row = counts
+                .entry(event.id)
+                .or_insert_with(|| vec![0; bucket_count]);
+            if event.kind.has_button_state() {
+                presses
+                    .entry((event.id, event.contact))
+                    .or_default()
+                    .push(event.clone());
+            } else if event.frame < frame_count {
+                row[(event.frame / frames_per_bucket) as usize] += 1;
+            }
+        }
+        for ((id, _), events) in &presses {
+            let row = counts.get_mut(id).expect("row created for every input");
+            for range in down_ranges(events, frame_count) {
+                add_range(row, range, frames_per_bucket, frame_count);
+            }
+        }
+
+        let rows = counts
+            .into_iter()
+            .map(|(id, row)| {
+                let fractions = row
+                    .iter()
+                    .enumerate()
+                    .map(|(bucket, &count)| {
+                        let start = bucket as u64 * frames_per_bucket;
+                        let len = (start + frames_per_bucket).min(frame_count) - start;
+                        (count as f32 / len as f32).min(1.0)
+                    })
+                    .collect();
+                (id, fractions)
+            })
+            .collect();
+
+        Self {
+            frame_count,
+            frames_per_bucket,
+            rows,
+        }
+    }
+
+    /// Number of frames in the log the map was computed for.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Frames covered by each bucket (the last one may be shorter).
+    pub fn frames_per_bucket(&self) -> u64 {
+        self.frames_per_bucket
+    }
+
+    /// Fraction of active frames per bucket of an input, or `None` if the
+    /// input has no events.
+    pub fn row(&self, id: u32) -> Option<&[f32]> {
+        self.rows.get(&id).map(Vec::as_slice)
+    }
+}
+
+/// Count the frames of a press in the buckets it overlaps.
+fn add_range(row: &mut [u64], range: Range<u64>, frames_per_bucket: u64, frame_count: u64) {
+    let end = range.end.min(frame_count);
+    let mut frame = range.start;
+    while frame < end {
+        let bucket = frame / frames_per_bucket;
+        let bucket_end = ((bucket + 1) * frames_per_bucket).min(end);
+        row[bucket as usize] += bucket_end - frame;
+        frame = bucket_end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log::ButtonState;
+    use crate::core::normalize::{HoldEncoding, normalize};
+    use crate::core::test_util::{axis, button, log_with};
+
+    #[test]
+    fn test_bucket_size() {
+        let map = DensityMap::compute(&log_with(1000, vec![]), 512);
+        assert_eq!(map.frames_per_bucket(), 2);
+        assert_eq!(map.frame_count(), 1000);
+
+        // Short logs get one bucket per frame
+        let map = DensityMap::compute(&log_with(30, vec![]), 512);
+        assert_eq!(map.frames_per_bucket(), 1);
+        assert!(map.row(0).is_none());
+    }
+
+    #[test]
+    fn test_holds_fill_buckets() {
+        // Down for frames 5..25, with buckets of 10 frames
+        let log = log_with(
+            40,
+            vec![
+                button(5, 0, ButtonState::Pressed),
+                button(25, 0, ButtonState::Released),
+                axis(12, 1, 0.5),
+                axis(13, 1, 0.5),
+            ],
+        );
+        let map = DensityMap::compute(&log, 4);
+        assert_eq!(map.frames_per_bucket(), 10);
+        assert_eq!(map.row(0), Some(&[0.5, 1.0, 0.5, 0.0][..]));
+        assert_eq!(map.row(1), Some(&[0.0, 0.2, 0.0, 0.0][..]));
+    }
+
+    #[test]
+    fn test_dense_and_sparse_holds_match() {
+        let sparse = log_with(
+            100,
+            vec![
+                button(10, 0, ButtonState::Pressed),
+                button(60, 0, ButtonState::Released),
+                button(80, 0, ButtonState::Pressed),
+            ],
+        );
+        let mut dense = sparse.clone();
+        for change in normalize(&dense, Some(HoldEncoding::Dense), false) {
+            change.apply(&mut dense);
+        }
+        assert!(dense.events.len() > 50);
+        assert_eq!(
+            DensityMap::compute(&sparse, 8),
+            DensityMap::compute(&dense, 8)
+        );
+    }
+}
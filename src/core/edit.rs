@@ -0,0 +1,580 @@
+//! Editing of input events.
+//!
+//! This module changes the events of one input at a time. Every edit returns
+//! an `EventChange` holding the input's events before and after the edit, so
+//! it can be applied to the log and undone again by applying its inverse.
+//!
+//! Inputs with button states are edited as the frame ranges they are down
+//! for and re-encoded afterwards, which keeps their Pressed/Held/Released
+//! sequence consistent. Dense logs (with a Held event on every down frame)
+//! stay dense; sparse logs stay sparse. Touch contacts are separate streams
+//! of presses, so an edit only changes the events of one contact.
+
+use std::ops::Range;
+
+use super::log::{ButtonState, InputEvent, InputKind, InputLog};
+
+/// The events of one input before and after an edit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventChange {
+    /// The edited input
+    pub id: u32,
+    /// The input's events before the edit, in frame order
+    pub before: Vec<InputEvent>,
+    /// The input's events after the edit, in frame order
+    pub after: Vec<InputEvent>,
+}
+
+impl EventChange {
+    /// Whether the edit leaves the events unchanged.
+    pub fn is_noop(&self) -> bool {
+        self.before == self.after
+    }
+
+    /// Replace the input's events with the edited ones.
+    pub fn apply(&self, log: &mut InputLog) {
+        replace_events(log, self.id, &self.after);
+    }
+
+    /// The change that undoes this one.
+    pub fn inverse(&self) -> EventChange {
+        EventChange {
+            id: self.id,
+            before: self.after.clone(),
+            after: self.before.clone(),
+        }
+    }
+}
+
+/// Replace all events of an input, keeping the log sorted by frame.
+fn replace_events(log: &mut InputLog, id: u32, events: &[InputEvent]) {
+    log.events.retain(|e| e.id != id);
+    log.events.extend_from_slice(events);
+    // Stable, so events of other inputs keep their relative order
+    log.events.sort_by_key(|e| e.frame);
+}
+
+/// The events of an input in frame order.
+pub fn input_events(log: &InputLog, id: u32) -> Vec<InputEvent> {
+    let mut events: Vec<InputEvent> = log.events.iter().filter(|e| e.id == id).cloned().collect();
+    events.sort_by_key(|e| e.frame);
+    events
+}
+
+/// The events of one touch contact of an input, in frame order.
+fn contact_events(events: &[InputEvent], contact: u16) -> Vec<InputEvent> {
+    events
+        .iter()
+        .filter(|e| e.contact == contact)
+        .cloned()
+        .collect()
+}
+
+/// The kind of an input, taken from its first event.
+pub fn input_kind(log: &InputLog, id: u32) -> Option<InputKind> {
+    log.events.iter().find(|e| e.id == id).map(|e| e.kind)
+}
+
+/// Frame ranges an input with button states is down for.
+///
+/// `events` must belong to a single touch contact and be in frame order. A
+/// press that is never released lasts until `frame_count`. A repeated
+/// Pressed starts a new range right where the previous one ends, so
+/// touching ranges are separate presses.
+pub fn down_ranges(events: &[InputEvent], frame_count: u64) -> Vec<Range<u64>> {
+    let mut ranges = Vec::new();
+    let mut down_since: Option<u64> = None;
+    for event in events {
+        match (event.state, down_since) {
+            (ButtonState::Pressed, Some(since)) => {
+                if event.frame > since {
+                    ranges.push(since..event.frame);
+                }
+                down_since = Some(event.frame);
+            }
+            (ButtonState::Pressed | ButtonState::Held, None) => down_since = Some(event.frame),
+            (ButtonState::Held, Some(_)) => {}
+            (ButtonState::Released, Some(since)) => {
+                ranges.push(since..event.frame.max(since + 1));
+                down_since = None;
+            }
+            (ButtonState::Released, None) => {}
+        }
+    }
+    if let Some(since) = down_since {
+        ranges.push(since..frame_count.max(since + 1));
+    }
+    ranges
+}
+
+/// Whether a touch contact of an input with button states is down on a frame.
+pub fn is_down_at(log: &InputLog, id: u32, contact: u16, frame: u64) -> bool {
+    let events = contact_events(&input_events(log, id), contact);
+    down_ranges(&events, log.metadata.frame_count)
+        .iter()
+        .any(|range| range.contains(&frame))
+}
+
+/// The touch contact a click on a frame refers to.
+///
+/// This is the lowest contact that is down on the frame, else the contact of
+/// the input's last event at or before it, else that of its first event.
+/// Inputs other than touches always use contact 0.
+pub fn contact_at(log: &InputLog, id: u32, frame: u64) -> u16 {
+    let events = input_events(log, id);
+    let mut contacts: Vec<u16> = events.iter().map(|e| e.contact).collect();
+    contacts.sort_unstable();
+    contacts.dedup();
+    contacts
+        .into_iter()
+        .find(|&contact| {
+            down_ranges(&contact_events(&events, contact), log.metadata.frame_count)
+                .iter()
+                .any(|range| range.contains(&frame))
+        })
+        .or_else(|| {
+            events
+                .iter()
+                .rev()
+                .find(|e| e.frame <= frame)
+                .map(|e| e.contact)
+        })
+        .or_else(|| events.first().map(|e| e.contact))
+        .unwrap_or(0)
+}
+
+/// Press or release a touch contact of an input with button states over a
+/// range of frames.
+///
+/// Only the events of `contact` change; other contacts keep their presses.
+/// Pressing merges the range with presses it overlaps or touches, so
+/// clicking the frame after a hold extends it. Releasing cuts the range out
+/// of any press; what remains after the cut becomes a press of its own.
+///
+/// # Arguments
+/// * `log` - The log to edit (not modified; apply the returned change)
+/// * `id` - The input to edit
+/// * `kind` - Kind of the input, used if it has no events yet
+/// * `contact` - The touch contact to edit (0 for other kinds)
+/// * `frames` - The frames to press or release
+/// * `down` - Whether the contact is down on these frames afterwards
+pub fn set_button_down(
+    log: &InputLog,
+    id: u32,
+    kind: InputKind,
+    contact: u16,
+    frames: Range<u64>,
+    down: bool,
+) -> EventChange {
+    let before = input_events(log, id);
+    let stream = contact_events(&before, contact);
+    let ranges = down_ranges(&stream, log.metadata.frame_count);
+
+    let mut edited: Vec<Range<u64>> = Vec::new();
+    if down {
+        let mut merged = frames.clone();
+        for range in ranges {
+            if range.start <= merged.end && range.end >= merged.start {
+                merged = merged.start.min(range.start)..merged.end.max(range.end);
+            } else {
+                edited.push(range);
+            }
+        }
+        if !merged.is_empty() {
+            edited.push(merged);
+        }
+        edited.sort_by_key(|range| range.start);
+    } else {
+        for range in ranges {
+            if range.end <= frames.start || range.start >= frames.end {
+                edited.push(range);
+                continue;
+            }
+            if range.start < frames.start {
+                edited.push(range.start..frames.start);
+            }
+            if range.end > frames.end {
+                edited.push(frames.end..range.end);
+            }
+        }
+    }
+
+    let frame_count = log.metadata.frame_count;
+    let dense = is_dense(&before);
+    let encoded = encode_presses(&stream, kind, id, contact, &edited, frame_count, dense);
+    if encoded == stream {
+        let after = before.clone();
+        return EventChange { id, before, after };
+    }
+
+    let mut after: Vec<InputEvent> = before
+        .iter()
+        .filter(|e| e.contact != contact)
+        .cloned()
+        .collect();
+    after.extend(encoded);
+    // Stable, so contacts keep their order within a frame
+    after.sort_by_key(|e| e.frame);
+    EventChange { id, before, after }
+}
+
+/// Toggle whether a touch contact of an input with button states is down on
+/// a single frame.
+pub fn toggle_button(
+    log: &InputLog,
+    id: u32,
+    kind: InputKind,
+    contact: u16,
+    frame: u64,
+) -> EventChange {
+    let down = !is_down_at(log, id, contact, frame);
+    set_button_down(log, id, kind, contact, frame..frame + 1, down)
+}
+
+/// Remove the whole press of a touch contact that covers a frame.
+///
+/// Returns a no-op change if the contact is not down on that frame.
+pub fn remove_press(
+    log: &InputLog,
+    id: u32,
+    kind: InputKind,
+    contact: u16,
+    frame: u64,
+) -> EventChange {
+    let events = contact_events(&input_events(log, id), contact);
+    let ranges = down_ranges(&events, log.metadata.frame_count);
+    match ranges.into_iter().find(|range| range.contains(&frame)) {
+        Some(range) => set_button_down(log, id, kind, contact, range, false),
+        None => set_button_down(log, id, kind, contact, frame..frame, false),
+    }
+}
+
+/// Whether events encode holds densely, with a Held event on every down frame.
+pub fn is_dense(events: &[InputEvent]) -> bool {
+    events.iter().any(|e| e.state == ButtonState::Held)
+}
+
+/// Encode the press ranges of one touch contact as events.
+///
+/// Each press gets a Pressed event, a Held event on every further frame if
+/// `dense` is set, and a Released event unless it lasts until the end of the
+/// log or the next press starts right away. Values are taken from the
+/// original events of the contact so keys keep their key code and touch
+/// points their position.
+pub fn encode_presses(
+    original: &[InputEvent],
+    kind: InputKind,
+    id: u32,
+    contact: u16,
+    ranges: &[Range<u64>],
+    frame_count: u64,
+    dense: bool,
+) -> Vec<InputEvent> {
+    let event = |frame: u64, state: ButtonState| {
+        // The event at the frame, else the closest one before it, else the first one
+        let template = original
+            .iter()
+            .rev()
+            .find(|e| e.frame <= frame)
+            .or(original.first());
+        InputEvent {
+            frame,
+            id,
+            kind: template.map(|e| e.kind).unwrap_or(kind),
+            state,
+            value: template.map(|e| e.value).unwrap_or_default(),
+            contact,
+        }
+    };
+
+    let mut events = Vec::new();
+    for (i, range) in ranges.iter().enumerate() {
+        events.push(event(range.start, ButtonState::Pressed));
+        if dense {
+            events.extend((range.start + 1..range.end).map(|f| event(f, ButtonState::Held)));
+        }
+        let next_starts_here = ranges
+            .get(i + 1)
+            .is_some_and(|next| next.start == range.end);
+        if range.end < frame_count && !next_starts_here {
+            events.push(event(range.end, ButtonState::Released));
+        }
+    }
+    events
+}
+
+/// Value of an analog input in effect on a frame: its last event at or before it.
+pub fn value_at(log: &InputLog, id: u32, frame: u64) -> Option<[f32; 2]> {
+    input_events(log, id)
+        .iter()
+        .rev()
+        .find(|e| e.frame <= frame)
+        .map(|e| e.value)
+}
+
+/// Set the value of an analog input on a frame.
+///
+/// Replaces the value of the input's event on that frame, or inserts a new
+/// event if it has none there. A new event takes its contact from the event
+/// in effect on the frame.
+pub fn set_value(
+    log: &InputLog,
+    id: u32,
+    kind: InputKind,
+    frame: u64,
+    value: [f32; 2],
+) -> EventChange {
+    let before = input_events(log, id);
+    let mut after = before.clone();
+    match after.iter_mut().find(|e| e.frame == frame) {
+        Some(event) => event.value = value,
+        None => {
+            let contact = before
+                .iter()
+                .rev()
+                .find(|e| e.frame <= frame)
+                .or(before.first())
+                .map_or(0, |e| e.contact);
+            let at = after.partition_point(|e| e.frame < frame);
+            after.insert(
+                at,
+                InputEvent {
+                    frame,
+                    id,
+                    kind,
+                    state: ButtonState::Released,
+                    value,
+                    contact,
+                },
+            );
+        }
+    }
+    EventChange { id, before, after }
+}
+
+/// Remove all events of an input on a frame.
+pub fn remove_events_at(log: &InputLog, id: u32, frame: u64) -> EventChange {
+    let before = input_events(log, id);
+    let after = before
+        .iter()
+        .filter(|e| e.frame != frame)
+        .cloned()
+        .collect();
+    EventChange { id, before, after }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_util::{axis, button, log_with};
+
+    fn states(events: &[InputEvent]) -> Vec<(u64, ButtonState)> {
+        events.iter().map(|e| (e.frame, e.state)).collect()
+    }
+
+    #[test]
+    fn test_down_ranges() {
+        let events = vec![
+            button(2, 0, ButtonState::Pressed),
+            button(5, 0, ButtonState::Released),
+            // Repeated press without a release
+            button(8, 0, ButtonState::Pressed),
+            button(10, 0, ButtonState::Pressed),
+            button(12, 0, ButtonState::Released),
+            // Held without a press, never released
+            button(15, 0, ButtonState::Held),
+        ];
+        assert_eq!(down_ranges(&events, 20), vec![2..5, 8..10, 10..12, 15..20]);
+    }
+
+    #[test]
+    fn test_toggle_extends_and_splits_sparse_press() {
+        let log = log_with(
+            20,
+            vec![
+                button(2, 0, ButtonState::Pressed),
+                button(5, 0, ButtonState::Released),
+            ],
+        );
+
+        // Clicking the frame after the hold extends it
+        let change = toggle_button(&log, 0, InputKind::Button, 0, 5);
+        assert_eq!(
+            states(&change.after),
+            vec![(2, ButtonState::Pressed), (6, ButtonState::Released)]
+        );
+
+        // Clicking inside the hold splits it
+        let change = toggle_button(&log, 0, InputKind::Button, 0, 3);
+        assert_eq!(
+            states(&change.after),
+            vec![
+                (2, ButtonState::Pressed),
+                (3, ButtonState::Released),
+                (4, ButtonState::Pressed),
+                (5, ButtonState::Released),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toggle_keeps_dense_log_dense() {
+        let log = log_with(
+            20,
+            vec![
+                button(2, 0, ButtonState::Pressed),
+                button(3, 0, ButtonState::Held),
+                button(4, 0, ButtonState::Released),
+            ],
+        );
+        let change = toggle_button(&log, 0, InputKind::Button, 0, 4);
+        assert_eq!(
+            states(&change.after),
+            vec![
+                (2, ButtonState::Pressed),
+                (3, ButtonState::Held),
+                (4, ButtonState::Held),
+                (5, ButtonState::Released),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_press_and_apply_inverse() {
+        let mut log = log_with(
+            20,
+            vec![
+                button(2, 0, ButtonState::Pressed),
+                button(4, 0, ButtonState::Released),
+                button(9, 0, ButtonState::Pressed),
+                button(11, 0, ButtonState::Released),
+            ],
+        );
+        let original = log.events.clone();
+
+        let change = remove_press(&log, 0, InputKind::Button, 0, 3);
+        assert_eq!(
+            states(&change.after),
+            vec![(9, ButtonState::Pressed), (11, ButtonState::Released)]
+        );
+        change.apply(&mut log);
+        assert_eq!(log.events.len(), 2);
+        change.inverse().apply(&mut log);
+        assert_eq!(log.events, original);
+
+        // Nothing to remove on an up frame
+        assert!(remove_press(&log, 0, InputKind::Button, 0, 6).is_noop());
+    }
+
+    #[test]
+    fn test_press_on_new_input_and_at_log_end() {
+        let log = log_with(20, Vec::new());
+        let change = set_button_down(&log, 7, InputKind::Key, 0, 18..20, true);
+        // Held until the end of the log: no Released
+        assert_eq!(change.after.len(), 1);
+        assert_eq!(change.after[0].id, 7);
+        assert_eq!(change.after[0].kind, InputKind::Key);
+        assert_eq!(change.after[0].state, ButtonState::Pressed);
+    }
+
+    #[test]
+    fn test_touch_keeps_contact_and_position() {
+        let mut pressed = button(2, 0, ButtonState::Pressed);
+        pressed.kind = InputKind::Touch;
+        pressed.value = [100.0, 50.0];
+        pressed.contact = 4;
+        let mut released = pressed.clone();
+        released.frame = 4;
+        released.state = ButtonState::Released;
+        let log = log_with(20, vec![pressed, released]);
+
+        let change = toggle_button(&log, 0, InputKind::Touch, 4, 4);
+        let released = change.after.last().unwrap();
+        assert_eq!(released.frame, 5);
+        assert_eq!(released.contact, 4);
+        assert_eq!(released.value, [100.0, 50.0]);
+    }
+
+    #[test]
+    fn test_edits_only_change_one_contact() {
+        let touch = |frame, contact, state, x| InputEvent {
+            kind: InputKind::Touch,
+            contact,
+            value: [x, 0.0],
+            ..button(frame, 0, state)
+        };
+        // Contact 1 lands while contact 0 is down and is lifted after it
+        let mut log = log_with(
+            20,
+            vec![
+                touch(2, 0, ButtonState::Pressed, 10.0),
+                touch(4, 1, ButtonState::Pressed, 90.0),
+                touch(6, 0, ButtonState::Released, 10.0),
+                touch(9, 1, ButtonState::Released, 90.0),
+            ],
+        );
+        let contact_states =
+            |events: &[InputEvent], contact| states(&contact_events(events, contact));
+
+        // Frame 5 is covered by both; the lowest contact is picked
+        assert_eq!(contact_at(&log, 0, 5), 0);
+        assert_eq!(contact_at(&log, 0, 7), 1);
+        assert!(is_down_at(&log, 0, 1, 8));
+        assert!(!is_down_at(&log, 0, 0, 8));
+
+        // Extending contact 0 leaves contact 1 alone
+        let change = toggle_button(&log, 0, InputKind::Touch, 0, 6);
+        assert_eq!(
+            contact_states(&change.after, 0),
+            vec![(2, ButtonState::Pressed), (7, ButtonState::Released)]
+        );
+        assert_eq!(
+            contact_states(&change.after, 1),
+            vec![(4, ButtonState::Pressed), (9, ButtonState::Released)]
+        );
+        let moved = contact_events(&change.after, 1);
+        assert!(moved.iter().all(|e| e.value == [90.0, 0.0]));
+
+        // Removing contact 1's press keeps contact 0's overlapping one
+        let change = remove_press(&log, 0, InputKind::Touch, 1, 5);
+        assert_eq!(
+            states(&change.after),
+            vec![(2, ButtonState::Pressed), (6, ButtonState::Released)]
+        );
+        assert!(change.after.iter().all(|e| e.contact == 0));
+        assert!(remove_press(&log, 0, InputKind::Touch, 1, 2).is_noop());
+
+        change.apply(&mut log);
+        change.inverse().apply(&mut log);
+        assert_eq!(log.events.len(), 4);
+    }
+
+    #[test]
+    fn test_set_and_remove_value() {
+        let mut log = log_with(20, vec![axis(0, 3, 0.0), axis(10, 3, 1.0)]);
+
+        let change = set_value(&log, 3, InputKind::Axis1D, 10, [0.5, 0.0]);
+        assert_eq!(change.after.len(), 2);
+        assert_eq!(change.after[1].value, [0.5, 0.0]);
+
+        let change = set_value(&log, 3, InputKind::Axis1D, 5, [-0.25, 0.0]);
+        change.apply(&mut log);
+        assert_eq!(value_at(&log, 3, 7), Some([-0.25, 0.0]));
+        assert_eq!(
+            log.events.iter().map(|e| e.frame).collect::<Vec<_>>(),
+            [0, 5, 10]
+        );
+
+        let change = remove_events_at(&log, 3, 5);
+        change.apply(&mut log);
+        assert_eq!(value_at(&log, 3, 7), Some([0.0, 0.0]));
+
+        // A new sample continues the contact of the one before it
+        let mut pointer = axis(0, 3, 0.0);
+        pointer.kind = InputKind::PointerPosition;
+        pointer.contact = 2;
+        let log = log_with(20, vec![pointer]);
+        let change = set_value(&log, 3, InputKind::PointerPosition, 4, [1.0, 1.0]);
+        assert_eq!(change.after[1].contact, 2);
+    }
+}
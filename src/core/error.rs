@@ -44,6 +44,19 @@ pub enum AppError {
         column: Option<usize>,
     },
 
+    /// Binary file ends before all of its events
+    #[error("Truncated file")]
+    TruncatedFile {
+        /// Path to the truncated file
+        path: Option<PathBuf>,
+        /// Number of events declared in the header
+        declared: u64,
+        /// Number of complete events before the cut
+        recoverable: usize,
+        /// Number of bytes missing from the end of the file, if known
+        missing_bytes: Option<u64>,
+    },
+
     /// File version is not supported
     #[error("Unsupported version")]
     UnsupportedVersion {
@@ -100,6 +113,7 @@ impl AppError {
             Self::FileNotFound { .. }
                 | Self::FileReadError { .. }
                 | Self::InvalidFormat { .. }
+                | Self::TruncatedFile { .. }
                 | Self::UnsupportedVersion { .. }
                 | Self::UnsupportedFileType { .. }
                 | Self::IoError { .. }
@@ -121,12 +135,23 @@ impl AppError {
         )
     }
 
+    /// Number of events that can still be loaded from a damaged file, if any.
+    ///
+    /// The error dialog offers to load these events anyway.
+    pub fn recoverable_events(&self) -> Option<usize> {
+        match self {
+            Self::TruncatedFile { recoverable, .. } if *recoverable > 0 => Some(*recoverable),
+            _ => None,
+        }
+    }
+
     /// Get the file path associated with this error, if any.
     pub fn file_path(&self) -> Option<&PathBuf> {
         match self {
             Self::FileNotFound { path } => Some(path),
             Self::FileReadError { path, .. } => Some(path),
             Self::InvalidFormat { path, .. } => path.as_ref(),
+            Self::TruncatedFile { path, .. } => path.as_ref(),
             Self::UnsupportedVersion { path, .. } => path.as_ref(),
             Self::UnsupportedFileType { path, .. } => Some(path),
             Self::IoError { path, .. } => path.as_ref(),
@@ -140,6 +165,7 @@ impl AppError {
             Self::FileNotFound { .. } => "File Not Found",
             Self::FileReadError { .. } => "Cannot Read File",
             Self::InvalidFormat { .. } => "Invalid File Format",
+            Self::TruncatedFile { .. } => "Truncated File",
             Self::UnsupportedVersion { .. } => "Unsupported Version",
             Self::UnsupportedFileType { .. } => "Unsupported File Type",
             Self::IoError { .. } => "I/O Error",
@@ -168,6 +194,16 @@ impl AppError {
                 )
             }
             Self::InvalidFormat { message, .. } => message.clone(),
+            Self::TruncatedFile {
+                declared,
+                recoverable,
+                ..
+            } => {
+                format!(
+                    "The file ends early: it declares {} events, but only {} complete events are present.",
+                    declared, recoverable
+                )
+            }
             Self::UnsupportedVersion {
                 found, supported, ..
             } => {
@@ -223,6 +259,21 @@ impl AppError {
                 }
                 lines.push(format!("Details: {}", message));
             }
+            Self::TruncatedFile {
+                path,
+                declared,
+                recoverable,
+                missing_bytes,
+            } => {
+                if let Some(p) = path {
+                    lines.push(format!("File: {}", p.display()));
+                }
+                lines.push(format!("Declared Events: {}", declared));
+                lines.push(format!("Recoverable Events: {}", recoverable));
+                if let Some(bytes) = missing_bytes {
+                    lines.push(format!("Missing Bytes: {}", bytes));
+                }
+            }
             Self::UnsupportedVersion {
                 path,
                 found,
@@ -352,6 +403,79 @@ pub fn from_parse_error(
             line: None,
             column: None,
         },
+        ParseError::InvalidLine {
+            line,
+            column,
+            message,
+        } => AppError::InvalidFormat {
+            path,
+            message: message.clone(),
+            line: Some(*line),
+            column: *column,
+        },
+        ParseError::Truncated {
+            header_count,
+            recoverable,
+            missing_bytes,
+        } => AppError::TruncatedFile {
+            path,
+            declared: *header_count,
+            recoverable: *recoverable,
+            missing_bytes: *missing_bytes,
+        },
+        ParseError::ChecksumMismatch { stored, computed } => AppError::InvalidFormat {
+            path,
+            message: format!(
+                "Checksum mismatch: the file is corrupted (stored {:08X}, computed {:08X})",
+                stored, computed
+            ),
+            line: None,
+            column: None,
+        },
+        ParseError::InvalidTimestamps { frame } => AppError::InvalidFormat {
+            path,
+            message: format!(
+                "Invalid frame timestamps: frame {} starts before the previous frame",
+                frame
+            ),
+            line: None,
+            column: None,
+        },
+        ParseError::InvalidAnnotation { index, reason } => AppError::InvalidFormat {
+            path,
+            message: format!("Invalid annotation at index {}: {}", index, reason),
+            line: None,
+            column: None,
+        },
+        ParseError::UnsupportedFlags { flags } => AppError::InvalidFormat {
+            path,
+            message: format!(
+                "Unsupported header flags {:#x}: the file was written by a newer version",
+                flags
+            ),
+            line: None,
+            column: None,
+        },
+        ParseError::InvalidEncoding(e) => AppError::InvalidFormat {
+            path,
+            message: format!("File is not valid UTF-8: {}", e),
+            line: None,
+            column: None,
+        },
+    }
+}
+
+/// Create an AppError from a warning reported by the lenient JSON parser.
+pub fn from_parse_warning(
+    path: Option<PathBuf>,
+    warning: &crate::core::parser::ParseWarning,
+) -> AppError {
+    AppError::InvalidFormat {
+        path,
+        message: warning.message.clone(),
+        // Binary formats have no lines; their warnings use 0
+        line: (warning.line > 0).then_some(warning.line),
+        column: (warning.column > 0).then_some(warning.column),
     }
 }
 
@@ -438,4 +562,20 @@ mod tests {
             _ => panic!("Expected FileReadError error"),
         }
     }
+
+    #[test]
+    fn test_truncated_file_error() {
+        let parse_error = crate::core::parser::ParseError::Truncated {
+            header_count: 10,
+            recoverable: 7,
+            missing_bytes: Some(60),
+        };
+        let error = from_parse_error(Some(PathBuf::from("/test/file.ilb")), &parse_error);
+
+        assert_eq!(error.recoverable_events(), Some(7));
+        assert_eq!(error.dialog_title(), "Truncated File");
+        assert!(error.brief_description().contains("10 events"));
+        assert!(error.detailed_info().contains("Missing Bytes: 60"));
+        assert!(!error.supports_retry());
+    }
 }
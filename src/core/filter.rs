@@ -3,12 +3,12 @@
 //! This module defines the filter state used to control which inputs
 //! are displayed on the timeline.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use super::log::{InputKind, InputLog, InputMapping};
 
 /// Filter state for controlling input visibility on the timeline.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FilterState {
     /// Set of visible input IDs. Empty means all inputs are visible.
     pub visible_ids: HashSet<u32>,
@@ -18,6 +18,22 @@ pub struct FilterState {
     pub show_axis1d: bool,
     /// Whether to show 2D axis inputs
     pub show_axis2d: bool,
+    /// Whether to show trigger inputs
+    pub show_trigger: bool,
+    /// Whether to show mouse delta inputs
+    pub show_mouse_delta: bool,
+    /// Whether to show pointer position inputs
+    pub show_pointer: bool,
+    /// Whether to show touch inputs
+    pub show_touch: bool,
+    /// Whether to show keyboard keys
+    pub show_key: bool,
+    /// Whether to show the game event and telemetry tracks
+    pub show_annotations: bool,
+    /// Players whose inputs are hidden, independent of `visible_ids`
+    pub hidden_players: HashSet<u32>,
+    /// Player of each input that belongs to one, taken from the log's mappings
+    input_players: HashMap<u32, u32>,
     /// Whether the filter has been initialized with input IDs from a log
     initialized: bool,
 }
@@ -29,6 +45,14 @@ impl Default for FilterState {
             show_button: true,
             show_axis1d: true,
             show_axis2d: true,
+            show_trigger: true,
+            show_mouse_delta: true,
+            show_pointer: true,
+            show_touch: true,
+            show_key: true,
+            show_annotations: true,
+            hidden_players: HashSet::new(),
+            input_players: HashMap::new(),
             initialized: false,
         }
     }
@@ -49,28 +73,93 @@ impl FilterState {
         for mapping in log.get_effective_mappings() {
             self.visible_ids.insert(mapping.id);
         }
+        self.hidden_players.clear();
+        self.update_players(log);
         self.initialized = true;
     }
 
+    /// Make inputs that appeared after initialization visible.
+    ///
+    /// Used when events are appended to a followed log; inputs the user has
+    /// already hidden stay hidden.
+    pub fn show_new_inputs(&mut self, ids: &[u32]) {
+        if self.initialized && !self.visible_ids.is_empty() {
+            self.visible_ids.extend(ids.iter().copied());
+        }
+    }
+
+    /// Refresh which player each input belongs to after the mappings changed.
+    pub fn update_players(&mut self, log: &InputLog) {
+        self.input_players = log
+            .mappings
+            .iter()
+            .filter_map(|m| m.player.map(|player| (m.id, player)))
+            .collect();
+    }
+
+    /// Check whether a player's inputs are shown.
+    pub fn is_player_visible(&self, player: u32) -> bool {
+        !self.hidden_players.contains(&player)
+    }
+
+    /// Show or hide all inputs of a player.
+    pub fn set_player_visible(&mut self, player: u32, visible: bool) {
+        if visible {
+            self.hidden_players.remove(&player);
+        } else {
+            self.hidden_players.insert(player);
+        }
+    }
+
     /// Reset the filter to show all inputs from the log.
     #[allow(dead_code)] // Will be used for filter reset functionality
     pub fn reset(&mut self, log: &InputLog) {
         self.initialize_from_log(log);
-        self.show_button = true;
-        self.show_axis1d = true;
-        self.show_axis2d = true;
+        for kind in InputKind::ALL {
+            *self.type_flag_mut(kind) = true;
+        }
+        self.show_annotations = true;
     }
 
-    /// Check if an input ID is visible based on the current filter settings.
-    pub fn is_visible(&self, id: u32, kind: InputKind) -> bool {
-        // Check type filter first
-        let type_visible = match kind {
+    /// Whether inputs of a type are shown.
+    pub fn is_type_visible(&self, kind: InputKind) -> bool {
+        match kind {
             InputKind::Button => self.show_button,
             InputKind::Axis1D => self.show_axis1d,
             InputKind::Axis2D => self.show_axis2d,
-        };
+            InputKind::Trigger => self.show_trigger,
+            InputKind::MouseDelta => self.show_mouse_delta,
+            InputKind::PointerPosition => self.show_pointer,
+            InputKind::Touch => self.show_touch,
+            InputKind::Key => self.show_key,
+        }
+    }
+
+    /// The flag controlling the visibility of a type.
+    pub fn type_flag_mut(&mut self, kind: InputKind) -> &mut bool {
+        match kind {
+            InputKind::Button => &mut self.show_button,
+            InputKind::Axis1D => &mut self.show_axis1d,
+            InputKind::Axis2D => &mut self.show_axis2d,
+            InputKind::Trigger => &mut self.show_trigger,
+            InputKind::MouseDelta => &mut self.show_mouse_delta,
+            InputKind::PointerPosition => &mut self.show_pointer,
+            InputKind::Touch => &mut self.show_touch,
+            InputKind::Key => &mut self.show_key,
+        }
+    }
+
+    /// Check if an input ID is visible based on the current filter settings.
+    pub fn is_visible(&self, id: u32, kind: InputKind) -> bool {
+        // Check type filter first
+        if !self.is_type_visible(kind) {
+            return false;
+        }
 
-        if !type_visible {
+        // Hidden players hide all of their inputs
+        if let Some(player) = self.input_players.get(&id)
+            && self.hidden_players.contains(player)
+        {
             return false;
         }
 
@@ -153,27 +242,16 @@ impl FilterState {
 
     /// Count how many input types are currently enabled.
     pub fn enabled_type_count(&self) -> u32 {
-        let mut count = 0;
-        if self.show_button {
-            count += 1;
-        }
-        if self.show_axis1d {
-            count += 1;
-        }
-        if self.show_axis2d {
-            count += 1;
-        }
-        count
+        InputKind::ALL
+            .iter()
+            .filter(|&&kind| self.is_type_visible(kind))
+            .count() as u32
     }
 
     /// Check if a specific input type can be disabled.
     /// Returns false if this is the last enabled type (to prevent empty view).
     pub fn can_disable_type(&self, kind: InputKind) -> bool {
-        let is_currently_enabled = match kind {
-            InputKind::Button => self.show_button,
-            InputKind::Axis1D => self.show_axis1d,
-            InputKind::Axis2D => self.show_axis2d,
-        };
+        let is_currently_enabled = self.is_type_visible(kind);
 
         // Can always enable, but can only disable if at least one other type remains
         !is_currently_enabled || self.enabled_type_count() > 1
@@ -185,11 +263,7 @@ impl FilterState {
     pub fn set_type_visible(&mut self, kind: InputKind, visible: bool) -> bool {
         // Always allow enabling
         if visible {
-            match kind {
-                InputKind::Button => self.show_button = true,
-                InputKind::Axis1D => self.show_axis1d = true,
-                InputKind::Axis2D => self.show_axis2d = true,
-            }
+            *self.type_flag_mut(kind) = true;
             return true;
         }
 
@@ -198,11 +272,7 @@ impl FilterState {
             return false;
         }
 
-        match kind {
-            InputKind::Button => self.show_button = false,
-            InputKind::Axis1D => self.show_axis1d = false,
-            InputKind::Axis2D => self.show_axis2d = false,
-        }
+        *self.type_flag_mut(kind) = false;
         true
     }
 }
@@ -220,16 +290,25 @@ mod tests {
                     id: 0,
                     name: "A Button".to_string(),
                     color: None,
+                    player: None,
+                    device: None,
+                    group: None,
                 },
                 InputMapping {
                     id: 1,
                     name: "B Button".to_string(),
                     color: None,
+                    player: None,
+                    device: None,
+                    group: None,
                 },
                 InputMapping {
                     id: 10,
                     name: "Left Stick X".to_string(),
                     color: None,
+                    player: None,
+                    device: None,
+                    group: None,
                 },
             ],
             events: vec![
@@ -239,6 +318,7 @@ mod tests {
                     kind: InputKind::Button,
                     state: crate::core::log::ButtonState::Pressed,
                     value: [1.0, 0.0],
+                    contact: 0,
                 },
                 InputEvent {
                     frame: 0,
@@ -246,11 +326,23 @@ mod tests {
                     kind: InputKind::Axis1D,
                     state: crate::core::log::ButtonState::Released,
                     value: [0.5, 0.0],
+                    contact: 0,
                 },
             ],
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
         }
     }
 
+    /// Filter with only the Button, Axis1D and Axis2D types enabled.
+    fn legacy_types_filter() -> FilterState {
+        let mut filter = FilterState::new();
+        for kind in &InputKind::ALL[3..] {
+            *filter.type_flag_mut(*kind) = false;
+        }
+        filter
+    }
+
     #[test]
     fn test_default_filter_state() {
         let filter = FilterState::default();
@@ -344,9 +436,10 @@ mod tests {
 
     #[test]
     fn test_enabled_type_count() {
-        let mut filter = FilterState::new();
-
         // All enabled by default
+        assert_eq!(FilterState::new().enabled_type_count(), 8);
+
+        let mut filter = legacy_types_filter();
         assert_eq!(filter.enabled_type_count(), 3);
 
         // Disable one
@@ -364,7 +457,7 @@ mod tests {
 
     #[test]
     fn test_can_disable_type() {
-        let mut filter = FilterState::new();
+        let mut filter = legacy_types_filter();
 
         // All enabled - can disable any
         assert!(filter.can_disable_type(InputKind::Button));
@@ -383,7 +476,7 @@ mod tests {
 
     #[test]
     fn test_set_type_visible() {
-        let mut filter = FilterState::new();
+        let mut filter = legacy_types_filter();
 
         // Disable Button type
         assert!(filter.set_type_visible(InputKind::Button, false));
@@ -401,4 +494,32 @@ mod tests {
         assert!(filter.set_type_visible(InputKind::Button, true));
         assert!(filter.show_button);
     }
+
+    #[test]
+    fn test_player_visibility() {
+        let mut log = create_test_log();
+        log.mappings[0].player = Some(0);
+        log.mappings[1].player = Some(1);
+        let mut filter = FilterState::new();
+        filter.initialize_from_log(&log);
+
+        filter.set_player_visible(1, false);
+        assert!(!filter.is_player_visible(1));
+        assert!(filter.is_visible(0, InputKind::Button));
+        assert!(!filter.is_visible(1, InputKind::Button));
+        // Inputs without a player are unaffected
+        assert!(filter.is_visible(10, InputKind::Axis1D));
+
+        // Showing the player again restores the per-input choice
+        filter.set_id_visible(1, false);
+        filter.set_player_visible(1, true);
+        assert!(!filter.is_visible(1, InputKind::Button));
+        filter.set_id_visible(1, true);
+        assert!(filter.is_visible(1, InputKind::Button));
+
+        // Loading a log shows all players again
+        filter.set_player_visible(0, false);
+        filter.initialize_from_log(&log);
+        assert!(filter.hidden_players.is_empty());
+    }
 }
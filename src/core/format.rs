@@ -0,0 +1,664 @@
+//! Log file formats and the registry used to open and save them.
+//!
+//! Every supported file format implements `LogFormat`. The `FormatRegistry`
+//! chooses a format by looking at the file content (the `ILOG` magic of
+//! binary logs, the structure of JSON logs) and only falls back to the file
+//! extension when no format recognizes the content, so renamed files still
+//! open.
+//!
+//! Teams can add their engine's native replay format by implementing
+//! `LogFormat` and installing a registry at startup, before anything loads a
+//! file:
+//!
+//! ```ignore
+//! let mut registry = FormatRegistry::default();
+//! registry.register(Box::new(MyReplayFormat));
+//! format::install(registry);
+//! ```
+
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use super::error::{self, AppError};
+use super::log::InputLog;
+use super::parser::{self, BINARY_MAGIC, ParseError, ParseWarning};
+use super::writer;
+
+/// Number of bytes read from a file to detect its format.
+pub const SNIFF_LEN: usize = 64 * 1024;
+
+/// Options for writing a log file.
+///
+/// Formats ignore the options that don't apply to them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Write the compressed variant of the format, if it has one
+    pub compress: bool,
+    /// End binary files with a CRC-32 trailer
+    pub checksum: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            compress: false,
+            checksum: true,
+        }
+    }
+}
+
+/// A file format that input logs can be read from and, optionally, written to.
+pub trait LogFormat: Send + Sync {
+    /// Human-readable name shown in file dialogs.
+    fn name(&self) -> &'static str;
+
+    /// File extensions (without the dot) used by this format.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Check whether `head` looks like the start of a file in this format.
+    ///
+    /// `head` holds at most `SNIFF_LEN` bytes and may end in the middle of
+    /// the file, so implementations should only inspect its beginning.
+    fn detect(&self, head: &[u8]) -> bool;
+
+    /// Parse a complete file.
+    fn parse(&self, data: &[u8]) -> Result<InputLog, ParseError>;
+
+    /// Parse a complete file, skipping or repairing bad entries where the
+    /// format allows it.
+    ///
+    /// The default implementation is strict and never reports warnings.
+    fn parse_lenient(&self, data: &[u8]) -> Result<(InputLog, Vec<ParseWarning>), ParseError> {
+        self.parse(data).map(|log| (log, Vec::new()))
+    }
+
+    /// Whether `write` is supported; read-only formats are not offered in
+    /// save dialogs.
+    fn can_write(&self) -> bool {
+        false
+    }
+
+    /// Serialize a log into this format, or `None` for read-only formats.
+    fn write(&self, _log: &InputLog, _options: &WriteOptions) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Whether `write_compressed` is supported.
+    fn can_compress(&self) -> bool {
+        false
+    }
+
+    /// Serialize a log into the compressed variant of this format, or `None`
+    /// if the format has none.
+    fn write_compressed(&self, _log: &InputLog, _options: &WriteOptions) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+// ============================================================================
+// Built-in formats
+// ============================================================================
+
+/// Longest valid UTF-8 prefix of a sniffed buffer.
+///
+/// The buffer may have been cut in the middle of a multi-byte character, so
+/// invalid trailing bytes are ignored rather than rejecting the whole file.
+fn text_prefix(head: &[u8]) -> &str {
+    match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(e) => std::str::from_utf8(&head[..e.valid_up_to()]).unwrap_or_default(),
+    }
+}
+
+/// Pretty-printed or single-line JSON document (.ilj).
+struct JsonFormat;
+
+impl LogFormat for JsonFormat {
+    fn name(&self) -> &'static str {
+        "Input Log JSON"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ilj"]
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        let text = text_prefix(head);
+        text.trim_start().starts_with('{') && parser::json_events_start(text).is_some()
+    }
+
+    fn parse(&self, data: &[u8]) -> Result<InputLog, ParseError> {
+        parser::parse_json(std::str::from_utf8(data)?)
+    }
+
+    fn parse_lenient(&self, data: &[u8]) -> Result<(InputLog, Vec<ParseWarning>), ParseError> {
+        let parsed = parser::parse_json_lenient(std::str::from_utf8(data)?)?;
+        Ok((parsed.log, parsed.warnings))
+    }
+
+    fn can_write(&self) -> bool {
+        true
+    }
+
+    fn write(&self, log: &InputLog, _options: &WriteOptions) -> Option<Vec<u8>> {
+        Some(writer::write_json(log).into_bytes())
+    }
+}
+
+/// Newline-delimited JSON with a header line (.iljl).
+struct JsonLinesFormat;
+
+impl LogFormat for JsonLinesFormat {
+    fn name(&self) -> &'static str {
+        "Input Log JSON Lines"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["iljl"]
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        // The header line is a complete object with metadata but no events
+        let text = text_prefix(head);
+        let first_line = text.trim_start().lines().next().unwrap_or_default();
+        match serde_json::from_str::<serde_json::Value>(first_line) {
+            Ok(serde_json::Value::Object(header)) => {
+                header.contains_key("metadata") && !header.contains_key("events")
+            }
+            _ => false,
+        }
+    }
+
+    fn parse(&self, data: &[u8]) -> Result<InputLog, ParseError> {
+        parser::parse_json_lines(std::str::from_utf8(data)?)
+    }
+
+    fn can_write(&self) -> bool {
+        true
+    }
+
+    fn write(&self, log: &InputLog, _options: &WriteOptions) -> Option<Vec<u8>> {
+        Some(writer::write_json_lines(log).into_bytes())
+    }
+}
+
+/// Fixed-size binary records (.ilb).
+struct BinaryFormat;
+
+impl LogFormat for BinaryFormat {
+    fn name(&self) -> &'static str {
+        "Input Log Binary"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ilb"]
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        head.starts_with(&BINARY_MAGIC)
+    }
+
+    fn parse(&self, data: &[u8]) -> Result<InputLog, ParseError> {
+        parser::parse_binary(data)
+    }
+
+    fn parse_lenient(&self, data: &[u8]) -> Result<(InputLog, Vec<ParseWarning>), ParseError> {
+        let parsed = parser::parse_binary_lenient(data)?;
+        Ok((parsed.log, parsed.warnings))
+    }
+
+    fn can_write(&self) -> bool {
+        true
+    }
+
+    fn write(&self, log: &InputLog, options: &WriteOptions) -> Option<Vec<u8>> {
+        Some(writer::write_binary(log, options.checksum))
+    }
+
+    fn can_compress(&self) -> bool {
+        true
+    }
+
+    fn write_compressed(&self, log: &InputLog, options: &WriteOptions) -> Option<Vec<u8>> {
+        Some(writer::write_binary_compressed(log, options.checksum))
+    }
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+/// Ordered collection of the formats the viewer can open and save.
+pub struct FormatRegistry {
+    formats: Vec<Box<dyn LogFormat>>,
+}
+
+impl Default for FormatRegistry {
+    /// Registry with the built-in `.ilj`, `.iljl` and `.ilb` formats.
+    fn default() -> Self {
+        Self {
+            formats: vec![
+                Box::new(JsonFormat),
+                Box::new(JsonLinesFormat),
+                Box::new(BinaryFormat),
+            ],
+        }
+    }
+}
+
+impl FormatRegistry {
+    /// Add a format. Formats registered later are tried first, so a custom
+    /// format can take over an extension or content the built-ins would
+    /// also accept.
+    #[allow(dead_code)] // Will be used by builds that add engine-specific formats
+    pub fn register(&mut self, format: Box<dyn LogFormat>) {
+        self.formats.insert(0, format);
+    }
+
+    /// All registered formats, in detection order.
+    pub fn formats(&self) -> impl Iterator<Item = &dyn LogFormat> {
+        self.formats.iter().map(|format| format.as_ref())
+    }
+
+    /// Formats that can be used to save a log.
+    pub fn writable_formats(&self) -> impl Iterator<Item = &dyn LogFormat> {
+        self.formats().filter(|format| format.can_write())
+    }
+
+    /// Every extension of every registered format, without duplicates.
+    pub fn extensions(&self) -> Vec<&'static str> {
+        let mut extensions: Vec<&'static str> = Vec::new();
+        for ext in self.formats().flat_map(|format| format.extensions()) {
+            if !extensions.contains(ext) {
+                extensions.push(ext);
+            }
+        }
+        extensions
+    }
+
+    /// Extensions formatted for display, e.g. ".ilj, .iljl, .ilb".
+    pub fn extension_list(&self) -> String {
+        self.extensions()
+            .iter()
+            .map(|ext| format!(".{}", ext))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Format registered for the extension of `path`, if any.
+    pub fn by_extension(&self, path: &Path) -> Option<&dyn LogFormat> {
+        self.index_by_extension(path)
+            .map(|index| self.formats[index].as_ref())
+    }
+
+    /// Position of the format registered for the extension of `path`.
+    ///
+    /// Formats are compared by position rather than by pointer, since the
+    /// built-in formats are zero-sized and share an address.
+    fn index_by_extension(&self, path: &Path) -> Option<usize> {
+        let ext = path.extension()?.to_str()?;
+        self.formats().position(|format| {
+            format
+                .extensions()
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(ext))
+        })
+    }
+
+    /// Choose the format of a file from its content, using the extension only
+    /// to break ties or when no format recognizes the content.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file, used for its extension
+    /// * `head` - The first bytes of the file (at most `SNIFF_LEN` are needed)
+    pub fn detect(&self, path: &Path, head: &[u8]) -> Option<&dyn LogFormat> {
+        let by_extension = self.index_by_extension(path);
+        let matches: Vec<usize> = (0..self.formats.len())
+            .filter(|&index| self.formats[index].detect(head))
+            .collect();
+
+        let index = match (matches.first(), by_extension) {
+            // Several formats accept the content: prefer the one the extension names
+            (Some(_), Some(ext_index)) if matches.contains(&ext_index) => ext_index,
+            (Some(&detected), _) => detected,
+            (None, by_extension) => by_extension?,
+        };
+        Some(self.formats[index].as_ref())
+    }
+
+    /// Check whether a file can probably be opened, without parsing it.
+    ///
+    /// Files with a registered extension are accepted as-is; other files are
+    /// accepted when their first bytes are recognized by a format.
+    pub fn can_open(&self, path: &Path) -> bool {
+        if self.by_extension(path).is_some() {
+            return true;
+        }
+        read_head(path).is_ok_and(|head| self.detect(path, &head).is_some())
+    }
+
+    /// Read and parse a log file in whichever format it is written in.
+    ///
+    /// # Returns
+    /// * `Ok(InputLog)` - Successfully parsed input log
+    /// * `Err(AppError)` - Unrecognized format, I/O failure or parse error
+    pub fn load(&self, path: &Path) -> Result<InputLog, AppError> {
+        let data = std::fs::read(path).map_err(|e| error::from_io_error(path.to_path_buf(), e))?;
+        let format = self
+            .detect(path, &data[..data.len().min(SNIFF_LEN)])
+            .ok_or_else(|| self.unsupported(path))?;
+        format
+            .parse(&data)
+            .map_err(|e| error::from_parse_error(Some(path.to_path_buf()), &e))
+    }
+
+    /// Read and parse a log file, recovering from bad entries where the
+    /// format supports it.
+    ///
+    /// # Returns
+    /// * `Ok((InputLog, warnings))` - The recovered log and one error per skipped or repaired entry
+    /// * `Err(AppError)` - Unrecognized format, I/O failure or unrecoverable parse error
+    pub fn load_lenient(&self, path: &Path) -> Result<(InputLog, Vec<AppError>), AppError> {
+        let data = std::fs::read(path).map_err(|e| error::from_io_error(path.to_path_buf(), e))?;
+        let format = self
+            .detect(path, &data[..data.len().min(SNIFF_LEN)])
+            .ok_or_else(|| self.unsupported(path))?;
+        let (log, warnings) = format
+            .parse_lenient(&data)
+            .map_err(|e| error::from_parse_error(Some(path.to_path_buf()), &e))?;
+        let warnings = warnings
+            .iter()
+            .map(|w| error::from_parse_warning(Some(path.to_path_buf()), w))
+            .collect();
+        Ok((log, warnings))
+    }
+
+    /// Write a log to a file, choosing the format from its extension.
+    ///
+    /// With `options.compress` set, formats that have a compressed variant
+    /// write that instead; other formats ignore it.
+    ///
+    /// # Returns
+    /// * `Ok(())` - The file was written
+    /// * `Err(AppError)` - No writable format for the extension, or I/O failure
+    pub fn save(
+        &self,
+        log: &InputLog,
+        path: &Path,
+        options: &WriteOptions,
+    ) -> Result<(), AppError> {
+        let data = self
+            .by_extension(path)
+            .and_then(|format| {
+                let compressed = if options.compress {
+                    format.write_compressed(log, options)
+                } else {
+                    None
+                };
+                compressed.or_else(|| format.write(log, options))
+            })
+            .ok_or_else(|| AppError::UnsupportedFileType {
+                path: path.to_path_buf(),
+                expected: self
+                    .writable_formats()
+                    .flat_map(|format| format.extensions())
+                    .map(|ext| format!(".{}", ext))
+                    .collect(),
+            })?;
+
+        std::fs::write(path, data).map_err(|e| error::from_io_error(path.to_path_buf(), e))
+    }
+
+    /// Error for a file that no registered format recognizes.
+    pub fn unsupported(&self, path: &Path) -> AppError {
+        AppError::UnsupportedFileType {
+            path: path.to_path_buf(),
+            expected: self
+                .extensions()
+                .iter()
+                .map(|ext| format!(".{}", ext))
+                .collect(),
+        }
+    }
+}
+
+/// Read up to `SNIFF_LEN` bytes from the start of a file.
+fn read_head(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    std::fs::File::open(path)?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)?;
+    Ok(head)
+}
+
+static REGISTRY: OnceLock<FormatRegistry> = OnceLock::new();
+
+/// Install the registry used by the application.
+///
+/// Must be called before the first file is opened; afterwards the registry
+/// is fixed and the rejected registry is returned.
+#[allow(dead_code)] // Will be used by builds that add engine-specific formats
+pub fn install(registry: FormatRegistry) -> Result<(), FormatRegistry> {
+    REGISTRY.set(registry)
+}
+
+/// The registry used by the application, with the built-in formats unless
+/// another one was installed.
+pub fn registry() -> &'static FormatRegistry {
+    REGISTRY.get_or_init(FormatRegistry::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log::ButtonState;
+    use crate::core::test_util::{button, log_with};
+    use std::path::PathBuf;
+
+    fn assets() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("assets")
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("format_test_{}_{}", std::process::id(), name))
+    }
+
+    fn create_test_log() -> InputLog {
+        log_with(4, vec![button(1, 0, ButtonState::Pressed)])
+    }
+
+    #[test]
+    fn test_load_by_extension() {
+        let registry = FormatRegistry::default();
+        let json = registry.load(&assets().join("sample.ilj")).unwrap();
+        let binary = registry.load(&assets().join("sample.ilb")).unwrap();
+        assert_eq!(json.metadata.frame_count, 120);
+        assert_eq!(binary.metadata.frame_count, 120);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let registry = FormatRegistry::default();
+        let path = temp_path("unknown.txt");
+        std::fs::write(&path, "frame,id\n1,0\n").unwrap();
+        let result = registry.load(&path);
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(result, Err(AppError::UnsupportedFileType { .. })));
+
+        let result = registry.load(Path::new("/nonexistent/path/log.ilj"));
+        assert!(matches!(result, Err(AppError::FileNotFound { .. })));
+    }
+
+    #[test]
+    fn test_detect_by_content() {
+        let registry = FormatRegistry::default();
+        let log = create_test_log();
+        let unknown = Path::new("replay.dat");
+
+        let binary = writer::write_binary(&log, true);
+        let json = writer::write_json(&log).into_bytes();
+        let json_lines = writer::write_json_lines(&log).into_bytes();
+        assert_eq!(
+            registry.detect(unknown, &binary).unwrap().name(),
+            "Input Log Binary"
+        );
+        assert_eq!(
+            registry.detect(unknown, &json).unwrap().name(),
+            "Input Log JSON"
+        );
+        assert_eq!(
+            registry.detect(unknown, &json_lines).unwrap().name(),
+            "Input Log JSON Lines"
+        );
+
+        // Content wins over a misleading extension
+        assert_eq!(
+            registry
+                .detect(Path::new("log.ilj"), &binary)
+                .unwrap()
+                .name(),
+            "Input Log Binary"
+        );
+        assert!(registry.detect(unknown, b"garbage").is_none());
+        assert!(registry.detect(Path::new("log.ilb"), b"garbage").is_some());
+    }
+
+    #[test]
+    fn test_load_renamed_file() {
+        let registry = FormatRegistry::default();
+        let path = temp_path("renamed.dat");
+        std::fs::copy(assets().join("sample.ilb"), &path).unwrap();
+        assert!(registry.can_open(&path));
+        let result = registry.load(&path);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result.unwrap().metadata.frame_count, 120);
+    }
+
+    #[test]
+    fn test_save_round_trip() {
+        let registry = FormatRegistry::default();
+        let log = create_test_log();
+        for ext in ["ilj", "iljl", "ilb"] {
+            let path = temp_path(&format!("save.{}", ext));
+            registry
+                .save(&log, &path, &WriteOptions::default())
+                .unwrap();
+            let loaded = registry.load(&path);
+            std::fs::remove_file(&path).ok();
+            assert_eq!(loaded.unwrap().events.len(), 1, "{}", ext);
+        }
+
+        let result = registry.save(&log, Path::new("log.txt"), &WriteOptions::default());
+        assert!(matches!(result, Err(AppError::UnsupportedFileType { .. })));
+    }
+
+    #[test]
+    fn test_save_compressed_only_on_request() {
+        let registry = FormatRegistry::default();
+        let log = create_test_log();
+        let path = temp_path("compress.ilb");
+
+        let compress = WriteOptions {
+            compress: true,
+            ..Default::default()
+        };
+
+        registry
+            .save(&log, &path, &WriteOptions::default())
+            .unwrap();
+        let plain = std::fs::read(&path).unwrap();
+        registry.save(&log, &path, &compress).unwrap();
+        let compressed = std::fs::read(&path).unwrap();
+        let loaded = registry.load(&path);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(plain, writer::write_binary(&log, true));
+        assert_eq!(compressed, writer::write_binary_compressed(&log, true));
+        assert_eq!(loaded.unwrap().events.len(), 1);
+
+        // JSON has no compressed variant, so the flag is ignored
+        let path = temp_path("compress.ilj");
+        registry.save(&log, &path, &compress).unwrap();
+        let loaded = registry.load(&path);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded.unwrap().events.len(), 1);
+        assert!(!registry.by_extension(&path).unwrap().can_compress());
+    }
+
+    #[test]
+    fn test_save_without_checksum() {
+        let registry = FormatRegistry::default();
+        let log = create_test_log();
+        let path = temp_path("no_checksum.ilb");
+
+        for compress in [false, true] {
+            let options = WriteOptions {
+                compress,
+                checksum: false,
+            };
+            registry.save(&log, &path, &options).unwrap();
+            let written = std::fs::read(&path).unwrap();
+            let loaded = registry.load(&path);
+
+            let expected = if compress {
+                writer::write_binary_compressed(&log, false)
+            } else {
+                writer::write_binary(&log, false)
+            };
+            assert_eq!(written, expected);
+            assert_eq!(loaded.unwrap().events, log.events);
+        }
+        std::fs::remove_file(&path).ok();
+    }
+
+    struct CustomFormat;
+
+    impl LogFormat for CustomFormat {
+        fn name(&self) -> &'static str {
+            "Engine Replay"
+        }
+
+        fn extensions(&self) -> &'static [&'static str] {
+            &["rpl"]
+        }
+
+        fn detect(&self, head: &[u8]) -> bool {
+            head.starts_with(b"RPL1")
+        }
+
+        fn parse(&self, data: &[u8]) -> Result<InputLog, ParseError> {
+            let mut log = create_test_log();
+            log.metadata.frame_count = data.len() as u64;
+            Ok(log)
+        }
+    }
+
+    #[test]
+    fn test_register_custom_format() {
+        let mut registry = FormatRegistry::default();
+        registry.register(Box::new(CustomFormat));
+
+        assert!(registry.extensions().contains(&"rpl"));
+        assert!(
+            registry
+                .writable_formats()
+                .all(|f| f.name() != "Engine Replay")
+        );
+        let detected = registry.detect(Path::new("capture.bin"), b"RPL1....");
+        assert_eq!(detected.unwrap().name(), "Engine Replay");
+
+        let path = temp_path("capture.rpl");
+        std::fs::write(&path, b"RPL1 data").unwrap();
+        let loaded = registry.load(&path);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded.unwrap().metadata.frame_count, 9);
+
+        let result = registry.save(
+            &create_test_log(),
+            Path::new("out.rpl"),
+            &WriteOptions::default(),
+        );
+        assert!(matches!(result, Err(AppError::UnsupportedFileType { .. })));
+    }
+}
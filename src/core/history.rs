@@ -0,0 +1,350 @@
+//! Undo and redo of user edits.
+//!
+//! Every change the user makes to bookmarks, the input filter, the frame
+//! selection or the events of the log is recorded as a `Command` holding the
+//! state before and after the change. Undoing a command applies its inverse,
+//! so the application only needs to know how to apply a command forwards.
+
+use super::edit::EventChange;
+use super::filter::FilterState;
+use super::log::Bookmark;
+
+/// Default number of commands kept in the undo history.
+pub const DEFAULT_HISTORY_LIMIT: usize = 200;
+
+/// A reversible change made by the user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// A bookmark was added
+    AddBookmark(Bookmark),
+    /// A bookmark was removed
+    RemoveBookmark(Bookmark),
+    /// The label of the bookmark at `frame` was changed
+    RenameBookmark {
+        frame: u64,
+        before: Option<String>,
+        after: Option<String>,
+    },
+    /// All bookmarks were replaced at once, e.g. by "Clear All"
+    ReplaceBookmarks {
+        before: Vec<Bookmark>,
+        after: Vec<Bookmark>,
+    },
+    /// The input filter was changed
+    ChangeFilter {
+        before: Box<FilterState>,
+        after: Box<FilterState>,
+    },
+    /// The frame selection was changed, as (start, end) frames
+    ChangeSelection {
+        before: Option<(u64, u64)>,
+        after: Option<(u64, u64)>,
+    },
+    /// The events of one input were edited
+    EditEvents(EventChange),
+    /// The events of several inputs were edited at once, e.g. by normalization
+    EditLog {
+        label: String,
+        changes: Vec<EventChange>,
+    },
+}
+
+impl Command {
+    /// The command that undoes this one.
+    pub fn inverse(&self) -> Command {
+        match self {
+            Command::AddBookmark(bookmark) => Command::RemoveBookmark(bookmark.clone()),
+            Command::RemoveBookmark(bookmark) => Command::AddBookmark(bookmark.clone()),
+            Command::RenameBookmark {
+                frame,
+                before,
+                after,
+            } => Command::RenameBookmark {
+                frame: *frame,
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Command::ReplaceBookmarks { before, after } => Command::ReplaceBookmarks {
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Command::ChangeFilter { before, after } => Command::ChangeFilter {
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Command::ChangeSelection { before, after } => Command::ChangeSelection {
+                before: *after,
+                after: *before,
+            },
+            Command::EditEvents(change) => Command::EditEvents(change.inverse()),
+            Command::EditLog { label, changes } => Command::EditLog {
+                label: label.clone(),
+                changes: changes.iter().rev().map(EventChange::inverse).collect(),
+            },
+        }
+    }
+
+    /// Short description of the command for the history panel.
+    pub fn describe(&self) -> String {
+        match self {
+            Command::AddBookmark(bookmark) => format!("Add bookmark at F{}", bookmark.frame),
+            Command::RemoveBookmark(bookmark) => {
+                format!("Remove bookmark at F{}", bookmark.frame)
+            }
+            Command::RenameBookmark { frame, after, .. } => match after {
+                Some(label) => format!("Label bookmark at F{} \"{}\"", frame, label),
+                None => format!("Clear bookmark label at F{}", frame),
+            },
+            Command::ReplaceBookmarks { after, .. } if after.is_empty() => {
+                "Clear all bookmarks".to_string()
+            }
+            Command::ReplaceBookmarks { after, .. } => {
+                format!("Replace bookmarks ({})", after.len())
+            }
+            Command::ChangeFilter { .. } => "Change input filter".to_string(),
+            Command::ChangeSelection { after, .. } => match after {
+                Some((start, end)) => format!("Select F{}-F{}", start, end),
+                None => "Clear selection".to_string(),
+            },
+            Command::EditEvents(change) => format!("Edit events of input {}", change.id),
+            Command::EditLog { label, .. } => label.clone(),
+        }
+    }
+}
+
+/// Undo and redo stacks of commands.
+#[derive(Debug, Clone)]
+pub struct History {
+    /// Commands that can be undone, oldest first
+    undo_stack: Vec<Command>,
+    /// Commands that can be redone, most recently undone last
+    redo_stack: Vec<Command>,
+    /// Maximum number of commands kept in the undo stack
+    limit: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl History {
+    /// Create an empty history keeping at most `limit` commands.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            limit: limit.max(1),
+        }
+    }
+
+    /// Record a command that was just applied.
+    ///
+    /// This discards the redo stack, and the oldest command when the history
+    /// is full.
+    pub fn push(&mut self, command: Command) {
+        self.redo_stack.clear();
+        self.undo_stack.push(command);
+        if self.undo_stack.len() > self.limit {
+            let excess = self.undo_stack.len() - self.limit;
+            self.undo_stack.drain(..excess);
+        }
+    }
+
+    /// Take the most recent command off the undo stack.
+    ///
+    /// # Returns
+    /// The command to apply to undo it (the inverse of the recorded command),
+    /// or `None` if there is nothing to undo.
+    pub fn undo(&mut self) -> Option<Command> {
+        let command = self.undo_stack.pop()?;
+        let inverse = command.inverse();
+        self.redo_stack.push(command);
+        Some(inverse)
+    }
+
+    /// Take the most recently undone command off the redo stack.
+    ///
+    /// # Returns
+    /// The command to apply again, or `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<Command> {
+        let command = self.redo_stack.pop()?;
+        self.undo_stack.push(command.clone());
+        Some(command)
+    }
+
+    /// Whether there is a command to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Whether there is a command to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Commands that can be undone, oldest first.
+    pub fn undo_stack(&self) -> &[Command] {
+        &self.undo_stack
+    }
+
+    /// Commands that can be redone, most recently undone last.
+    pub fn redo_stack(&self) -> &[Command] {
+        &self.redo_stack
+    }
+
+    /// Forget all commands, e.g. when a new file is loaded.
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log::{ButtonState, InputEvent, InputKind};
+
+    fn bookmark(frame: u64) -> Bookmark {
+        Bookmark { frame, label: None }
+    }
+
+    fn selection(before: Option<(u64, u64)>, after: Option<(u64, u64)>) -> Command {
+        Command::ChangeSelection { before, after }
+    }
+
+    #[test]
+    fn test_inverse_is_involution() {
+        let press = InputEvent {
+            frame: 2,
+            id: 1,
+            kind: InputKind::Button,
+            state: ButtonState::Pressed,
+            value: [0.0, 0.0],
+            contact: 0,
+        };
+        let commands = vec![
+            Command::AddBookmark(bookmark(3)),
+            Command::RemoveBookmark(bookmark(4)),
+            Command::RenameBookmark {
+                frame: 5,
+                before: None,
+                after: Some("boss".to_string()),
+            },
+            Command::ReplaceBookmarks {
+                before: vec![bookmark(1), bookmark(2)],
+                after: vec![],
+            },
+            selection(None, Some((2, 8))),
+            Command::EditEvents(EventChange {
+                id: 1,
+                before: vec![],
+                after: vec![press.clone()],
+            }),
+            Command::EditLog {
+                label: "Normalize".to_string(),
+                changes: vec![
+                    EventChange {
+                        id: 1,
+                        before: vec![],
+                        after: vec![press.clone()],
+                    },
+                    EventChange {
+                        id: 2,
+                        before: vec![InputEvent { id: 2, ..press }],
+                        after: vec![],
+                    },
+                ],
+            },
+        ];
+        for command in commands {
+            assert_ne!(command.inverse(), command);
+            assert_eq!(command.inverse().inverse(), command);
+        }
+    }
+
+    #[test]
+    fn test_inverse_of_add_removes() {
+        assert_eq!(
+            Command::AddBookmark(bookmark(7)).inverse(),
+            Command::RemoveBookmark(bookmark(7))
+        );
+    }
+
+    #[test]
+    fn test_undo_redo_order() {
+        let mut history = History::default();
+        assert!(!history.can_undo());
+        assert!(history.undo().is_none());
+
+        history.push(selection(None, Some((0, 5))));
+        history.push(selection(Some((0, 5)), Some((2, 9))));
+
+        assert_eq!(history.undo(), Some(selection(Some((2, 9)), Some((0, 5)))));
+        assert_eq!(history.undo(), Some(selection(Some((0, 5)), None)));
+        assert!(history.undo().is_none());
+        assert!(history.can_redo());
+
+        assert_eq!(history.redo(), Some(selection(None, Some((0, 5)))));
+        assert_eq!(history.redo(), Some(selection(Some((0, 5)), Some((2, 9)))));
+        assert!(history.redo().is_none());
+        assert_eq!(history.undo_stack().len(), 2);
+    }
+
+    #[test]
+    fn test_push_clears_redo() {
+        let mut history = History::default();
+        history.push(Command::AddBookmark(bookmark(1)));
+        history.undo();
+        assert!(history.can_redo());
+
+        history.push(Command::AddBookmark(bookmark(2)));
+        assert!(!history.can_redo());
+        assert_eq!(history.undo(), Some(Command::RemoveBookmark(bookmark(2))));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_limit_drops_oldest() {
+        let mut history = History::new(2);
+        for frame in 0..4 {
+            history.push(Command::AddBookmark(bookmark(frame)));
+        }
+        assert_eq!(
+            history.undo_stack(),
+            &[
+                Command::AddBookmark(bookmark(2)),
+                Command::AddBookmark(bookmark(3))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut history = History::default();
+        history.push(Command::AddBookmark(bookmark(1)));
+        history.push(Command::AddBookmark(bookmark(2)));
+        history.undo();
+        history.clear();
+        assert!(!history.can_undo());
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            Command::AddBookmark(bookmark(12)).describe(),
+            "Add bookmark at F12"
+        );
+        assert_eq!(
+            Command::ReplaceBookmarks {
+                before: vec![bookmark(1)],
+                after: vec![],
+            }
+            .describe(),
+            "Clear all bookmarks"
+        );
+        assert_eq!(selection(Some((1, 2)), None).describe(), "Clear selection");
+    }
+}
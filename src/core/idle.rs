@@ -0,0 +1,411 @@
+//! Idle gap and dead-input detection.
+//!
+//! This module analyzes an input log for stretches of inactivity (AFK periods),
+//! stretches where the log contains no events at all (dropped input), and
+//! inputs that are declared in the mappings but never appear in the events.
+
+use std::collections::{BTreeSet, HashSet};
+
+use super::log::{ButtonState, InputEvent, InputKind, InputLog};
+
+/// Default minimum length of a reported span, in frames (1 second at 60 FPS).
+pub const DEFAULT_MIN_IDLE_FRAMES: u64 = 60;
+
+/// Default deadzone for axis inputs.
+pub const DEFAULT_DEADZONE: f32 = 0.1;
+
+/// Settings for idle gap detection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdleConfig {
+    /// Minimum number of consecutive frames for a span to be reported
+    pub min_frames: u64,
+    /// Axis values with a magnitude at or below this are treated as neutral
+    pub deadzone: f32,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            min_frames: DEFAULT_MIN_IDLE_FRAMES,
+            deadzone: DEFAULT_DEADZONE,
+        }
+    }
+}
+
+/// Classification of a detected span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleKind {
+    /// No button is down and every axis is inside the deadzone
+    Idle,
+    /// The log contains no events at all for these frames
+    NoEvents,
+}
+
+impl IdleKind {
+    /// Get a short human-readable label for this kind.
+    pub fn label(&self) -> &'static str {
+        match self {
+            IdleKind::Idle => "Idle",
+            IdleKind::NoEvents => "No events",
+        }
+    }
+}
+
+/// A stretch of frames detected by the analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleSpan {
+    /// First frame of the span
+    pub start: u64,
+    /// Last frame of the span (inclusive)
+    pub end: u64,
+    /// What kind of span this is
+    pub kind: IdleKind,
+}
+
+impl IdleSpan {
+    /// Number of frames covered by the span.
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// Result of running idle detection on a log.
+#[derive(Debug, Clone, Default)]
+pub struct IdleReport {
+    /// Detected spans, sorted by start frame
+    pub spans: Vec<IdleSpan>,
+    /// IDs declared in the mappings that never appear in the events (sorted)
+    pub unused_inputs: Vec<u32>,
+}
+
+impl IdleReport {
+    /// Count the spans of a given kind.
+    pub fn count(&self, kind: IdleKind) -> usize {
+        self.spans.iter().filter(|s| s.kind == kind).count()
+    }
+}
+
+/// Run idle gap and dead-input detection on the given log.
+pub fn detect_idle(log: &InputLog, config: &IdleConfig) -> IdleReport {
+    let frame_count = log.metadata.frame_count;
+    let min_frames = config.min_frames.max(1);
+
+    // Work on a frame-ordered view so unsorted logs are still handled correctly
+    let mut events: Vec<&InputEvent> = log.events.iter().collect();
+    events.sort_by_key(|e| e.frame);
+
+    let mut spans = find_idle_spans(&events, frame_count, config.deadzone, min_frames);
+    spans.extend(find_event_gaps(&events, frame_count, min_frames));
+    spans.sort_by_key(|s| (s.start, s.end));
+
+    IdleReport {
+        spans,
+        unused_inputs: find_unused_inputs(log),
+    }
+}
+
+/// Find spans where no button is down and all axes are inside the deadzone.
+///
+/// Input state is carried forward between events, so a button that is pressed
+/// and later released keeps the frames in between active even if the log only
+/// records the edges. Touch contacts are tracked separately, so lifting one
+/// finger does not end a hold by another.
+fn find_idle_spans(
+    events: &[&InputEvent],
+    frame_count: u64,
+    deadzone: f32,
+    min_frames: u64,
+) -> Vec<IdleSpan> {
+    let mut spans = Vec::new();
+    let mut buttons_down: HashSet<(u32, u16)> = HashSet::new();
+    let mut axes_active: HashSet<u32> = HashSet::new();
+    let mut idle_start: Option<u64> = Some(0);
+
+    let close_span = |spans: &mut Vec<IdleSpan>, start: u64, end_exclusive: u64| {
+        if end_exclusive > start && end_exclusive - start >= min_frames {
+            spans.push(IdleSpan {
+                start,
+                end: end_exclusive - 1,
+                kind: IdleKind::Idle,
+            });
+        }
+    };
+
+    let mut i = 0;
+    while i < events.len() {
+        let frame = events[i].frame;
+        if frame >= frame_count {
+            break;
+        }
+
+        // Apply every event on this frame before evaluating it
+        let mut moved = false;
+        while i < events.len() && events[i].frame == frame {
+            let event = events[i];
+            match event.kind {
+                InputKind::Button | InputKind::Key | InputKind::Touch => match event.state {
+                    ButtonState::Pressed | ButtonState::Held => {
+                        buttons_down.insert((event.id, event.contact));
+                    }
+                    ButtonState::Released => {
+                        buttons_down.remove(&(event.id, event.contact));
+                    }
+                },
+                InputKind::Axis1D | InputKind::Axis2D | InputKind::Trigger => {
+                    if axis_magnitude(event) > deadzone {
+                        axes_active.insert(event.id);
+                    } else {
+                        axes_active.remove(&event.id);
+                    }
+                }
+                // Movement only counts on the frame it happens
+                InputKind::MouseDelta => moved |= axis_magnitude(event) > deadzone,
+                InputKind::PointerPosition => moved = true,
+            }
+            i += 1;
+        }
+
+        let active = moved || !buttons_down.is_empty() || !axes_active.is_empty();
+        match (active, idle_start) {
+            (true, Some(start)) => {
+                close_span(&mut spans, start, frame);
+                idle_start = None;
+            }
+            (false, None) => idle_start = Some(frame),
+            _ => {}
+        }
+    }
+
+    if let Some(start) = idle_start {
+        close_span(&mut spans, start, frame_count);
+    }
+
+    spans
+}
+
+/// Find spans of frames that contain no events at all.
+fn find_event_gaps(events: &[&InputEvent], frame_count: u64, min_frames: u64) -> Vec<IdleSpan> {
+    let mut spans = Vec::new();
+    let mut next_frame = 0u64;
+
+    let push_gap = |spans: &mut Vec<IdleSpan>, start: u64, end_exclusive: u64| {
+        if end_exclusive > start && end_exclusive - start >= min_frames {
+            spans.push(IdleSpan {
+                start,
+                end: end_exclusive - 1,
+                kind: IdleKind::NoEvents,
+            });
+        }
+    };
+
+    for event in events {
+        if event.frame >= frame_count {
+            break;
+        }
+        push_gap(&mut spans, next_frame, event.frame);
+        next_frame = next_frame.max(event.frame + 1);
+    }
+    push_gap(&mut spans, next_frame, frame_count);
+
+    spans
+}
+
+/// Find mapped input IDs that never appear in the events.
+fn find_unused_inputs(log: &InputLog) -> Vec<u32> {
+    let event_ids: HashSet<u32> = log.events.iter().map(|e| e.id).collect();
+    let unused: BTreeSet<u32> = log
+        .mappings
+        .iter()
+        .map(|m| m.id)
+        .filter(|id| !event_ids.contains(id))
+        .collect();
+    unused.into_iter().collect()
+}
+
+/// Magnitude of an axis event's value (1D uses the absolute value).
+fn axis_magnitude(event: &InputEvent) -> f32 {
+    match event.kind {
+        InputKind::Axis2D | InputKind::MouseDelta => {
+            (event.value[0] * event.value[0] + event.value[1] * event.value[1]).sqrt()
+        }
+        _ => event.value[0].abs(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_util::{axis, button, log_with, mapping};
+
+    fn create_test_log(frame_count: u64, events: Vec<InputEvent>) -> InputLog {
+        InputLog {
+            mappings: vec![
+                mapping(0, "A Button"),
+                mapping(10, "Left Stick X"),
+                mapping(99, "Unused"),
+            ],
+            ..log_with(frame_count, events)
+        }
+    }
+
+    fn config(min_frames: u64) -> IdleConfig {
+        IdleConfig {
+            min_frames,
+            deadzone: 0.2,
+        }
+    }
+
+    #[test]
+    fn test_empty_log_is_one_gap() {
+        let log = create_test_log(100, vec![]);
+        let report = detect_idle(&log, &config(10));
+
+        assert_eq!(
+            report.spans,
+            vec![
+                IdleSpan {
+                    start: 0,
+                    end: 99,
+                    kind: IdleKind::Idle
+                },
+                IdleSpan {
+                    start: 0,
+                    end: 99,
+                    kind: IdleKind::NoEvents
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sparse_hold_is_not_idle() {
+        // Button held from frame 10 to 50 with edge events only
+        let log = create_test_log(
+            100,
+            vec![
+                button(10, 0, ButtonState::Pressed),
+                button(50, 0, ButtonState::Released),
+            ],
+        );
+        let report = detect_idle(&log, &config(5));
+
+        let idle: Vec<_> = report
+            .spans
+            .iter()
+            .filter(|s| s.kind == IdleKind::Idle)
+            .map(|s| (s.start, s.end))
+            .collect();
+        assert_eq!(idle, vec![(0, 9), (50, 99)]);
+
+        // The same hold shows up as a gap in event coverage
+        let gaps: Vec<_> = report
+            .spans
+            .iter()
+            .filter(|s| s.kind == IdleKind::NoEvents)
+            .map(|s| (s.start, s.end))
+            .collect();
+        assert_eq!(gaps, vec![(0, 9), (11, 49), (51, 99)]);
+    }
+
+    #[test]
+    fn test_touch_contacts_are_tracked_separately() {
+        let touch = |frame, contact, state| InputEvent {
+            kind: InputKind::Touch,
+            contact,
+            ..button(frame, 0, state)
+        };
+        // Contact 1 stays down after contact 0 lifts
+        let log = create_test_log(
+            100,
+            vec![
+                touch(10, 0, ButtonState::Pressed),
+                touch(20, 1, ButtonState::Pressed),
+                touch(30, 0, ButtonState::Released),
+                touch(60, 1, ButtonState::Released),
+            ],
+        );
+        let report = detect_idle(&log, &config(5));
+
+        let idle: Vec<_> = report
+            .spans
+            .iter()
+            .filter(|s| s.kind == IdleKind::Idle)
+            .map(|s| (s.start, s.end))
+            .collect();
+        assert_eq!(idle, vec![(0, 9), (60, 99)]);
+    }
+
+    #[test]
+    fn test_axis_inside_deadzone_is_idle() {
+        let log = create_test_log(
+            40,
+            vec![
+                axis(0, 10, 0.1),
+                axis(10, 10, 0.9),
+                axis(20, 10, -0.15),
+                axis(30, 10, 0.05),
+            ],
+        );
+        let report = detect_idle(&log, &config(5));
+
+        let idle: Vec<_> = report
+            .spans
+            .iter()
+            .filter(|s| s.kind == IdleKind::Idle)
+            .map(|s| (s.start, s.end))
+            .collect();
+        assert_eq!(idle, vec![(0, 9), (20, 39)]);
+    }
+
+    #[test]
+    fn test_min_frames_threshold() {
+        let log = create_test_log(
+            30,
+            vec![
+                button(3, 0, ButtonState::Pressed),
+                button(4, 0, ButtonState::Released),
+            ],
+        );
+
+        // Leading gap of 3 frames is below the threshold
+        let report = detect_idle(&log, &config(4));
+        assert!(report.spans.iter().all(|s| s.start != 0));
+        assert_eq!(report.count(IdleKind::Idle), 1);
+        assert_eq!(report.spans.last().unwrap().len(), 25);
+    }
+
+    #[test]
+    fn test_unsorted_events_are_handled() {
+        let log = create_test_log(
+            20,
+            vec![
+                button(15, 0, ButtonState::Released),
+                button(5, 0, ButtonState::Pressed),
+            ],
+        );
+        let report = detect_idle(&log, &config(3));
+
+        let idle: Vec<_> = report
+            .spans
+            .iter()
+            .filter(|s| s.kind == IdleKind::Idle)
+            .map(|s| (s.start, s.end))
+            .collect();
+        assert_eq!(idle, vec![(0, 4), (15, 19)]);
+    }
+
+    #[test]
+    fn test_unused_inputs() {
+        let log = create_test_log(10, vec![button(0, 0, ButtonState::Pressed)]);
+        let report = detect_idle(&log, &config(1));
+        assert_eq!(report.unused_inputs, vec![10, 99]);
+    }
+
+    #[test]
+    fn test_events_beyond_frame_count_are_ignored() {
+        let log = create_test_log(10, vec![button(50, 0, ButtonState::Pressed)]);
+        let report = detect_idle(&log, &config(1));
+        assert_eq!(report.count(IdleKind::NoEvents), 1);
+        assert_eq!(report.spans[0].end, 9);
+    }
+}
@@ -0,0 +1,313 @@
+//! Layout of the input rows on the timeline.
+//!
+//! By default rows are shown in input ID order. The user can drag rows into
+//! another order, pin rows to the top of the timeline, make single rows taller
+//! or shorter and collapse the named groups inputs belong to
+//! (`InputMapping::group`). The layout is stored with
+//! the mapping profile of the game, so it comes back for every log the
+//! profile is applied to.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Height multipliers that can be picked for a single row.
+pub const ROW_SCALES: [f32; 4] = [0.5, 1.0, 2.0, 3.0];
+
+/// Order, pinned rows, row heights and collapsed groups of the timeline.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RowLayout {
+    /// Input IDs in display order; inputs not listed follow in ID order
+    #[serde(default)]
+    pub order: Vec<u32>,
+    /// Input IDs pinned to the top of the timeline, in display order
+    #[serde(default)]
+    pub pinned: Vec<u32>,
+    /// Names of the groups whose rows are collapsed
+    #[serde(default)]
+    pub collapsed: BTreeSet<String>,
+    /// Height multiplier per input ID; inputs not listed use 1.0
+    #[serde(default)]
+    pub row_scales: BTreeMap<u32, f32>,
+}
+
+/// A row produced by `RowLayout::arrange`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutRow {
+    /// Header of a named group
+    Group {
+        /// Group name
+        name: String,
+        /// Number of inputs in the group
+        inputs: usize,
+        /// Whether the inputs of the group are hidden
+        collapsed: bool,
+    },
+    /// An input, by ID
+    Input(u32),
+}
+
+impl RowLayout {
+    /// Whether the layout is the default one (ID order, nothing pinned,
+    /// resized or collapsed).
+    pub fn is_default(&self) -> bool {
+        self.order.is_empty()
+            && self.pinned.is_empty()
+            && self.collapsed.is_empty()
+            && self.row_scales.is_empty()
+    }
+
+    /// Sort input IDs into display order.
+    pub fn sort(&self, ids: &mut [u32]) {
+        ids.sort_by_key(|id| {
+            let position = self.order.iter().position(|o| o == id);
+            (position.unwrap_or(usize::MAX), *id)
+        });
+    }
+
+    /// Arrange inputs into rows, leaving out pinned inputs.
+    ///
+    /// Inputs are sorted into display order. The inputs of a named group are
+    /// gathered under a header where the first of them would be; ungrouped
+    /// inputs keep their own place.
+    ///
+    /// # Arguments
+    /// * `inputs` - ID and group name of each input to arrange
+    pub fn arrange(&self, inputs: &[(u32, Option<&str>)]) -> Vec<LayoutRow> {
+        let mut ids: Vec<u32> = inputs
+            .iter()
+            .map(|(id, _)| *id)
+            .filter(|id| !self.is_pinned(*id))
+            .collect();
+        self.sort(&mut ids);
+        let group_of = |id: u32| {
+            inputs
+                .iter()
+                .find(|(input, _)| *input == id)
+                .and_then(|(_, group)| *group)
+        };
+
+        let mut rows = Vec::new();
+        let mut placed: BTreeSet<&str> = BTreeSet::new();
+        for &id in &ids {
+            let Some(name) = group_of(id) else {
+                rows.push(LayoutRow::Input(id));
+                continue;
+            };
+            if !placed.insert(name) {
+                continue;
+            }
+            let members: Vec<u32> = ids
+                .iter()
+                .copied()
+                .filter(|&member| group_of(member) == Some(name))
+                .collect();
+            let collapsed = self.is_collapsed(name);
+            rows.push(LayoutRow::Group {
+                name: name.to_string(),
+                inputs: members.len(),
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend(members.into_iter().map(LayoutRow::Input));
+            }
+        }
+        rows
+    }
+
+    /// Move a row before another one, or to the end.
+    ///
+    /// # Arguments
+    /// * `ids` - All input IDs of the log
+    /// * `id` - The input to move
+    /// * `before` - The input to move it in front of, or `None` for the end
+    pub fn move_row(&mut self, ids: &[u32], id: u32, before: Option<u32>) {
+        if before == Some(id) {
+            return;
+        }
+        let mut order = ids.to_vec();
+        self.sort(&mut order);
+        order.retain(|&other| other != id);
+        let index = before
+            .and_then(|before| order.iter().position(|&other| other == before))
+            .unwrap_or(order.len());
+        order.insert(index, id);
+        self.order = order;
+
+        // Pinned rows are reordered among themselves
+        if self.is_pinned(id) {
+            let mut pinned = self.pinned.clone();
+            self.sort(&mut pinned);
+            self.pinned = pinned;
+        }
+    }
+
+    /// Whether an input is pinned to the top.
+    pub fn is_pinned(&self, id: u32) -> bool {
+        self.pinned.contains(&id)
+    }
+
+    /// Pin an input to the top, or unpin it.
+    ///
+    /// # Returns
+    /// Whether the input is pinned afterwards.
+    pub fn toggle_pin(&mut self, id: u32) -> bool {
+        if self.is_pinned(id) {
+            self.pinned.retain(|&pinned| pinned != id);
+            false
+        } else {
+            self.pinned.push(id);
+            true
+        }
+    }
+
+    /// Height multiplier of an input row.
+    pub fn scale(&self, id: u32) -> f32 {
+        self.row_scales.get(&id).copied().unwrap_or(1.0)
+    }
+
+    /// Set the height multiplier of an input row.
+    pub fn set_scale(&mut self, id: u32, scale: f32) {
+        if scale == 1.0 {
+            self.row_scales.remove(&id);
+        } else {
+            self.row_scales.insert(id, scale);
+        }
+    }
+
+    /// Whether the rows of a named group are collapsed.
+    pub fn is_collapsed(&self, group: &str) -> bool {
+        self.collapsed.contains(group)
+    }
+
+    /// Collapse or expand a named group.
+    pub fn toggle_collapsed(&mut self, group: &str) {
+        if !self.collapsed.remove(group) {
+            self.collapsed.insert(group.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(id: u32) -> LayoutRow {
+        LayoutRow::Input(id)
+    }
+
+    fn group(name: &str, inputs: usize, collapsed: bool) -> LayoutRow {
+        LayoutRow::Group {
+            name: name.to_string(),
+            inputs,
+            collapsed,
+        }
+    }
+
+    #[test]
+    fn test_default_layout_is_id_order() {
+        let layout = RowLayout::default();
+        assert!(layout.is_default());
+        let rows = layout.arrange(&[(3, None), (1, None), (2, None)]);
+        assert_eq!(rows, vec![input(1), input(2), input(3)]);
+    }
+
+    #[test]
+    fn test_move_row() {
+        let ids = [0, 1, 2, 3];
+        let mut layout = RowLayout::default();
+
+        layout.move_row(&ids, 3, Some(1));
+        assert_eq!(layout.order, vec![0, 3, 1, 2]);
+        layout.move_row(&ids, 0, None);
+        assert_eq!(layout.order, vec![3, 1, 2, 0]);
+        // Moving a row in front of itself changes nothing
+        layout.move_row(&ids, 2, Some(2));
+        assert_eq!(layout.order, vec![3, 1, 2, 0]);
+
+        // Inputs missing from the order (e.g. new in this log) come last by ID
+        let mut more = vec![7, 0, 5, 1];
+        layout.sort(&mut more);
+        assert_eq!(more, vec![1, 0, 5, 7]);
+    }
+
+    #[test]
+    fn test_groups_gather_at_first_member() {
+        let mut layout = RowLayout::default();
+        let inputs = [
+            (0, Some("Move")),
+            (1, None),
+            (2, Some("Move")),
+            (3, Some("Aim")),
+        ];
+        assert_eq!(
+            layout.arrange(&inputs),
+            vec![
+                group("Move", 2, false),
+                input(0),
+                input(2),
+                input(1),
+                group("Aim", 1, false),
+                input(3),
+            ]
+        );
+
+        layout.toggle_collapsed("Move");
+        layout.move_row(&[0, 1, 2, 3], 1, Some(0));
+        assert_eq!(
+            layout.arrange(&inputs),
+            vec![
+                input(1),
+                group("Move", 2, true),
+                group("Aim", 1, false),
+                input(3)
+            ]
+        );
+        layout.toggle_collapsed("Move");
+        assert!(!layout.is_collapsed("Move"));
+    }
+
+    #[test]
+    fn test_pinned_rows_are_left_out() {
+        let mut layout = RowLayout::default();
+        assert!(layout.toggle_pin(2));
+        assert!(layout.toggle_pin(0));
+        assert_eq!(
+            layout.arrange(&[(0, None), (1, None), (2, Some("Move"))]),
+            vec![input(1)]
+        );
+
+        // Reordering a pinned row reorders the pinned rows
+        layout.move_row(&[0, 1, 2], 0, Some(2));
+        assert_eq!(layout.pinned, vec![0, 2]);
+
+        assert!(!layout.toggle_pin(2));
+        assert_eq!(layout.pinned, vec![0]);
+    }
+
+    #[test]
+    fn test_row_scales() {
+        let mut layout = RowLayout::default();
+        layout.set_scale(4, 2.0);
+        assert_eq!(layout.scale(4), 2.0);
+        assert_eq!(layout.scale(5), 1.0);
+        assert!(!layout.is_default());
+        layout.set_scale(4, 1.0);
+        assert!(layout.row_scales.is_empty());
+        assert!(layout.is_default());
+    }
+
+    #[test]
+    fn test_serde_defaults() {
+        let layout: RowLayout = serde_json::from_str("{}").unwrap();
+        assert!(layout.is_default());
+        // Per-row scales survive a round trip with their integer keys
+        let layout = RowLayout {
+            order: vec![2, 1],
+            pinned: vec![1],
+            collapsed: BTreeSet::from(["Move".to_string()]),
+            row_scales: BTreeMap::from([(7, 0.5)]),
+        };
+        let json = serde_json::to_string(&layout).unwrap();
+        assert_eq!(serde_json::from_str::<RowLayout>(&json).unwrap(), layout);
+    }
+}
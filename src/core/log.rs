@@ -9,15 +9,70 @@
 use serde::{Deserialize, Serialize};
 
 /// Input type classification.
+///
+/// The discriminants are stored in binary logs; new kinds are only ever
+/// appended. Compressed logs store the kind in 3 bits, so at most 8 kinds fit.
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum InputKind {
     /// Button input (on/off state)
     Button = 0,
-    /// Single-axis input (e.g., trigger)
+    /// Single-axis input from -1 to 1 (e.g., a steering axis)
     Axis1D = 1,
     /// Dual-axis input (e.g., analog stick)
     Axis2D = 2,
+    /// One-sided analog input from 0 to 1 (e.g., a gamepad trigger)
+    Trigger = 3,
+    /// Relative mouse movement or wheel in `value`, summed up over frames
+    MouseDelta = 4,
+    /// Absolute pointer position in screen pixels
+    PointerPosition = 5,
+    /// Touch contact at a screen position; `state` tracks the contact and
+    /// `InputEvent::contact` tells simultaneous contacts apart
+    Touch = 6,
+    /// Keyboard key with button states; `value[0]` holds the key code
+    Key = 7,
+}
+
+impl InputKind {
+    /// All kinds in discriminant order.
+    pub const ALL: [InputKind; 8] = [
+        InputKind::Button,
+        InputKind::Axis1D,
+        InputKind::Axis2D,
+        InputKind::Trigger,
+        InputKind::MouseDelta,
+        InputKind::PointerPosition,
+        InputKind::Touch,
+        InputKind::Key,
+    ];
+
+    /// Look up a kind by its `repr(u8)` value.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.get(value as usize).copied()
+    }
+
+    /// Whether events of this kind carry a meaningful `ButtonState`.
+    ///
+    /// Such events need an explicit state in .ilj files; for other kinds it
+    /// defaults to Released.
+    pub fn has_button_state(self) -> bool {
+        matches!(self, Self::Button | Self::Touch | Self::Key)
+    }
+
+    /// Whether the input is analog with a resting value of zero.
+    pub fn is_analog(self) -> bool {
+        matches!(
+            self,
+            Self::Axis1D | Self::Axis2D | Self::Trigger | Self::MouseDelta
+        )
+    }
+
+    /// Whether the input keeps its value until the next sample, so samples
+    /// repeating the previous value carry no information.
+    pub fn is_sampled(self) -> bool {
+        matches!(self, Self::Axis1D | Self::Axis2D | Self::Trigger)
+    }
 }
 
 /// Button state for button-type inputs.
@@ -33,7 +88,7 @@ pub enum ButtonState {
 }
 
 /// A single input event at a specific frame.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct InputEvent {
     /// Frame number when this event occurred
     pub frame: u64,
@@ -41,14 +96,35 @@ pub struct InputEvent {
     pub id: u32,
     /// Type of input
     pub kind: InputKind,
-    /// Button state (only valid for Button kind)
+    /// Button state (only valid for kinds with `has_button_state`)
     pub state: ButtonState,
     /// Input value (1D uses index 0, 2D uses both)
     pub value: [f32; 2],
+    /// Touch contact identifier (0 for other kinds)
+    #[serde(default)]
+    pub contact: u16,
+}
+
+/// Type of device an input comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DeviceType {
+    /// Game controller
+    Gamepad,
+    /// Keyboard
+    Keyboard,
+    /// Mouse
+    Mouse,
+    /// Touch screen
+    Touch,
+    /// Any other device (e.g. a wheel or arcade stick)
+    Other,
 }
 
 /// Mapping from input ID to display information.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+///
+/// Names only need to be unique per player: in a multi-player log every
+/// player's controller usually has the same buttons.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct InputMapping {
     /// Input identifier
     pub id: u32,
@@ -57,6 +133,66 @@ pub struct InputMapping {
     /// Optional RGB color for visualization
     #[serde(default)]
     pub color: Option<[u8; 3]>,
+    /// Player or device index (0-based) for logs with several players
+    #[serde(default)]
+    pub player: Option<u32>,
+    /// Type of device the input comes from
+    #[serde(default)]
+    pub device: Option<DeviceType>,
+    /// Named group the input belongs to, e.g. "Movement"
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+impl InputMapping {
+    /// Name qualified with the player, e.g. "P2 Jump", for lists that mix players.
+    pub fn qualified_name(&self) -> String {
+        match self.player {
+            Some(player) => format!("P{} {}", player + 1, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Display label for a player index, e.g. "Player 1".
+pub fn player_label(player: u32) -> String {
+    format!("Player {}", player + 1)
+}
+
+/// A game event or telemetry sample written by the game next to the inputs.
+///
+/// Annotations without a value are discrete events such as "player died";
+/// those with a value are samples of the telemetry channel named by `label`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Frame the annotation belongs to
+    pub frame: u64,
+    /// Event text, or the channel name of a telemetry sample
+    pub label: String,
+    /// Sample value of a telemetry channel
+    #[serde(default)]
+    pub value: Option<f32>,
+}
+
+/// The samples of one telemetry channel, in frame order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TelemetryChannel {
+    /// Channel name
+    pub name: String,
+    /// (frame, value) pairs sorted by frame
+    pub samples: Vec<(u64, f32)>,
+    /// Smallest sample value
+    pub min: f32,
+    /// Largest sample value
+    pub max: f32,
+}
+
+impl TelemetryChannel {
+    /// Value in effect at a frame: the last sample at or before it.
+    pub fn value_at(&self, frame: u64) -> Option<f32> {
+        let index = self.samples.partition_point(|&(f, _)| f <= frame);
+        index.checked_sub(1).map(|i| self.samples[i].1)
+    }
 }
 
 /// Metadata about the input log.
@@ -85,20 +221,112 @@ pub struct InputLog {
     pub mappings: Vec<InputMapping>,
     /// All input events sorted by frame
     pub events: Vec<InputEvent>,
+    /// Start time of each frame in microseconds, indexed by frame.
+    ///
+    /// Empty if the log was recorded without timestamps, in which case every
+    /// frame lasts `1 / target_fps`.
+    #[serde(default)]
+    pub frame_timestamps: Vec<u64>,
+    /// Game events and telemetry samples, sorted by frame
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
 }
 
 impl InputLog {
     /// Get the display name for an input ID.
     ///
     /// Returns the mapped name if available, otherwise falls back to "Input #N" format.
+    /// Inputs that belong to a player are prefixed with it (see `qualified_name`).
     pub fn get_input_name(&self, id: u32) -> String {
         self.mappings
             .iter()
             .find(|m| m.id == id)
-            .map(|m| m.name.clone())
+            .map(InputMapping::qualified_name)
             .unwrap_or_else(|| format!("Input #{}", id))
     }
 
+    /// Get the player an input ID belongs to, if any.
+    pub fn get_input_player(&self, id: u32) -> Option<u32> {
+        self.mappings
+            .iter()
+            .find(|m| m.id == id)
+            .and_then(|m| m.player)
+    }
+
+    /// Distinct player indices used by the mappings, in ascending order.
+    ///
+    /// Empty for single-player logs, which have no player information.
+    pub fn players(&self) -> Vec<u32> {
+        let players: std::collections::BTreeSet<u32> =
+            self.mappings.iter().filter_map(|m| m.player).collect();
+        players.into_iter().collect()
+    }
+
+    /// Screen-space position of each pointer event, indexed like `events`.
+    ///
+    /// `PointerPosition` events report their value as-is. `MouseDelta` events
+    /// are accumulated per input in frame order starting from the origin, so a
+    /// relative mouse can be drawn like an absolute pointer. Other kinds are `None`.
+    pub fn pointer_positions(&self) -> Vec<Option<[f32; 2]>> {
+        let mut order: Vec<usize> = (0..self.events.len()).collect();
+        order.sort_by_key(|&i| self.events[i].frame);
+
+        let mut positions = vec![None; self.events.len()];
+        let mut accumulated: std::collections::HashMap<u32, [f32; 2]> =
+            std::collections::HashMap::new();
+        for i in order {
+            let event = &self.events[i];
+            positions[i] = match event.kind {
+                InputKind::PointerPosition => Some(event.value),
+                InputKind::MouseDelta => {
+                    let total = accumulated.entry(event.id).or_insert([0.0, 0.0]);
+                    total[0] += event.value[0];
+                    total[1] += event.value[1];
+                    Some(*total)
+                }
+                _ => None,
+            };
+        }
+        positions
+    }
+
+    /// Discrete game events (annotations without a value), in frame order.
+    pub fn markers(&self) -> impl Iterator<Item = &Annotation> {
+        self.annotations.iter().filter(|a| a.value.is_none())
+    }
+
+    /// Telemetry channels in order of their first sample.
+    ///
+    /// Non-finite sample values are skipped.
+    pub fn telemetry_channels(&self) -> Vec<TelemetryChannel> {
+        let mut channels: Vec<TelemetryChannel> = Vec::new();
+        for annotation in &self.annotations {
+            let Some(value) = annotation.value.filter(|v| v.is_finite()) else {
+                continue;
+            };
+            let index = match channels.iter().position(|c| c.name == annotation.label) {
+                Some(index) => index,
+                None => {
+                    channels.push(TelemetryChannel {
+                        name: annotation.label.clone(),
+                        samples: Vec::new(),
+                        min: value,
+                        max: value,
+                    });
+                    channels.len() - 1
+                }
+            };
+            let channel = &mut channels[index];
+            channel.samples.push((annotation.frame, value));
+            channel.min = channel.min.min(value);
+            channel.max = channel.max.max(value);
+        }
+        for channel in &mut channels {
+            channel.samples.sort_by_key(|&(frame, _)| frame);
+        }
+        channels
+    }
+
     /// Get the color for an input ID, if one is mapped.
     pub fn get_input_color(&self, id: u32) -> Option<[u8; 3]> {
         self.mappings
@@ -131,6 +359,9 @@ impl InputLog {
                     id,
                     name: format!("Input #{}", id),
                     color: None,
+                    player: None,
+                    device: None,
+                    group: None,
                 });
             }
         }
@@ -139,10 +370,51 @@ impl InputLog {
         result.sort_by_key(|m| m.id);
         result
     }
+
+    /// Whether the log carries recorded per-frame timestamps.
+    pub fn has_frame_timestamps(&self) -> bool {
+        !self.frame_timestamps.is_empty()
+    }
+
+    /// Nominal frame duration in microseconds derived from the target FPS.
+    pub fn nominal_frame_duration_us(&self) -> u64 {
+        1_000_000 / u64::from(self.metadata.target_fps.max(1))
+    }
+
+    /// Recorded duration of a frame in microseconds.
+    ///
+    /// # Returns
+    /// The time between the start of `frame` and the start of the next frame,
+    /// or `None` if either timestamp is missing.
+    pub fn frame_duration_us(&self, frame: u64) -> Option<u64> {
+        let index = usize::try_from(frame).ok()?;
+        let start = *self.frame_timestamps.get(index)?;
+        let end = *self.frame_timestamps.get(index + 1)?;
+        Some(end.saturating_sub(start))
+    }
+
+    /// Duration of a frame in microseconds, falling back to the nominal duration.
+    pub fn effective_frame_duration_us(&self, frame: u64) -> u64 {
+        self.frame_duration_us(frame)
+            .unwrap_or_else(|| self.nominal_frame_duration_us())
+    }
+
+    /// Time of the start of a frame in seconds, relative to the first frame.
+    ///
+    /// Uses recorded timestamps when available and `frame / target_fps` otherwise.
+    pub fn frame_time_secs(&self, frame: u64) -> f64 {
+        let recorded = usize::try_from(frame)
+            .ok()
+            .and_then(|index| self.frame_timestamps.get(index));
+        match (recorded, self.frame_timestamps.first()) {
+            (Some(&ts), Some(&first)) => ts.saturating_sub(first) as f64 / 1_000_000.0,
+            _ => frame as f64 / f64::from(self.metadata.target_fps.max(1)),
+        }
+    }
 }
 
 /// A bookmark marking an important frame.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Bookmark {
     /// Frame number of the bookmark
     pub frame: u64,
@@ -172,6 +444,79 @@ mod tests {
         assert_eq!(InputKind::Button as u8, 0);
         assert_eq!(InputKind::Axis1D as u8, 1);
         assert_eq!(InputKind::Axis2D as u8, 2);
+        assert_eq!(InputKind::Trigger as u8, 3);
+        assert_eq!(InputKind::Key as u8, 7);
+        for kind in InputKind::ALL {
+            assert_eq!(InputKind::from_u8(kind as u8), Some(kind));
+        }
+        assert_eq!(InputKind::from_u8(8), None);
+    }
+
+    #[test]
+    fn test_telemetry_channels() {
+        let annotation = |frame, label: &str, value| Annotation {
+            frame,
+            label: label.to_string(),
+            value,
+        };
+        let log = InputLog {
+            annotations: vec![
+                annotation(0, "HP", Some(100.0)),
+                annotation(2, "checkpoint", None),
+                annotation(5, "Speed", Some(3.5)),
+                annotation(8, "HP", Some(40.0)),
+                annotation(9, "HP", Some(f32::NAN)),
+            ],
+            ..Default::default()
+        };
+
+        let markers: Vec<_> = log.markers().map(|a| a.label.as_str()).collect();
+        assert_eq!(markers, vec!["checkpoint"]);
+
+        let channels = log.telemetry_channels();
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[0].name, "HP");
+        assert_eq!(channels[0].samples, vec![(0, 100.0), (8, 40.0)]);
+        assert_eq!((channels[0].min, channels[0].max), (40.0, 100.0));
+        assert_eq!(channels[1].name, "Speed");
+
+        assert_eq!(channels[0].value_at(7), Some(100.0));
+        assert_eq!(channels[0].value_at(8), Some(40.0));
+        assert_eq!(channels[1].value_at(4), None);
+    }
+
+    #[test]
+    fn test_pointer_positions_accumulate_mouse_deltas() {
+        let event = |frame, id, kind, value| InputEvent {
+            frame,
+            id,
+            kind,
+            state: ButtonState::Released,
+            value,
+            contact: 0,
+        };
+        let log = InputLog {
+            events: vec![
+                event(0, 0, InputKind::MouseDelta, [2.0, 1.0]),
+                event(1, 1, InputKind::PointerPosition, [10.0, 20.0]),
+                event(1, 2, InputKind::Button, [0.0, 0.0]),
+                // Out of frame order: still accumulated after frame 0
+                event(3, 0, InputKind::MouseDelta, [-1.0, 4.0]),
+                event(2, 0, InputKind::MouseDelta, [0.5, 0.0]),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            log.pointer_positions(),
+            vec![
+                Some([2.0, 1.0]),
+                Some([10.0, 20.0]),
+                None,
+                Some([1.5, 5.0]),
+                Some([2.5, 1.0]),
+            ]
+        );
     }
 
     #[test]
@@ -204,11 +549,17 @@ mod tests {
                     id: 0,
                     name: "A Button".to_string(),
                     color: Some([255, 0, 0]),
+                    player: None,
+                    device: None,
+                    group: None,
                 },
                 InputMapping {
                     id: 1,
                     name: "B Button".to_string(),
                     color: None,
+                    player: None,
+                    device: None,
+                    group: None,
                 },
             ],
             ..Default::default()
@@ -225,6 +576,9 @@ mod tests {
                 id: 0,
                 name: "A Button".to_string(),
                 color: None,
+                player: None,
+                device: None,
+                group: None,
             }],
             ..Default::default()
         };
@@ -242,11 +596,17 @@ mod tests {
                     id: 0,
                     name: "A Button".to_string(),
                     color: Some([255, 0, 0]),
+                    player: None,
+                    device: None,
+                    group: None,
                 },
                 InputMapping {
                     id: 1,
                     name: "B Button".to_string(),
                     color: None,
+                    player: None,
+                    device: None,
+                    group: None,
                 },
             ],
             ..Default::default()
@@ -265,11 +625,17 @@ mod tests {
                     id: 0,
                     name: "A Button".to_string(),
                     color: Some([255, 0, 0]),
+                    player: None,
+                    device: None,
+                    group: None,
                 },
                 InputMapping {
                     id: 1,
                     name: "B Button".to_string(),
                     color: None,
+                    player: None,
+                    device: None,
+                    group: None,
                 },
             ],
             events: vec![
@@ -279,6 +645,7 @@ mod tests {
                     kind: InputKind::Button,
                     state: ButtonState::Pressed,
                     value: [1.0, 0.0],
+                    contact: 0,
                 },
                 InputEvent {
                     frame: 1,
@@ -286,6 +653,7 @@ mod tests {
                     kind: InputKind::Button,
                     state: ButtonState::Pressed,
                     value: [1.0, 0.0],
+                    contact: 0,
                 },
             ],
             ..Default::default()
@@ -306,6 +674,9 @@ mod tests {
                 id: 0,
                 name: "A Button".to_string(),
                 color: Some([255, 0, 0]),
+                player: None,
+                device: None,
+                group: None,
             }],
             events: vec![
                 InputEvent {
@@ -314,6 +685,7 @@ mod tests {
                     kind: InputKind::Button,
                     state: ButtonState::Pressed,
                     value: [1.0, 0.0],
+                    contact: 0,
                 },
                 InputEvent {
                     frame: 1,
@@ -321,6 +693,7 @@ mod tests {
                     kind: InputKind::Button,
                     state: ButtonState::Pressed,
                     value: [1.0, 0.0],
+                    contact: 0,
                 },
                 InputEvent {
                     frame: 2,
@@ -328,6 +701,7 @@ mod tests {
                     kind: InputKind::Axis1D,
                     state: ButtonState::Released,
                     value: [0.5, 0.0],
+                    contact: 0,
                 },
             ],
             ..Default::default()
@@ -356,4 +730,56 @@ mod tests {
         let effective = log.get_effective_mappings();
         assert!(effective.is_empty());
     }
+
+    #[test]
+    fn test_frame_timing_without_timestamps() {
+        let log = InputLog::default();
+        assert!(!log.has_frame_timestamps());
+        assert_eq!(log.nominal_frame_duration_us(), 16_666);
+        assert_eq!(log.frame_duration_us(0), None);
+        assert_eq!(log.effective_frame_duration_us(0), 16_666);
+        assert!((log.frame_time_secs(30) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_frame_timing_with_timestamps() {
+        let log = InputLog {
+            frame_timestamps: vec![1_000, 17_000, 50_000],
+            ..Default::default()
+        };
+        assert!(log.has_frame_timestamps());
+        assert_eq!(log.frame_duration_us(0), Some(16_000));
+        assert_eq!(log.frame_duration_us(1), Some(33_000));
+        // The last frame has no successor, so it falls back to the nominal duration
+        assert_eq!(log.frame_duration_us(2), None);
+        assert_eq!(log.effective_frame_duration_us(2), 16_666);
+        assert!((log.frame_time_secs(2) - 0.049).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_players_with_duplicate_names() {
+        let mapping = |id, player| InputMapping {
+            id,
+            name: "Jump".to_string(),
+            color: None,
+            player,
+            device: Some(DeviceType::Gamepad),
+            group: None,
+        };
+        let log = InputLog {
+            mappings: vec![
+                mapping(0, Some(1)),
+                mapping(1, Some(0)),
+                mapping(2, Some(1)),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(log.players(), vec![0, 1]);
+        assert_eq!(log.get_input_player(1), Some(0));
+        assert_eq!(log.get_input_name(0), "P2 Jump");
+        assert_eq!(log.get_input_name(1), "P1 Jump");
+        assert_eq!(player_label(1), "Player 2");
+        assert!(InputLog::default().players().is_empty());
+    }
 }
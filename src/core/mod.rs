@@ -1,9 +1,28 @@
 //! Core module for input log data structures and processing.
 
+pub mod compression;
 pub mod config;
+pub mod csv_io;
+pub mod density;
+pub mod edit;
 pub mod error;
 pub mod filter;
+pub mod format;
+pub mod history;
+pub mod idle;
+pub mod layout;
 pub mod log;
+pub mod normalize;
 pub mod parser;
 pub mod playback;
+pub mod profile;
 pub mod search;
+pub mod splice;
+pub mod stats;
+pub mod stream;
+pub mod tail;
+#[cfg(test)]
+pub mod test_util;
+pub mod timing;
+pub mod validate;
+pub mod writer;
@@ -0,0 +1,247 @@
+//! Normalization of event encodings.
+//!
+//! Engines encode held buttons differently: some emit a Held event on every
+//! frame a button is down ("dense"), others only emit the Pressed and
+//! Released edges ("sparse"). Both describe the same presses, so a log can
+//! be converted between the two forms without losing information.
+//!
+//! Axis inputs keep their value until the next sample, so samples that
+//! repeat the previous value carry no information and can be dropped.
+//!
+//! Like the functions in `edit`, normalization returns one `EventChange` per
+//! changed input, which can be applied to the log and undone again.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::edit::{EventChange, down_ranges, encode_presses, input_events, is_dense};
+use super::log::{InputEvent, InputKind, InputLog};
+
+/// How held buttons are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoldEncoding {
+    /// A Held event on every frame between Pressed and Released
+    Dense,
+    /// Only Pressed and Released events
+    Sparse,
+}
+
+impl HoldEncoding {
+    /// Encoding used by a log: dense if it has any Held event.
+    pub fn detect(log: &InputLog) -> Self {
+        if is_dense(&log.events) {
+            Self::Dense
+        } else {
+            Self::Sparse
+        }
+    }
+
+    /// Parse an encoding name as used on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dense" => Some(Self::Dense),
+            "sparse" => Some(Self::Sparse),
+            _ => None,
+        }
+    }
+}
+
+/// Normalize the events of a log.
+///
+/// # Arguments
+/// * `log` - The log to normalize (not modified; apply the returned changes)
+/// * `holds` - Encoding to convert inputs with button states to, if any
+/// * `dedup_axes` - Whether to drop axis samples that repeat the previous value
+///
+/// # Returns
+/// One change per input whose events change, in input ID order.
+pub fn normalize(
+    log: &InputLog,
+    holds: Option<HoldEncoding>,
+    dedup_axes: bool,
+) -> Vec<EventChange> {
+    let ids: BTreeSet<u32> = log.events.iter().map(|e| e.id).collect();
+    ids.into_iter()
+        .map(|id| normalize_input(log, id, holds, dedup_axes))
+        .filter(|change| !change.is_noop())
+        .collect()
+}
+
+/// Normalize the events of one input.
+fn normalize_input(
+    log: &InputLog,
+    id: u32,
+    holds: Option<HoldEncoding>,
+    dedup_axes: bool,
+) -> EventChange {
+    let before = input_events(log, id);
+    let after = match (before.first().map(|e| e.kind), holds) {
+        (Some(kind), Some(holds)) if kind.has_button_state() => {
+            encode_holds(&before, kind, id, log.metadata.frame_count, holds)
+        }
+        (Some(kind), _) if dedup_axes && kind.is_sampled() => dedup_samples(&before),
+        _ => before.clone(),
+    };
+    EventChange { id, before, after }
+}
+
+/// Re-encode the presses of an input, each touch contact on its own.
+fn encode_holds(
+    events: &[InputEvent],
+    kind: InputKind,
+    id: u32,
+    frame_count: u64,
+    holds: HoldEncoding,
+) -> Vec<InputEvent> {
+    let mut contacts: BTreeMap<u16, Vec<InputEvent>> = BTreeMap::new();
+    for event in events {
+        contacts
+            .entry(event.contact)
+            .or_default()
+            .push(event.clone());
+    }
+
+    let mut encoded = Vec::with_capacity(events.len());
+    for (&contact, stream) in &contacts {
+        let ranges = down_ranges(stream, frame_count);
+        encoded.extend(encode_presses(
+            stream,
+            kind,
+            id,
+            contact,
+            &ranges,
+            frame_count,
+            holds == HoldEncoding::Dense,
+        ));
+    }
+    // Stable, so contacts keep their order within a frame
+    encoded.sort_by_key(|e| e.frame);
+    encoded
+}
+
+/// Drop samples that repeat the value of the previous one.
+fn dedup_samples(events: &[InputEvent]) -> Vec<InputEvent> {
+    let mut kept: Vec<InputEvent> = Vec::with_capacity(events.len());
+    for event in events {
+        if kept.last().is_none_or(|last| last.value != event.value) {
+            kept.push(event.clone());
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log::ButtonState;
+    use crate::core::test_util::{axis, button, event, log_with};
+
+    fn normalized(log: &InputLog, holds: Option<HoldEncoding>, dedup: bool) -> Vec<InputEvent> {
+        let mut log = log.clone();
+        for change in normalize(&log, holds, dedup) {
+            change.apply(&mut log);
+        }
+        log.events
+    }
+
+    #[test]
+    fn test_dense_and_sparse_round_trip() {
+        let sparse = log_with(
+            20,
+            vec![
+                button(2, 0, ButtonState::Pressed),
+                button(6, 0, ButtonState::Released),
+                button(10, 0, ButtonState::Pressed),
+            ],
+        );
+        assert_eq!(HoldEncoding::detect(&sparse), HoldEncoding::Sparse);
+
+        let dense = normalized(&sparse, Some(HoldEncoding::Dense), false);
+        let states: Vec<(u64, ButtonState)> = dense.iter().map(|e| (e.frame, e.state)).collect();
+        assert_eq!(states.len(), 1 + 3 + 1 + 1 + 9);
+        assert_eq!(states[0], (2, ButtonState::Pressed));
+        assert_eq!(states[1], (3, ButtonState::Held));
+        assert_eq!(states[4], (6, ButtonState::Released));
+        // The last press lasts until the end of the log
+        assert_eq!(states.last(), Some(&(19, ButtonState::Held)));
+
+        let dense_log = log_with(20, dense);
+        assert_eq!(HoldEncoding::detect(&dense_log), HoldEncoding::Dense);
+        let back = normalized(&dense_log, Some(HoldEncoding::Sparse), false);
+        assert_eq!(back, sparse.events);
+
+        // Already normalized logs need no changes
+        assert!(normalize(&sparse, Some(HoldEncoding::Sparse), false).is_empty());
+        assert!(normalize(&dense_log, Some(HoldEncoding::Dense), false).is_empty());
+    }
+
+    #[test]
+    fn test_touch_contacts_are_encoded_separately() {
+        let touch = |frame, contact, state| InputEvent {
+            contact,
+            ..event(frame, 3, InputKind::Touch, state)
+        };
+        let log = log_with(
+            20,
+            vec![
+                touch(1, 0, ButtonState::Pressed),
+                touch(2, 1, ButtonState::Pressed),
+                touch(2, 0, ButtonState::Held),
+                touch(3, 0, ButtonState::Released),
+                touch(3, 1, ButtonState::Held),
+                touch(4, 1, ButtonState::Released),
+            ],
+        );
+
+        let sparse = normalized(&log, Some(HoldEncoding::Sparse), false);
+        let events: Vec<(u64, u16, ButtonState)> = sparse
+            .iter()
+            .map(|e| (e.frame, e.contact, e.state))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (1, 0, ButtonState::Pressed),
+                (2, 1, ButtonState::Pressed),
+                (3, 0, ButtonState::Released),
+                (4, 1, ButtonState::Released),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedup_axes() {
+        let log = log_with(
+            20,
+            vec![
+                axis(0, 1, 0.5),
+                axis(1, 1, 0.5),
+                axis(2, 1, 0.75),
+                axis(3, 1, 0.75),
+                axis(4, 1, 0.5),
+                button(4, 0, ButtonState::Pressed),
+            ],
+        );
+
+        let events = normalized(&log, None, true);
+        let axis_frames: Vec<u64> = events
+            .iter()
+            .filter(|e| e.id == 1)
+            .map(|e| e.frame)
+            .collect();
+        assert_eq!(axis_frames, vec![0, 2, 4]);
+        assert_eq!(events.len(), 4);
+
+        // Without dedup, axis samples are left alone
+        assert!(normalize(&log, Some(HoldEncoding::Sparse), false).is_empty());
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(HoldEncoding::from_name("dense"), Some(HoldEncoding::Dense));
+        assert_eq!(
+            HoldEncoding::from_name("sparse"),
+            Some(HoldEncoding::Sparse)
+        );
+        assert_eq!(HoldEncoding::from_name("edges"), None);
+    }
+}
@@ -1,22 +1,49 @@
-//! Parser for input log files (.ilj and .ilb formats).
+//! Parser for input log files (.ilj, .iljl and .ilb formats).
 //!
-//! This module provides functionality to parse both JSON-formatted (.ilj) and
-//! binary-formatted (.ilb) input log files into the internal `InputLog` structure.
+//! This module provides functionality to parse JSON-formatted (.ilj),
+//! newline-delimited JSON (.iljl) and binary-formatted (.ilb) input log files
+//! into the internal `InputLog` structure.
 
 // Allow dead code for Phase 1 - these types will be used in later phases
 #![allow(dead_code)]
-
 use bytemuck::{Pod, Zeroable};
 use serde::Deserialize;
 use thiserror::Error;
 
-use super::log::{ButtonState, InputEvent, InputKind, InputLog, InputMapping, LogMetadata};
+use super::compression;
+use super::log::{
+    Annotation, ButtonState, DeviceType, InputEvent, InputKind, InputLog, InputMapping, LogMetadata,
+};
 
 /// Expected magic number for binary files: "ILOG"
-const BINARY_MAGIC: [u8; 4] = *b"ILOG";
+pub const BINARY_MAGIC: [u8; 4] = *b"ILOG";
 
 /// Currently supported binary format version
-const BINARY_VERSION: u32 = 1;
+pub const BINARY_VERSION: u32 = 1;
+
+/// Header flag: the events are followed by a CRC-32 of the header and events.
+pub const BINARY_FLAG_CHECKSUM: u32 = 1 << 0;
+
+/// Header flag: the events are stored as compressed records (see `compression`).
+pub const BINARY_FLAG_COMPRESSED: u32 = 1 << 1;
+
+/// Header flag: the events are followed by a block of per-frame timestamps.
+pub const BINARY_FLAG_TIMESTAMPS: u32 = 1 << 2;
+
+/// Header flag: a block of annotations follows the events and timestamps.
+pub const BINARY_FLAG_ANNOTATIONS: u32 = 1 << 3;
+
+/// All header flags understood by this version of the parser.
+pub const BINARY_KNOWN_FLAGS: u32 = BINARY_FLAG_CHECKSUM
+    | BINARY_FLAG_COMPRESSED
+    | BINARY_FLAG_TIMESTAMPS
+    | BINARY_FLAG_ANNOTATIONS;
+
+/// Annotation record flag: the record carries a telemetry value.
+pub const BINARY_ANNOTATION_HAS_VALUE: u8 = 1 << 0;
+
+/// Size of the optional checksum trailer in bytes.
+pub const BINARY_CHECKSUM_SIZE: usize = 4;
 
 /// Errors that can occur during input log parsing.
 #[derive(Debug, Error)]
@@ -65,6 +92,45 @@ pub enum ParseError {
         header_count: u64,
         actual_count: usize,
     },
+
+    /// Binary file ends before all events declared in the header
+    #[error(
+        "File is truncated: header declares {header_count} events, but only {recoverable} complete events are present"
+    )]
+    Truncated {
+        header_count: u64,
+        recoverable: usize,
+        /// Unknown for compressed files, whose records vary in size
+        missing_bytes: Option<u64>,
+    },
+
+    /// Checksum trailer does not match the file content
+    #[error("Checksum mismatch: file stores {stored:08X}, content hashes to {computed:08X}")]
+    ChecksumMismatch { stored: u32, computed: u32 },
+
+    /// Per-frame timestamps go backwards
+    #[error("Invalid frame timestamps: frame {frame} starts before the previous frame")]
+    InvalidTimestamps { frame: usize },
+
+    /// Binary annotation record that cannot be decoded
+    #[error("Invalid annotation at index {index}: {reason}")]
+    InvalidAnnotation { index: usize, reason: String },
+
+    /// Binary header uses flags this version does not understand
+    #[error("Unsupported header flags {flags:#x}")]
+    UnsupportedFlags { flags: u32 },
+
+    /// Invalid line in a newline-delimited JSON file
+    #[error("Invalid entry on line {line}: {message}")]
+    InvalidLine {
+        line: usize,
+        column: Option<usize>,
+        message: String,
+    },
+
+    /// Text-based file that is not valid UTF-8
+    #[error("File is not valid UTF-8: {0}")]
+    InvalidEncoding(#[from] std::str::Utf8Error),
 }
 
 // ============================================================================
@@ -79,6 +145,10 @@ struct JsonInputLog {
     #[serde(default)]
     mappings: Vec<JsonMapping>,
     events: Vec<JsonEvent>,
+    #[serde(default)]
+    timestamps_us: Vec<u64>,
+    #[serde(default)]
+    annotations: Vec<JsonAnnotation>,
 }
 
 /// Metadata section in JSON format.
@@ -99,6 +169,49 @@ struct JsonMapping {
     name: String,
     #[serde(default)]
     color: Option<String>,
+    #[serde(default)]
+    player: Option<u32>,
+    #[serde(default)]
+    device: Option<String>,
+    #[serde(default)]
+    group: Option<String>,
+}
+
+/// Game event or telemetry sample in JSON format.
+#[derive(Debug, Deserialize)]
+struct JsonAnnotation {
+    frame: u64,
+    label: String,
+    #[serde(default)]
+    value: Option<f32>,
+}
+
+/// Header line of a newline-delimited JSON (.iljl) file.
+#[derive(Debug, Deserialize)]
+struct JsonLinesHeader {
+    version: u32,
+    metadata: JsonLinesMetadata,
+    #[serde(default)]
+    mappings: Vec<JsonMapping>,
+    #[serde(default)]
+    timestamps_us: Vec<u64>,
+    #[serde(default)]
+    annotations: Vec<JsonAnnotation>,
+}
+
+/// Metadata in a .iljl header.
+///
+/// `frame_count` is optional because a game writing the file incrementally
+/// doesn't know it up front; it is extended to cover the events.
+#[derive(Debug, Deserialize)]
+struct JsonLinesMetadata {
+    target_fps: u32,
+    #[serde(default)]
+    frame_count: u64,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    source: Option<String>,
 }
 
 /// Input event in JSON format.
@@ -110,6 +223,8 @@ struct JsonEvent {
     #[serde(default)]
     state: Option<String>,
     value: [f32; 2],
+    #[serde(default)]
+    contact: u16,
 }
 
 // ============================================================================
@@ -127,7 +242,7 @@ pub struct BinaryHeader {
     pub magic: [u8; 4],
     /// Format version number (currently 1)
     pub version: u32,
-    /// Reserved flags (currently unused, must be 0)
+    /// Feature flags (`BINARY_FLAG_*`); unknown bits are rejected
     pub flags: u32,
     /// Target frames per second of the original game
     pub target_fps: u32,
@@ -156,8 +271,61 @@ impl BinaryHeader {
             });
         }
 
+        // Check flags
+        if self.flags & !BINARY_KNOWN_FLAGS != 0 {
+            return Err(ParseError::UnsupportedFlags { flags: self.flags });
+        }
+
         Ok(())
     }
+
+    /// Whether the events are followed by a CRC-32 trailer.
+    pub fn has_checksum(&self) -> bool {
+        self.flags & BINARY_FLAG_CHECKSUM != 0
+    }
+
+    /// Whether the events are stored as compressed records.
+    pub fn is_compressed(&self) -> bool {
+        self.flags & BINARY_FLAG_COMPRESSED != 0
+    }
+
+    /// Whether the events are followed by per-frame timestamps.
+    pub fn has_timestamps(&self) -> bool {
+        self.flags & BINARY_FLAG_TIMESTAMPS != 0
+    }
+
+    /// Whether the file ends with a block of annotations.
+    pub fn has_annotations(&self) -> bool {
+        self.flags & BINARY_FLAG_ANNOTATIONS != 0
+    }
+}
+
+/// Lookup table for `crc32`, built at compile time.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 (IEEE 802.3, as used by zip and PNG) of a byte slice.
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
 }
 
 /// Binary event structure (24 bytes).
@@ -170,12 +338,13 @@ pub struct BinaryEvent {
     pub frame: u64,
     /// Input identifier
     pub id: u32,
-    /// Input kind (0=Button, 1=Axis1D, 2=Axis2D)
+    /// Input kind (0=Button, 1=Axis1D, 2=Axis2D, 3=Trigger, 4=MouseDelta,
+    /// 5=PointerPosition, 6=Touch, 7=Key)
     pub kind: u8,
     /// Button state (0=Released, 1=Pressed, 2=Held)
     pub state: u8,
-    /// Padding for alignment
-    pub _padding: [u8; 2],
+    /// Touch contact identifier, little-endian (zero padding in older files)
+    pub contact: [u8; 2],
     /// Input values (1D uses index 0, 2D uses both)
     pub value: [f32; 2],
 }
@@ -184,18 +353,25 @@ impl BinaryEvent {
     /// Size of a single event in bytes.
     pub const SIZE: usize = std::mem::size_of::<Self>();
 
+    /// Create a binary event from an `InputEvent`.
+    pub fn from_input_event(event: &InputEvent) -> Self {
+        Self {
+            frame: event.frame,
+            id: event.id,
+            kind: event.kind as u8,
+            state: event.state as u8,
+            contact: event.contact.to_le_bytes(),
+            value: event.value,
+        }
+    }
+
     /// Convert the binary event to an `InputEvent`.
     pub fn to_input_event(self, index: usize) -> Result<InputEvent, ParseError> {
-        let kind = match self.kind {
-            0 => InputKind::Button,
-            1 => InputKind::Axis1D,
-            2 => InputKind::Axis2D,
-            _ => {
-                return Err(ParseError::InvalidBinaryEvent {
-                    index,
-                    reason: format!("invalid kind value: {}", self.kind),
-                });
-            }
+        let Some(kind) = InputKind::from_u8(self.kind) else {
+            return Err(ParseError::InvalidBinaryEvent {
+                index,
+                reason: format!("invalid kind value: {}", self.kind),
+            });
         };
 
         let state = match self.state {
@@ -216,6 +392,12 @@ impl BinaryEvent {
             kind,
             state,
             value: self.value,
+            // Older writers leave the padding zeroed; only touches use it
+            contact: if kind == InputKind::Touch {
+                u16::from_le_bytes(self.contact)
+            } else {
+                0
+            },
         })
     }
 }
@@ -272,24 +454,57 @@ pub fn parse_json(content: &str) -> Result<InputLog, ParseError> {
         .map(convert_event)
         .collect::<Result<Vec<_>, _>>()?;
 
+    validate_timestamps(&json_log.timestamps_us)?;
+
     Ok(InputLog {
         metadata,
         mappings,
         events,
+        frame_timestamps: json_log.timestamps_us,
+        annotations: convert_annotations(json_log.annotations),
     })
 }
 
+/// Check that per-frame timestamps never go backwards.
+fn validate_timestamps(timestamps: &[u64]) -> Result<(), ParseError> {
+    match timestamps.windows(2).position(|pair| pair[1] < pair[0]) {
+        Some(index) => Err(ParseError::InvalidTimestamps { frame: index + 1 }),
+        None => Ok(()),
+    }
+}
+
+/// Convert JSON annotations, sorting them by frame.
+fn convert_annotations(json: Vec<JsonAnnotation>) -> Vec<Annotation> {
+    let mut annotations: Vec<Annotation> = json
+        .into_iter()
+        .map(|a| Annotation {
+            frame: a.frame,
+            label: a.label,
+            value: a.value,
+        })
+        .collect();
+    annotations.sort_by_key(|a| a.frame);
+    annotations
+}
+
 /// Convert a JSON mapping to an `InputMapping`.
 fn convert_mapping(json: JsonMapping) -> Result<InputMapping, ParseError> {
     let color = match json.color {
         Some(hex) => Some(parse_hex_color(&hex)?),
         None => None,
     };
+    let device = match json.device {
+        Some(device) => Some(parse_device_type(&device)?),
+        None => None,
+    };
 
     Ok(InputMapping {
         id: json.id,
         name: json.name,
         color,
+        player: json.player,
+        device,
+        group: json.group,
     })
 }
 
@@ -297,20 +512,15 @@ fn convert_mapping(json: JsonMapping) -> Result<InputMapping, ParseError> {
 fn convert_event(json: JsonEvent) -> Result<InputEvent, ParseError> {
     let kind = parse_input_kind(&json.kind)?;
 
-    // For Button inputs, state is required; for axis inputs, default to Released
-    let state = match kind {
-        InputKind::Button => match &json.state {
-            Some(s) => parse_button_state(s)?,
-            None => {
-                return Err(ParseError::MissingField {
-                    field: "state (required for Button kind)",
-                });
-            }
-        },
-        InputKind::Axis1D | InputKind::Axis2D => match &json.state {
-            Some(s) => parse_button_state(s)?,
-            None => ButtonState::Released,
-        },
+    // For Button-like inputs, state is required; for analog inputs, default to Released
+    let state = match &json.state {
+        Some(s) => parse_button_state(s)?,
+        None if kind.has_button_state() => {
+            return Err(ParseError::MissingField {
+                field: "state (required for Button, Touch and Key kinds)",
+            });
+        }
+        None => ButtonState::Released,
     };
 
     Ok(InputEvent {
@@ -319,6 +529,7 @@ fn convert_event(json: JsonEvent) -> Result<InputEvent, ParseError> {
         kind,
         state,
         value: json.value,
+        contact: json.contact,
     })
 }
 
@@ -346,58 +557,1144 @@ fn parse_hex_color(hex: &str) -> Result<[u8; 3], ParseError> {
     Ok([r, g, b])
 }
 
-/// Parse an input kind string into an `InputKind` enum.
-fn parse_input_kind(s: &str) -> Result<InputKind, ParseError> {
-    match s {
-        "Button" => Ok(InputKind::Button),
-        "Axis1D" => Ok(InputKind::Axis1D),
-        "Axis2D" => Ok(InputKind::Axis2D),
-        _ => Err(ParseError::InvalidEnumValue {
-            field: "kind",
-            value: s.to_string(),
-            expected: "Button, Axis1D, Axis2D",
-        }),
+/// Parse an input kind string into an `InputKind` enum.
+pub(crate) fn parse_input_kind(s: &str) -> Result<InputKind, ParseError> {
+    match s {
+        "Button" => Ok(InputKind::Button),
+        "Axis1D" => Ok(InputKind::Axis1D),
+        "Axis2D" => Ok(InputKind::Axis2D),
+        "Trigger" => Ok(InputKind::Trigger),
+        "MouseDelta" => Ok(InputKind::MouseDelta),
+        "PointerPosition" => Ok(InputKind::PointerPosition),
+        "Touch" => Ok(InputKind::Touch),
+        "Key" => Ok(InputKind::Key),
+        _ => Err(ParseError::InvalidEnumValue {
+            field: "kind",
+            value: s.to_string(),
+            expected: "Button, Axis1D, Axis2D, Trigger, MouseDelta, PointerPosition, Touch, Key",
+        }),
+    }
+}
+
+/// Parse a device type string into a `DeviceType` enum.
+pub(crate) fn parse_device_type(s: &str) -> Result<DeviceType, ParseError> {
+    match s {
+        "Gamepad" => Ok(DeviceType::Gamepad),
+        "Keyboard" => Ok(DeviceType::Keyboard),
+        "Mouse" => Ok(DeviceType::Mouse),
+        "Touch" => Ok(DeviceType::Touch),
+        "Other" => Ok(DeviceType::Other),
+        _ => Err(ParseError::InvalidEnumValue {
+            field: "device",
+            value: s.to_string(),
+            expected: "Gamepad, Keyboard, Mouse, Touch, Other",
+        }),
+    }
+}
+
+/// Parse a button state string into a `ButtonState` enum.
+pub(crate) fn parse_button_state(s: &str) -> Result<ButtonState, ParseError> {
+    match s {
+        "Released" => Ok(ButtonState::Released),
+        "Pressed" => Ok(ButtonState::Pressed),
+        "Held" => Ok(ButtonState::Held),
+        _ => Err(ParseError::InvalidEnumValue {
+            field: "state",
+            value: s.to_string(),
+            expected: "Released, Pressed, Held",
+        }),
+    }
+}
+
+// ============================================================================
+// Lenient JSON parser implementation
+// ============================================================================
+
+/// A recoverable problem found while parsing a log leniently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseWarning {
+    /// Description of the problem and how it was handled
+    pub message: String,
+    /// Index of the affected entry in the file's `events` array, if any
+    pub event_index: Option<usize>,
+    /// Line of the affected entry (1-indexed, 0 for binary files)
+    pub line: usize,
+    /// Column of the affected entry (1-indexed, 0 for binary files)
+    pub column: usize,
+}
+
+/// Result of a lenient parse: the recovered log plus everything that was skipped or repaired.
+#[derive(Debug, Clone)]
+pub struct LenientParse {
+    /// The log built from all usable entries
+    pub log: InputLog,
+    /// Problems that were skipped or repaired
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Parse a JSON string into an `InputLog`, skipping or repairing bad entries.
+///
+/// Unlike `parse_json`, problems in individual mappings or events do not
+/// abort the parse:
+/// - events with an unknown `kind` or missing fields are skipped
+/// - a missing or invalid Button `state` is inferred from the previous state
+/// - mappings with an invalid color are kept without a color
+/// - a truncated file keeps every complete event before the cut
+///
+/// # Arguments
+/// * `content` - The JSON string to parse
+///
+/// # Returns
+/// * `Ok(LenientParse)` - The recovered log and a warning per skipped or repaired entry
+/// * `Err(ParseError)` - The version or metadata could not be read
+pub fn parse_json_lenient(content: &str) -> Result<LenientParse, ParseError> {
+    let mut scanner = JsonScanner::new(content);
+    let Some((fields, closed)) = scanner.scan_object() else {
+        return Err(strict_json_error(content));
+    };
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, span)| *span)
+    };
+
+    let mut warnings = Vec::new();
+    let mut warn = |message: String, event_index: Option<usize>, offset: usize| {
+        let (line, column) = line_column(content, offset);
+        warnings.push(ParseWarning {
+            message,
+            event_index,
+            line,
+            column,
+        });
+    };
+
+    // Version and metadata are required and parsed strictly
+    let version: u32 = match field("version") {
+        Some(span) if span.complete => {
+            serde_json::from_str(span.slice(content)).map_err(|_| strict_json_error(content))?
+        }
+        _ => return Err(ParseError::MissingField { field: "version" }),
+    };
+    if version != 1 {
+        return Err(ParseError::UnsupportedVersion { version });
+    }
+
+    let json_metadata: JsonMetadata = match field("metadata") {
+        Some(span) if span.complete => {
+            serde_json::from_str(span.slice(content)).map_err(|_| strict_json_error(content))?
+        }
+        _ => return Err(ParseError::MissingField { field: "metadata" }),
+    };
+
+    // Mappings: skip broken entries, drop invalid colors
+    let mut mappings = Vec::new();
+    if let Some(span) = field("mappings") {
+        for (i, entry) in scan_array_at(content, span).into_iter().enumerate() {
+            if !entry.complete {
+                warn(
+                    format!("Mapping {} is truncated and was skipped", i),
+                    None,
+                    entry.start,
+                );
+                continue;
+            }
+            let json: JsonMapping = match serde_json::from_str(entry.slice(content)) {
+                Ok(json) => json,
+                Err(e) => {
+                    warn(
+                        format!("Mapping {} was skipped: {}", i, e),
+                        None,
+                        entry.start,
+                    );
+                    continue;
+                }
+            };
+            let color = match json.color.as_deref().map(parse_hex_color) {
+                Some(Ok(color)) => Some(color),
+                Some(Err(e)) => {
+                    warn(
+                        format!("Mapping {} ('{}'): {}; color ignored", i, json.name, e),
+                        None,
+                        entry.start,
+                    );
+                    None
+                }
+                None => None,
+            };
+            let device = match json.device.as_deref().map(parse_device_type) {
+                Some(Ok(device)) => Some(device),
+                Some(Err(e)) => {
+                    warn(
+                        format!("Mapping {} ('{}'): {}; device ignored", i, json.name, e),
+                        None,
+                        entry.start,
+                    );
+                    None
+                }
+                None => None,
+            };
+            mappings.push(InputMapping {
+                id: json.id,
+                name: json.name,
+                color,
+                player: json.player,
+                device,
+                group: None,
+            });
+        }
+    }
+
+    // Events: skip broken entries, repair button states
+    let mut events = Vec::new();
+    let mut last_state: std::collections::HashMap<u32, ButtonState> =
+        std::collections::HashMap::new();
+    match field("events") {
+        Some(span) => {
+            for (i, entry) in scan_array_at(content, span).into_iter().enumerate() {
+                if !entry.complete {
+                    warn(
+                        format!("Event {} is truncated and was skipped", i),
+                        Some(i),
+                        entry.start,
+                    );
+                    continue;
+                }
+                let json: JsonEvent = match serde_json::from_str(entry.slice(content)) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        warn(
+                            format!("Event {} was skipped: {}", i, e),
+                            Some(i),
+                            entry.start,
+                        );
+                        continue;
+                    }
+                };
+                let kind = match parse_input_kind(&json.kind) {
+                    Ok(kind) => kind,
+                    Err(e) => {
+                        warn(
+                            format!("Event {} was skipped: {}", i, e),
+                            Some(i),
+                            entry.start,
+                        );
+                        continue;
+                    }
+                };
+                let parsed_state = json.state.as_deref().map(parse_button_state);
+                let state = match (kind, parsed_state) {
+                    (_, Some(Ok(state))) => state,
+                    (kind, problem) if kind.has_button_state() => {
+                        // Continue a press that is already down, otherwise start one
+                        let state = match last_state.get(&json.id) {
+                            Some(ButtonState::Pressed) | Some(ButtonState::Held) => {
+                                ButtonState::Held
+                            }
+                            _ => ButtonState::Pressed,
+                        };
+                        let reason = match problem {
+                            Some(Err(e)) => e.to_string(),
+                            _ => "missing state".to_string(),
+                        };
+                        warn(
+                            format!("Event {}: {}; assumed {:?}", i, reason, state),
+                            Some(i),
+                            entry.start,
+                        );
+                        state
+                    }
+                    (_, Some(Err(e))) => {
+                        warn(
+                            format!("Event {}: {}; assumed Released", i, e),
+                            Some(i),
+                            entry.start,
+                        );
+                        ButtonState::Released
+                    }
+                    (_, None) => ButtonState::Released,
+                };
+                if kind.has_button_state() {
+                    last_state.insert(json.id, state);
+                }
+                events.push(InputEvent {
+                    frame: json.frame,
+                    id: json.id,
+                    kind,
+                    state,
+                    value: json.value,
+                    contact: json.contact,
+                });
+            }
+        }
+        None if closed => return Err(ParseError::MissingField { field: "events" }),
+        None => warn(
+            "File ends before the events array; no events were loaded".to_string(),
+            None,
+            content.len(),
+        ),
+    }
+
+    // Timestamps: drop them entirely if they can't be trusted
+    let mut frame_timestamps = Vec::new();
+    if let Some(span) = field("timestamps_us") {
+        if !span.complete {
+            warn(
+                "Frame timestamps are truncated and were ignored".to_string(),
+                None,
+                span.start,
+            );
+        } else {
+            let parsed = serde_json::from_str::<Vec<u64>>(span.slice(content))
+                .map_err(ParseError::from)
+                .and_then(|timestamps| validate_timestamps(&timestamps).map(|()| timestamps));
+            match parsed {
+                Ok(timestamps) => frame_timestamps = timestamps,
+                Err(e) => warn(
+                    format!("Frame timestamps were ignored: {}", e),
+                    None,
+                    span.start,
+                ),
+            }
+        }
+    }
+
+    // Annotations: skip broken entries
+    let mut annotations = Vec::new();
+    if let Some(span) = field("annotations") {
+        for (i, entry) in scan_array_at(content, span).into_iter().enumerate() {
+            if !entry.complete {
+                warn(
+                    format!("Annotation {} is truncated and was skipped", i),
+                    None,
+                    entry.start,
+                );
+                continue;
+            }
+            match serde_json::from_str::<JsonAnnotation>(entry.slice(content)) {
+                Ok(json) => annotations.push(json),
+                Err(e) => warn(
+                    format!("Annotation {} was skipped: {}", i, e),
+                    None,
+                    entry.start,
+                ),
+            }
+        }
+    }
+
+    if !closed {
+        warn(
+            format!("File is truncated; loaded {} events", events.len()),
+            None,
+            content.len(),
+        );
+    }
+
+    Ok(LenientParse {
+        log: InputLog {
+            metadata: LogMetadata {
+                version,
+                target_fps: json_metadata.target_fps,
+                frame_count: json_metadata.frame_count,
+                created_at: json_metadata.created_at,
+                source: json_metadata.source,
+            },
+            mappings,
+            events,
+            frame_timestamps,
+            annotations: convert_annotations(annotations),
+        },
+        warnings,
+    })
+}
+
+/// Find the byte offset just after the `[` that opens the `events` array.
+///
+/// Returns `None` if the content does not (yet) contain an `events` array,
+/// e.g. because a writer has only written the header so far.
+pub fn json_events_start(content: &str) -> Option<usize> {
+    let mut scanner = JsonScanner::new(content);
+    let (fields, _) = scanner.scan_object()?;
+    let (_, span) = fields.into_iter().find(|(key, _)| key == "events")?;
+    (content.as_bytes().get(span.start) == Some(&b'[')).then_some(span.start + 1)
+}
+
+/// Parse the complete events at the start of a slice of a JSON `events` array.
+///
+/// The slice may start in the middle of the array (directly after a
+/// previously parsed event) and may end in the middle of an event, as
+/// happens while a file is still being written. Parsing stops before the
+/// first incomplete event.
+///
+/// # Arguments
+/// * `content` - Content of the events array from some element boundary onward
+///
+/// # Returns
+/// * `Ok((events, consumed, finished))` - The parsed events, the number of
+///   bytes consumed, and whether the closing `]` of the array was reached
+/// * `Err(ParseError)` - A complete event is invalid
+pub fn parse_json_events_partial(
+    content: &str,
+) -> Result<(Vec<InputEvent>, usize, bool), ParseError> {
+    let mut scanner = JsonScanner::new(content);
+    let mut events = Vec::new();
+    let mut consumed = 0;
+
+    loop {
+        scanner.skip_whitespace();
+        match scanner.peek() {
+            Some(b',') => {
+                scanner.pos += 1;
+                continue;
+            }
+            Some(b']') => return Ok((events, scanner.pos + 1, true)),
+            None => return Ok((events, consumed, false)),
+            Some(_) => {}
+        }
+
+        let element = scanner.skip_value();
+        if !element.complete {
+            return Ok((events, consumed, false));
+        }
+        let json: JsonEvent = serde_json::from_str(element.slice(content))?;
+        events.push(convert_event(json)?);
+        consumed = element.end;
+    }
+}
+
+/// Get the error a strict parse reports, for structure the lenient parser cannot recover.
+fn strict_json_error(content: &str) -> ParseError {
+    match serde_json::from_str::<JsonInputLog>(content) {
+        Err(e) => ParseError::JsonSyntax(e),
+        Ok(_) => ParseError::MissingField { field: "events" },
+    }
+}
+
+/// Convert a byte offset into a 1-indexed line and column.
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+/// Scan the elements of the array found at `span`.
+fn scan_array_at(content: &str, span: JsonSpan) -> Vec<JsonSpan> {
+    let mut scanner = JsonScanner::new(content);
+    scanner.pos = span.start;
+    scanner.scan_array().unwrap_or_default()
+}
+
+/// Byte range of a JSON value located by `JsonScanner`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct JsonSpan {
+    /// Offset of the first byte of the value
+    start: usize,
+    /// Offset one past the last byte of the value
+    end: usize,
+    /// False if the input ended before the value did
+    complete: bool,
+}
+
+impl JsonSpan {
+    fn slice<'a>(&self, content: &'a str) -> &'a str {
+        &content[self.start..self.end]
+    }
+}
+
+/// Minimal JSON scanner that locates values without parsing them.
+///
+/// It is used to split a log into top-level fields and array elements so
+/// each entry can be parsed on its own, and it tolerates input that ends in
+/// the middle of a value.
+struct JsonScanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> JsonScanner<'a> {
+    fn new(content: &'a str) -> Self {
+        Self {
+            bytes: content.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|b| b.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// Skip a string starting at the current `"`. Returns false if it is unterminated.
+    fn skip_string(&mut self) -> bool {
+        self.pos += 1;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'\\' => self.pos += 1,
+                b'"' => return true,
+                _ => {}
+            }
+        }
+        self.pos = self.bytes.len();
+        false
+    }
+
+    /// Skip any value starting at the current position and return its span.
+    fn skip_value(&mut self) -> JsonSpan {
+        let start = self.pos;
+        let complete = match self.peek() {
+            Some(b'"') => self.skip_string(),
+            Some(b'{') | Some(b'[') => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek() {
+                        None => break false,
+                        Some(b'"') => {
+                            if !self.skip_string() {
+                                break false;
+                            }
+                            continue;
+                        }
+                        Some(b'{') | Some(b'[') => depth += 1,
+                        Some(b'}') | Some(b']') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                break true;
+                            }
+                        }
+                        Some(_) => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => {
+                // Scalar: runs until a delimiter; a scalar cut off by the end of input is incomplete
+                while self
+                    .peek()
+                    .is_some_and(|b| !matches!(b, b',' | b']' | b'}') && !b.is_ascii_whitespace())
+                {
+                    self.pos += 1;
+                }
+                self.pos > start && self.pos < self.bytes.len()
+            }
+        };
+        JsonSpan {
+            start,
+            end: self.pos,
+            complete,
+        }
+    }
+
+    /// Scan an object at the current position into `(key, value span)` pairs.
+    ///
+    /// Returns `None` if there is no object here, otherwise the fields found
+    /// and whether the closing brace was reached.
+    fn scan_object(&mut self) -> Option<(Vec<(String, JsonSpan)>, bool)> {
+        self.skip_whitespace();
+        if self.peek() != Some(b'{') {
+            return None;
+        }
+        self.pos += 1;
+
+        let mut fields = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'}') => return Some((fields, true)),
+                Some(b'"') => {}
+                _ => return Some((fields, false)),
+            }
+            let key_span = self.skip_value();
+            let Ok(key) =
+                serde_json::from_slice::<String>(&self.bytes[key_span.start..key_span.end])
+            else {
+                return Some((fields, false));
+            };
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                return Some((fields, false));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let value = self.skip_value();
+            fields.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => return Some((fields, true)),
+                _ => return Some((fields, false)),
+            }
+        }
+    }
+
+    /// Scan an array at the current position into element spans.
+    ///
+    /// Returns `None` if there is no array here. Elements cut off by the end
+    /// of input are included and marked incomplete.
+    fn scan_array(&mut self) -> Option<Vec<JsonSpan>> {
+        self.skip_whitespace();
+        if self.peek() != Some(b'[') {
+            return None;
+        }
+        self.pos += 1;
+
+        let mut elements = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b']') | None => return Some(elements),
+                _ => {}
+            }
+            let element = self.skip_value();
+            if element.end == element.start {
+                // Not a value (e.g. a stray delimiter); stop rather than loop forever
+                return Some(elements);
+            }
+            elements.push(element);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                _ => return Some(elements),
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Newline-delimited JSON parser implementation
+// ============================================================================
+
+/// Incremental parser for newline-delimited JSON (.iljl) content.
+///
+/// A `.iljl` file starts with a header line holding `version`, `metadata`
+/// and `mappings`, followed by one event object per line:
+///
+/// ```text
+/// {"version": 1, "metadata": {"target_fps": 60}, "mappings": [...]}
+/// {"frame": 0, "id": 0, "kind": "Button", "state": "Pressed", "value": [0.0, 0.0]}
+/// ```
+///
+/// Text can be fed in as it is written; only lines terminated by a newline
+/// are consumed, so a line that is still being written waits for the next call.
+#[derive(Debug, Clone, Default)]
+pub struct JsonLinesParser {
+    /// Log built from the header line (None until the header is complete)
+    header: Option<InputLog>,
+    /// Number of lines consumed so far
+    lines: usize,
+}
+
+impl JsonLinesParser {
+    /// Create a parser that expects the header line next.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The log described by the header line, without events.
+    pub fn header(&self) -> Option<&InputLog> {
+        self.header.as_ref()
+    }
+
+    /// Parse all complete lines at the start of `content`.
+    ///
+    /// # Arguments
+    /// * `content` - Text directly following the previously consumed bytes
+    ///
+    /// # Returns
+    /// * `Ok((events, consumed))` - Events from the complete lines and the
+    ///   number of bytes consumed (up to and including the last newline)
+    /// * `Err(ParseError)` - A complete line is invalid
+    pub fn feed(&mut self, content: &str) -> Result<(Vec<InputEvent>, usize), ParseError> {
+        let mut events = Vec::new();
+        let mut consumed = 0;
+
+        while let Some(end) = content[consumed..].find('\n') {
+            let line = &content[consumed..consumed + end];
+            self.parse_line(line, &mut events)?;
+            consumed += end + 1;
+        }
+
+        Ok((events, consumed))
+    }
+
+    /// Parse the final line of a file, which may lack a trailing newline.
+    ///
+    /// A line that ends in the middle of a JSON value (e.g. because the
+    /// writer crashed) is dropped; any other error is reported.
+    ///
+    /// # Returns
+    /// * `Ok((events, truncated))` - Events from the line and whether it was dropped
+    /// * `Err(ParseError)` - The line is complete but invalid
+    pub fn finish(&mut self, remainder: &str) -> Result<(Vec<InputEvent>, bool), ParseError> {
+        let mut events = Vec::new();
+        match self.parse_line(remainder, &mut events) {
+            Ok(()) => Ok((events, false)),
+            Err(ParseError::InvalidLine { .. }) if self.is_truncated(remainder) => {
+                Ok((events, true))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Check whether a line ends before its JSON value is complete.
+    fn is_truncated(&self, line: &str) -> bool {
+        serde_json::from_str::<serde_json::Value>(line).is_err_and(|e| e.is_eof())
+    }
+
+    /// Parse one line into the header or an event.
+    fn parse_line(&mut self, line: &str, events: &mut Vec<InputEvent>) -> Result<(), ParseError> {
+        self.lines += 1;
+        let line_number = self.lines;
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+
+        let at_line = |e: ParseError| match e {
+            ParseError::JsonSyntax(e) => {
+                let message = e.to_string();
+                // serde_json appends its own (always line 1) position
+                let message = match message.rsplit_once(" at line ") {
+                    Some((message, _)) => message.to_string(),
+                    None => message,
+                };
+                ParseError::InvalidLine {
+                    line: line_number,
+                    column: (e.column() > 0).then_some(e.column()),
+                    message,
+                }
+            }
+            other => ParseError::InvalidLine {
+                line: line_number,
+                column: None,
+                message: other.to_string(),
+            },
+        };
+
+        match self.header {
+            None => {
+                self.header = Some(parse_json_lines_header(line).map_err(at_line)?);
+            }
+            Some(_) => {
+                let json: JsonEvent =
+                    serde_json::from_str(line).map_err(|e| at_line(ParseError::JsonSyntax(e)))?;
+                events.push(convert_event(json).map_err(at_line)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse the header line of a `.iljl` file into a log without events.
+fn parse_json_lines_header(line: &str) -> Result<InputLog, ParseError> {
+    let header: JsonLinesHeader = serde_json::from_str(line)?;
+    if header.version != 1 {
+        return Err(ParseError::UnsupportedVersion {
+            version: header.version,
+        });
+    }
+
+    let mappings = header
+        .mappings
+        .into_iter()
+        .map(convert_mapping)
+        .collect::<Result<Vec<_>, _>>()?;
+    validate_timestamps(&header.timestamps_us)?;
+
+    Ok(InputLog {
+        metadata: LogMetadata {
+            version: header.version,
+            target_fps: header.metadata.target_fps,
+            frame_count: header.metadata.frame_count,
+            created_at: header.metadata.created_at,
+            source: header.metadata.source,
+        },
+        mappings,
+        events: Vec::new(),
+        frame_timestamps: header.timestamps_us,
+        annotations: convert_annotations(header.annotations),
+    })
+}
+
+/// Parse newline-delimited JSON (.iljl) content into an `InputLog`.
+///
+/// A truncated final line, as left behind by a writer that crashed mid-line,
+/// is dropped. `frame_count` is extended to cover all events.
+///
+/// # Arguments
+/// * `content` - The file content
+///
+/// # Returns
+/// * `Ok(InputLog)` - Successfully parsed input log
+/// * `Err(ParseError)` - Missing header or an invalid line
+pub fn parse_json_lines(content: &str) -> Result<InputLog, ParseError> {
+    let mut parser = JsonLinesParser::new();
+    let (mut events, consumed) = parser.feed(content)?;
+    let (last, _) = parser.finish(&content[consumed..])?;
+    events.extend(last);
+
+    let mut log = parser.header.ok_or(ParseError::MissingField {
+        field: "header line",
+    })?;
+    if let Some(max_frame) = events.iter().map(|e| e.frame).max() {
+        log.metadata.frame_count = log.metadata.frame_count.max(max_frame + 1);
+    }
+    log.events = events;
+    Ok(log)
+}
+
+// ============================================================================
+// Binary parser implementation
+// ============================================================================
+
+/// Parse a binary byte slice into an `InputLog`.
+///
+/// # Arguments
+/// * `data` - The raw bytes of the .ilb file
+///
+/// # Returns
+/// * `Ok(InputLog)` - Successfully parsed input log
+/// * `Err(ParseError)` - Parsing failed with a descriptive error
+///
+/// # Binary Format
+/// The binary format consists of:
+/// - A 32-byte header (BinaryHeader)
+/// - Followed by N events: `BinaryEvent`s of 24 bytes each or, if the header
+///   has `BINARY_FLAG_COMPRESSED` set, the records of `compression`
+/// - Followed by the per-frame timestamps, if the header has
+///   `BINARY_FLAG_TIMESTAMPS` set: a u64 count and that many u64 values, or
+///   varints in a compressed file
+/// - Followed by the annotations, if the header has
+///   `BINARY_FLAG_ANNOTATIONS` set: a u32 count and that many records of a u64
+///   frame, u8 flags (`BINARY_ANNOTATION_HAS_VALUE`), f32 value, u16 label
+///   length and the UTF-8 label
+/// - Followed by a little-endian CRC-32 of everything before it, if the
+///   header has `BINARY_FLAG_CHECKSUM` set
+///
+/// A file that ends early fails with `ParseError::Truncated`, which reports
+/// how many complete events `parse_binary_lenient` can still recover.
+///
+/// # Example
+/// ```ignore
+/// let data = std::fs::read("log.ilb")?;
+/// let log = parse_binary(&data)?;
+/// ```
+pub fn parse_binary(data: &[u8]) -> Result<InputLog, ParseError> {
+    let (header, events) = decode_binary(data)?;
+    match events {
+        Ok(body) => Ok(build_binary_log(&header, body)),
+        Err(truncation) => Err(ParseError::Truncated {
+            header_count: header.event_count,
+            recoverable: truncation.events.len(),
+            missing_bytes: truncation.missing_bytes,
+        }),
+    }
+}
+
+/// Parse a binary byte slice, recovering the complete events of a truncated file.
+///
+/// A file cut short (e.g. a partially copied capture) keeps every complete
+/// event before the cut and reports a single warning; `frame_count` is
+/// shortened to end after the last recovered event. All other problems,
+/// including a checksum mismatch, are still errors.
+///
+/// # Arguments
+/// * `data` - The raw bytes of the .ilb file
+///
+/// # Returns
+/// * `Ok(LenientParse)` - The log and, for a truncated file, one warning
+/// * `Err(ParseError)` - The file is corrupt in a way that cannot be recovered
+pub fn parse_binary_lenient(data: &[u8]) -> Result<LenientParse, ParseError> {
+    let (header, events) = decode_binary(data)?;
+    let truncation = match events {
+        Ok(body) => {
+            return Ok(LenientParse {
+                log: build_binary_log(&header, body),
+                warnings: Vec::new(),
+            });
+        }
+        Err(truncation) => truncation,
+    };
+
+    let recoverable = truncation.events.len();
+    let mut log = build_binary_log(&header, BinaryBody::events(truncation.events));
+    let last_frame = log.events.iter().map(|e| e.frame + 1).max().unwrap_or(0);
+    log.metadata.frame_count = log.metadata.frame_count.min(last_frame);
+
+    let missing = match truncation.missing_bytes {
+        Some(bytes) => format!(" ({} bytes missing)", bytes),
+        None => String::new(),
+    };
+    let warning = ParseWarning {
+        message: format!(
+            "File is truncated{}: loaded {} of {} events",
+            missing, recoverable, header.event_count
+        ),
+        event_index: Some(recoverable),
+        line: 0,
+        column: 0,
+    };
+    Ok(LenientParse {
+        log,
+        warnings: vec![warning],
+    })
+}
+
+/// The complete events before the end of a truncated binary file.
+struct Truncation {
+    events: Vec<InputEvent>,
+    /// Number of missing bytes, if it can be known
+    missing_bytes: Option<u64>,
+}
+
+/// Everything a binary file stores after its header.
+struct BinaryBody {
+    events: Vec<InputEvent>,
+    timestamps: Vec<u64>,
+    annotations: Vec<Annotation>,
+}
+
+impl BinaryBody {
+    /// A body with events only.
+    fn events(events: Vec<InputEvent>) -> Self {
+        Self {
+            events,
+            timestamps: Vec::new(),
+            annotations: Vec::new(),
+        }
+    }
+}
+
+/// Read the header, events, timestamps and annotations of a binary file,
+/// verifying its size and checksum.
+///
+/// A truncated file is not an error at this level: the complete events
+/// before the cut are returned as `Err(Truncation)` so that callers can
+/// decide whether to report or recover them. Timestamps and annotations cut
+/// short are dropped.
+fn decode_binary(
+    data: &[u8],
+) -> Result<(BinaryHeader, Result<BinaryBody, Truncation>), ParseError> {
+    let header = read_binary_header(data)?;
+    let body = &data[BinaryHeader::SIZE..];
+    let trailer_len = if header.has_checksum() {
+        BINARY_CHECKSUM_SIZE
+    } else {
+        0
+    };
+
+    // The checksum of a compressed file is computed before its records are
+    // decoded. A mismatch is reported once decoding shows the file is not
+    // merely truncated, since a cut file cannot match either.
+    let mut checksum_error = None;
+    if header.is_compressed() && header.has_checksum() && body.len() >= BINARY_CHECKSUM_SIZE {
+        let (content, trailer) = data.split_at(data.len() - BINARY_CHECKSUM_SIZE);
+        let stored = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let computed = crc32(content);
+        if stored != computed {
+            checksum_error = Some(ParseError::ChecksumMismatch { stored, computed });
+        }
+    }
+
+    // Decode the events and find where they end
+    let (events, events_len) = if header.is_compressed() {
+        let decoded = compression::decode_events(body, header.event_count, header.frame_count)?;
+        if !decoded.complete {
+            let truncation = Truncation {
+                events: decoded.events,
+                missing_bytes: None,
+            };
+            return Ok((header, Err(truncation)));
+        }
+        (Some(decoded.events), decoded.consumed)
+    } else {
+        let expected_len = header.event_count.saturating_mul(BinaryEvent::SIZE as u64);
+        if (body.len() as u64) < expected_len {
+            let whole = body.len() / BinaryEvent::SIZE;
+            let truncation = Truncation {
+                events: parse_binary_events(&body[..whole * BinaryEvent::SIZE])?,
+                missing_bytes: Some(expected_len + trailer_len as u64 - body.len() as u64),
+            };
+            return Ok((header, Err(truncation)));
+        }
+        // Fixed-size records are parsed after the checksum was verified
+        (None, expected_len as usize)
+    };
+    let take_events = |events: Option<Vec<InputEvent>>| match events {
+        Some(events) => Ok(events),
+        None => parse_binary_events(&body[..events_len]),
+    };
+
+    // Per-frame timestamps follow the events
+    let (timestamps, timestamps_len) = if header.has_timestamps() {
+        let block = &body[events_len..];
+        let decoded = if header.is_compressed() {
+            compression::decode_timestamps(block)?
+        } else {
+            read_binary_timestamps(block)?
+        };
+        match decoded {
+            Some(decoded) => decoded,
+            None => {
+                let truncation = Truncation {
+                    events: take_events(events)?,
+                    missing_bytes: None,
+                };
+                return Ok((header, Err(truncation)));
+            }
+        }
+    } else {
+        (Vec::new(), 0)
+    };
+
+    // Annotations come last
+    let (annotations, annotations_len) = if header.has_annotations() {
+        match read_binary_annotations(&body[events_len + timestamps_len..])? {
+            Some(decoded) => decoded,
+            None => {
+                let truncation = Truncation {
+                    events: take_events(events)?,
+                    missing_bytes: None,
+                };
+                return Ok((header, Err(truncation)));
+            }
+        }
+    } else {
+        (Vec::new(), 0)
+    };
+    let content_len = events_len + timestamps_len + annotations_len;
+
+    let rest = &body[content_len..];
+    if rest.len() < trailer_len {
+        let truncation = Truncation {
+            events: take_events(events)?,
+            missing_bytes: Some((trailer_len - rest.len()) as u64),
+        };
+        return Ok((header, Err(truncation)));
+    }
+    let (trailer, extra) = rest.split_at(trailer_len);
+
+    // Data after the declared events means the header is wrong
+    if header.is_compressed() && !extra.is_empty() {
+        return Err(ParseError::InvalidBinaryEvent {
+            index: header.event_count as usize,
+            reason: format!("{} unexpected bytes after the last event", extra.len()),
+        });
+    }
+    if extra.len() >= BinaryEvent::SIZE {
+        return Err(ParseError::EventCountMismatch {
+            header_count: header.event_count,
+            actual_count: header.event_count as usize + extra.len() / BinaryEvent::SIZE,
+        });
+    }
+
+    if let Some(error) = checksum_error {
+        return Err(error);
+    }
+    if header.has_checksum() && !header.is_compressed() {
+        let stored = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let computed = crc32(&data[..BinaryHeader::SIZE + content_len]);
+        if stored != computed {
+            return Err(ParseError::ChecksumMismatch { stored, computed });
+        }
+    }
+
+    validate_timestamps(&timestamps)?;
+    let body = BinaryBody {
+        events: take_events(events)?,
+        timestamps,
+        annotations,
+    };
+    Ok((header, Ok(body)))
+}
+
+/// Read the fixed-size timestamp block of an uncompressed binary file.
+///
+/// Returns `Ok(None)` if the data ends before the last timestamp.
+fn read_binary_timestamps(data: &[u8]) -> Result<Option<(Vec<u64>, usize)>, ParseError> {
+    let read_u64 = |offset: usize| {
+        data.get(offset..offset + 8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap_or_default()))
+    };
+    let Some(count) = read_u64(0) else {
+        return Ok(None);
+    };
+    let len = count
+        .checked_mul(8)
+        .and_then(|len| len.checked_add(8))
+        .and_then(|len| usize::try_from(len).ok());
+    match len {
+        Some(len) if len <= data.len() => {
+            let timestamps = (0..count as usize)
+                .filter_map(|i| read_u64(8 + i * 8))
+                .collect();
+            Ok(Some((timestamps, len)))
+        }
+        _ => Ok(None),
     }
 }
 
-/// Parse a button state string into a `ButtonState` enum.
-fn parse_button_state(s: &str) -> Result<ButtonState, ParseError> {
-    match s {
-        "Released" => Ok(ButtonState::Released),
-        "Pressed" => Ok(ButtonState::Pressed),
-        "Held" => Ok(ButtonState::Held),
-        _ => Err(ParseError::InvalidEnumValue {
-            field: "state",
-            value: s.to_string(),
-            expected: "Released, Pressed, Held",
-        }),
+/// Read the annotation block of a binary file.
+///
+/// Returns `Ok(None)` if the data ends before the last annotation.
+fn read_binary_annotations(data: &[u8]) -> Result<Option<(Vec<Annotation>, usize)>, ParseError> {
+    let Some(count) = data.get(..4) else {
+        return Ok(None);
+    };
+    let count = u32::from_le_bytes(count.try_into().unwrap_or_default());
+    let mut pos = 4;
+    let mut annotations = Vec::new();
+    for index in 0..count as usize {
+        // u64 frame, u8 flags, f32 value, u16 label length
+        let Some(fixed) = data.get(pos..pos + 15) else {
+            return Ok(None);
+        };
+        let frame = u64::from_le_bytes(fixed[..8].try_into().unwrap_or_default());
+        let flags = fixed[8];
+        let value = f32::from_le_bytes(fixed[9..13].try_into().unwrap_or_default());
+        let label_len = u16::from_le_bytes([fixed[13], fixed[14]]) as usize;
+        pos += 15;
+
+        if flags & !BINARY_ANNOTATION_HAS_VALUE != 0 {
+            return Err(ParseError::InvalidAnnotation {
+                index,
+                reason: format!("unknown flags {:#x}", flags),
+            });
+        }
+        let Some(label) = data.get(pos..pos + label_len) else {
+            return Ok(None);
+        };
+        let label = std::str::from_utf8(label).map_err(|e| ParseError::InvalidAnnotation {
+            index,
+            reason: format!("label is not valid UTF-8: {}", e),
+        })?;
+        pos += label_len;
+
+        annotations.push(Annotation {
+            frame,
+            label: label.to_string(),
+            value: (flags & BINARY_ANNOTATION_HAS_VALUE != 0).then_some(value),
+        });
     }
+    annotations.sort_by_key(|a| a.frame);
+    Ok(Some((annotations, pos)))
 }
 
-// ============================================================================
-// Binary parser implementation
-// ============================================================================
-
-/// Parse a binary byte slice into an `InputLog`.
-///
-/// # Arguments
-/// * `data` - The raw bytes of the .ilb file
-///
-/// # Returns
-/// * `Ok(InputLog)` - Successfully parsed input log
-/// * `Err(ParseError)` - Parsing failed with a descriptive error
-///
-/// # Binary Format
-/// The binary format consists of:
-/// - A 32-byte header (BinaryHeader)
-/// - Followed by N events (BinaryEvent), each 24 bytes
-///
-/// # Example
-/// ```ignore
-/// let data = std::fs::read("log.ilb")?;
-/// let log = parse_binary(&data)?;
-/// ```
-pub fn parse_binary(data: &[u8]) -> Result<InputLog, ParseError> {
+/// Read and validate the header of a binary file.
+fn read_binary_header(data: &[u8]) -> Result<BinaryHeader, ParseError> {
     // Check minimum size for header
     if data.len() < BinaryHeader::SIZE {
         return Err(ParseError::FileTooSmall {
@@ -406,33 +1703,23 @@ pub fn parse_binary(data: &[u8]) -> Result<InputLog, ParseError> {
         });
     }
 
-    // Parse and validate header
-    let header: &BinaryHeader = bytemuck::from_bytes(&data[..BinaryHeader::SIZE]);
+    // Parse and validate header (the buffer is not guaranteed to be aligned)
+    let header: BinaryHeader = bytemuck::pod_read_unaligned(&data[..BinaryHeader::SIZE]);
     header.validate()?;
+    Ok(header)
+}
 
-    // Calculate expected data size
-    let event_data_start = BinaryHeader::SIZE;
-    let event_data = &data[event_data_start..];
-    let actual_event_count = event_data.len() / BinaryEvent::SIZE;
-
-    // Verify event count matches header
-    if actual_event_count != header.event_count as usize {
-        return Err(ParseError::EventCountMismatch {
-            header_count: header.event_count,
-            actual_count: actual_event_count,
-        });
-    }
-
-    // Parse events
-    let binary_events: &[BinaryEvent] =
-        bytemuck::cast_slice(&event_data[..actual_event_count * BinaryEvent::SIZE]);
-
-    let events = binary_events
-        .iter()
+/// Convert consecutive binary event records into events.
+fn parse_binary_events(event_data: &[u8]) -> Result<Vec<InputEvent>, ParseError> {
+    event_data
+        .chunks_exact(BinaryEvent::SIZE)
         .enumerate()
-        .map(|(i, e)| (*e).to_input_event(i))
-        .collect::<Result<Vec<_>, _>>()?;
+        .map(|(i, chunk)| bytemuck::pod_read_unaligned::<BinaryEvent>(chunk).to_input_event(i))
+        .collect()
+}
 
+/// Build a log from a binary header and its body.
+fn build_binary_log(header: &BinaryHeader, body: BinaryBody) -> InputLog {
     // Build metadata (binary format doesn't include created_at or source)
     let metadata = LogMetadata {
         version: header.version,
@@ -443,13 +1730,15 @@ pub fn parse_binary(data: &[u8]) -> Result<InputLog, ParseError> {
     };
 
     // Binary format doesn't include mappings - create default mappings from events
-    let mappings = generate_default_mappings(&events);
+    let mappings = generate_default_mappings(&body.events);
 
-    Ok(InputLog {
+    InputLog {
         metadata,
         mappings,
-        events,
-    })
+        events: body.events,
+        frame_timestamps: body.timestamps,
+        annotations: body.annotations,
+    }
 }
 
 /// Generate default mappings from events.
@@ -462,14 +1751,19 @@ fn generate_default_mappings(events: &[InputEvent]) -> Vec<InputMapping> {
     // Collect unique input IDs (BTreeSet keeps them sorted)
     let unique_ids: BTreeSet<u32> = events.iter().map(|e| e.id).collect();
 
-    unique_ids
-        .into_iter()
-        .map(|id| InputMapping {
-            id,
-            name: format!("Input {}", id),
-            color: None,
-        })
-        .collect()
+    unique_ids.into_iter().map(default_mapping).collect()
+}
+
+/// Create the default mapping used for an input ID without mapping information.
+pub fn default_mapping(id: u32) -> InputMapping {
+    InputMapping {
+        id,
+        name: format!("Input {}", id),
+        color: None,
+        player: None,
+        device: None,
+        group: None,
+    }
 }
 
 #[cfg(test)]
@@ -718,7 +2012,7 @@ mod tests {
             id,
             kind,
             state,
-            _padding: [0, 0],
+            contact: [0, 0],
             value: [1.0, 0.0],
         }
     }
@@ -850,24 +2144,328 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_binary_event_count_mismatch() {
+    fn test_parse_binary_truncated() {
         let header = create_test_header(100, 5); // Claims 5 events
         let events = [create_test_binary_event(0, 0, 0, 0)]; // Only 1 event
 
         let mut data: Vec<u8> = bytemuck::bytes_of(&header).to_vec();
         data.extend_from_slice(bytemuck::cast_slice(&events));
+        data.extend_from_slice(&[0u8; 10]); // Half of the second event
 
         let result = parse_binary(&data);
         assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
-            ParseError::EventCountMismatch {
+            ParseError::Truncated {
                 header_count: 5,
-                actual_count: 1
+                recoverable: 1,
+                missing_bytes: Some(86)
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_binary_event_count_mismatch() {
+        let header = create_test_header(100, 1); // Claims 1 event
+        let events = [
+            create_test_binary_event(0, 0, 0, 0),
+            create_test_binary_event(1, 0, 0, 0),
+        ];
+
+        let mut data: Vec<u8> = bytemuck::bytes_of(&header).to_vec();
+        data.extend_from_slice(bytemuck::cast_slice(&events));
+
+        let result = parse_binary(&data);
+        assert!(matches!(
+            result.unwrap_err(),
+            ParseError::EventCountMismatch {
+                header_count: 1,
+                actual_count: 2
             }
         ));
     }
 
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    /// Build a binary file with a checksum trailer.
+    fn create_checksummed_binary(events: &[BinaryEvent]) -> Vec<u8> {
+        let mut header = create_test_header(10, events.len() as u64);
+        header.flags = BINARY_FLAG_CHECKSUM;
+        let mut data: Vec<u8> = bytemuck::bytes_of(&header).to_vec();
+        data.extend_from_slice(bytemuck::cast_slice(events));
+        let crc = crc32(&data);
+        data.extend_from_slice(&crc.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_binary_checksum() {
+        let events = [
+            create_test_binary_event(0, 0, 0, 1),
+            create_test_binary_event(3, 0, 0, 0),
+        ];
+        let data = create_checksummed_binary(&events);
+        assert_eq!(parse_binary(&data).unwrap().events.len(), 2);
+
+        // A single flipped bit in an event is detected
+        let mut corrupted = data.clone();
+        corrupted[BinaryHeader::SIZE] ^= 0x04;
+        assert!(matches!(
+            parse_binary(&corrupted),
+            Err(ParseError::ChecksumMismatch { .. })
+        ));
+
+        // A missing trailer is a truncation with every event recoverable
+        let cut = &data[..data.len() - 2];
+        assert!(matches!(
+            parse_binary(cut),
+            Err(ParseError::Truncated {
+                recoverable: 2,
+                missing_bytes: Some(2),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_binary_rejects_oversized_run() {
+        // 40 bytes: a header declaring 2^34 events and one record repeated 2^34 - 1 times
+        let mut header = create_test_header(120, 1 << 34);
+        header.flags = BINARY_FLAG_COMPRESSED;
+        let mut data: Vec<u8> = bytemuck::bytes_of(&header).to_vec();
+        data.extend_from_slice(&[0x00, 0x00, 0x20, 0xFF, 0xFF, 0xFF, 0xFF, 0x3F]);
+        assert_eq!(data.len(), 40);
+
+        assert!(matches!(
+            parse_binary(&data),
+            Err(ParseError::InvalidBinaryEvent { .. })
+        ));
+        assert!(parse_binary_lenient(&data).is_err());
+    }
+
+    #[test]
+    fn test_parse_compressed_binary_checksum() {
+        let log = InputLog {
+            metadata: LogMetadata {
+                frame_count: 10,
+                ..Default::default()
+            },
+            events: vec![InputEvent {
+                frame: 2,
+                id: 0,
+                kind: InputKind::Button,
+                state: ButtonState::Pressed,
+                value: [0.0, 0.0],
+                contact: 0,
+            }],
+            ..Default::default()
+        };
+        let data = crate::core::writer::write_binary_compressed(&log, true);
+        assert_eq!(parse_binary(&data).unwrap().events, log.events);
+
+        let mut corrupted = data.clone();
+        corrupted[BinaryHeader::SIZE] ^= 0x04;
+        assert!(matches!(
+            parse_binary(&corrupted),
+            Err(ParseError::ChecksumMismatch { .. })
+        ));
+
+        // A cut trailer is still a truncation, not a mismatch
+        let cut = &data[..data.len() - 2];
+        assert!(matches!(
+            parse_binary(cut),
+            Err(ParseError::Truncated { recoverable: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_binary_unknown_flags() {
+        let mut header = create_test_header(10, 0);
+        header.flags = 0x80;
+        let data = bytemuck::bytes_of(&header).to_vec();
+        assert!(matches!(
+            parse_binary(&data),
+            Err(ParseError::UnsupportedFlags { flags: 0x80 })
+        ));
+    }
+
+    #[test]
+    fn test_parse_binary_lenient_recovers_truncated_file() {
+        let events = [
+            create_test_binary_event(0, 0, 0, 1),
+            create_test_binary_event(4, 1, 0, 1),
+            create_test_binary_event(8, 0, 0, 0),
+        ];
+        let data = create_checksummed_binary(&events);
+        let cut = &data[..BinaryHeader::SIZE + 2 * BinaryEvent::SIZE + 5];
+
+        let parsed = parse_binary_lenient(cut).unwrap();
+        assert_eq!(parsed.log.events.len(), 2);
+        assert_eq!(parsed.log.metadata.frame_count, 5);
+        assert_eq!(parsed.log.mappings.len(), 2);
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].event_index, Some(2));
+
+        // Intact files load without warnings
+        let parsed = parse_binary_lenient(&data).unwrap();
+        assert_eq!(parsed.log.events.len(), 3);
+        assert!(parsed.warnings.is_empty());
+
+        // Corruption is not recoverable
+        let mut corrupted = data.clone();
+        corrupted[BinaryHeader::SIZE] ^= 0x04;
+        assert!(parse_binary_lenient(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_parse_binary_timestamps() {
+        let mut header = create_test_header(3, 1);
+        header.flags = BINARY_FLAG_TIMESTAMPS;
+        let mut data: Vec<u8> = bytemuck::bytes_of(&header).to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&create_test_binary_event(0, 0, 0, 1)));
+        data.extend_from_slice(&3u64.to_le_bytes());
+        for timestamp in [0u64, 16_000, 70_000] {
+            data.extend_from_slice(&timestamp.to_le_bytes());
+        }
+        let log = parse_binary(&data).unwrap();
+        assert_eq!(log.frame_timestamps, vec![0, 16_000, 70_000]);
+
+        // A timestamp block cut short keeps the events but drops the timestamps
+        let cut = &data[..data.len() - 4];
+        assert!(matches!(
+            parse_binary(cut),
+            Err(ParseError::Truncated { recoverable: 1, .. })
+        ));
+        let parsed = parse_binary_lenient(cut).unwrap();
+        assert_eq!(parsed.log.events.len(), 1);
+        assert!(parsed.log.frame_timestamps.is_empty());
+
+        // Timestamps must not go backwards
+        let len = data.len();
+        data[len - 8..].copy_from_slice(&1_000u64.to_le_bytes());
+        assert!(matches!(
+            parse_binary(&data),
+            Err(ParseError::InvalidTimestamps { frame: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_parse_json_players() {
+        let content = r#"{"version": 1, "metadata": {"target_fps": 60, "frame_count": 1},
+            "mappings": [
+                {"id": 0, "name": "Jump", "player": 0, "device": "Gamepad"},
+                {"id": 1, "name": "Jump", "player": 1, "device": "Keyboard"},
+                {"id": 2, "name": "Pause"}
+            ],
+            "events": []}"#;
+        let log = parse_json(content).unwrap();
+        assert_eq!(log.mappings.len(), 3);
+        assert_eq!(log.mappings[1].player, Some(1));
+        assert_eq!(log.mappings[1].device, Some(DeviceType::Keyboard));
+        assert_eq!(log.mappings[2].player, None);
+
+        // Unknown devices are rejected, or ignored with a warning when lenient
+        let bad = content.replace("Keyboard", "Toaster");
+        assert!(matches!(
+            parse_json(&bad),
+            Err(ParseError::InvalidEnumValue {
+                field: "device",
+                ..
+            })
+        ));
+        let parsed = parse_json_lenient(&bad).unwrap();
+        assert_eq!(parsed.log.mappings[1].device, None);
+        assert_eq!(parsed.log.mappings[1].player, Some(1));
+        assert_eq!(parsed.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_binary_annotations() {
+        let mut header = create_test_header(10, 1);
+        header.flags = BINARY_FLAG_ANNOTATIONS;
+        let mut data: Vec<u8> = bytemuck::bytes_of(&header).to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&create_test_binary_event(0, 0, 0, 1)));
+        data.extend_from_slice(&2u32.to_le_bytes());
+        for (frame, flags, value, label) in [(7u64, 0u8, 0.0f32, "boss"), (3, 1, 42.5, "HP")] {
+            data.extend_from_slice(&frame.to_le_bytes());
+            data.push(flags);
+            data.extend_from_slice(&value.to_le_bytes());
+            data.extend_from_slice(&(label.len() as u16).to_le_bytes());
+            data.extend_from_slice(label.as_bytes());
+        }
+
+        // Annotations are sorted by frame
+        let log = parse_binary(&data).unwrap();
+        assert_eq!(log.annotations.len(), 2);
+        assert_eq!(log.annotations[0].label, "HP");
+        assert_eq!(log.annotations[0].value, Some(42.5));
+        assert_eq!(log.annotations[1].frame, 7);
+        assert_eq!(log.annotations[1].value, None);
+
+        // A cut label keeps the events but drops the annotations
+        let parsed = parse_binary_lenient(&data[..data.len() - 1]).unwrap();
+        assert_eq!(parsed.log.events.len(), 1);
+        assert!(parsed.log.annotations.is_empty());
+
+        // Labels must be UTF-8
+        let len = data.len();
+        data[len - 1] = 0xFF;
+        assert!(matches!(
+            parse_binary(&data),
+            Err(ParseError::InvalidAnnotation { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_json_annotations() {
+        let content = r#"{"version": 1, "metadata": {"target_fps": 60, "frame_count": 100},
+            "events": [], "annotations": [
+                {"frame": 40, "label": "player died"},
+                {"frame": 10, "label": "HP", "value": 80},
+                {"frame": "soon", "label": "broken"}
+            ]}"#;
+        assert!(parse_json(content).is_err());
+
+        // Lenient loading skips the broken entry and sorts the rest
+        let parsed = parse_json_lenient(content).unwrap();
+        assert_eq!(parsed.warnings.len(), 1);
+        let annotations = &parsed.log.annotations;
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[0].frame, 10);
+        assert_eq!(annotations[0].value, Some(80.0));
+        assert_eq!(annotations[1].label, "player died");
+
+        // The array is optional
+        let plain =
+            r#"{"version": 1, "metadata": {"target_fps": 60, "frame_count": 1}, "events": []}"#;
+        assert!(parse_json(plain).unwrap().annotations.is_empty());
+    }
+
+    #[test]
+    fn test_parse_json_timestamps() {
+        let content = r#"{"version": 1, "metadata": {"target_fps": 60, "frame_count": 3},
+            "events": [], "timestamps_us": [0, 16000, 9000]}"#;
+        assert!(matches!(
+            parse_json(content),
+            Err(ParseError::InvalidTimestamps { frame: 2 })
+        ));
+
+        // Lenient loading ignores bad timestamps with a warning
+        let parsed = parse_json_lenient(content).unwrap();
+        assert!(parsed.log.frame_timestamps.is_empty());
+        assert_eq!(parsed.warnings.len(), 1);
+
+        let valid = content.replace("9000", "33000");
+        assert_eq!(
+            parse_json(&valid).unwrap().frame_timestamps,
+            vec![0, 16_000, 33_000]
+        );
+    }
+
     #[test]
     fn test_parse_binary_generates_default_mappings() {
         let header = create_test_header(100, 3);
@@ -913,4 +2511,252 @@ mod tests {
         // Verify mappings were generated
         assert!(!log.mappings.is_empty());
     }
+
+    // ========================================================================
+    // Lenient JSON parsing
+    // ========================================================================
+
+    const LENIENT_HEADER: &str = r##"{
+  "version": 1,
+  "metadata": { "target_fps": 60, "frame_count": 100 },
+  "mappings": [
+    { "id": 0, "name": "Jump", "color": "#FF0000" },
+    { "id": 1, "name": "Fire", "color": "not-a-color" }
+  ],
+  "events": [
+"##;
+
+    #[test]
+    fn test_parse_json_lenient_matches_strict_on_valid_input() {
+        let content = include_str!("../../assets/sample.ilj");
+        let strict = parse_json(content).unwrap();
+        let lenient = parse_json_lenient(content).unwrap();
+
+        assert!(lenient.warnings.is_empty());
+        assert_eq!(lenient.log.events.len(), strict.events.len());
+        assert_eq!(lenient.log.mappings.len(), strict.mappings.len());
+        assert_eq!(
+            lenient.log.metadata.frame_count,
+            strict.metadata.frame_count
+        );
+    }
+
+    #[test]
+    fn test_parse_json_lenient_skips_and_repairs() {
+        let content = format!(
+            "{}{}",
+            LENIENT_HEADER,
+            r#"    { "frame": 0, "id": 0, "kind": "Button", "state": "Pressed", "value": [0, 0] },
+    { "frame": 1, "id": 0, "kind": "Button", "value": [0, 0] },
+    { "frame": 2, "id": 2, "kind": "Joystick", "value": [0, 0] },
+    { "frame": 3, "id": 0, "kind": "Button", "state": "Released", "value": [0, 0] },
+    { "frame": 4, "id": 0, "kind": "Button", "state": "Bogus", "value": [0, 0] },
+    { "frame": 5, "kind": "Button", "state": "Pressed", "value": [0, 0] }
+  ]
+}"#
+        );
+        let parsed = parse_json_lenient(&content).unwrap();
+
+        // Invalid color is dropped but the mapping is kept
+        assert_eq!(parsed.log.mappings.len(), 2);
+        assert_eq!(parsed.log.mappings[1].color, None);
+
+        // Unknown kind and missing id are skipped; states are repaired
+        let states: Vec<_> = parsed
+            .log
+            .events
+            .iter()
+            .map(|e| (e.frame, e.state))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                (0, ButtonState::Pressed),
+                (1, ButtonState::Held),
+                (3, ButtonState::Released),
+                (4, ButtonState::Pressed),
+            ]
+        );
+
+        let indices: Vec<_> = parsed.warnings.iter().map(|w| w.event_index).collect();
+        assert_eq!(indices, vec![None, Some(1), Some(2), Some(4), Some(5)]);
+
+        // Positions point at the offending entries
+        assert_eq!(parsed.warnings[0].line, 6);
+        assert_eq!(parsed.warnings[0].column, 5);
+        assert_eq!(parsed.warnings[1].line, 10);
+        assert_eq!(parsed.warnings[2].line, 11);
+        assert!(parsed.warnings[2].message.contains("Joystick"));
+    }
+
+    #[test]
+    fn test_parse_json_lenient_truncated_file() {
+        let content = format!(
+            "{}{}",
+            LENIENT_HEADER,
+            r#"    { "frame": 0, "id": 0, "kind": "Button", "state": "Pressed", "value": [0, 0] },
+    { "frame": 1, "id": 0, "kind": "Button", "state": "Held", "value": [0, 0] },
+    { "frame": 2, "id": 0, "kind": "Butt"#
+        );
+        assert!(parse_json(&content).is_err());
+
+        let parsed = parse_json_lenient(&content).unwrap();
+        assert_eq!(parsed.log.events.len(), 2);
+
+        let messages: Vec<_> = parsed.warnings.iter().map(|w| w.message.as_str()).collect();
+        assert!(messages.iter().any(|m| m.contains("Event 2 is truncated")));
+        assert!(messages.last().unwrap().contains("File is truncated"));
+    }
+
+    #[test]
+    fn test_parse_json_lenient_unrecoverable() {
+        assert!(matches!(
+            parse_json_lenient("not json"),
+            Err(ParseError::JsonSyntax(_))
+        ));
+        assert!(matches!(
+            parse_json_lenient(r#"{"version": 2, "metadata": {}, "events": []}"#),
+            Err(ParseError::UnsupportedVersion { version: 2 })
+        ));
+        assert!(matches!(
+            parse_json_lenient(r#"{"version": 1, "events": []}"#),
+            Err(ParseError::MissingField { field: "metadata" })
+        ));
+        // A truncated header cannot be recovered
+        assert!(parse_json_lenient(r#"{"version": 1, "metadata": {"target_fps"#).is_err());
+    }
+
+    #[test]
+    fn test_line_column() {
+        let content = "ab\ncd\n\u{e9}f";
+        assert_eq!(line_column(content, 0), (1, 1));
+        assert_eq!(line_column(content, 4), (2, 2));
+        // Columns count characters, not bytes
+        assert_eq!(line_column(content, 8), (3, 2));
+    }
+
+    #[test]
+    fn test_json_scanner_handles_strings_with_brackets() {
+        let content = r#"[{"name": "a]\"}"}, 2, "x"]"#;
+        let mut scanner = JsonScanner::new(content);
+        let elements = scanner.scan_array().unwrap();
+        let slices: Vec<_> = elements.iter().map(|e| e.slice(content)).collect();
+        assert_eq!(slices, vec![r#"{"name": "a]\"}"}"#, "2", r#""x""#]);
+        assert!(elements.iter().all(|e| e.complete));
+    }
+
+    #[test]
+    fn test_json_events_start() {
+        let content = r#"{"version": 1, "metadata": {}, "events": [ {"frame": 0}"#;
+        let start = json_events_start(content).unwrap();
+        assert_eq!(&content[start..start + 2], " {");
+
+        assert_eq!(json_events_start(r#"{"version": 1, "metadata": {"#), None);
+    }
+
+    #[test]
+    fn test_parse_json_events_partial() {
+        let event =
+            r#"{"frame": 1, "id": 0, "kind": "Button", "state": "Pressed", "value": [0, 0]}"#;
+        let content = format!(" {},\n  {},\n  {{\"frame\": 2, \"id\"", event, event);
+
+        let (events, consumed, finished) = parse_json_events_partial(&content).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(!finished);
+        // Consumption stops right after the last complete event
+        assert!(content[..consumed].ends_with('}'));
+        assert!(content[consumed..].starts_with(",\n  {\"frame\": 2"));
+
+        // Continuing from the consumed offset with the rest of the event and the closing bracket
+        let rest = format!(
+            "{}{}]}}",
+            &content[consumed..],
+            r#": 0, "kind": "Axis1D", "value": [0.5, 0]}"#
+        );
+        let (events, _, finished) = parse_json_events_partial(&rest).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, InputKind::Axis1D);
+        assert!(finished);
+    }
+
+    const JSON_LINES_HEADER: &str = r##"{"version": 1, "metadata": {"target_fps": 60, "source": "Game"}, "mappings": [{"id": 0, "name": "Jump", "color": "#FF0000"}]}"##;
+
+    #[test]
+    fn test_parse_json_lines() {
+        let content = format!(
+            "{}\n{}\n\n{}\n",
+            JSON_LINES_HEADER,
+            r#"{"frame": 3, "id": 0, "kind": "Button", "state": "Pressed", "value": [0, 0]}"#,
+            r#"{"frame": 9, "id": 1, "kind": "Axis1D", "value": [0.5, 0]}"#
+        );
+
+        let log = parse_json_lines(&content).unwrap();
+        assert_eq!(log.metadata.target_fps, 60);
+        assert_eq!(log.metadata.source.as_deref(), Some("Game"));
+        assert_eq!(log.mappings[0].color, Some([255, 0, 0]));
+        assert_eq!(log.events.len(), 2);
+        assert_eq!(log.events[1].kind, InputKind::Axis1D);
+        // frame_count is extended to cover the events
+        assert_eq!(log.metadata.frame_count, 10);
+    }
+
+    #[test]
+    fn test_parse_json_lines_truncated_last_line() {
+        let content = format!(
+            "{}\n{}\n{}",
+            JSON_LINES_HEADER,
+            r#"{"frame": 3, "id": 0, "kind": "Button", "state": "Pressed", "value": [0, 0]}"#,
+            r#"{"frame": 4, "id": 0, "kind": "But"#
+        );
+        let log = parse_json_lines(&content).unwrap();
+        assert_eq!(log.events.len(), 1);
+
+        // A complete final line without a newline is still parsed
+        let content = format!(
+            "{}\n{}",
+            JSON_LINES_HEADER,
+            r#"{"frame": 3, "id": 0, "kind": "Button", "state": "Pressed", "value": [0, 0]}"#
+        );
+        assert_eq!(parse_json_lines(&content).unwrap().events.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_json_lines_reports_bad_line() {
+        let content = format!(
+            "{}\n{}\n{}\n",
+            JSON_LINES_HEADER,
+            r#"{"frame": 3, "id": 0, "kind": "Button", "state": "Pressed", "value": [0, 0]}"#,
+            r#"{"frame": 4, "id": 0, "kind": "Lever", "value": [0, 0]}"#
+        );
+        match parse_json_lines(&content) {
+            Err(ParseError::InvalidLine { line, message, .. }) => {
+                assert_eq!(line, 3);
+                assert!(message.contains("Lever"));
+            }
+            other => panic!("expected InvalidLine, got {:?}", other),
+        }
+
+        assert!(matches!(
+            parse_json_lines(""),
+            Err(ParseError::MissingField { .. })
+        ));
+    }
+
+    #[test]
+    fn test_json_lines_parser_incremental() {
+        let event = r#"{"frame": 1, "id": 0, "kind": "Button", "state": "Held", "value": [0, 0]}"#;
+        let mut parser = JsonLinesParser::new();
+
+        // Half of the header: nothing consumed yet
+        let (events, consumed) = parser.feed(&JSON_LINES_HEADER[..20]).unwrap();
+        assert!(events.is_empty());
+        assert_eq!(consumed, 0);
+        assert!(parser.header().is_none());
+
+        let content = format!("{}\n{}\n{}", JSON_LINES_HEADER, event, &event[..10]);
+        let (events, consumed) = parser.feed(&content).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(parser.header().is_some());
+        assert_eq!(&content[consumed..], &event[..10]);
+    }
 }
@@ -68,23 +68,24 @@ impl PlaybackState {
         }
     }
 
-    /// Check if enough time has elapsed to advance to the next frame.
+    /// Check if the current frame has been shown for its duration at `now`.
     ///
-    /// This method determines whether the playback should move to the next frame
-    /// based on the target FPS and current speed multiplier.
+    /// Frames of logs with per-frame timestamps last as long as they did
+    /// during recording; other logs use 1 / target FPS. The duration is
+    /// scaled by the speed multiplier.
     ///
     /// # Arguments
-    /// * `target_fps` - The target frames per second for playback.
+    /// * `frame_duration` - How long the current frame lasts at 1x speed.
+    /// * `now` - The current time.
     ///
     /// # Returns
     /// `true` if enough time has passed to advance the frame, `false` otherwise.
-    pub fn should_advance(&self, target_fps: u32) -> bool {
-        if target_fps == 0 || self.speed <= 0.0 {
+    pub fn should_advance_after(&self, frame_duration: Duration, now: Instant) -> bool {
+        if self.speed <= 0.0 {
             return false;
         }
 
-        let frame_duration = Duration::from_secs_f32(1.0 / (target_fps as f32 * self.speed));
-        self.last_update.elapsed() >= frame_duration
+        now.saturating_duration_since(self.last_update) >= frame_duration.div_f32(self.speed)
     }
 
     /// Mark that a frame advance has occurred, updating the timestamp.
@@ -253,7 +254,6 @@ impl PlaybackState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::thread;
 
     #[test]
     fn test_new_default_values() {
@@ -387,40 +387,63 @@ mod tests {
         assert_eq!(state.speed, MAX_SPEED);
     }
 
+    /// Duration of one frame at 60 FPS.
+    const FRAME_60_FPS: Duration = Duration::from_micros(16_667);
+
     #[test]
     fn test_should_advance_invalid_inputs() {
-        let state = PlaybackState::new();
+        let mut state = PlaybackState::new();
+        state.speed = 0.0;
 
-        // Zero FPS should not advance
-        assert!(!state.should_advance(0));
+        // Zero speed should never advance
+        let later = state.last_update + Duration::from_secs(1);
+        assert!(!state.should_advance_after(FRAME_60_FPS, later));
     }
 
     #[test]
     fn test_should_advance_timing() {
-        let mut state = PlaybackState::new();
-        state.mark_advanced();
+        let state = PlaybackState::new();
+        let start = state.last_update;
 
         // Should not advance immediately at 60 FPS
-        assert!(!state.should_advance(60));
+        assert!(!state.should_advance_after(FRAME_60_FPS, start));
 
-        // Wait for approximately one frame at 60 FPS (~17ms)
-        thread::sleep(Duration::from_millis(20));
+        // Should advance once one frame (~17ms) has passed
+        let later = start + Duration::from_millis(20);
+        assert!(state.should_advance_after(FRAME_60_FPS, later));
 
-        // Should now be ready to advance
-        assert!(state.should_advance(60));
+        // A time before the last advance never advances
+        let earlier = start
+            .checked_sub(Duration::from_millis(20))
+            .unwrap_or(start);
+        assert!(!state.should_advance_after(FRAME_60_FPS, earlier));
+    }
+
+    #[test]
+    fn test_should_advance_after_frame_duration() {
+        let mut state = PlaybackState::new();
+        let later = state.last_update + Duration::from_millis(20);
+
+        // A 50ms hitch frame is held longer than a regular frame
+        assert!(state.should_advance_after(Duration::from_millis(16), later));
+        assert!(!state.should_advance_after(Duration::from_millis(50), later));
+
+        // Speed scales the recorded duration
+        state.speed = 4.0;
+        assert!(state.should_advance_after(Duration::from_millis(50), later));
     }
 
     #[test]
     fn test_should_advance_with_speed_multiplier() {
         let mut state = PlaybackState::new();
         state.speed = 2.0; // Double speed
-        state.mark_advanced();
 
-        // At 2x speed, frames should be twice as fast
         // At 60 FPS with 2x speed, frame duration is ~8.3ms
-        thread::sleep(Duration::from_millis(10));
+        let later = state.last_update + Duration::from_millis(10);
+        assert!(state.should_advance_after(FRAME_60_FPS, later));
 
-        assert!(state.should_advance(60));
+        state.speed = 1.0;
+        assert!(!state.should_advance_after(FRAME_60_FPS, later));
     }
 
     #[test]
@@ -0,0 +1,409 @@
+//! Mapping profiles.
+//!
+//! A mapping profile holds the name, color, group and expected kind of each
+//! input ID of a game. Applying a profile labels logs that carry no mapping
+//! information (binary logs only have "Input N" names), and editing the IDs of
+//! a profile keeps it usable when a new build renumbers its inputs.
+//!
+//! Profiles are stored next to the settings in the user's config directory.
+//! A profile can name the `source` of the logs it is meant for, so the
+//! matching profile can be suggested when a log is loaded. Profiles also
+//! keep the timeline's row layout for the game.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::edit::input_kind;
+use super::layout::RowLayout;
+use super::log::{InputKind, InputLog, InputMapping};
+use super::parser::default_mapping;
+
+/// Profiles filename for persistence.
+const PROFILES_FILENAME: &str = "profiles.json";
+
+/// Name, color, group and kind of one input ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileEntry {
+    /// Input identifier in the log
+    pub id: u32,
+    /// Display name for the input
+    pub name: String,
+    /// Optional RGB color for visualization
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+    /// Named group the input belongs to
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Kind the input is expected to have, used to spot renumbered IDs
+    #[serde(default)]
+    pub kind: Option<InputKind>,
+}
+
+impl ProfileEntry {
+    /// Create an entry from a log's mapping and the kind of its events.
+    pub fn from_mapping(mapping: &InputMapping, kind: Option<InputKind>) -> Self {
+        Self {
+            id: mapping.id,
+            name: mapping.name.clone(),
+            color: mapping.color,
+            group: mapping.group.clone(),
+            kind,
+        }
+    }
+}
+
+/// Result of applying a profile to a log.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApplyReport {
+    /// Number of inputs of the log that were labeled by the profile
+    pub applied: usize,
+    /// IDs whose events have a different kind than the profile expects
+    pub kind_mismatches: Vec<u32>,
+    /// IDs of the profile that the log doesn't contain
+    pub missing: Vec<u32>,
+}
+
+impl ApplyReport {
+    /// One-line summary for the status bar.
+    pub fn summary(&self, profile: &str) -> String {
+        let mut text = format!("Applied profile '{}' to {} inputs", profile, self.applied);
+        if !self.kind_mismatches.is_empty() {
+            text.push_str(&format!(
+                "; {} with a different kind (renumbered?)",
+                self.kind_mismatches.len()
+            ));
+        }
+        text
+    }
+}
+
+/// Named set of input mappings for one game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MappingProfile {
+    /// Profile name, usually the game's name
+    pub name: String,
+    /// Text matched against the `source` metadata of logs to suggest the profile
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Mapping of each input ID, sorted by ID
+    #[serde(default)]
+    pub entries: Vec<ProfileEntry>,
+    /// Order, pinned rows and collapsed groups of the timeline
+    #[serde(default, skip_serializing_if = "RowLayout::is_default")]
+    pub layout: RowLayout,
+}
+
+impl MappingProfile {
+    /// Create a profile from the mappings of a log.
+    ///
+    /// The log's `source` becomes the profile's source, so the profile is
+    /// suggested for other logs of the same game.
+    pub fn from_log(name: impl Into<String>, log: &InputLog) -> Self {
+        let entries = log
+            .get_effective_mappings()
+            .iter()
+            .map(|mapping| ProfileEntry::from_mapping(mapping, input_kind(log, mapping.id)))
+            .collect();
+        Self {
+            name: name.into(),
+            source: log.metadata.source.clone(),
+            entries,
+            layout: RowLayout::default(),
+        }
+    }
+
+    /// Whether the profile is meant for logs with the given `source`.
+    ///
+    /// The profile's source matches when the log's source contains it,
+    /// ignoring case, so "MyGame" matches "MyGame 1.4 (build 812)".
+    pub fn matches_source(&self, source: &str) -> bool {
+        match &self.source {
+            Some(pattern) if !pattern.trim().is_empty() => source
+                .to_lowercase()
+                .contains(&pattern.trim().to_lowercase()),
+            _ => false,
+        }
+    }
+
+    /// Replace the entries, keeping one entry per ID sorted by ID.
+    ///
+    /// When several entries have the same ID, the last one wins.
+    pub fn set_entries(&mut self, entries: Vec<ProfileEntry>) {
+        let mut unique: Vec<ProfileEntry> = Vec::with_capacity(entries.len());
+        for entry in entries.into_iter().rev() {
+            if !unique.iter().any(|e| e.id == entry.id) {
+                unique.push(entry);
+            }
+        }
+        unique.sort_by_key(|e| e.id);
+        self.entries = unique;
+    }
+
+    /// Label the inputs of a log with the profile's names, colors and groups.
+    ///
+    /// Player and device information of existing mappings is kept. Entries
+    /// without a color keep the log's color.
+    pub fn apply(&self, log: &mut InputLog) -> ApplyReport {
+        let mut report = ApplyReport::default();
+
+        for entry in &self.entries {
+            let log_kind = input_kind(log, entry.id);
+            let has_mapping = log.mappings.iter().any(|m| m.id == entry.id);
+            if log_kind.is_none() && !has_mapping {
+                report.missing.push(entry.id);
+                continue;
+            }
+            if let (Some(expected), Some(actual)) = (entry.kind, log_kind)
+                && expected != actual
+            {
+                report.kind_mismatches.push(entry.id);
+            }
+
+            if !has_mapping {
+                log.mappings.push(default_mapping(entry.id));
+            }
+            if let Some(mapping) = log.mappings.iter_mut().find(|m| m.id == entry.id) {
+                mapping.name = entry.name.clone();
+                if entry.color.is_some() {
+                    mapping.color = entry.color;
+                }
+                mapping.group = entry.group.clone();
+            }
+            report.applied += 1;
+        }
+
+        log.mappings.sort_by_key(|m| m.id);
+        report
+    }
+}
+
+/// All saved mapping profiles.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileStore {
+    /// Saved profiles
+    #[serde(default)]
+    pub profiles: Vec<MappingProfile>,
+}
+
+impl ProfileStore {
+    /// Get the profiles file path in the user's config directory.
+    fn get_profiles_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|mut path| {
+            path.push("input-log-viewer");
+            path.push(PROFILES_FILENAME);
+            path
+        })
+    }
+
+    /// Load profiles from disk, returning an empty store if loading fails.
+    pub fn load() -> Self {
+        Self::get_profiles_path()
+            .and_then(|path| std::fs::read_to_string(&path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save profiles to disk.
+    ///
+    /// Returns an error if saving fails.
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::get_profiles_path()
+            .ok_or_else(|| "Could not determine config directory".to_string())?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write profiles file: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Index of the profile to suggest for a log, based on its `source`.
+    ///
+    /// When several profiles match, the one with the longest (most specific)
+    /// source wins.
+    pub fn suggest(&self, log: &InputLog) -> Option<usize> {
+        let source = log.metadata.source.as_deref()?;
+        self.profiles
+            .iter()
+            .enumerate()
+            .filter(|(_, profile)| profile.matches_source(source))
+            .max_by_key(|(_, profile)| profile.source.as_ref().map_or(0, |s| s.trim().len()))
+            .map(|(index, _)| index)
+    }
+
+    /// Add a profile, replacing a profile with the same name.
+    ///
+    /// # Returns
+    /// The index of the stored profile.
+    pub fn upsert(&mut self, profile: MappingProfile) -> usize {
+        match self.profiles.iter().position(|p| p.name == profile.name) {
+            Some(index) => {
+                self.profiles[index] = profile;
+                index
+            }
+            None => {
+                self.profiles.push(profile);
+                self.profiles.len() - 1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log::{ButtonState, LogMetadata};
+    use crate::core::test_util::event;
+
+    fn binary_like_log() -> InputLog {
+        let events = vec![
+            event(0, 0, InputKind::Button, ButtonState::Pressed),
+            event(0, 1, InputKind::Axis1D, ButtonState::Pressed),
+        ];
+        InputLog {
+            metadata: LogMetadata {
+                frame_count: 10,
+                source: Some("Racer 2.1 (build 88)".to_string()),
+                ..Default::default()
+            },
+            mappings: vec![default_mapping(0), default_mapping(1)],
+            events,
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
+        }
+    }
+
+    fn entry(id: u32, name: &str, kind: InputKind) -> ProfileEntry {
+        ProfileEntry {
+            id,
+            name: name.to_string(),
+            color: Some([1, 2, 3]),
+            group: Some("Driving".to_string()),
+            kind: Some(kind),
+        }
+    }
+
+    #[test]
+    fn test_apply_labels_inputs() {
+        let profile = MappingProfile {
+            name: "Racer".to_string(),
+            source: Some("racer".to_string()),
+            entries: vec![
+                entry(0, "Boost", InputKind::Button),
+                entry(1, "Steer", InputKind::Axis2D),
+                entry(7, "Horn", InputKind::Button),
+            ],
+            layout: RowLayout::default(),
+        };
+        let mut log = binary_like_log();
+
+        let report = profile.apply(&mut log);
+        assert_eq!(report.applied, 2);
+        assert_eq!(report.kind_mismatches, vec![1]);
+        assert_eq!(report.missing, vec![7]);
+        assert_eq!(log.get_input_name(0), "Boost");
+        assert_eq!(log.mappings[1].name, "Steer");
+        assert_eq!(log.mappings[1].color, Some([1, 2, 3]));
+        assert_eq!(log.mappings[1].group.as_deref(), Some("Driving"));
+        assert!(report.summary("Racer").contains("different kind"));
+    }
+
+    #[test]
+    fn test_apply_adds_missing_mapping() {
+        let mut log = binary_like_log();
+        log.mappings.clear();
+        let profile = MappingProfile {
+            name: "Racer".to_string(),
+            source: None,
+            entries: vec![entry(1, "Steer", InputKind::Axis1D)],
+            layout: RowLayout::default(),
+        };
+
+        let report = profile.apply(&mut log);
+        assert_eq!(report.applied, 1);
+        assert!(report.kind_mismatches.is_empty());
+        assert_eq!(log.mappings.len(), 1);
+        assert_eq!(log.mappings[0].name, "Steer");
+    }
+
+    #[test]
+    fn test_from_log_round_trip() {
+        let mut log = binary_like_log();
+        log.mappings[0].name = "Boost".to_string();
+        let profile = MappingProfile::from_log("Racer", &log);
+        assert_eq!(profile.source.as_deref(), Some("Racer 2.1 (build 88)"));
+        assert_eq!(profile.entries.len(), 2);
+        assert_eq!(profile.entries[0].kind, Some(InputKind::Button));
+        assert_eq!(profile.entries[1].kind, Some(InputKind::Axis1D));
+
+        let mut other = binary_like_log();
+        assert_eq!(profile.apply(&mut other).applied, 2);
+        assert_eq!(other.get_input_name(0), "Boost");
+    }
+
+    #[test]
+    fn test_set_entries_deduplicates_and_sorts() {
+        let mut profile = MappingProfile {
+            name: "Racer".to_string(),
+            source: None,
+            entries: Vec::new(),
+            layout: RowLayout::default(),
+        };
+        profile.set_entries(vec![
+            entry(4, "Old", InputKind::Button),
+            entry(2, "Brake", InputKind::Trigger),
+            entry(4, "Horn", InputKind::Button),
+        ]);
+        let names: Vec<(u32, &str)> = profile
+            .entries
+            .iter()
+            .map(|e| (e.id, e.name.as_str()))
+            .collect();
+        assert_eq!(names, vec![(2, "Brake"), (4, "Horn")]);
+    }
+
+    #[test]
+    fn test_suggest_prefers_most_specific_source() {
+        let profile = |name: &str, source: Option<&str>| MappingProfile {
+            name: name.to_string(),
+            source: source.map(str::to_string),
+            entries: Vec::new(),
+            layout: RowLayout::default(),
+        };
+        let mut store = ProfileStore::default();
+        store.upsert(profile("Any", None));
+        store.upsert(profile("Racer", Some("RACER")));
+        store.upsert(profile("Racer 2", Some("racer 2.")));
+
+        let mut log = binary_like_log();
+        assert_eq!(store.suggest(&log), Some(2));
+
+        log.metadata.source = Some("Racer 1.0".to_string());
+        assert_eq!(store.suggest(&log), Some(1));
+
+        log.metadata.source = None;
+        assert_eq!(store.suggest(&log), None);
+    }
+
+    #[test]
+    fn test_upsert_replaces_by_name() {
+        let mut store = ProfileStore::default();
+        let mut profile = MappingProfile::from_log("Racer", &binary_like_log());
+        assert_eq!(store.upsert(profile.clone()), 0);
+        profile.entries.clear();
+        assert_eq!(store.upsert(profile), 0);
+        assert_eq!(store.profiles.len(), 1);
+        assert!(store.profiles[0].entries.is_empty());
+
+        let json = serde_json::to_string(&store).unwrap();
+        let loaded: ProfileStore = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, store);
+    }
+}
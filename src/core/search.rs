@@ -204,10 +204,33 @@ pub fn find_matches(log: &InputLog, query: &SearchQuery) -> Vec<u64> {
     frames
 }
 
+/// Find all frames with an annotation whose label contains `text`.
+///
+/// Matching ignores case and covers both game events and telemetry channel
+/// names. Returns unique frame numbers in ascending order; an empty or
+/// whitespace-only `text` matches nothing.
+pub fn find_annotation_matches(log: &InputLog, text: &str) -> Vec<u64> {
+    let needle = text.trim().to_lowercase();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let mut frames: Vec<u64> = log
+        .annotations
+        .iter()
+        .filter(|a| a.label.to_lowercase().contains(&needle))
+        .map(|a| a.frame)
+        .collect();
+    frames.sort_unstable();
+    frames.dedup();
+
+    frames
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::log::{InputEvent, InputMapping, LogMetadata};
+    use crate::core::log::{Annotation, InputEvent, InputMapping, LogMetadata};
 
     fn create_test_log() -> InputLog {
         InputLog {
@@ -223,16 +246,25 @@ mod tests {
                     id: 0,
                     name: "A Button".to_string(),
                     color: None,
+                    player: None,
+                    device: None,
+                    group: None,
                 },
                 InputMapping {
                     id: 1,
                     name: "B Button".to_string(),
                     color: None,
+                    player: None,
+                    device: None,
+                    group: None,
                 },
                 InputMapping {
                     id: 10,
                     name: "Left Stick X".to_string(),
                     color: None,
+                    player: None,
+                    device: None,
+                    group: None,
                 },
             ],
             events: vec![
@@ -242,6 +274,7 @@ mod tests {
                     kind: InputKind::Button,
                     state: ButtonState::Pressed,
                     value: [1.0, 0.0],
+                    contact: 0,
                 },
                 InputEvent {
                     frame: 1,
@@ -249,6 +282,7 @@ mod tests {
                     kind: InputKind::Button,
                     state: ButtonState::Held,
                     value: [1.0, 0.0],
+                    contact: 0,
                 },
                 InputEvent {
                     frame: 5,
@@ -256,6 +290,7 @@ mod tests {
                     kind: InputKind::Button,
                     state: ButtonState::Released,
                     value: [0.0, 0.0],
+                    contact: 0,
                 },
                 InputEvent {
                     frame: 10,
@@ -263,6 +298,7 @@ mod tests {
                     kind: InputKind::Button,
                     state: ButtonState::Pressed,
                     value: [1.0, 0.0],
+                    contact: 0,
                 },
                 InputEvent {
                     frame: 15,
@@ -270,6 +306,7 @@ mod tests {
                     kind: InputKind::Axis1D,
                     state: ButtonState::Released,
                     value: [0.75, 0.0],
+                    contact: 0,
                 },
                 InputEvent {
                     frame: 20,
@@ -277,8 +314,11 @@ mod tests {
                     kind: InputKind::Button,
                     state: ButtonState::Pressed,
                     value: [1.0, 0.0],
+                    contact: 0,
                 },
             ],
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
         }
     }
 
@@ -380,4 +420,26 @@ mod tests {
         assert_eq!(query.kind, Some(InputKind::Button));
         assert_eq!(query.button_state, Some(ButtonState::Pressed));
     }
+
+    #[test]
+    fn test_find_annotation_matches() {
+        let mut log = create_test_log();
+        let annotation = |frame, label: &str, value| Annotation {
+            frame,
+            label: label.to_string(),
+            value,
+        };
+        log.annotations = vec![
+            annotation(5, "Checkpoint A", None),
+            annotation(5, "checkpoint B", None),
+            annotation(12, "HP", Some(50.0)),
+            annotation(30, "Player died", None),
+        ];
+
+        assert_eq!(find_annotation_matches(&log, "CHECKPOINT"), vec![5]);
+        assert_eq!(find_annotation_matches(&log, "hp"), vec![12]);
+        assert_eq!(find_annotation_matches(&log, " died "), vec![30]);
+        assert!(find_annotation_matches(&log, "  ").is_empty());
+        assert!(find_annotation_matches(&log, "respawn").is_empty());
+    }
 }
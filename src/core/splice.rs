@@ -0,0 +1,614 @@
+//! Cutting logs apart and joining them together.
+//!
+//! `slice` cuts a frame range out of a log, `concat` appends one log after
+//! another, and `merge` combines logs that record different inputs over the
+//! same frames. All operations return a new log and keep the input logs
+//! unchanged.
+//!
+//! Cutting in the middle of a press would leave the slice with Held events
+//! that have no Pressed before them, so the state of every input at the cut
+//! is synthesized: held buttons get a Pressed event at frame 0 and analog
+//! inputs get their last value. Likewise, buttons still held at the end of
+//! the first log of a `concat` are released where the second log begins.
+
+use std::collections::{BTreeSet, HashMap};
+
+use thiserror::Error;
+
+use super::log::{Annotation, ButtonState, InputEvent, InputKind, InputLog, InputMapping};
+
+/// Errors that can occur while slicing or combining logs.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum SpliceError {
+    /// The frame range does not contain any frame of the log
+    #[error("Frames {start}-{end} are outside the log ({frame_count} frames)")]
+    EmptyRange {
+        start: u64,
+        end: u64,
+        frame_count: u64,
+    },
+
+    /// The logs were recorded at different frame rates
+    #[error("Logs have different target FPS ({first} and {second})")]
+    FpsMismatch { first: u32, second: u32 },
+
+    /// The logs to merge record some of the same inputs
+    #[error("Logs both contain input IDs {}", format_ids(.0))]
+    OverlappingIds(Vec<u32>),
+}
+
+/// Format a list of input IDs for an error message.
+fn format_ids(ids: &[u32]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Key identifying one stream of events: the input ID and the touch contact.
+type StreamKey = (u32, u16);
+
+fn stream_key(event: &InputEvent) -> StreamKey {
+    (event.id, event.contact)
+}
+
+// ============================================================================
+// Slice
+// ============================================================================
+
+/// Cut the frames `start..=end` out of a log.
+///
+/// Frames are rebased so that `start` becomes frame 0, and the state of every
+/// input at `start` is synthesized (see the module documentation). `end` is
+/// clamped to the last frame of the log.
+///
+/// # Arguments
+/// * `log` - The log to cut from
+/// * `start` - First frame of the slice
+/// * `end` - Last frame of the slice (inclusive)
+///
+/// # Returns
+/// The slice, or `SpliceError::EmptyRange` if no frame of the log is in the range.
+pub fn slice(log: &InputLog, start: u64, end: u64) -> Result<InputLog, SpliceError> {
+    let frame_count = log.metadata.frame_count;
+    if start > end || start >= frame_count {
+        return Err(SpliceError::EmptyRange {
+            start,
+            end,
+            frame_count,
+        });
+    }
+    let end = end.min(frame_count - 1);
+
+    // Last event of each stream before the cut
+    let mut last_before: HashMap<StreamKey, &InputEvent> = HashMap::new();
+    for event in log.events.iter().filter(|e| e.frame < start) {
+        last_before.insert(stream_key(event), event);
+    }
+
+    let mut events: Vec<InputEvent> = Vec::new();
+    let mut at_start: HashMap<StreamKey, ButtonState> = HashMap::new();
+    for event in log
+        .events
+        .iter()
+        .filter(|e| (start..=end).contains(&e.frame))
+    {
+        let mut event = event.clone();
+        if event.frame == start {
+            at_start.entry(stream_key(&event)).or_insert(event.state);
+        }
+        event.frame -= start;
+        events.push(event);
+    }
+
+    let mut synthesized = Vec::new();
+    for (key, last) in &last_before {
+        let first_at_start = at_start.get(key).copied();
+        if last.kind.has_button_state() {
+            let down = matches!(last.state, ButtonState::Pressed | ButtonState::Held);
+            if !down {
+                continue;
+            }
+            match first_at_start {
+                // The press continues into the slice: it starts there now
+                Some(ButtonState::Held) => {
+                    for event in events.iter_mut().filter(|e| e.frame == 0) {
+                        if stream_key(event) == *key && event.state == ButtonState::Held {
+                            event.state = ButtonState::Pressed;
+                        }
+                    }
+                }
+                // The press ends right at the cut, so it isn't part of the slice
+                Some(ButtonState::Released) => {
+                    events.retain(|e| {
+                        !(e.frame == 0 && stream_key(e) == *key && e.state == ButtonState::Released)
+                    });
+                }
+                Some(ButtonState::Pressed) => {}
+                None => synthesized.push(InputEvent {
+                    frame: 0,
+                    state: ButtonState::Pressed,
+                    ..(*last).clone()
+                }),
+            }
+        } else if first_at_start.is_none() && carries_value(last) {
+            synthesized.push(InputEvent {
+                frame: 0,
+                ..(*last).clone()
+            });
+        }
+    }
+    // Hash map order is arbitrary; keep the synthesized events stable
+    synthesized.sort_by_key(|e| (e.id, e.contact));
+    synthesized.extend(events);
+    let events = synthesized;
+
+    let frame_timestamps = match log.frame_timestamps.get(start as usize..) {
+        Some(recorded) => {
+            let base = recorded.first().copied().unwrap_or(0);
+            recorded
+                .iter()
+                .take((end - start + 1) as usize)
+                .map(|ts| ts.saturating_sub(base))
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let mut metadata = log.metadata.clone();
+    metadata.frame_count = end - start + 1;
+
+    Ok(InputLog {
+        metadata,
+        mappings: log.mappings.clone(),
+        events,
+        frame_timestamps,
+        annotations: slice_annotations(&log.annotations, start, end),
+    })
+}
+
+/// Whether the last value of a stream still holds at a later frame.
+///
+/// Mouse deltas are relative motion within one frame, and analog inputs at
+/// their resting value need no event.
+fn carries_value(event: &InputEvent) -> bool {
+    match event.kind {
+        InputKind::MouseDelta => false,
+        kind if kind.is_analog() => event.value != [0.0, 0.0],
+        _ => true,
+    }
+}
+
+/// Annotations in `start..=end`, rebased, with the telemetry values at the cut.
+fn slice_annotations(annotations: &[Annotation], start: u64, end: u64) -> Vec<Annotation> {
+    // Last telemetry sample of each channel before the cut
+    let mut last_values: HashMap<&str, &Annotation> = HashMap::new();
+    for annotation in annotations.iter().filter(|a| a.frame < start) {
+        if annotation.value.is_some() {
+            last_values.insert(annotation.label.as_str(), annotation);
+        }
+    }
+    for annotation in annotations.iter().filter(|a| a.frame == start) {
+        last_values.remove(annotation.label.as_str());
+    }
+
+    let mut result: Vec<Annotation> = last_values
+        .into_values()
+        .map(|a| Annotation {
+            frame: 0,
+            ..a.clone()
+        })
+        .collect();
+    result.sort_by(|a, b| a.label.cmp(&b.label));
+    result.extend(
+        annotations
+            .iter()
+            .filter(|a| (start..=end).contains(&a.frame))
+            .map(|a| Annotation {
+                frame: a.frame - start,
+                ..a.clone()
+            }),
+    );
+    result
+}
+
+// ============================================================================
+// Concat
+// ============================================================================
+
+/// Append `second` after `first`.
+///
+/// The frames of `second` are offset by the frame count of `first`, and
+/// buttons still held at the end of `first` are released at the join.
+/// Metadata comes from `first`.
+///
+/// # Returns
+/// The combined log, or `SpliceError::FpsMismatch` if the logs were recorded
+/// at different frame rates.
+pub fn concat(first: &InputLog, second: &InputLog) -> Result<InputLog, SpliceError> {
+    check_fps(first, second)?;
+    let offset = first.metadata.frame_count;
+
+    let mut events = first.events.clone();
+
+    // Release buttons that are still down at the end of the first log
+    let mut last_state: HashMap<StreamKey, &InputEvent> = HashMap::new();
+    for event in first.events.iter().filter(|e| e.kind.has_button_state()) {
+        last_state.insert(stream_key(event), event);
+    }
+    let continued: BTreeSet<StreamKey> = second
+        .events
+        .iter()
+        .filter(|e| e.frame == 0)
+        .map(stream_key)
+        .collect();
+    let mut releases: Vec<InputEvent> = last_state
+        .into_iter()
+        .filter(|(key, event)| {
+            matches!(event.state, ButtonState::Pressed | ButtonState::Held)
+                && !continued.contains(key)
+        })
+        .map(|(_, event)| InputEvent {
+            frame: offset,
+            state: ButtonState::Released,
+            value: [0.0, 0.0],
+            ..event.clone()
+        })
+        .collect();
+    releases.sort_by_key(|e| (e.id, e.contact));
+    events.extend(releases);
+
+    events.extend(second.events.iter().map(|e| InputEvent {
+        frame: e.frame + offset,
+        ..e.clone()
+    }));
+
+    let mut annotations = first.annotations.clone();
+    annotations.extend(second.annotations.iter().map(|a| Annotation {
+        frame: a.frame + offset,
+        ..a.clone()
+    }));
+
+    let frame_timestamps = if first.has_frame_timestamps() || second.has_frame_timestamps() {
+        let mut timestamps = complete_timestamps(first);
+        let join = match timestamps.last() {
+            Some(&last) => last + first.effective_frame_duration_us(offset.saturating_sub(1)),
+            None => 0,
+        };
+        let rest = complete_timestamps(second);
+        let base = rest.first().copied().unwrap_or(0);
+        timestamps.extend(rest.iter().map(|ts| join + ts.saturating_sub(base)));
+        timestamps
+    } else {
+        Vec::new()
+    };
+
+    let mut metadata = first.metadata.clone();
+    metadata.frame_count = offset + second.metadata.frame_count;
+
+    Ok(InputLog {
+        metadata,
+        mappings: merge_mappings(&first.mappings, &second.mappings),
+        events,
+        frame_timestamps,
+        annotations,
+    })
+}
+
+/// Timestamps of all frames of a log, filling missing ones at the nominal rate.
+fn complete_timestamps(log: &InputLog) -> Vec<u64> {
+    extend_timestamps(
+        &log.frame_timestamps,
+        log.metadata.frame_count as usize,
+        log.nominal_frame_duration_us(),
+    )
+}
+
+/// Extend recorded timestamps to `len` frames, `step` microseconds apart.
+fn extend_timestamps(recorded: &[u64], len: usize, step: u64) -> Vec<u64> {
+    let mut timestamps: Vec<u64> = recorded.iter().take(len).copied().collect();
+    while timestamps.len() < len {
+        let next = timestamps.last().map_or(0, |last| last + step);
+        timestamps.push(next);
+    }
+    timestamps
+}
+
+// ============================================================================
+// Merge
+// ============================================================================
+
+/// Combine two logs that record different inputs over the same frames.
+///
+/// The merged log is as long as the longer one. Metadata comes from `first`,
+/// and so do the frame timestamps unless only `second` has them.
+///
+/// # Returns
+/// The merged log, or an error if the frame rates differ or both logs
+/// contain events or mappings for the same input ID.
+pub fn merge(first: &InputLog, second: &InputLog) -> Result<InputLog, SpliceError> {
+    check_fps(first, second)?;
+
+    let first_ids = input_ids(first);
+    let overlap: Vec<u32> = input_ids(second)
+        .intersection(&first_ids)
+        .copied()
+        .collect();
+    if !overlap.is_empty() {
+        return Err(SpliceError::OverlappingIds(overlap));
+    }
+
+    let mut events = first.events.clone();
+    events.extend_from_slice(&second.events);
+    // Stable, so each log's events keep their relative order
+    events.sort_by_key(|e| e.frame);
+
+    let mut annotations = first.annotations.clone();
+    annotations.extend_from_slice(&second.annotations);
+    annotations.sort_by_key(|a| a.frame);
+
+    let frame_count = first.metadata.frame_count.max(second.metadata.frame_count);
+    let frame_timestamps = match (first.has_frame_timestamps(), second.has_frame_timestamps()) {
+        (false, false) => Vec::new(),
+        (true, _) => extend_timestamps(
+            &first.frame_timestamps,
+            frame_count as usize,
+            first.nominal_frame_duration_us(),
+        ),
+        (false, true) => extend_timestamps(
+            &second.frame_timestamps,
+            frame_count as usize,
+            second.nominal_frame_duration_us(),
+        ),
+    };
+
+    let mut metadata = first.metadata.clone();
+    metadata.frame_count = frame_count;
+
+    Ok(InputLog {
+        metadata,
+        mappings: merge_mappings(&first.mappings, &second.mappings),
+        events,
+        frame_timestamps,
+        annotations,
+    })
+}
+
+/// Input IDs that have events or mappings in a log.
+fn input_ids(log: &InputLog) -> BTreeSet<u32> {
+    log.events
+        .iter()
+        .map(|e| e.id)
+        .chain(log.mappings.iter().map(|m| m.id))
+        .collect()
+}
+
+/// Mappings of both logs, keeping the first log's mapping for shared IDs.
+fn merge_mappings(first: &[InputMapping], second: &[InputMapping]) -> Vec<InputMapping> {
+    let mut mappings = first.to_vec();
+    for mapping in second {
+        if !mappings.iter().any(|m| m.id == mapping.id) {
+            mappings.push(mapping.clone());
+        }
+    }
+    mappings
+}
+
+fn check_fps(first: &InputLog, second: &InputLog) -> Result<(), SpliceError> {
+    if first.metadata.target_fps != second.metadata.target_fps {
+        return Err(SpliceError::FpsMismatch {
+            first: first.metadata.target_fps,
+            second: second.metadata.target_fps,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_util::{axis, button, log_with};
+
+    fn states(log: &InputLog, id: u32) -> Vec<(u64, ButtonState)> {
+        log.events
+            .iter()
+            .filter(|e| e.id == id)
+            .map(|e| (e.frame, e.state))
+            .collect()
+    }
+
+    #[test]
+    fn test_slice_rebases_and_synthesizes_press() {
+        let log = log_with(
+            100,
+            vec![
+                button(10, 0, ButtonState::Pressed),
+                button(30, 0, ButtonState::Released),
+                button(40, 0, ButtonState::Pressed),
+                button(45, 0, ButtonState::Released),
+                axis(5, 1, 0.5),
+            ],
+        );
+
+        let sliced = slice(&log, 20, 49).unwrap();
+        assert_eq!(sliced.metadata.frame_count, 30);
+        assert_eq!(
+            states(&sliced, 0),
+            vec![
+                (0, ButtonState::Pressed),
+                (10, ButtonState::Released),
+                (20, ButtonState::Pressed),
+                (25, ButtonState::Released),
+            ]
+        );
+        // The axis keeps the value it had at the cut
+        let axis_events: Vec<_> = sliced.events.iter().filter(|e| e.id == 1).collect();
+        assert_eq!(axis_events.len(), 1);
+        assert_eq!(axis_events[0].frame, 0);
+        assert_eq!(axis_events[0].value[0], 0.5);
+    }
+
+    #[test]
+    fn test_slice_dense_log_turns_held_into_pressed() {
+        let log = log_with(
+            10,
+            vec![
+                button(2, 0, ButtonState::Pressed),
+                button(3, 0, ButtonState::Held),
+                button(4, 0, ButtonState::Held),
+                button(5, 0, ButtonState::Released),
+            ],
+        );
+
+        let sliced = slice(&log, 3, 9).unwrap();
+        assert_eq!(
+            states(&sliced, 0),
+            vec![
+                (0, ButtonState::Pressed),
+                (1, ButtonState::Held),
+                (2, ButtonState::Released),
+            ]
+        );
+
+        // A release right at the cut drops the press entirely
+        let sliced = slice(&log, 5, 9).unwrap();
+        assert!(states(&sliced, 0).is_empty());
+    }
+
+    #[test]
+    fn test_slice_range_checks() {
+        let log = log_with(10, vec![]);
+        assert_eq!(slice(&log, 5, 100).unwrap().metadata.frame_count, 5);
+        assert_eq!(
+            slice(&log, 10, 20).unwrap_err(),
+            SpliceError::EmptyRange {
+                start: 10,
+                end: 20,
+                frame_count: 10,
+            }
+        );
+        assert!(slice(&log, 6, 5).is_err());
+    }
+
+    #[test]
+    fn test_slice_timestamps_and_annotations() {
+        let mut log = log_with(4, vec![]);
+        log.frame_timestamps = vec![1000, 2000, 3500, 4000];
+        log.annotations = vec![
+            Annotation {
+                frame: 0,
+                label: "hp".to_string(),
+                value: Some(100.0),
+            },
+            Annotation {
+                frame: 1,
+                label: "spawn".to_string(),
+                value: None,
+            },
+            Annotation {
+                frame: 2,
+                label: "checkpoint".to_string(),
+                value: None,
+            },
+        ];
+
+        let sliced = slice(&log, 2, 3).unwrap();
+        assert_eq!(sliced.frame_timestamps, vec![0, 500]);
+        assert_eq!(
+            sliced.annotations,
+            vec![
+                Annotation {
+                    frame: 0,
+                    label: "hp".to_string(),
+                    value: Some(100.0),
+                },
+                Annotation {
+                    frame: 0,
+                    label: "checkpoint".to_string(),
+                    value: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_concat_offsets_and_releases_at_join() {
+        let first = log_with(10, vec![button(8, 0, ButtonState::Pressed)]);
+        let second = log_with(
+            5,
+            vec![
+                button(1, 0, ButtonState::Pressed),
+                button(2, 0, ButtonState::Released),
+            ],
+        );
+
+        let joined = concat(&first, &second).unwrap();
+        assert_eq!(joined.metadata.frame_count, 15);
+        assert_eq!(
+            states(&joined, 0),
+            vec![
+                (8, ButtonState::Pressed),
+                (10, ButtonState::Released),
+                (11, ButtonState::Pressed),
+                (12, ButtonState::Released),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_concat_timestamps() {
+        let mut first = log_with(2, vec![]);
+        first.frame_timestamps = vec![500, 1500];
+        let second = log_with(2, vec![]);
+
+        let joined = concat(&first, &second).unwrap();
+        // The second log has no timestamps and runs at the nominal 60 FPS
+        assert_eq!(joined.frame_timestamps, vec![500, 1500, 18166, 34832]);
+    }
+
+    #[test]
+    fn test_concat_rejects_fps_mismatch() {
+        let first = log_with(1, vec![]);
+        let mut second = log_with(1, vec![]);
+        second.metadata.target_fps = 30;
+        assert_eq!(
+            concat(&first, &second).unwrap_err(),
+            SpliceError::FpsMismatch {
+                first: 60,
+                second: 30,
+            }
+        );
+    }
+
+    #[test]
+    fn test_merge_combines_disjoint_inputs() {
+        let mut first = log_with(10, vec![button(3, 0, ButtonState::Pressed)]);
+        first.mappings.push(InputMapping {
+            id: 0,
+            name: "Jump".to_string(),
+            color: None,
+            player: None,
+            device: None,
+            group: None,
+        });
+        let second = log_with(20, vec![axis(1, 1, 0.25), axis(5, 1, 0.0)]);
+
+        let merged = merge(&first, &second).unwrap();
+        assert_eq!(merged.metadata.frame_count, 20);
+        let frames: Vec<(u64, u32)> = merged.events.iter().map(|e| (e.frame, e.id)).collect();
+        assert_eq!(frames, vec![(1, 1), (3, 0), (5, 1)]);
+        assert_eq!(merged.mappings.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_rejects_overlapping_ids() {
+        let first = log_with(
+            10,
+            vec![button(3, 0, ButtonState::Pressed), axis(1, 2, 1.0)],
+        );
+        let second = log_with(10, vec![button(4, 2, ButtonState::Pressed)]);
+        let error = merge(&first, &second).unwrap_err();
+        assert_eq!(error, SpliceError::OverlappingIds(vec![2]));
+        assert_eq!(error.to_string(), "Logs both contain input IDs 2");
+    }
+}
@@ -0,0 +1,584 @@
+//! Per-input statistics.
+//!
+//! This module computes press counts, hold durations, press rates and axis
+//! usage for every input in a log, either over the whole log or over a frame
+//! range. The output is deterministic (inputs are ordered by ID and all
+//! values are derived from frames only) so it can be serialized to JSON and
+//! compared across runs.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::log::{ButtonState, InputEvent, InputKind, InputLog};
+
+/// Default threshold for counting an axis as "pushed".
+pub const DEFAULT_AXIS_THRESHOLD: f32 = 0.5;
+
+/// Default number of histogram bins for axis values.
+pub const DEFAULT_HISTOGRAM_BINS: usize = 10;
+
+/// Settings for statistics computation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatsConfig {
+    /// Axis magnitudes above this value count as "past threshold"
+    pub axis_threshold: f32,
+    /// Number of bins in axis value histograms
+    pub histogram_bins: usize,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            axis_threshold: DEFAULT_AXIS_THRESHOLD,
+            histogram_bins: DEFAULT_HISTOGRAM_BINS,
+        }
+    }
+}
+
+/// Statistics for a whole log or a frame range of it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogStats {
+    /// First frame included in the statistics
+    pub start_frame: u64,
+    /// Last frame included in the statistics (inclusive)
+    pub end_frame: u64,
+    /// Target FPS used to convert frames to time
+    pub target_fps: u32,
+    /// Axis threshold used for `frames_past_threshold`
+    pub axis_threshold: f32,
+    /// Per-input statistics, ordered by input ID
+    pub inputs: Vec<InputStats>,
+}
+
+/// Statistics for a single input.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InputStats {
+    /// Input identifier
+    pub id: u32,
+    /// Display name of the input
+    pub name: String,
+    /// Type of input
+    pub kind: InputKind,
+    /// Number of presses that started inside the range
+    pub press_count: u64,
+    /// Presses per minute of range time
+    pub presses_per_minute: f64,
+    /// Hold duration statistics (None if there were no presses)
+    pub hold: Option<HoldStats>,
+    /// Axis statistics (None for buttons)
+    pub axis: Option<AxisStats>,
+}
+
+/// Hold duration statistics, in frames and milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HoldStats {
+    /// Average hold duration in frames
+    pub avg_frames: f64,
+    /// Shortest hold in frames
+    pub min_frames: u64,
+    /// Longest hold in frames
+    pub max_frames: u64,
+    /// Average hold duration in milliseconds
+    pub avg_ms: f64,
+    /// Shortest hold in milliseconds
+    pub min_ms: f64,
+    /// Longest hold in milliseconds
+    pub max_ms: f64,
+}
+
+/// Usage statistics for an axis input.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AxisStats {
+    /// Number of frames with a magnitude above the threshold
+    pub frames_past_threshold: u64,
+    /// Time above the threshold in milliseconds
+    pub ms_past_threshold: f64,
+    /// Fraction of the range spent above the threshold (0.0 - 1.0)
+    pub fraction_past_threshold: f64,
+    /// Frame-weighted histogram of axis values
+    pub histogram: Histogram,
+}
+
+/// A histogram with equally sized bins over `[min, max]`.
+///
+/// 1D axes use their signed value over `[-1, 1]`, 2D axes use their
+/// magnitude over `[0, 1]`. Values outside the range land in the edge bins.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Histogram {
+    /// Lower bound of the first bin
+    pub min: f32,
+    /// Upper bound of the last bin
+    pub max: f32,
+    /// Number of frames per bin
+    pub counts: Vec<u64>,
+}
+
+impl Histogram {
+    fn new(min: f32, max: f32, bins: usize) -> Self {
+        Self {
+            min,
+            max,
+            counts: vec![0; bins.max(1)],
+        }
+    }
+
+    fn add(&mut self, value: f32, weight: u64) {
+        let bins = self.counts.len();
+        let t = ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0);
+        let index = ((t * bins as f32) as usize).min(bins - 1);
+        self.counts[index] += weight;
+    }
+}
+
+impl LogStats {
+    /// Serialize the statistics as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Compute statistics for every input in the log.
+///
+/// # Arguments
+/// * `log` - The input log to analyze
+/// * `range` - Optional inclusive frame range; `None` covers the whole log
+/// * `config` - Threshold and histogram settings
+///
+/// # Returns
+/// Statistics for all inputs from the effective mappings, ordered by ID.
+pub fn compute_stats(log: &InputLog, range: Option<(u64, u64)>, config: &StatsConfig) -> LogStats {
+    let last_frame = log.metadata.frame_count.saturating_sub(1);
+    let (start, end) = match range {
+        Some((a, b)) => (a.min(b), a.max(b).min(last_frame)),
+        None => (0, last_frame),
+    };
+    let target_fps = log.metadata.target_fps.max(1);
+    let range_frames = if log.metadata.frame_count == 0 || start > end {
+        0
+    } else {
+        end - start + 1
+    };
+
+    // Group events per input, keeping frame order stable
+    let mut per_input: BTreeMap<u32, Vec<&InputEvent>> = BTreeMap::new();
+    for event in &log.events {
+        per_input.entry(event.id).or_default().push(event);
+    }
+    for events in per_input.values_mut() {
+        events.sort_by_key(|e| e.frame);
+    }
+
+    let ctx = RangeContext {
+        start,
+        end,
+        range_frames,
+        target_fps,
+    };
+
+    let inputs = log
+        .get_effective_mappings()
+        .into_iter()
+        .map(|mapping| {
+            let events = per_input
+                .get(&mapping.id)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let kind = events.first().map(|e| e.kind).unwrap_or(InputKind::Button);
+            input_stats(
+                mapping.id,
+                mapping.qualified_name(),
+                kind,
+                events,
+                &ctx,
+                config,
+            )
+        })
+        .collect();
+
+    LogStats {
+        start_frame: start,
+        end_frame: end,
+        target_fps,
+        axis_threshold: config.axis_threshold,
+        inputs,
+    }
+}
+
+/// Frame range and timing shared by all per-input computations.
+struct RangeContext {
+    start: u64,
+    end: u64,
+    range_frames: u64,
+    target_fps: u32,
+}
+
+impl RangeContext {
+    fn frames_to_ms(&self, frames: f64) -> f64 {
+        frames * 1000.0 / self.target_fps as f64
+    }
+}
+
+fn input_stats(
+    id: u32,
+    name: String,
+    kind: InputKind,
+    events: &[&InputEvent],
+    ctx: &RangeContext,
+    config: &StatsConfig,
+) -> InputStats {
+    let holds = match kind {
+        InputKind::Button | InputKind::Touch | InputKind::Key => button_holds(events, ctx),
+        InputKind::Axis1D | InputKind::Axis2D | InputKind::Trigger | InputKind::MouseDelta => {
+            axis_holds(events, ctx, config.axis_threshold)
+        }
+        // A pointer is always somewhere; there is nothing to hold
+        InputKind::PointerPosition => Vec::new(),
+    };
+    let press_count = holds.len() as u64;
+
+    let minutes = ctx.range_frames as f64 / ctx.target_fps as f64 / 60.0;
+    let presses_per_minute = if minutes > 0.0 {
+        press_count as f64 / minutes
+    } else {
+        0.0
+    };
+
+    let hold = (!holds.is_empty()).then(|| {
+        let min = *holds.iter().min().unwrap_or(&0);
+        let max = *holds.iter().max().unwrap_or(&0);
+        let avg = holds.iter().sum::<u64>() as f64 / holds.len() as f64;
+        HoldStats {
+            avg_frames: avg,
+            min_frames: min,
+            max_frames: max,
+            avg_ms: ctx.frames_to_ms(avg),
+            min_ms: ctx.frames_to_ms(min as f64),
+            max_ms: ctx.frames_to_ms(max as f64),
+        }
+    });
+
+    // Mouse deltas and pointer positions are unbounded, so they get no histogram
+    let axis = match kind {
+        InputKind::Axis1D | InputKind::Axis2D | InputKind::Trigger => {
+            Some(axis_stats(kind, events, ctx, config))
+        }
+        _ => None,
+    };
+
+    InputStats {
+        id,
+        name,
+        kind,
+        press_count,
+        presses_per_minute,
+        hold,
+        axis,
+    }
+}
+
+/// Collect the durations of button presses that start inside the range.
+///
+/// A `Held` event without a preceding press is treated as the press start so
+/// sparse logs are counted correctly. Holds still open at the end of the
+/// range are clipped to it. Each touch contact is held on its own.
+fn button_holds(events: &[&InputEvent], ctx: &RangeContext) -> Vec<u64> {
+    let mut holds = Vec::new();
+    // Press frame of each contact that is currently down
+    let mut down_since: BTreeMap<u16, u64> = BTreeMap::new();
+
+    let finish = |holds: &mut Vec<u64>, since: u64, until: u64| {
+        if since >= ctx.start && since <= ctx.end {
+            holds.push(until.min(ctx.end + 1).saturating_sub(since).max(1));
+        }
+    };
+
+    for event in events {
+        let since = down_since.get(&event.contact).copied();
+        match (event.state, since) {
+            (ButtonState::Pressed, Some(since)) => {
+                // Press without a release in between: close the previous hold
+                finish(&mut holds, since, event.frame);
+                down_since.insert(event.contact, event.frame);
+            }
+            (ButtonState::Pressed, None) | (ButtonState::Held, None) => {
+                down_since.insert(event.contact, event.frame);
+            }
+            (ButtonState::Released, Some(since)) => {
+                finish(&mut holds, since, event.frame);
+                down_since.remove(&event.contact);
+            }
+            _ => {}
+        }
+    }
+
+    for since in down_since.into_values() {
+        finish(&mut holds, since, ctx.end + 1);
+    }
+
+    holds
+}
+
+/// Collect the durations of stretches where the axis is past the threshold.
+///
+/// Each stretch that starts inside the range counts as one "press".
+fn axis_holds(events: &[&InputEvent], ctx: &RangeContext, threshold: f32) -> Vec<u64> {
+    let mut holds = Vec::new();
+    let mut active_since: Option<u64> = None;
+
+    for event in events {
+        let active = axis_magnitude(event) > threshold;
+        match (active, active_since) {
+            (true, None) => active_since = Some(event.frame),
+            (false, Some(since)) => {
+                if since >= ctx.start && since <= ctx.end {
+                    holds.push(event.frame.min(ctx.end + 1) - since);
+                }
+                active_since = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(since) = active_since
+        && since >= ctx.start
+        && since <= ctx.end
+    {
+        holds.push(ctx.end + 1 - since);
+    }
+
+    holds
+}
+
+/// Compute frame-weighted axis usage, carrying each value forward until the next event.
+fn axis_stats(
+    kind: InputKind,
+    events: &[&InputEvent],
+    ctx: &RangeContext,
+    config: &StatsConfig,
+) -> AxisStats {
+    let mut histogram = match kind {
+        InputKind::Axis2D | InputKind::Trigger => Histogram::new(0.0, 1.0, config.histogram_bins),
+        _ => Histogram::new(-1.0, 1.0, config.histogram_bins),
+    };
+    let mut frames_past_threshold = 0u64;
+
+    if ctx.range_frames > 0 {
+        // Axes rest at zero until their first event
+        let mut segment_start = 0u64;
+        let mut value = 0.0f32;
+        let mut magnitude = 0.0f32;
+
+        let mut add_segment = |from: u64, to_exclusive: u64, value: f32, magnitude: f32| {
+            let from = from.max(ctx.start);
+            let to = to_exclusive.min(ctx.end + 1);
+            if to > from {
+                let frames = to - from;
+                histogram.add(value, frames);
+                if magnitude > config.axis_threshold {
+                    frames_past_threshold += frames;
+                }
+            }
+        };
+
+        for event in events {
+            add_segment(segment_start, event.frame, value, magnitude);
+            segment_start = event.frame;
+            magnitude = axis_magnitude(event);
+            value = match kind {
+                InputKind::Axis2D => magnitude,
+                _ => event.value[0],
+            };
+        }
+        add_segment(segment_start, ctx.end + 1, value, magnitude);
+    }
+
+    let fraction_past_threshold = if ctx.range_frames > 0 {
+        frames_past_threshold as f64 / ctx.range_frames as f64
+    } else {
+        0.0
+    };
+
+    AxisStats {
+        frames_past_threshold,
+        ms_past_threshold: ctx.frames_to_ms(frames_past_threshold as f64),
+        fraction_past_threshold,
+        histogram,
+    }
+}
+
+/// Magnitude of an axis event's value (1D uses the absolute value).
+fn axis_magnitude(event: &InputEvent) -> f32 {
+    match event.kind {
+        InputKind::Axis2D | InputKind::MouseDelta => {
+            (event.value[0] * event.value[0] + event.value[1] * event.value[1]).sqrt()
+        }
+        _ => event.value[0].abs(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_util::{axis, button, log_with, mapping};
+
+    fn create_test_log(frame_count: u64, events: Vec<InputEvent>) -> InputLog {
+        InputLog {
+            mappings: vec![mapping(0, "Jump"), mapping(10, "Move X")],
+            ..log_with(frame_count, events)
+        }
+    }
+
+    fn stats_for(stats: &LogStats, id: u32) -> &InputStats {
+        stats.inputs.iter().find(|s| s.id == id).unwrap()
+    }
+
+    #[test]
+    fn test_button_press_count_and_holds() {
+        let log = create_test_log(
+            3600,
+            vec![
+                button(10, 0, ButtonState::Pressed),
+                button(11, 0, ButtonState::Held),
+                button(14, 0, ButtonState::Released),
+                button(100, 0, ButtonState::Pressed),
+                button(112, 0, ButtonState::Released),
+            ],
+        );
+        let stats = compute_stats(&log, None, &StatsConfig::default());
+        let jump = stats_for(&stats, 0);
+
+        assert_eq!(jump.press_count, 2);
+        // One minute of log at 60 FPS
+        assert!((jump.presses_per_minute - 2.0).abs() < 1e-9);
+
+        let hold = jump.hold.as_ref().unwrap();
+        assert_eq!(hold.min_frames, 4);
+        assert_eq!(hold.max_frames, 12);
+        assert!((hold.avg_frames - 8.0).abs() < 1e-9);
+        assert!((hold.min_ms - 4000.0 / 60.0).abs() < 1e-9);
+        assert!((hold.max_ms - 200.0).abs() < 1e-9);
+        assert!(jump.axis.is_none());
+    }
+
+    #[test]
+    fn test_sparse_held_counts_as_press() {
+        let log = create_test_log(
+            100,
+            vec![
+                button(5, 0, ButtonState::Held),
+                button(9, 0, ButtonState::Released),
+            ],
+        );
+        let stats = compute_stats(&log, None, &StatsConfig::default());
+        let jump = stats_for(&stats, 0);
+        assert_eq!(jump.press_count, 1);
+        assert_eq!(jump.hold.as_ref().unwrap().max_frames, 4);
+    }
+
+    #[test]
+    fn test_touch_contacts_are_held_separately() {
+        let touch = |frame, contact, state| InputEvent {
+            kind: InputKind::Touch,
+            contact,
+            ..button(frame, 0, state)
+        };
+        // Contact 1 lands while contact 0 is still down
+        let log = create_test_log(
+            100,
+            vec![
+                touch(10, 0, ButtonState::Pressed),
+                touch(12, 1, ButtonState::Pressed),
+                touch(14, 0, ButtonState::Released),
+                touch(20, 1, ButtonState::Released),
+            ],
+        );
+        let stats = compute_stats(&log, None, &StatsConfig::default());
+        let touches = stats_for(&stats, 0);
+
+        assert_eq!(touches.press_count, 2);
+        let hold = touches.hold.as_ref().unwrap();
+        assert_eq!(hold.min_frames, 4);
+        assert_eq!(hold.max_frames, 8);
+    }
+
+    #[test]
+    fn test_range_limits_presses_and_clips_holds() {
+        let log = create_test_log(
+            200,
+            vec![
+                button(10, 0, ButtonState::Pressed),
+                button(20, 0, ButtonState::Released),
+                button(50, 0, ButtonState::Pressed),
+                button(90, 0, ButtonState::Released),
+            ],
+        );
+        let stats = compute_stats(&log, Some((15, 59)), &StatsConfig::default());
+        assert_eq!(stats.start_frame, 15);
+        assert_eq!(stats.end_frame, 59);
+
+        let jump = stats_for(&stats, 0);
+        // Press at frame 10 started before the range and is not counted
+        assert_eq!(jump.press_count, 1);
+        // Hold from frame 50 is clipped to the end of the range
+        assert_eq!(jump.hold.as_ref().unwrap().max_frames, 10);
+    }
+
+    #[test]
+    fn test_axis_time_past_threshold_and_histogram() {
+        let log = create_test_log(
+            100,
+            vec![axis(0, 10, 0.0), axis(20, 10, 1.0), axis(50, 10, -0.2)],
+        );
+        let config = StatsConfig {
+            axis_threshold: 0.5,
+            histogram_bins: 4,
+        };
+        let stats = compute_stats(&log, None, &config);
+        let move_x = stats_for(&stats, 10);
+
+        assert_eq!(move_x.kind, InputKind::Axis1D);
+        assert_eq!(move_x.press_count, 1);
+        let axis_stats = move_x.axis.as_ref().unwrap();
+        assert_eq!(axis_stats.frames_past_threshold, 30);
+        assert!((axis_stats.fraction_past_threshold - 0.3).abs() < 1e-9);
+        assert!((axis_stats.ms_past_threshold - 500.0).abs() < 1e-9);
+
+        // Bins over [-1, 1]: [-1,-0.5) [-0.5,0) [0,0.5) [0.5,1]
+        assert_eq!(axis_stats.histogram.counts, vec![0, 50, 20, 30]);
+        assert_eq!(axis_stats.histogram.counts.iter().sum::<u64>(), 100);
+    }
+
+    #[test]
+    fn test_unused_input_has_zero_stats() {
+        let log = create_test_log(100, vec![]);
+        let stats = compute_stats(&log, None, &StatsConfig::default());
+
+        assert_eq!(stats.inputs.len(), 2);
+        let jump = stats_for(&stats, 0);
+        assert_eq!(jump.press_count, 0);
+        assert_eq!(jump.presses_per_minute, 0.0);
+        assert!(jump.hold.is_none());
+    }
+
+    #[test]
+    fn test_json_output_is_reproducible() {
+        let log = create_test_log(
+            100,
+            vec![
+                axis(3, 10, 0.7),
+                button(1, 0, ButtonState::Pressed),
+                button(4, 0, ButtonState::Released),
+            ],
+        );
+        let a = compute_stats(&log, None, &StatsConfig::default()).to_json();
+        let b = compute_stats(&log, None, &StatsConfig::default()).to_json();
+        assert_eq!(a, b);
+
+        let value: serde_json::Value = serde_json::from_str(&a).unwrap();
+        let inputs = value["inputs"].as_array().unwrap();
+        assert_eq!(inputs[0]["id"], 0);
+        assert_eq!(inputs[1]["id"], 10);
+        assert_eq!(inputs[1]["kind"], "Axis1D");
+    }
+}
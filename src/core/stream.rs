@@ -0,0 +1,1060 @@
+//! Live input streaming over a local socket.
+//!
+//! Instead of writing a file, a running game can push its input events
+//! straight to the viewer over TCP or a Unix domain socket on the local
+//! machine. The connection carries a small framed protocol in which every
+//! frame is a one-byte type, a little-endian `u32` payload length and the
+//! payload:
+//!
+//! ```text
+//! Hello   (1): magic "ILST", version: u32, target_fps: u32, source: UTF-8 (may be empty)
+//! Mapping (2): the `InputMapping` as a JSON object
+//! Events  (3): any number of 24-byte `BinaryEvent` records
+//! End     (4): empty
+//! ```
+//!
+//! The sender opens with `Hello` followed by one `Mapping` per known input
+//! (the mapping handshake). `Events` frames follow, and further `Mapping`
+//! frames may be sent whenever a new input appears. `End` marks a clean
+//! finish; a sender that simply disconnects leaves the received events intact.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use super::log::{InputEvent, InputLog, InputMapping};
+use super::parser::{BinaryEvent, ParseError};
+
+/// Endpoint the viewer listens on when none is given.
+pub const DEFAULT_ENDPOINT: &str = "127.0.0.1:7878";
+
+/// Magic number at the start of a `Hello` payload: "ILST"
+const STREAM_MAGIC: [u8; 4] = *b"ILST";
+
+/// Currently supported protocol version
+const STREAM_VERSION: u32 = 1;
+
+/// Size of the type and length prefix of every frame
+const FRAME_HEADER_SIZE: usize = 5;
+
+/// Largest accepted payload; anything bigger is treated as a corrupt stream
+const MAX_PAYLOAD_SIZE: usize = 16 * 1024 * 1024;
+
+/// How long blocking socket operations wait before checking for a stop request
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+const TYPE_HELLO: u8 = 1;
+const TYPE_MAPPING: u8 = 2;
+const TYPE_EVENTS: u8 = 3;
+const TYPE_END: u8 = 4;
+
+/// Errors that can occur while streaming.
+#[derive(Debug, Error)]
+pub enum StreamError {
+    /// Socket error
+    #[error("Socket error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The endpoint string could not be understood
+    #[error("Invalid endpoint '{0}': expected PORT, HOST:PORT or unix:PATH")]
+    InvalidEndpoint(String),
+
+    /// The endpoint does not refer to the local machine
+    #[error("Endpoint '{0}' is not on localhost")]
+    NotLocal(String),
+
+    /// The peer sent something that doesn't follow the protocol
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+
+    /// An event record could not be converted
+    #[error(transparent)]
+    InvalidEvent(#[from] ParseError),
+
+    /// The replay speed is too slow to pace the log
+    #[error("Replay speed {0} is too slow to pace this log")]
+    InvalidSpeed(f64),
+}
+
+// ============================================================================
+// Endpoints
+// ============================================================================
+
+/// Local address to listen on or connect to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    /// TCP on a loopback address
+    Tcp(SocketAddr),
+    /// Unix domain socket at the given path
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl Endpoint {
+    /// Parse an endpoint string.
+    ///
+    /// Accepted forms are a bare port (`7878`, meaning `127.0.0.1:7878`),
+    /// `HOST:PORT` (optionally prefixed with `tcp:`) resolving to a loopback
+    /// address, and `unix:PATH` on Unix platforms.
+    pub fn parse(value: &str) -> Result<Self, StreamError> {
+        let value = value.trim();
+        let invalid = || StreamError::InvalidEndpoint(value.to_string());
+
+        if let Some(path) = value.strip_prefix("unix:") {
+            #[cfg(unix)]
+            {
+                if path.is_empty() {
+                    return Err(invalid());
+                }
+                return Ok(Self::Unix(PathBuf::from(path)));
+            }
+            #[cfg(not(unix))]
+            {
+                let _ = path;
+                return Err(invalid());
+            }
+        }
+
+        let address = value.strip_prefix("tcp:").unwrap_or(value);
+        if let Ok(port) = address.parse::<u16>() {
+            return Ok(Self::Tcp(SocketAddr::from(([127, 0, 0, 1], port))));
+        }
+
+        let mut candidates = address.to_socket_addrs().map_err(|_| invalid())?.peekable();
+        if candidates.peek().is_none() {
+            return Err(invalid());
+        }
+        candidates
+            .find(|addr| addr.ip().is_loopback())
+            .map(Self::Tcp)
+            .ok_or_else(|| StreamError::NotLocal(value.to_string()))
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{}", addr),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+// ============================================================================
+// Protocol messages
+// ============================================================================
+
+/// A single protocol frame.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Opens the stream
+    Hello {
+        /// Target frames per second of the game
+        target_fps: u32,
+        /// Name of the sending application
+        source: Option<String>,
+    },
+    /// Display information for an input
+    Mapping(InputMapping),
+    /// A batch of input events
+    Events(Vec<InputEvent>),
+    /// The sender finished cleanly
+    End,
+}
+
+impl Message {
+    /// Encode the message as a complete frame.
+    pub fn encode(&self) -> Vec<u8> {
+        let (kind, payload) = match self {
+            Self::Hello { target_fps, source } => {
+                let mut payload = Vec::new();
+                payload.extend_from_slice(&STREAM_MAGIC);
+                payload.extend_from_slice(&STREAM_VERSION.to_le_bytes());
+                payload.extend_from_slice(&target_fps.to_le_bytes());
+                payload.extend_from_slice(source.as_deref().unwrap_or("").as_bytes());
+                (TYPE_HELLO, payload)
+            }
+            Self::Mapping(mapping) => {
+                // Serializing a mapping to memory cannot fail
+                let payload = serde_json::to_vec(mapping).expect("mapping serializes");
+                (TYPE_MAPPING, payload)
+            }
+            Self::Events(events) => {
+                let mut payload = Vec::with_capacity(events.len() * BinaryEvent::SIZE);
+                for event in events {
+                    payload.extend_from_slice(bytemuck::bytes_of(&BinaryEvent::from_input_event(
+                        event,
+                    )));
+                }
+                (TYPE_EVENTS, payload)
+            }
+            Self::End => (TYPE_END, Vec::new()),
+        };
+
+        let mut frame = Vec::with_capacity(FRAME_HEADER_SIZE + payload.len());
+        frame.push(kind);
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&payload);
+        frame
+    }
+}
+
+/// Incremental decoder that turns received bytes into messages.
+///
+/// Bytes can be pushed in arbitrary chunks; incomplete frames stay buffered
+/// until the rest arrives.
+#[derive(Debug, Default)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+    /// Number of events decoded so far (for error messages)
+    events_decoded: usize,
+}
+
+impl FrameDecoder {
+    /// Create an empty decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append received bytes.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Whether part of a frame is still waiting for more bytes.
+    pub fn has_partial_frame(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// Decode the next complete message, if one has been received.
+    pub fn next_message(&mut self) -> Result<Option<Message>, StreamError> {
+        if self.buffer.len() < FRAME_HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let kind = self.buffer[0];
+        let length = u32::from_le_bytes([
+            self.buffer[1],
+            self.buffer[2],
+            self.buffer[3],
+            self.buffer[4],
+        ]) as usize;
+        if length > MAX_PAYLOAD_SIZE {
+            return Err(StreamError::Protocol(format!(
+                "frame of {} bytes exceeds the {} byte limit",
+                length, MAX_PAYLOAD_SIZE
+            )));
+        }
+        if self.buffer.len() < FRAME_HEADER_SIZE + length {
+            return Ok(None);
+        }
+
+        let payload: Vec<u8> = self
+            .buffer
+            .drain(..FRAME_HEADER_SIZE + length)
+            .skip(FRAME_HEADER_SIZE)
+            .collect();
+        self.decode(kind, &payload).map(Some)
+    }
+
+    /// Decode a single frame payload.
+    fn decode(&mut self, kind: u8, payload: &[u8]) -> Result<Message, StreamError> {
+        match kind {
+            TYPE_HELLO => {
+                if payload.len() < 12 || payload[..4] != STREAM_MAGIC {
+                    return Err(StreamError::Protocol(
+                        "stream does not start with a valid Hello".to_string(),
+                    ));
+                }
+                let version = u32::from_le_bytes([payload[4], payload[5], payload[6], payload[7]]);
+                if version != STREAM_VERSION {
+                    return Err(StreamError::Protocol(format!(
+                        "unsupported protocol version {}: expected version {}",
+                        version, STREAM_VERSION
+                    )));
+                }
+                let target_fps =
+                    u32::from_le_bytes([payload[8], payload[9], payload[10], payload[11]]);
+                let source = String::from_utf8_lossy(&payload[12..]).to_string();
+                Ok(Message::Hello {
+                    target_fps,
+                    source: (!source.is_empty()).then_some(source),
+                })
+            }
+            TYPE_MAPPING => serde_json::from_slice(payload)
+                .map(Message::Mapping)
+                .map_err(|e| StreamError::Protocol(format!("invalid Mapping frame: {}", e))),
+            TYPE_EVENTS => {
+                if !payload.len().is_multiple_of(BinaryEvent::SIZE) {
+                    return Err(StreamError::Protocol(format!(
+                        "Events frame of {} bytes is not a multiple of {}",
+                        payload.len(),
+                        BinaryEvent::SIZE
+                    )));
+                }
+                let events = payload
+                    .chunks_exact(BinaryEvent::SIZE)
+                    .enumerate()
+                    .map(|(i, chunk)| {
+                        bytemuck::pod_read_unaligned::<BinaryEvent>(chunk)
+                            .to_input_event(self.events_decoded + i)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.events_decoded += events.len();
+                Ok(Message::Events(events))
+            }
+            TYPE_END => Ok(Message::End),
+            other => Err(StreamError::Protocol(format!(
+                "unknown frame type {}",
+                other
+            ))),
+        }
+    }
+}
+
+// ============================================================================
+// Connections
+// ============================================================================
+
+/// An accepted or established socket connection.
+enum Connection {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Connection {
+    /// Connect to an endpoint.
+    fn connect(endpoint: &Endpoint) -> io::Result<Self> {
+        match endpoint {
+            Endpoint::Tcp(addr) => {
+                let stream = TcpStream::connect(addr)?;
+                // Events are small and latency matters more than throughput
+                stream.set_nodelay(true)?;
+                Ok(Self::Tcp(stream))
+            }
+            #[cfg(unix)]
+            Endpoint::Unix(path) => UnixStream::connect(path).map(Self::Unix),
+        }
+    }
+
+    /// Make reads return periodically so stop requests are noticed.
+    fn set_read_timeout(&self, timeout: Duration) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => {
+                stream.set_nonblocking(false)?;
+                stream.set_read_timeout(Some(timeout))
+            }
+            #[cfg(unix)]
+            Self::Unix(stream) => {
+                stream.set_nonblocking(false)?;
+                stream.set_read_timeout(Some(timeout))
+            }
+        }
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+/// A bound listening socket.
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    /// Bind to an endpoint in non-blocking mode.
+    ///
+    /// Returns the listener together with the endpoint actually bound (which
+    /// differs from the requested one when TCP port 0 was used).
+    fn bind(endpoint: &Endpoint) -> io::Result<(Self, Endpoint)> {
+        match endpoint {
+            Endpoint::Tcp(addr) => {
+                let listener = TcpListener::bind(addr)?;
+                listener.set_nonblocking(true)?;
+                let bound = Endpoint::Tcp(listener.local_addr()?);
+                Ok((Self::Tcp(listener), bound))
+            }
+            #[cfg(unix)]
+            Endpoint::Unix(path) => {
+                // A socket file left behind by an earlier session blocks binding;
+                // anything else at the path is the user's and is left alone
+                if let Ok(metadata) = std::fs::symlink_metadata(path) {
+                    if !metadata.file_type().is_socket() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::AlreadyExists,
+                            format!("{} exists and is not a socket", path.display()),
+                        ));
+                    }
+                    if UnixStream::connect(path).is_ok() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::AddrInUse,
+                            format!("another receiver is listening on {}", path.display()),
+                        ));
+                    }
+                    std::fs::remove_file(path)?;
+                }
+                let listener = UnixListener::bind(path)?;
+                listener.set_nonblocking(true)?;
+                Ok((Self::Unix(listener), endpoint.clone()))
+            }
+        }
+    }
+
+    /// Accept a pending connection, if any.
+    fn accept(&self) -> io::Result<Option<Connection>> {
+        let result = match self {
+            Self::Tcp(listener) => listener.accept().map(|(s, _)| Connection::Tcp(s)),
+            #[cfg(unix)]
+            Self::Unix(listener) => listener.accept().map(|(s, _)| Connection::Unix(s)),
+        };
+        match result {
+            Ok(connection) => Ok(Some(connection)),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Socket file bound by a Unix listener, removed again when receiving stops.
+#[cfg(unix)]
+struct SocketFile {
+    path: PathBuf,
+    /// Device and inode of the socket, to tell it apart from a later file
+    /// created at the same path
+    dev: u64,
+    ino: u64,
+}
+
+#[cfg(unix)]
+impl SocketFile {
+    /// Identify the socket that was just bound at `path`.
+    fn new(path: &std::path::Path) -> io::Result<Self> {
+        let metadata = std::fs::symlink_metadata(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+        })
+    }
+
+    /// Remove the file if it is still the socket that was bound.
+    fn remove(&self) {
+        let is_ours = std::fs::symlink_metadata(&self.path).is_ok_and(|metadata| {
+            metadata.file_type().is_socket()
+                && metadata.dev() == self.dev
+                && metadata.ino() == self.ino
+        });
+        if is_ours {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+// ============================================================================
+// Receiving
+// ============================================================================
+
+/// Connection state of a `StreamReceiver`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamStatus {
+    /// Waiting for a sender to connect
+    Listening,
+    /// A sender is connected
+    Connected,
+    /// The sender finished or disconnected; no more events will arrive
+    Finished,
+    /// The connection failed
+    Failed(String),
+}
+
+impl StreamStatus {
+    /// Short label for display.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Listening => "Waiting for sender…".to_string(),
+            Self::Connected => "Connected".to_string(),
+            Self::Finished => "Finished".to_string(),
+            Self::Failed(reason) => format!("Failed: {}", reason),
+        }
+    }
+}
+
+/// Notification from the receiving thread.
+enum Update {
+    Connected,
+    Message(Message),
+    Disconnected,
+    Failed(String),
+}
+
+/// What changed in the log during a `StreamReceiver::poll`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamPoll {
+    /// Number of events appended
+    pub events: usize,
+    /// Input IDs that appeared for the first time (sorted)
+    pub new_ids: Vec<u32>,
+}
+
+/// Listens on a local socket and collects streamed events.
+///
+/// Socket I/O happens on a background thread; `poll` moves everything
+/// received so far into an `InputLog` on the caller's thread. A receiver
+/// accepts a single sender; start a new one to record another session.
+pub struct StreamReceiver {
+    endpoint: Endpoint,
+    status: StreamStatus,
+    updates: Receiver<Update>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    /// Input IDs already present in the log
+    known_ids: HashSet<u32>,
+    /// Socket file this receiver created, if listening on a Unix socket
+    #[cfg(unix)]
+    socket_file: Option<SocketFile>,
+}
+
+impl StreamReceiver {
+    /// Bind to an endpoint and start waiting for a sender.
+    ///
+    /// # Arguments
+    /// * `endpoint` - Local endpoint to listen on (TCP port 0 picks a free port)
+    ///
+    /// # Returns
+    /// * `Ok(StreamReceiver)` - The socket is bound and the receiving thread runs
+    /// * `Err(StreamError)` - The socket could not be bound
+    pub fn listen(endpoint: &Endpoint) -> Result<Self, StreamError> {
+        let (listener, bound) = Listener::bind(endpoint)?;
+        #[cfg(unix)]
+        let socket_file = match &bound {
+            Endpoint::Unix(path) => Some(SocketFile::new(path)?),
+            Endpoint::Tcp(_) => None,
+        };
+        let (sender, updates) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_stop = Arc::clone(&stop);
+        let thread = std::thread::Builder::new()
+            .name("input-stream".to_string())
+            .spawn(move || receive(listener, &thread_stop, &sender))?;
+
+        Ok(Self {
+            endpoint: bound,
+            status: StreamStatus::Listening,
+            updates,
+            stop,
+            thread: Some(thread),
+            known_ids: HashSet::new(),
+            #[cfg(unix)]
+            socket_file,
+        })
+    }
+
+    /// The endpoint being listened on.
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Current connection state.
+    pub fn status(&self) -> &StreamStatus {
+        &self.status
+    }
+
+    /// Whether events may still arrive.
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.status,
+            StreamStatus::Listening | StreamStatus::Connected
+        )
+    }
+
+    /// Apply everything received since the last poll to `log`.
+    ///
+    /// `Hello` updates the metadata, `Mapping` adds or replaces a mapping and
+    /// events are appended with `frame_count` extended to cover them.
+    pub fn poll(&mut self, log: &mut InputLog) -> StreamPoll {
+        let mut result = StreamPoll::default();
+
+        loop {
+            let update = match self.updates.try_recv() {
+                Ok(update) => update,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if self.is_active() {
+                        self.status = StreamStatus::Finished;
+                    }
+                    break;
+                }
+            };
+
+            match update {
+                Update::Connected => self.status = StreamStatus::Connected,
+                Update::Disconnected => self.status = StreamStatus::Finished,
+                Update::Failed(reason) => self.status = StreamStatus::Failed(reason),
+                Update::Message(Message::Hello { target_fps, source }) => {
+                    if target_fps > 0 {
+                        log.metadata.target_fps = target_fps;
+                    }
+                    log.metadata.source = source;
+                }
+                Update::Message(Message::Mapping(mapping)) => {
+                    if self.known_ids.insert(mapping.id) {
+                        result.new_ids.push(mapping.id);
+                    }
+                    match log.mappings.iter_mut().find(|m| m.id == mapping.id) {
+                        Some(existing) => *existing = mapping,
+                        None => log.mappings.push(mapping),
+                    }
+                }
+                Update::Message(Message::Events(events)) => {
+                    result.events += events.len();
+                    for event in events {
+                        if self.known_ids.insert(event.id) {
+                            result.new_ids.push(event.id);
+                        }
+                        log.metadata.frame_count = log.metadata.frame_count.max(event.frame + 1);
+                        log.events.push(event);
+                    }
+                }
+                Update::Message(Message::End) => self.status = StreamStatus::Finished,
+            }
+        }
+
+        if result.events > 0 && !log.events.is_sorted_by_key(|e| e.frame) {
+            // Keep the log ordered even if a sender interleaves frames
+            log.events.sort_by_key(|e| e.frame);
+        }
+        result.new_ids.sort_unstable();
+        result
+    }
+}
+
+impl Drop for StreamReceiver {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        #[cfg(unix)]
+        if let Some(socket_file) = &self.socket_file {
+            socket_file.remove();
+        }
+    }
+}
+
+/// Body of the receiving thread: accept one sender and forward its messages.
+fn receive(listener: Listener, stop: &AtomicBool, updates: &Sender<Update>) {
+    let mut connection = loop {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+        match listener.accept() {
+            Ok(Some(connection)) => break connection,
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                let _ = updates.send(Update::Failed(e.to_string()));
+                return;
+            }
+        }
+    };
+    // Only one sender per session
+    drop(listener);
+
+    if let Err(e) = connection.set_read_timeout(POLL_INTERVAL) {
+        let _ = updates.send(Update::Failed(e.to_string()));
+        return;
+    }
+    if updates.send(Update::Connected).is_err() {
+        return;
+    }
+
+    let mut decoder = FrameDecoder::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut handshake_done = false;
+    while !stop.load(Ordering::Relaxed) {
+        let read = match connection.read(&mut buffer) {
+            Ok(0) => {
+                let update = if decoder.has_partial_frame() {
+                    Update::Failed("sender disconnected in the middle of a frame".to_string())
+                } else {
+                    Update::Disconnected
+                };
+                let _ = updates.send(update);
+                return;
+            }
+            Ok(read) => read,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock
+                        | io::ErrorKind::TimedOut
+                        | io::ErrorKind::Interrupted
+                ) =>
+            {
+                continue;
+            }
+            Err(e) => {
+                let _ = updates.send(Update::Failed(e.to_string()));
+                return;
+            }
+        };
+
+        decoder.push(&buffer[..read]);
+        loop {
+            let message = match decoder.next_message() {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                Err(e) => {
+                    let _ = updates.send(Update::Failed(e.to_string()));
+                    return;
+                }
+            };
+            if !handshake_done && !matches!(message, Message::Hello { .. }) {
+                let _ = updates.send(Update::Failed(
+                    "stream does not start with a Hello frame".to_string(),
+                ));
+                return;
+            }
+            handshake_done = true;
+
+            let is_end = matches!(message, Message::End);
+            if updates.send(Update::Message(message)).is_err() || is_end {
+                return;
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Sending
+// ============================================================================
+
+/// Client side of the protocol, used by games and the `send` command.
+pub struct StreamSender {
+    connection: Connection,
+}
+
+impl StreamSender {
+    /// Connect to a listening viewer.
+    pub fn connect(endpoint: &Endpoint) -> Result<Self, StreamError> {
+        Ok(Self {
+            connection: Connection::connect(endpoint)?,
+        })
+    }
+
+    /// Send a single message.
+    pub fn send(&mut self, message: &Message) -> Result<(), StreamError> {
+        self.connection.write_all(&message.encode())?;
+        Ok(())
+    }
+}
+
+/// Replay a log over a stream in real time.
+///
+/// Sends the `Hello` and mapping handshake, then one `Events` frame per
+/// frame of the log, paced at `target_fps × speed`, and finally `End`.
+///
+/// # Arguments
+/// * `log` - Log to replay
+/// * `sender` - Connected sender
+/// * `speed` - Playback speed multiplier (1.0 = real time)
+///
+/// # Returns
+/// The number of events sent, or `StreamError::InvalidSpeed` before anything
+/// is sent when the last frame would be due too far in the future.
+pub fn replay(log: &InputLog, sender: &mut StreamSender, speed: f64) -> Result<usize, StreamError> {
+    let mut events = log.events.clone();
+    events.sort_by_key(|e| e.frame);
+
+    let fps = f64::from(log.metadata.target_fps.max(1)) * speed.max(f64::MIN_POSITIVE);
+    let offset = |frame: u64| {
+        Duration::try_from_secs_f64(frame as f64 / fps)
+            .map_err(|_| StreamError::InvalidSpeed(speed))
+    };
+    if let Some(last) = events.last() {
+        offset(last.frame)?;
+    }
+
+    sender.send(&Message::Hello {
+        target_fps: log.metadata.target_fps,
+        source: log.metadata.source.clone(),
+    })?;
+    for mapping in &log.mappings {
+        sender.send(&Message::Mapping(mapping.clone()))?;
+    }
+
+    let start = Instant::now();
+    for batch in events.chunk_by(|a, b| a.frame == b.frame) {
+        let due = start
+            .checked_add(offset(batch[0].frame)?)
+            .ok_or(StreamError::InvalidSpeed(speed))?;
+        if let Some(wait) = due.checked_duration_since(Instant::now()) {
+            std::thread::sleep(wait);
+        }
+        sender.send(&Message::Events(batch.to_vec()))?;
+    }
+
+    sender.send(&Message::End)?;
+    Ok(events.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log::{ButtonState, DeviceType, LogMetadata};
+    use crate::core::test_util::{button, mapping};
+
+    fn create_test_log() -> InputLog {
+        InputLog {
+            metadata: LogMetadata {
+                target_fps: 1000,
+                frame_count: 20,
+                source: Some("Stand-in".to_string()),
+                ..Default::default()
+            },
+            mappings: vec![InputMapping {
+                color: Some([1, 2, 3]),
+                ..mapping(0, "Jump")
+            }],
+            events: vec![
+                button(0, 0, ButtonState::Pressed),
+                button(3, 1, ButtonState::Pressed),
+                button(3, 0, ButtonState::Released),
+                button(10, 1, ButtonState::Released),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_endpoint_parse() {
+        assert_eq!(
+            Endpoint::parse("7878").unwrap(),
+            Endpoint::Tcp(SocketAddr::from(([127, 0, 0, 1], 7878)))
+        );
+        assert_eq!(
+            Endpoint::parse("tcp:127.0.0.1:9000").unwrap(),
+            Endpoint::Tcp(SocketAddr::from(([127, 0, 0, 1], 9000)))
+        );
+        assert!(matches!(
+            Endpoint::parse("10.1.2.3:7878"),
+            Err(StreamError::NotLocal(_))
+        ));
+        assert!(Endpoint::parse("not an endpoint").is_err());
+        #[cfg(unix)]
+        assert_eq!(
+            Endpoint::parse("unix:/tmp/ilv.sock").unwrap(),
+            Endpoint::Unix(PathBuf::from("/tmp/ilv.sock"))
+        );
+    }
+
+    #[test]
+    fn test_decoder_handles_split_frames() {
+        let mut bytes = Message::Hello {
+            target_fps: 60,
+            source: Some("Game".to_string()),
+        }
+        .encode();
+        bytes.extend(Message::Events(vec![button(5, 2, ButtonState::Held)]).encode());
+
+        let mut decoder = FrameDecoder::new();
+        let mut messages = Vec::new();
+        // Feed one byte at a time to exercise partial frames
+        for byte in bytes {
+            decoder.push(&[byte]);
+            while let Some(message) = decoder.next_message().unwrap() {
+                messages.push(message);
+            }
+        }
+
+        assert!(!decoder.has_partial_frame());
+        assert!(matches!(
+            &messages[0],
+            Message::Hello { target_fps: 60, source: Some(s) } if s == "Game"
+        ));
+        match &messages[1] {
+            Message::Events(events) => {
+                assert_eq!(events.len(), 1);
+                assert_eq!(events[0].frame, 5);
+                assert_eq!(events[0].state, ButtonState::Held);
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decoder_rejects_bad_frames() {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&[9, 0, 0, 0, 0]);
+        assert!(matches!(
+            decoder.next_message(),
+            Err(StreamError::Protocol(_))
+        ));
+
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&[TYPE_EVENTS, 3, 0, 0, 0, 1, 2, 3]);
+        assert!(decoder.next_message().is_err());
+    }
+
+    #[test]
+    fn test_mapping_round_trip() {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(
+            &Message::Mapping(InputMapping {
+                id: 7,
+                name: "Fire".to_string(),
+                color: None,
+                player: None,
+                device: None,
+                group: None,
+            })
+            .encode(),
+        );
+        match decoder.next_message().unwrap() {
+            Some(Message::Mapping(mapping)) => {
+                assert_eq!(mapping.id, 7);
+                assert_eq!(mapping.name, "Fire");
+                assert_eq!(mapping.color, None);
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_mapping_round_trip_keeps_player_device_and_group() {
+        let mapping = InputMapping {
+            id: 3,
+            name: "Steer".to_string(),
+            color: Some([10, 20, 30]),
+            player: Some(1),
+            device: Some(DeviceType::Gamepad),
+            group: Some("Driving".to_string()),
+        };
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&Message::Mapping(mapping).encode());
+        match decoder.next_message().unwrap() {
+            Some(Message::Mapping(decoded)) => {
+                assert_eq!(decoded.id, 3);
+                assert_eq!(decoded.name, "Steer");
+                assert_eq!(decoded.color, Some([10, 20, 30]));
+                assert_eq!(decoded.player, Some(1));
+                assert_eq!(decoded.device, Some(DeviceType::Gamepad));
+                assert_eq!(decoded.group.as_deref(), Some("Driving"));
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&[TYPE_MAPPING, 2, 0, 0, 0, b'{', b'x']);
+        assert!(matches!(
+            decoder.next_message(),
+            Err(StreamError::Protocol(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_bind_leaves_other_files_alone() {
+        let dir = std::env::temp_dir().join(format!("ilv-stream-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("events.ilj");
+        std::fs::write(&path, "not a socket").unwrap();
+
+        let endpoint = Endpoint::Unix(path.clone());
+        assert!(StreamReceiver::listen(&endpoint).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a socket");
+
+        // A socket is removed again once the receiver is dropped
+        let socket = dir.join("live.sock");
+        let receiver = StreamReceiver::listen(&Endpoint::Unix(socket.clone())).unwrap();
+        assert!(socket.exists());
+        drop(receiver);
+        assert!(!socket.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_replay_over_tcp() {
+        let endpoint = Endpoint::Tcp(SocketAddr::from(([127, 0, 0, 1], 0)));
+        let mut receiver = StreamReceiver::listen(&endpoint).unwrap();
+        let bound = receiver.endpoint().clone();
+
+        let source = create_test_log();
+        let sent = std::thread::spawn(move || {
+            let mut sender = StreamSender::connect(&bound).unwrap();
+            replay(&source, &mut sender, 10.0).unwrap()
+        });
+
+        let mut log = InputLog::default();
+        let mut new_ids = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while receiver.is_active() && Instant::now() < deadline {
+            new_ids.extend(receiver.poll(&mut log).new_ids);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(sent.join().unwrap(), 4);
+        assert_eq!(receiver.status(), &StreamStatus::Finished);
+        assert_eq!(log.metadata.target_fps, 1000);
+        assert_eq!(log.metadata.source.as_deref(), Some("Stand-in"));
+        assert_eq!(log.metadata.frame_count, 11);
+        assert_eq!(log.mappings[0].name, "Jump");
+        assert_eq!(log.events.len(), 4);
+        new_ids.sort_unstable();
+        assert_eq!(new_ids, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_replay_rejects_too_slow_speed() {
+        let endpoint = Endpoint::Tcp(SocketAddr::from(([127, 0, 0, 1], 0)));
+        let receiver = StreamReceiver::listen(&endpoint).unwrap();
+        let mut sender = StreamSender::connect(receiver.endpoint()).unwrap();
+
+        let result = replay(&create_test_log(), &mut sender, 1e-300);
+        assert!(matches!(result, Err(StreamError::InvalidSpeed(_))));
+    }
+}
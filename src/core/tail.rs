@@ -0,0 +1,489 @@
+//! Live tail ("Follow") support for logs that are still being written.
+//!
+//! A `LogTail` remembers how many bytes of a log file have been consumed and,
+//! when polled, parses only the complete events appended since then. Partial
+//! trailing records are left in the file until the writer finishes them, so
+//! a half-written binary record or JSON object never causes an error.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use super::error::{self, AppError};
+use super::log::{InputEvent, InputLog};
+use super::parser::{self, BinaryEvent, BinaryHeader, JsonLinesParser, ParseError};
+
+/// Format-specific position of the tail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TailFormat {
+    /// `.ilj`: appended objects in the `events` array
+    Json,
+    /// `.iljl`: appended event lines
+    JsonLines,
+    /// `.ilb`: appended fixed-size `BinaryEvent` records
+    Binary,
+}
+
+/// Result of polling a followed file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TailUpdate {
+    /// No complete events were appended
+    Unchanged,
+    /// New events were appended to the log
+    Appended {
+        /// Number of new events
+        events: usize,
+        /// Input IDs that appeared for the first time (sorted)
+        new_ids: Vec<u32>,
+    },
+    /// The file shrank or was replaced, so the log was loaded again from scratch
+    Reloaded,
+}
+
+/// Follows a log file and appends newly written events.
+#[derive(Debug)]
+pub struct LogTail {
+    path: PathBuf,
+    format: TailFormat,
+    /// Number of bytes of the file that have been consumed
+    offset: u64,
+    /// Whether the JSON events array has been closed (no more events can follow)
+    finished: bool,
+    /// Line parser state for `.iljl` files
+    json_lines: JsonLinesParser,
+}
+
+impl LogTail {
+    /// Open a log for following and load everything that is complete so far.
+    ///
+    /// Unlike `FormatRegistry::load`, this accepts files that end in the
+    /// middle of an event and ignores the binary header's event count.
+    ///
+    /// # Arguments
+    /// * `path` - Path to an `.ilj` or `.ilb` file
+    ///
+    /// # Returns
+    /// * `Ok((LogTail, InputLog))` - The tail and the log loaded so far
+    /// * `Err(AppError)` - The file cannot be read or its header is invalid
+    pub fn open(path: &Path) -> Result<(Self, InputLog), AppError> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|s| s.to_lowercase());
+        let format = match extension.as_deref() {
+            Some("ilj") => TailFormat::Json,
+            Some("iljl") => TailFormat::JsonLines,
+            Some("ilb") => TailFormat::Binary,
+            _ => {
+                return Err(AppError::UnsupportedFileType {
+                    path: path.to_path_buf(),
+                    expected: vec![".ilj".into(), ".iljl".into(), ".ilb".into()],
+                });
+            }
+        };
+
+        let data = std::fs::read(path).map_err(|e| error::from_io_error(path.to_path_buf(), e))?;
+        let to_app_error = |e: ParseError| error::from_parse_error(Some(path.to_path_buf()), &e);
+
+        let mut json_lines = JsonLinesParser::new();
+        let (log, offset, finished) = match format {
+            TailFormat::Json => open_json(&data).map_err(to_app_error)?,
+            TailFormat::JsonLines => {
+                open_json_lines(&data, &mut json_lines).map_err(to_app_error)?
+            }
+            TailFormat::Binary => open_binary(&data).map_err(to_app_error)?,
+        };
+
+        let tail = Self {
+            path: path.to_path_buf(),
+            format,
+            offset,
+            finished,
+            json_lines,
+        };
+        Ok((tail, log))
+    }
+
+    /// Path of the followed file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read complete events appended since the last poll into `log`.
+    ///
+    /// `frame_count` is extended to cover the newest event.
+    pub fn poll(&mut self, log: &mut InputLog) -> Result<TailUpdate, AppError> {
+        let len = std::fs::metadata(&self.path)
+            .map_err(|e| error::from_io_error(self.path.clone(), e))?
+            .len();
+
+        if len < self.offset {
+            // The file was truncated or replaced; start over
+            let (tail, new_log) = Self::open(&self.path)?;
+            *self = tail;
+            *log = new_log;
+            return Ok(TailUpdate::Reloaded);
+        }
+        if len == self.offset || self.finished {
+            return Ok(TailUpdate::Unchanged);
+        }
+        if self.offset == 0 {
+            // The events array or header line was not written yet when the file was opened
+            let (tail, new_log) = Self::open(&self.path)?;
+            if tail.offset == 0 {
+                return Ok(TailUpdate::Unchanged);
+            }
+            *self = tail;
+            *log = new_log;
+            return Ok(TailUpdate::Reloaded);
+        }
+
+        let appended = self.read_appended()?;
+        let to_app_error = |e: ParseError| error::from_parse_error(Some(self.path.clone()), &e);
+
+        let (events, consumed) = match self.format {
+            TailFormat::Binary => {
+                parse_binary_records(&appended, log.events.len()).map_err(to_app_error)?
+            }
+            TailFormat::Json => {
+                // Only hand complete UTF-8 to the parser; a split character waits for the next poll
+                let text = match std::str::from_utf8(&appended) {
+                    Ok(text) => text,
+                    Err(e) => std::str::from_utf8(&appended[..e.valid_up_to()]).unwrap_or(""),
+                };
+                let (events, consumed, finished) =
+                    parser::parse_json_events_partial(text).map_err(to_app_error)?;
+                self.finished = finished;
+                (events, consumed)
+            }
+            TailFormat::JsonLines => {
+                // Lines are only consumed up to a newline, so a split character waits as well
+                let text = match std::str::from_utf8(&appended) {
+                    Ok(text) => text,
+                    Err(e) => std::str::from_utf8(&appended[..e.valid_up_to()]).unwrap_or(""),
+                };
+                self.json_lines.feed(text).map_err(to_app_error)?
+            }
+        };
+        self.offset += consumed as u64;
+
+        if events.is_empty() {
+            return Ok(TailUpdate::Unchanged);
+        }
+
+        let new_ids = append_events(log, events.iter().cloned(), self.format);
+        Ok(TailUpdate::Appended {
+            events: events.len(),
+            new_ids,
+        })
+    }
+
+    /// Read everything after the consumed offset.
+    fn read_appended(&self) -> Result<Vec<u8>, AppError> {
+        let io_error = |e| error::from_io_error(self.path.clone(), e);
+        let mut file = File::open(&self.path).map_err(io_error)?;
+        file.seek(SeekFrom::Start(self.offset)).map_err(io_error)?;
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer).map_err(io_error)?;
+        Ok(buffer)
+    }
+}
+
+/// Load the complete part of a JSON log that may still be growing.
+fn open_json(data: &[u8]) -> Result<(InputLog, u64, bool), ParseError> {
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(e) => std::str::from_utf8(&data[..e.valid_up_to()]).unwrap_or(""),
+    };
+
+    // The header is read leniently; events are read below so the offset is known
+    let mut log = parser::parse_json_lenient(text)?.log;
+    log.events.clear();
+
+    let Some(start) = parser::json_events_start(text) else {
+        // The events array has not been written yet
+        return Ok((log, 0, false));
+    };
+    let (events, consumed, finished) = parser::parse_json_events_partial(&text[start..])?;
+    append_events(&mut log, events.into_iter(), TailFormat::Json);
+
+    Ok((log, (start + consumed) as u64, finished))
+}
+
+/// Load the complete lines of a newline-delimited JSON log that may still be growing.
+fn open_json_lines(
+    data: &[u8],
+    parser: &mut JsonLinesParser,
+) -> Result<(InputLog, u64, bool), ParseError> {
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(e) => std::str::from_utf8(&data[..e.valid_up_to()]).unwrap_or(""),
+    };
+
+    let (events, consumed) = parser.feed(text)?;
+    let Some(header) = parser.header() else {
+        // The header line has not been written completely yet
+        return Ok((InputLog::default(), 0, false));
+    };
+
+    let mut log = header.clone();
+    append_events(&mut log, events.into_iter(), TailFormat::JsonLines);
+    Ok((log, consumed as u64, false))
+}
+
+/// Load the complete part of a binary log that may still be growing.
+///
+/// A checksum trailer is shorter than a record and therefore never parsed.
+/// Compressed logs are written in one piece, so they are loaded like a
+/// regular file and marked as finished.
+fn open_binary(data: &[u8]) -> Result<(InputLog, u64, bool), ParseError> {
+    if data.len() < BinaryHeader::SIZE {
+        return Err(ParseError::FileTooSmall {
+            expected: BinaryHeader::SIZE,
+            found: data.len(),
+        });
+    }
+    let header: BinaryHeader = bytemuck::pod_read_unaligned(&data[..BinaryHeader::SIZE]);
+    header.validate()?;
+
+    // Variable-length layouts and trailing blocks can't be followed record by record
+    if header.is_compressed() || header.has_timestamps() || header.has_annotations() {
+        let parsed = parser::parse_binary_lenient(data)?;
+        return Ok((parsed.log, data.len() as u64, true));
+    }
+
+    let (events, consumed) = parse_binary_records(&data[BinaryHeader::SIZE..], 0)?;
+
+    let mut log = InputLog::default();
+    log.metadata.version = header.version;
+    log.metadata.target_fps = header.target_fps;
+    log.metadata.frame_count = header.frame_count;
+    append_events(&mut log, events.into_iter(), TailFormat::Binary);
+
+    Ok((log, (BinaryHeader::SIZE + consumed) as u64, false))
+}
+
+/// Parse all complete binary records, ignoring a partial trailing record.
+///
+/// Returns the events and the number of bytes consumed.
+fn parse_binary_records(
+    data: &[u8],
+    first_index: usize,
+) -> Result<(Vec<InputEvent>, usize), ParseError> {
+    let events = data
+        .chunks_exact(BinaryEvent::SIZE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            bytemuck::pod_read_unaligned::<BinaryEvent>(chunk).to_input_event(first_index + i)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let consumed = events.len() * BinaryEvent::SIZE;
+    Ok((events, consumed))
+}
+
+/// Append events to the log, extending `frame_count` and, for binary logs,
+/// adding default mappings for new IDs.
+///
+/// Returns the IDs that were not present in the log before (sorted).
+fn append_events(
+    log: &mut InputLog,
+    events: impl Iterator<Item = InputEvent>,
+    format: TailFormat,
+) -> Vec<u32> {
+    let mut known: HashSet<u32> = log.events.iter().map(|e| e.id).collect();
+    known.extend(log.mappings.iter().map(|m| m.id));
+
+    let mut new_ids = Vec::new();
+    for event in events {
+        if known.insert(event.id) {
+            new_ids.push(event.id);
+        }
+        log.metadata.frame_count = log.metadata.frame_count.max(event.frame + 1);
+        log.events.push(event);
+    }
+
+    new_ids.sort_unstable();
+    if format == TailFormat::Binary {
+        // Binary logs carry no mappings; name new inputs like the parser does
+        log.mappings
+            .extend(new_ids.iter().copied().map(parser::default_mapping));
+    }
+    new_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ilv_tail_{}_{}", std::process::id(), name))
+    }
+
+    fn binary_header(frame_count: u64, event_count: u64) -> BinaryHeader {
+        BinaryHeader {
+            magic: *b"ILOG",
+            version: 1,
+            flags: 0,
+            target_fps: 60,
+            frame_count,
+            event_count,
+        }
+    }
+
+    fn binary_event(frame: u64, id: u32) -> BinaryEvent {
+        BinaryEvent {
+            frame,
+            id,
+            kind: 0,
+            state: 1,
+            contact: [0; 2],
+            value: [0.0, 0.0],
+        }
+    }
+
+    fn append(path: &Path, bytes: &[u8]) {
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+            .unwrap();
+        file.write_all(bytes).unwrap();
+    }
+
+    #[test]
+    fn test_binary_tail_waits_for_complete_records() {
+        let path = temp_path("binary.ilb");
+        let _ = std::fs::remove_file(&path);
+
+        // Header claims zero events, as a live writer would before finishing
+        append(&path, bytemuck::bytes_of(&binary_header(0, 0)));
+        append(&path, bytemuck::bytes_of(&binary_event(0, 1)));
+
+        let (mut tail, mut log) = LogTail::open(&path).unwrap();
+        assert_eq!(log.events.len(), 1);
+        assert_eq!(log.metadata.frame_count, 1);
+        assert_eq!(log.mappings.len(), 1);
+
+        // Half a record: nothing happens yet
+        let record = binary_event(10, 2);
+        let bytes = bytemuck::bytes_of(&record);
+        append(&path, &bytes[..10]);
+        assert_eq!(tail.poll(&mut log).unwrap(), TailUpdate::Unchanged);
+
+        // Completing the record appends it
+        append(&path, &bytes[10..]);
+        assert_eq!(
+            tail.poll(&mut log).unwrap(),
+            TailUpdate::Appended {
+                events: 1,
+                new_ids: vec![2]
+            }
+        );
+        assert_eq!(log.events.len(), 2);
+        assert_eq!(log.metadata.frame_count, 11);
+        assert_eq!(log.get_input_name(2), "Input 2");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_json_tail_appends_events() {
+        let path = temp_path("json.ilj");
+        let _ = std::fs::remove_file(&path);
+
+        append(
+            &path,
+            br#"{"version": 1, "metadata": {"target_fps": 60, "frame_count": 0},
+  "mappings": [{"id": 0, "name": "Jump"}],
+  "events": [
+    {"frame": 0, "id": 0, "kind": "Button", "state": "Pressed", "value": [0, 0]},
+    {"frame": 1, "id": 0, "kind": "Bu"#,
+        );
+
+        let (mut tail, mut log) = LogTail::open(&path).unwrap();
+        assert_eq!(log.events.len(), 1);
+        assert_eq!(log.metadata.frame_count, 1);
+        assert_eq!(log.get_input_name(0), "Jump");
+
+        append(
+            &path,
+            br#"tton", "state": "Released", "value": [0, 0]},
+    {"frame": 5, "id": 3, "kind": "Axis1D", "value": [0.5, 0]}"#,
+        );
+        assert_eq!(
+            tail.poll(&mut log).unwrap(),
+            TailUpdate::Appended {
+                events: 2,
+                new_ids: vec![3]
+            }
+        );
+        assert_eq!(log.events.len(), 3);
+        assert_eq!(log.metadata.frame_count, 6);
+
+        // Closing the file ends the tail
+        append(&path, b"\n  ]\n}\n");
+        assert_eq!(tail.poll(&mut log).unwrap(), TailUpdate::Unchanged);
+        assert!(tail.finished);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_json_lines_tail_waits_for_complete_lines() {
+        let path = temp_path("lines.iljl");
+        let _ = std::fs::remove_file(&path);
+
+        // Only part of the header has been written
+        append(&path, br#"{"version": 1, "metadata": {"target_fps": 60}"#);
+        let (mut tail, mut log) = LogTail::open(&path).unwrap();
+        assert!(log.events.is_empty());
+
+        append(
+            &path,
+            br#", "mappings": [{"id": 0, "name": "Jump"}]}
+{"frame": 0, "id": 0, "kind": "Button", "state": "Pressed", "value": [0, 0]}
+{"frame": 2, "id": 0, "kind": "Button", "#,
+        );
+        assert_eq!(tail.poll(&mut log).unwrap(), TailUpdate::Reloaded);
+        assert_eq!(log.events.len(), 1);
+        assert_eq!(log.get_input_name(0), "Jump");
+
+        append(
+            &path,
+            br#""state": "Released", "value": [0, 0]}
+{"frame": 7, "id": 4, "kind": "Axis1D", "value": [1, 0]}
+"#,
+        );
+        assert_eq!(
+            tail.poll(&mut log).unwrap(),
+            TailUpdate::Appended {
+                events: 2,
+                new_ids: vec![4]
+            }
+        );
+        assert_eq!(log.metadata.frame_count, 8);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_tail_reloads_when_file_shrinks() {
+        let path = temp_path("shrink.ilb");
+        let _ = std::fs::remove_file(&path);
+
+        append(&path, bytemuck::bytes_of(&binary_header(100, 2)));
+        append(&path, bytemuck::bytes_of(&binary_event(0, 1)));
+        append(&path, bytemuck::bytes_of(&binary_event(1, 1)));
+        let (mut tail, mut log) = LogTail::open(&path).unwrap();
+        assert_eq!(log.events.len(), 2);
+
+        // Rewrite the file with fewer events
+        std::fs::write(&path, bytemuck::bytes_of(&binary_header(100, 0))).unwrap();
+        assert_eq!(tail.poll(&mut log).unwrap(), TailUpdate::Reloaded);
+        assert!(log.events.is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+//! Events and logs shared by the unit tests.
+
+use super::log::{ButtonState, InputEvent, InputKind, InputLog, InputMapping, LogMetadata};
+
+/// Event of any kind with a zero value on touch contact 0.
+pub fn event(frame: u64, id: u32, kind: InputKind, state: ButtonState) -> InputEvent {
+    InputEvent {
+        frame,
+        id,
+        kind,
+        state,
+        value: [0.0, 0.0],
+        contact: 0,
+    }
+}
+
+/// Button event.
+pub fn button(frame: u64, id: u32, state: ButtonState) -> InputEvent {
+    event(frame, id, InputKind::Button, state)
+}
+
+/// 1D axis sample.
+pub fn axis(frame: u64, id: u32, value: f32) -> InputEvent {
+    InputEvent {
+        value: [value, 0.0],
+        ..event(frame, id, InputKind::Axis1D, ButtonState::Released)
+    }
+}
+
+/// Mapping with only a name.
+pub fn mapping(id: u32, name: &str) -> InputMapping {
+    InputMapping {
+        id,
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Log of `frame_count` frames at 60 FPS without mappings.
+pub fn log_with(frame_count: u64, events: Vec<InputEvent>) -> InputLog {
+    InputLog {
+        metadata: LogMetadata {
+            frame_count,
+            ..Default::default()
+        },
+        events,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,386 @@
+//! Input timing analysis.
+//!
+//! This module measures the frame delta between presses of one input (A) and
+//! the next press of another input (B), which is useful for tuning input
+//! buffers and checking how tight a sequence is in practice.
+
+use std::collections::{BTreeMap, HashSet};
+
+use super::log::{ButtonState, InputKind, InputLog};
+
+/// Default threshold for treating an axis as pressed.
+pub const DEFAULT_PRESS_THRESHOLD: f32 = 0.5;
+
+/// Default largest delta (in frames) that counts as frame-perfect.
+pub const DEFAULT_PERFECT_WINDOW: u64 = 1;
+
+/// Settings for timing analysis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingConfig {
+    /// Deltas at or below this many frames are flagged as frame-perfect
+    pub perfect_window: u64,
+    /// Axis magnitudes above this value count as a press
+    pub press_threshold: f32,
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self {
+            perfect_window: DEFAULT_PERFECT_WINDOW,
+            press_threshold: DEFAULT_PRESS_THRESHOLD,
+        }
+    }
+}
+
+/// A single A→B measurement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimingSample {
+    /// Frame of the A press
+    pub a_frame: u64,
+    /// Frame of the following B press
+    pub b_frame: u64,
+    /// Frames between the two presses
+    pub delta: u64,
+    /// Whether the delta is within the frame-perfect window
+    pub perfect: bool,
+}
+
+/// Summary percentiles of the measured deltas (nearest-rank, in frames).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingPercentiles {
+    /// Smallest delta
+    pub min: u64,
+    /// Median delta
+    pub p50: u64,
+    /// 90th percentile
+    pub p90: u64,
+    /// 95th percentile
+    pub p95: u64,
+    /// 99th percentile
+    pub p99: u64,
+    /// Largest delta
+    pub max: u64,
+    /// Mean delta
+    pub mean: f64,
+}
+
+/// Result of an A→B timing analysis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimingReport {
+    /// ID of the leading input
+    pub input_a: u32,
+    /// ID of the following input
+    pub input_b: u32,
+    /// All measurements, ordered by A press frame
+    pub samples: Vec<TimingSample>,
+    /// Number of A presses without a following B press
+    pub unmatched: usize,
+    /// Number of samples per delta (ordered by delta)
+    pub histogram: BTreeMap<u64, usize>,
+    /// Percentiles of the deltas (None if there are no samples)
+    pub percentiles: Option<TimingPercentiles>,
+}
+
+impl TimingReport {
+    /// Number of frame-perfect samples.
+    pub fn perfect_count(&self) -> usize {
+        self.samples.iter().filter(|s| s.perfect).count()
+    }
+}
+
+/// Measure the delta between each press of `input_a` and the next press of `input_b`.
+///
+/// # Arguments
+/// * `log` - The input log to analyze
+/// * `input_a` - ID of the leading input
+/// * `input_b` - ID of the following input
+/// * `range` - Optional inclusive frame range; both presses must lie inside it
+/// * `config` - Frame-perfect window and axis press threshold
+///
+/// # Returns
+/// A report with one sample per A press that has a following B press.
+/// B presses on the same frame as A count with a delta of 0, except when A
+/// and B are the same input, in which case the next distinct press is used.
+pub fn analyze_timing(
+    log: &InputLog,
+    input_a: u32,
+    input_b: u32,
+    range: Option<(u64, u64)>,
+    config: &TimingConfig,
+) -> TimingReport {
+    let (start, end) = match range {
+        Some((a, b)) => (a.min(b), a.max(b)),
+        None => (0, u64::MAX),
+    };
+    let in_range = |frame: u64| frame >= start && frame <= end;
+
+    let a_presses: Vec<u64> = press_frames(log, input_a, config.press_threshold)
+        .into_iter()
+        .filter(|&f| in_range(f))
+        .collect();
+    let b_presses: Vec<u64> = press_frames(log, input_b, config.press_threshold)
+        .into_iter()
+        .filter(|&f| in_range(f))
+        .collect();
+
+    let mut samples = Vec::new();
+    let mut unmatched = 0;
+    for &a_frame in &a_presses {
+        // First B press at or after A (strictly after when measuring an input against itself)
+        let index = if input_a == input_b {
+            b_presses.partition_point(|&b| b <= a_frame)
+        } else {
+            b_presses.partition_point(|&b| b < a_frame)
+        };
+        match b_presses.get(index) {
+            Some(&b_frame) => {
+                let delta = b_frame - a_frame;
+                samples.push(TimingSample {
+                    a_frame,
+                    b_frame,
+                    delta,
+                    perfect: delta <= config.perfect_window,
+                });
+            }
+            None => unmatched += 1,
+        }
+    }
+
+    let mut histogram = BTreeMap::new();
+    for sample in &samples {
+        *histogram.entry(sample.delta).or_insert(0) += 1;
+    }
+
+    let percentiles = compute_percentiles(&samples);
+
+    TimingReport {
+        input_a,
+        input_b,
+        samples,
+        unmatched,
+        histogram,
+        percentiles,
+    }
+}
+
+/// Collect the frames on which an input goes from up to down, in frame order.
+///
+/// A `Held` event without a preceding press counts as a press so sparse logs
+/// are handled. Axes count as pressed while their magnitude is above the
+/// threshold. Each touch contact goes up and down on its own, so a second
+/// finger landing while the first is down is a press too.
+fn press_frames(log: &InputLog, id: u32, threshold: f32) -> Vec<u64> {
+    let mut events: Vec<_> = log.events.iter().filter(|e| e.id == id).collect();
+    events.sort_by_key(|e| e.frame);
+
+    let mut presses = Vec::new();
+    // Contacts that are currently down
+    let mut down: HashSet<u16> = HashSet::new();
+    for event in events {
+        let now_down = match event.kind {
+            InputKind::Button | InputKind::Touch | InputKind::Key => match event.state {
+                ButtonState::Pressed => {
+                    // A repeated Pressed is a new press even without a release
+                    presses.push(event.frame);
+                    down.insert(event.contact);
+                    continue;
+                }
+                ButtonState::Held => true,
+                ButtonState::Released => false,
+            },
+            InputKind::Axis1D | InputKind::Trigger => event.value[0].abs() > threshold,
+            InputKind::PointerPosition => false,
+            InputKind::Axis2D | InputKind::MouseDelta => {
+                (event.value[0] * event.value[0] + event.value[1] * event.value[1]).sqrt()
+                    > threshold
+            }
+        };
+        if now_down && !down.contains(&event.contact) {
+            presses.push(event.frame);
+        }
+        if now_down {
+            down.insert(event.contact);
+        } else {
+            down.remove(&event.contact);
+        }
+    }
+
+    presses.dedup();
+    presses
+}
+
+/// Compute nearest-rank percentiles over the sample deltas.
+fn compute_percentiles(samples: &[TimingSample]) -> Option<TimingPercentiles> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let mut deltas: Vec<u64> = samples.iter().map(|s| s.delta).collect();
+    deltas.sort_unstable();
+
+    let rank = |p: f64| {
+        let index = ((p / 100.0) * deltas.len() as f64).ceil() as usize;
+        deltas[index.clamp(1, deltas.len()) - 1]
+    };
+
+    Some(TimingPercentiles {
+        min: deltas[0],
+        p50: rank(50.0),
+        p90: rank(90.0),
+        p95: rank(95.0),
+        p99: rank(99.0),
+        max: deltas[deltas.len() - 1],
+        mean: deltas.iter().sum::<u64>() as f64 / deltas.len() as f64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log::InputEvent;
+    use crate::core::test_util::{button, log_with};
+
+    fn tap(frame: u64, id: u32) -> [InputEvent; 2] {
+        [
+            button(frame, id, ButtonState::Pressed),
+            button(frame + 1, id, ButtonState::Released),
+        ]
+    }
+
+    fn create_test_log(events: Vec<InputEvent>) -> InputLog {
+        let mut events = events;
+        events.sort_by_key(|e| e.frame);
+        log_with(1000, events)
+    }
+
+    #[test]
+    fn test_deltas_and_unmatched() {
+        let mut events = Vec::new();
+        events.extend(tap(10, 0));
+        events.extend(tap(12, 1));
+        events.extend(tap(100, 0));
+        events.extend(tap(101, 1));
+        events.extend(tap(200, 0));
+        let log = create_test_log(events);
+
+        let report = analyze_timing(&log, 0, 1, None, &TimingConfig::default());
+
+        let deltas: Vec<_> = report.samples.iter().map(|s| s.delta).collect();
+        assert_eq!(deltas, vec![2, 1]);
+        assert_eq!(report.unmatched, 1);
+        assert_eq!(report.perfect_count(), 1);
+        assert!(report.samples[1].perfect);
+        assert_eq!(report.histogram.get(&2), Some(&1));
+    }
+
+    #[test]
+    fn test_same_frame_counts_as_zero() {
+        let mut events = Vec::new();
+        events.extend(tap(10, 0));
+        events.extend(tap(10, 1));
+        let log = create_test_log(events);
+
+        let report = analyze_timing(&log, 0, 1, None, &TimingConfig::default());
+        assert_eq!(report.samples[0].delta, 0);
+    }
+
+    #[test]
+    fn test_same_input_uses_next_press() {
+        let mut events = Vec::new();
+        events.extend(tap(10, 0));
+        events.extend(tap(15, 0));
+        let log = create_test_log(events);
+
+        let report = analyze_timing(&log, 0, 0, None, &TimingConfig::default());
+        assert_eq!(report.samples.len(), 1);
+        assert_eq!(report.samples[0].delta, 5);
+        assert_eq!(report.unmatched, 1);
+    }
+
+    #[test]
+    fn test_touch_contacts_press_separately() {
+        let touch = |frame, contact, state| InputEvent {
+            kind: InputKind::Touch,
+            contact,
+            ..button(frame, 0, state)
+        };
+        // Contact 1 lands (sparse) while contact 0 is down and outlasts it
+        let log = create_test_log(vec![
+            touch(10, 0, ButtonState::Pressed),
+            touch(12, 1, ButtonState::Held),
+            touch(15, 0, ButtonState::Released),
+            touch(16, 1, ButtonState::Held),
+            touch(20, 1, ButtonState::Released),
+        ]);
+
+        let report = analyze_timing(&log, 0, 0, None, &TimingConfig::default());
+        assert_eq!(report.samples.len(), 1);
+        assert_eq!(report.samples[0].a_frame, 10);
+        assert_eq!(report.samples[0].b_frame, 12);
+        assert_eq!(report.unmatched, 1);
+    }
+
+    #[test]
+    fn test_range_limits_both_presses() {
+        let mut events = Vec::new();
+        events.extend(tap(10, 0));
+        events.extend(tap(20, 1));
+        events.extend(tap(50, 0));
+        events.extend(tap(80, 1));
+        let log = create_test_log(events);
+
+        let report = analyze_timing(&log, 0, 1, Some((0, 60)), &TimingConfig::default());
+        assert_eq!(report.samples.len(), 1);
+        assert_eq!(report.samples[0].a_frame, 10);
+        // The B press at frame 80 lies outside the range
+        assert_eq!(report.unmatched, 1);
+    }
+
+    #[test]
+    fn test_sparse_held_and_axis_presses() {
+        let log = create_test_log(vec![
+            button(5, 0, ButtonState::Held),
+            button(9, 0, ButtonState::Released),
+            InputEvent {
+                frame: 8,
+                id: 1,
+                kind: InputKind::Axis1D,
+                state: ButtonState::Released,
+                value: [-0.9, 0.0],
+                contact: 0,
+            },
+        ]);
+
+        let report = analyze_timing(&log, 0, 1, None, &TimingConfig::default());
+        assert_eq!(report.samples.len(), 1);
+        assert_eq!(report.samples[0].delta, 3);
+    }
+
+    #[test]
+    fn test_percentiles_nearest_rank() {
+        let mut events = Vec::new();
+        // Deltas 1..=10
+        for i in 0..10u64 {
+            events.extend(tap(i * 100, 0));
+            events.extend(tap(i * 100 + i + 1, 1));
+        }
+        let log = create_test_log(events);
+
+        let report = analyze_timing(&log, 0, 1, None, &TimingConfig::default());
+        let p = report.percentiles.unwrap();
+        assert_eq!(p.min, 1);
+        assert_eq!(p.p50, 5);
+        assert_eq!(p.p90, 9);
+        assert_eq!(p.p95, 10);
+        assert_eq!(p.max, 10);
+        assert!((p.mean - 5.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_samples() {
+        let log = create_test_log(vec![]);
+        let report = analyze_timing(&log, 0, 1, None, &TimingConfig::default());
+        assert!(report.samples.is_empty());
+        assert!(report.percentiles.is_none());
+    }
+}
@@ -0,0 +1,523 @@
+//! Log consistency validation.
+//!
+//! The parsers accept any structurally valid log, so captures with broken
+//! button sequences, out-of-range frames or invalid axis values load without
+//! complaint. This module checks a loaded log for such problems and reports
+//! them as diagnostics with a severity and the location they refer to.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::error::AppError;
+use super::log::{ButtonState, InputKind, InputLog};
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Informational; the log is usable as is
+    Info,
+    /// Likely a capture problem; the viewer may show misleading data
+    Warning,
+    /// The log is inconsistent; some events cannot be displayed correctly
+    Error,
+}
+
+impl Severity {
+    /// Get a short label for display.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// A single problem found in a log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    /// How serious the problem is
+    pub severity: Severity,
+    /// Description of the problem
+    pub message: String,
+    /// Frame the problem refers to, if any
+    pub frame: Option<u64>,
+    /// Index of the offending event in `InputLog::events`, if any
+    pub event_index: Option<usize>,
+    /// Input ID the problem refers to, if any
+    pub input_id: Option<u32>,
+    /// Line in the source file (1-indexed), for problems found while loading
+    pub line: Option<usize>,
+    /// Column in the source file (1-indexed), for problems found while loading
+    pub column: Option<usize>,
+}
+
+impl Diagnostic {
+    fn at_event(
+        severity: Severity,
+        message: String,
+        index: usize,
+        frame: u64,
+        input_id: u32,
+    ) -> Self {
+        Self {
+            severity,
+            message,
+            frame: Some(frame),
+            event_index: Some(index),
+            input_id: Some(input_id),
+            line: None,
+            column: None,
+        }
+    }
+
+    /// Create a warning from a problem reported while loading the file.
+    ///
+    /// `AppError::InvalidFormat` keeps its line and column; other errors only
+    /// contribute their description.
+    pub fn from_load_warning(error: &AppError) -> Self {
+        let (line, column) = match error {
+            AppError::InvalidFormat { line, column, .. } => (*line, *column),
+            _ => (None, None),
+        };
+        Self {
+            severity: Severity::Warning,
+            message: error.brief_description(),
+            frame: None,
+            event_index: None,
+            input_id: None,
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.severity)?;
+        if let Some(line) = self.line {
+            write!(f, " [line {}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ", column {}", column)?;
+            }
+            write!(f, "]")?;
+        }
+        if let Some(frame) = self.frame {
+            write!(f, " [frame {}]", frame)?;
+        }
+        if let Some(index) = self.event_index {
+            write!(f, " [event {}]", index)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Get the highest severity in a list of diagnostics.
+pub fn max_severity(diagnostics: &[Diagnostic]) -> Option<Severity> {
+    diagnostics.iter().map(|d| d.severity).max()
+}
+
+/// Check a log for consistency problems.
+///
+/// # Arguments
+/// * `log` - The input log to check
+///
+/// # Returns
+/// All problems found, ordered by frame and event index. Problems that do not
+/// refer to a frame come first.
+pub fn validate(log: &InputLog) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let frame_count = log.metadata.frame_count;
+
+    // Mapping checks
+    let event_ids: HashSet<u32> = log.events.iter().map(|e| e.id).collect();
+    let mut mapped: HashSet<u32> = HashSet::new();
+    for mapping in &log.mappings {
+        let problem = if !mapped.insert(mapping.id) {
+            Some((
+                Severity::Warning,
+                format!("Input ID {} is mapped more than once", mapping.id),
+            ))
+        } else if !event_ids.contains(&mapping.id) {
+            Some((
+                Severity::Info,
+                format!("{} is mapped but has no events", mapping.name),
+            ))
+        } else {
+            None
+        };
+        if let Some((severity, message)) = problem {
+            diagnostics.push(Diagnostic {
+                severity,
+                message,
+                frame: None,
+                event_index: None,
+                input_id: Some(mapping.id),
+                line: None,
+                column: None,
+            });
+        }
+    }
+
+    // Order checks only look at the stored order
+    for (index, pair) in log.events.windows(2).enumerate() {
+        if pair[1].frame < pair[0].frame {
+            diagnostics.push(Diagnostic::at_event(
+                Severity::Warning,
+                format!(
+                    "Events are not sorted by frame: frame {} follows frame {}",
+                    pair[1].frame, pair[0].frame
+                ),
+                index + 1,
+                pair[1].frame,
+                pair[1].id,
+            ));
+        }
+    }
+
+    // Everything else is checked in frame order so unsorted logs are judged fairly
+    let mut order: Vec<usize> = (0..log.events.len()).collect();
+    order.sort_by_key(|&i| log.events[i].frame);
+
+    // Touch contacts of one input are separate streams, so state is keyed by
+    // input ID and contact
+    let mut seen: HashSet<(u64, u32, u16)> = HashSet::new();
+    let mut kinds: HashMap<u32, InputKind> = HashMap::new();
+    // Frame and event index of the press for each button that is currently down
+    let mut down: HashMap<(u32, u16), (u64, usize)> = HashMap::new();
+
+    for index in order {
+        let event = &log.events[index];
+        let name = log.get_input_name(event.id);
+
+        if event.frame >= frame_count {
+            diagnostics.push(Diagnostic::at_event(
+                Severity::Error,
+                format!(
+                    "{}: frame {} is beyond the frame count ({})",
+                    name, event.frame, frame_count
+                ),
+                index,
+                event.frame,
+                event.id,
+            ));
+        }
+
+        if !seen.insert((event.frame, event.id, event.contact)) {
+            diagnostics.push(Diagnostic::at_event(
+                Severity::Warning,
+                format!("{}: duplicate event on frame {}", name, event.frame),
+                index,
+                event.frame,
+                event.id,
+            ));
+        }
+
+        match kinds.get(&event.id) {
+            Some(&kind) if kind != event.kind => {
+                diagnostics.push(Diagnostic::at_event(
+                    Severity::Error,
+                    format!("{}: kind changes from {:?} to {:?}", name, kind, event.kind),
+                    index,
+                    event.frame,
+                    event.id,
+                ));
+            }
+            Some(_) => {}
+            None => {
+                kinds.insert(event.id, event.kind);
+            }
+        }
+
+        match event.kind {
+            InputKind::Button | InputKind::Touch | InputKind::Key => match event.state {
+                ButtonState::Pressed => {
+                    if let Some((press_frame, _)) =
+                        down.insert((event.id, event.contact), (event.frame, index))
+                    {
+                        diagnostics.push(Diagnostic::at_event(
+                            Severity::Warning,
+                            format!(
+                                "{}: Pressed again without Released (previous press on frame {})",
+                                name, press_frame
+                            ),
+                            index,
+                            event.frame,
+                            event.id,
+                        ));
+                    }
+                }
+                ButtonState::Held => {
+                    if let Entry::Vacant(entry) = down.entry((event.id, event.contact)) {
+                        diagnostics.push(Diagnostic::at_event(
+                            Severity::Warning,
+                            format!("{}: Held without a preceding Pressed", name),
+                            index,
+                            event.frame,
+                            event.id,
+                        ));
+                        // Treat as down from here on to avoid repeating the warning
+                        entry.insert((event.frame, index));
+                    }
+                }
+                ButtonState::Released => {
+                    down.remove(&(event.id, event.contact));
+                }
+            },
+            _ => {
+                let used = if matches!(event.kind, InputKind::Axis1D | InputKind::Trigger) {
+                    &event.value[..1]
+                } else {
+                    &event.value[..]
+                };
+                if used.iter().any(|v| !v.is_finite()) {
+                    diagnostics.push(Diagnostic::at_event(
+                        Severity::Error,
+                        format!("{}: axis value is not a finite number", name),
+                        index,
+                        event.frame,
+                        event.id,
+                    ));
+                }
+            }
+        }
+    }
+
+    // Buttons still down at the end of the log
+    let mut unreleased: Vec<_> = down.into_iter().collect();
+    unreleased.sort_by_key(|&((id, contact), (frame, _))| (frame, id, contact));
+    for ((id, _), (frame, index)) in unreleased {
+        diagnostics.push(Diagnostic::at_event(
+            Severity::Warning,
+            format!(
+                "{}: Pressed with no matching Released",
+                log.get_input_name(id)
+            ),
+            index,
+            frame,
+            id,
+        ));
+    }
+
+    diagnostics.sort_by_key(|d| (d.frame, d.event_index));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log::InputEvent;
+    use crate::core::test_util::{axis, button, log_with};
+
+    fn messages(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.message.as_str()).collect()
+    }
+
+    #[test]
+    fn test_valid_log_has_no_diagnostics() {
+        let log = log_with(
+            100,
+            vec![
+                button(0, 0, ButtonState::Pressed),
+                button(1, 0, ButtonState::Held),
+                button(2, 0, ButtonState::Released),
+                axis(2, 1, 0.5),
+            ],
+        );
+        assert!(validate(&log).is_empty());
+    }
+
+    #[test]
+    fn test_sample_file_is_valid() {
+        let content = include_str!("../../assets/sample.ilj");
+        let log = crate::core::parser::parse_json(content).unwrap();
+        let diagnostics = validate(&log);
+        assert!(
+            max_severity(&diagnostics) < Some(Severity::Warning),
+            "{:?}",
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn test_button_sequence_problems() {
+        let log = log_with(
+            100,
+            vec![
+                button(5, 0, ButtonState::Held),
+                button(6, 0, ButtonState::Released),
+                button(10, 0, ButtonState::Pressed),
+                button(12, 0, ButtonState::Pressed),
+            ],
+        );
+        let diagnostics = validate(&log);
+
+        assert_eq!(diagnostics.len(), 3);
+        assert!(
+            diagnostics[0]
+                .message
+                .contains("Held without a preceding Pressed")
+        );
+        assert_eq!(diagnostics[0].frame, Some(5));
+        assert!(
+            diagnostics[1]
+                .message
+                .contains("Pressed again without Released")
+        );
+        assert_eq!(diagnostics[1].event_index, Some(3));
+        assert!(diagnostics[2].message.contains("no matching Released"));
+        assert_eq!(diagnostics[2].frame, Some(12));
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+    }
+
+    #[test]
+    fn test_touch_contacts_are_checked_separately() {
+        let touch = |frame, contact, state| InputEvent {
+            kind: InputKind::Touch,
+            contact,
+            ..button(frame, 0, state)
+        };
+        // Two fingers down at once on the same input
+        let log = log_with(
+            100,
+            vec![
+                touch(0, 0, ButtonState::Pressed),
+                touch(0, 1, ButtonState::Pressed),
+                touch(1, 0, ButtonState::Held),
+                touch(1, 1, ButtonState::Held),
+                touch(2, 0, ButtonState::Released),
+                touch(3, 1, ButtonState::Held),
+            ],
+        );
+        let diagnostics = validate(&log);
+
+        // Only contact 1 is left down; nothing counts as a duplicate or repeat press
+        assert_eq!(messages(&diagnostics).len(), 1, "{:?}", diagnostics);
+        assert!(diagnostics[0].message.contains("no matching Released"));
+        assert_eq!(diagnostics[0].event_index, Some(1));
+    }
+
+    #[test]
+    fn test_frame_beyond_frame_count() {
+        let log = log_with(100, vec![axis(100, 1, 0.0)]);
+        let diagnostics = validate(&log);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].input_id, Some(1));
+    }
+
+    #[test]
+    fn test_unsorted_and_duplicate_events() {
+        let log = log_with(
+            100,
+            vec![axis(10, 1, 0.0), axis(5, 1, 0.1), axis(10, 1, 0.2)],
+        );
+        let diagnostics = validate(&log);
+
+        let msgs = messages(&diagnostics);
+        assert_eq!(msgs.len(), 2);
+        assert!(msgs[0].contains("not sorted"));
+        assert_eq!(diagnostics[0].event_index, Some(1));
+        assert!(msgs[1].contains("duplicate event on frame 10"));
+    }
+
+    #[test]
+    fn test_kind_change_and_nan() {
+        let log = log_with(
+            100,
+            vec![
+                axis(0, 1, 0.0),
+                button(1, 1, ButtonState::Released),
+                axis(2, 2, f32::NAN),
+            ],
+        );
+        let diagnostics = validate(&log);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].message.contains("kind changes"));
+        assert!(diagnostics[1].message.contains("not a finite number"));
+        assert_eq!(max_severity(&diagnostics), Some(Severity::Error));
+    }
+
+    #[test]
+    fn test_mapping_problems() {
+        let mut log = log_with(100, vec![axis(0, 1, 0.0)]);
+        log.mappings = vec![
+            crate::core::log::InputMapping {
+                id: 1,
+                name: "Stick".to_string(),
+                color: None,
+                player: None,
+                device: None,
+                group: None,
+            },
+            crate::core::log::InputMapping {
+                id: 1,
+                name: "Stick again".to_string(),
+                color: None,
+                player: None,
+                device: None,
+                group: None,
+            },
+            crate::core::log::InputMapping {
+                id: 2,
+                name: "Unused".to_string(),
+                color: None,
+                player: None,
+                device: None,
+                group: None,
+            },
+        ];
+        let diagnostics = validate(&log);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].frame, None);
+        assert_eq!(diagnostics[1].severity, Severity::Info);
+        assert_eq!(diagnostics[1].input_id, Some(2));
+    }
+
+    #[test]
+    fn test_display_format() {
+        let diagnostic = Diagnostic {
+            severity: Severity::Warning,
+            message: "Something".to_string(),
+            frame: Some(3),
+            event_index: Some(7),
+            input_id: None,
+            line: None,
+            column: None,
+        };
+        assert_eq!(
+            diagnostic.to_string(),
+            "warning [frame 3] [event 7]: Something"
+        );
+    }
+
+    #[test]
+    fn test_from_load_warning_keeps_position() {
+        let error = AppError::InvalidFormat {
+            path: None,
+            message: "Event 4 was skipped".to_string(),
+            line: Some(12),
+            column: Some(5),
+        };
+        let diagnostic = Diagnostic::from_load_warning(&error);
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(diagnostic.line, Some(12));
+        assert_eq!(
+            diagnostic.to_string(),
+            "warning [line 12, column 5]: Event 4 was skipped"
+        );
+    }
+}
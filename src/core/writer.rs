@@ -0,0 +1,603 @@
+//! Writers for input log files (.ilj, .iljl and .ilb formats).
+//!
+//! This module is the counterpart of `parser`: it serializes an `InputLog`
+//! into either format so that logs built in the viewer (e.g. from a live
+//! stream) can be saved and loaded again.
+
+use serde::Serialize;
+
+use super::compression;
+use super::log::{
+    Annotation, ButtonState, DeviceType, InputEvent, InputKind, InputLog, InputMapping,
+};
+use super::parser::{
+    BINARY_ANNOTATION_HAS_VALUE, BINARY_CHECKSUM_SIZE, BINARY_FLAG_ANNOTATIONS,
+    BINARY_FLAG_CHECKSUM, BINARY_FLAG_COMPRESSED, BINARY_FLAG_TIMESTAMPS, BINARY_MAGIC,
+    BINARY_VERSION, BinaryEvent, BinaryHeader, crc32,
+};
+
+// ============================================================================
+// Intermediate JSON structures for serialization
+// ============================================================================
+
+/// Top-level JSON structure for .ilj files.
+#[derive(Debug, Serialize)]
+struct JsonInputLog<'a> {
+    version: u32,
+    metadata: JsonMetadata<'a>,
+    mappings: Vec<JsonMapping<'a>>,
+    events: Vec<JsonEvent>,
+    #[serde(skip_serializing_if = "<[u64]>::is_empty")]
+    timestamps_us: &'a [u64],
+    #[serde(skip_serializing_if = "<[Annotation]>::is_empty")]
+    annotations: &'a [Annotation],
+}
+
+/// Header line of a newline-delimited JSON (.iljl) file.
+#[derive(Debug, Serialize)]
+struct JsonLinesHeader<'a> {
+    version: u32,
+    metadata: JsonMetadata<'a>,
+    mappings: Vec<JsonMapping<'a>>,
+    #[serde(skip_serializing_if = "<[u64]>::is_empty")]
+    timestamps_us: &'a [u64],
+    #[serde(skip_serializing_if = "<[Annotation]>::is_empty")]
+    annotations: &'a [Annotation],
+}
+
+/// Metadata section in JSON format.
+#[derive(Debug, Serialize)]
+struct JsonMetadata<'a> {
+    target_fps: u32,
+    frame_count: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<&'a str>,
+}
+
+/// Input mapping in JSON format.
+#[derive(Debug, Serialize)]
+struct JsonMapping<'a> {
+    id: u32,
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    player: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<&'a str>,
+}
+
+/// Input event in JSON format.
+#[derive(Debug, Serialize)]
+struct JsonEvent {
+    frame: u64,
+    id: u32,
+    kind: &'static str,
+    state: &'static str,
+    value: [f32; 2],
+    #[serde(skip_serializing_if = "is_zero")]
+    contact: u16,
+}
+
+fn is_zero(value: &u16) -> bool {
+    *value == 0
+}
+
+impl<'a> From<&'a InputLog> for JsonMetadata<'a> {
+    fn from(log: &'a InputLog) -> Self {
+        Self {
+            target_fps: log.metadata.target_fps,
+            frame_count: log.metadata.frame_count,
+            created_at: log.metadata.created_at.as_deref(),
+            source: log.metadata.source.as_deref(),
+        }
+    }
+}
+
+impl<'a> From<&'a InputMapping> for JsonMapping<'a> {
+    fn from(mapping: &'a InputMapping) -> Self {
+        Self {
+            id: mapping.id,
+            name: &mapping.name,
+            color: mapping
+                .color
+                .map(|[r, g, b]| format!("#{:02X}{:02X}{:02X}", r, g, b)),
+            player: mapping.player,
+            device: mapping.device.map(device_type_name),
+            group: mapping.group.as_deref(),
+        }
+    }
+}
+
+impl From<&InputEvent> for JsonEvent {
+    fn from(event: &InputEvent) -> Self {
+        Self {
+            frame: event.frame,
+            id: event.id,
+            kind: input_kind_name(event.kind),
+            state: button_state_name(event.state),
+            value: event.value,
+            contact: event.contact,
+        }
+    }
+}
+
+/// Name of an input kind as used in .ilj files.
+pub fn input_kind_name(kind: InputKind) -> &'static str {
+    match kind {
+        InputKind::Button => "Button",
+        InputKind::Axis1D => "Axis1D",
+        InputKind::Axis2D => "Axis2D",
+        InputKind::Trigger => "Trigger",
+        InputKind::MouseDelta => "MouseDelta",
+        InputKind::PointerPosition => "PointerPosition",
+        InputKind::Touch => "Touch",
+        InputKind::Key => "Key",
+    }
+}
+
+/// Name of a device type as used in .ilj files.
+pub fn device_type_name(device: DeviceType) -> &'static str {
+    match device {
+        DeviceType::Gamepad => "Gamepad",
+        DeviceType::Keyboard => "Keyboard",
+        DeviceType::Mouse => "Mouse",
+        DeviceType::Touch => "Touch",
+        DeviceType::Other => "Other",
+    }
+}
+
+/// Name of a button state as used in .ilj files.
+pub fn button_state_name(state: ButtonState) -> &'static str {
+    match state {
+        ButtonState::Released => "Released",
+        ButtonState::Pressed => "Pressed",
+        ButtonState::Held => "Held",
+    }
+}
+
+// ============================================================================
+// Writer implementation
+// ============================================================================
+
+/// Serialize an `InputLog` into the JSON (.ilj) format.
+///
+/// The output is pretty-printed and can be read back with `parser::parse_json`.
+pub fn write_json(log: &InputLog) -> String {
+    let json_log = JsonInputLog {
+        version: 1,
+        metadata: JsonMetadata::from(log),
+        mappings: log.mappings.iter().map(JsonMapping::from).collect(),
+        events: log.events.iter().map(JsonEvent::from).collect(),
+        timestamps_us: &log.frame_timestamps,
+        annotations: &log.annotations,
+    };
+
+    // Serializing plain structs with string keys cannot fail
+    serde_json::to_string_pretty(&json_log).unwrap_or_default()
+}
+
+/// Serialize an `InputLog` into the newline-delimited JSON (.iljl) format.
+///
+/// The first line holds the version, metadata, mappings and any frame
+/// timestamps and annotations; every following line holds one event. The output can be read back with
+/// `parser::parse_json_lines`.
+pub fn write_json_lines(log: &InputLog) -> String {
+    let header = JsonLinesHeader {
+        version: 1,
+        metadata: JsonMetadata::from(log),
+        mappings: log.mappings.iter().map(JsonMapping::from).collect(),
+        timestamps_us: &log.frame_timestamps,
+        annotations: &log.annotations,
+    };
+
+    let mut output = serde_json::to_string(&header).unwrap_or_default();
+    output.push('\n');
+    for event in &log.events {
+        output.push_str(&serde_json::to_string(&JsonEvent::from(event)).unwrap_or_default());
+        output.push('\n');
+    }
+    output
+}
+
+/// Serialize an `InputLog` into the binary (.ilb) format.
+///
+/// Mappings, `created_at` and `source` are not part of the binary format and
+/// are dropped. Frame timestamps and annotations, if any, follow the events.
+/// With `checksum` set, the file ends with a CRC-32 trailer so that
+/// corruption is detected on load.
+pub fn write_binary(log: &InputLog, checksum: bool) -> Vec<u8> {
+    let mut data = Vec::with_capacity(
+        BinaryHeader::SIZE
+            + log.events.len() * BinaryEvent::SIZE
+            + (log.frame_timestamps.len() + 1) * 8
+            + BINARY_CHECKSUM_SIZE,
+    );
+    data.extend_from_slice(bytemuck::bytes_of(&binary_header(
+        log,
+        checksum_flag(checksum),
+    )));
+    for event in &log.events {
+        data.extend_from_slice(bytemuck::bytes_of(&BinaryEvent::from_input_event(event)));
+    }
+    if log.has_frame_timestamps() {
+        data.extend_from_slice(&(log.frame_timestamps.len() as u64).to_le_bytes());
+        for timestamp in &log.frame_timestamps {
+            data.extend_from_slice(&timestamp.to_le_bytes());
+        }
+    }
+    write_binary_annotations(&mut data, &log.annotations);
+    if checksum {
+        data.extend_from_slice(&crc32(&data).to_le_bytes());
+    }
+    data
+}
+
+/// Serialize an `InputLog` into the compressed binary (.ilb) format.
+///
+/// Events are stored as variable-length records with delta-encoded frames
+/// and run-length encoded repeats (see `compression`), which shrinks logs
+/// dominated by held buttons and resting axes. Like `write_binary`, the file
+/// ends with a CRC-32 trailer if `checksum` is set.
+pub fn write_binary_compressed(log: &InputLog, checksum: bool) -> Vec<u8> {
+    let flags = checksum_flag(checksum) | BINARY_FLAG_COMPRESSED;
+    let mut data = bytemuck::bytes_of(&binary_header(log, flags)).to_vec();
+    data.extend_from_slice(&compression::encode_events(&log.events));
+    if log.has_frame_timestamps() {
+        data.extend_from_slice(&compression::encode_timestamps(&log.frame_timestamps));
+    }
+    write_binary_annotations(&mut data, &log.annotations);
+    if checksum {
+        data.extend_from_slice(&crc32(&data).to_le_bytes());
+    }
+    data
+}
+
+/// Header flag announcing the CRC-32 trailer, if one is written.
+fn checksum_flag(checksum: bool) -> u32 {
+    if checksum { BINARY_FLAG_CHECKSUM } else { 0 }
+}
+
+/// Append the annotation block of a binary file, if there are annotations.
+///
+/// The block is the same in plain and compressed files. Labels longer than
+/// `u16::MAX` bytes are cut at a character boundary.
+fn write_binary_annotations(data: &mut Vec<u8>, annotations: &[Annotation]) {
+    if annotations.is_empty() {
+        return;
+    }
+    data.extend_from_slice(&(annotations.len() as u32).to_le_bytes());
+    for annotation in annotations {
+        let mut label_len = annotation.label.len().min(u16::MAX as usize);
+        while !annotation.label.is_char_boundary(label_len) {
+            label_len -= 1;
+        }
+        let flags = if annotation.value.is_some() {
+            BINARY_ANNOTATION_HAS_VALUE
+        } else {
+            0
+        };
+        data.extend_from_slice(&annotation.frame.to_le_bytes());
+        data.push(flags);
+        data.extend_from_slice(&annotation.value.unwrap_or(0.0).to_le_bytes());
+        data.extend_from_slice(&(label_len as u16).to_le_bytes());
+        data.extend_from_slice(&annotation.label.as_bytes()[..label_len]);
+    }
+}
+
+/// Header for a binary file with the given flags.
+///
+/// `BINARY_FLAG_TIMESTAMPS` and `BINARY_FLAG_ANNOTATIONS` are added when the
+/// log has frame timestamps or annotations.
+fn binary_header(log: &InputLog, mut flags: u32) -> BinaryHeader {
+    if log.has_frame_timestamps() {
+        flags |= BINARY_FLAG_TIMESTAMPS;
+    }
+    if !log.annotations.is_empty() {
+        flags |= BINARY_FLAG_ANNOTATIONS;
+    }
+    BinaryHeader {
+        magic: BINARY_MAGIC,
+        version: BINARY_VERSION,
+        flags,
+        target_fps: log.metadata.target_fps,
+        frame_count: log.metadata.frame_count,
+        event_count: log.events.len() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log::LogMetadata;
+    use crate::core::parser::{parse_binary, parse_json, parse_json_lines};
+
+    fn create_test_log() -> InputLog {
+        InputLog {
+            metadata: LogMetadata {
+                target_fps: 120,
+                frame_count: 10,
+                source: Some("Test Game".to_string()),
+                ..Default::default()
+            },
+            mappings: vec![InputMapping {
+                id: 0,
+                name: "Jump".to_string(),
+                color: Some([255, 85, 0]),
+                player: None,
+                device: None,
+                group: None,
+            }],
+            events: vec![
+                InputEvent {
+                    frame: 1,
+                    id: 0,
+                    kind: InputKind::Button,
+                    state: ButtonState::Pressed,
+                    value: [0.0, 0.0],
+                    contact: 0,
+                },
+                InputEvent {
+                    frame: 2,
+                    id: 1,
+                    kind: InputKind::Axis2D,
+                    state: ButtonState::Released,
+                    value: [0.5, -1.0],
+                    contact: 0,
+                },
+            ],
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let log = create_test_log();
+        let parsed = parse_json(&write_json(&log)).unwrap();
+
+        assert_eq!(parsed.metadata.target_fps, 120);
+        assert_eq!(parsed.metadata.frame_count, 10);
+        assert_eq!(parsed.metadata.source.as_deref(), Some("Test Game"));
+        assert_eq!(parsed.mappings[0].name, "Jump");
+        assert_eq!(parsed.mappings[0].color, Some([255, 85, 0]));
+        assert_eq!(parsed.events.len(), 2);
+        assert_eq!(parsed.events[1].kind, InputKind::Axis2D);
+        assert_eq!(parsed.events[1].value, [0.5, -1.0]);
+    }
+
+    #[test]
+    fn test_json_round_trip_players() {
+        let mut log = create_test_log();
+        log.mappings[0].player = Some(1);
+        log.mappings[0].device = Some(DeviceType::Gamepad);
+        let content = write_json(&log);
+        assert!(content.contains("\"device\": \"Gamepad\""));
+
+        let parsed = parse_json(&content).unwrap();
+        assert_eq!(parsed.mappings[0].player, Some(1));
+        assert_eq!(parsed.mappings[0].device, Some(DeviceType::Gamepad));
+
+        // Single-player logs don't mention players
+        assert!(!write_json(&create_test_log()).contains("player"));
+    }
+
+    #[test]
+    fn test_json_round_trip_groups() {
+        let mut log = create_test_log();
+        log.mappings[0].group = Some("Movement".to_string());
+
+        let parsed = parse_json(&write_json(&log)).unwrap();
+        assert_eq!(parsed.mappings[0].group.as_deref(), Some("Movement"));
+        assert!(!write_json(&create_test_log()).contains("group"));
+    }
+
+    #[test]
+    fn test_json_lines_round_trip() {
+        let log = create_test_log();
+        let content = write_json_lines(&log);
+        assert_eq!(content.lines().count(), 3);
+
+        let parsed = parse_json_lines(&content).unwrap();
+        assert_eq!(parsed.metadata.frame_count, 10);
+        assert_eq!(parsed.mappings[0].color, Some([255, 85, 0]));
+        assert_eq!(parsed.events.len(), 2);
+        assert_eq!(parsed.events[1].value, [0.5, -1.0]);
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let log = create_test_log();
+        let data = write_binary(&log, true);
+        assert_eq!(
+            data.len(),
+            BinaryHeader::SIZE + 2 * BinaryEvent::SIZE + BINARY_CHECKSUM_SIZE
+        );
+
+        let parsed = parse_binary(&data).unwrap();
+        assert_eq!(parsed.metadata.target_fps, 120);
+        assert_eq!(parsed.metadata.frame_count, 10);
+        assert_eq!(parsed.events[0].state, ButtonState::Pressed);
+        assert_eq!(parsed.events[1].id, 1);
+        assert_eq!(parsed.events[1].value, [0.5, -1.0]);
+    }
+
+    #[test]
+    fn test_binary_without_checksum() {
+        let log = create_test_log();
+        for (data, with_checksum) in [
+            (write_binary(&log, false), write_binary(&log, true)),
+            (
+                write_binary_compressed(&log, false),
+                write_binary_compressed(&log, true),
+            ),
+        ] {
+            assert_eq!(data.len() + BINARY_CHECKSUM_SIZE, with_checksum.len());
+            let header: BinaryHeader = bytemuck::pod_read_unaligned(&data[..BinaryHeader::SIZE]);
+            assert!(!header.has_checksum());
+
+            let parsed = parse_binary(&data).unwrap();
+            assert_eq!(parsed.events, log.events);
+            assert_eq!(parsed.metadata.frame_count, 10);
+        }
+    }
+
+    #[test]
+    fn test_binary_compressed_round_trip() {
+        let mut log = create_test_log();
+        log.metadata.frame_count = 600;
+        for frame in 2..600 {
+            log.events.push(InputEvent {
+                frame,
+                id: 0,
+                kind: InputKind::Button,
+                state: ButtonState::Held,
+                value: [0.0, 0.0],
+                contact: 0,
+            });
+        }
+
+        let compressed = write_binary_compressed(&log, true);
+        let plain = write_binary(&log, true);
+        assert!(compressed.len() * 10 < plain.len());
+
+        let parsed = parse_binary(&compressed).unwrap();
+        assert_eq!(parsed.metadata.frame_count, 600);
+        assert_eq!(parsed.events, log.events);
+        assert_eq!(parse_binary(&plain).unwrap().events, parsed.events);
+    }
+
+    #[test]
+    fn test_frame_timestamps_round_trip() {
+        let mut log = create_test_log();
+        log.frame_timestamps = (0..10).map(|f| 1_000 + f * 8_333).collect();
+        // Frame 4 hitches
+        for timestamp in &mut log.frame_timestamps[5..] {
+            *timestamp += 40_000;
+        }
+
+        for parsed in [
+            parse_json(&write_json(&log)).unwrap(),
+            parse_json_lines(&write_json_lines(&log)).unwrap(),
+            parse_binary(&write_binary(&log, true)).unwrap(),
+            parse_binary(&write_binary_compressed(&log, true)).unwrap(),
+        ] {
+            assert_eq!(parsed.frame_timestamps, log.frame_timestamps);
+            assert_eq!(parsed.events, log.events);
+        }
+
+        // Logs without timestamps don't mention them
+        assert!(!write_json(&create_test_log()).contains("timestamps_us"));
+    }
+
+    #[test]
+    fn test_annotations_round_trip() {
+        let mut log = create_test_log();
+        log.annotations = vec![
+            Annotation {
+                frame: 1,
+                label: "checkpoint".to_string(),
+                value: None,
+            },
+            Annotation {
+                frame: 2,
+                label: "HP".to_string(),
+                value: Some(87.5),
+            },
+            Annotation {
+                frame: 4,
+                label: "player died ☠".to_string(),
+                value: None,
+            },
+        ];
+
+        for parsed in [
+            parse_json(&write_json(&log)).unwrap(),
+            parse_json_lines(&write_json_lines(&log)).unwrap(),
+            parse_binary(&write_binary(&log, true)).unwrap(),
+            parse_binary(&write_binary_compressed(&log, true)).unwrap(),
+        ] {
+            assert_eq!(parsed.annotations, log.annotations);
+            assert_eq!(parsed.events, log.events);
+        }
+
+        // Logs without annotations don't mention them
+        assert!(!write_json(&create_test_log()).contains("annotations"));
+    }
+
+    #[test]
+    fn test_extended_kinds_round_trip() {
+        let mut log = create_test_log();
+        let event = |frame, id, kind, state, value, contact| InputEvent {
+            frame,
+            id,
+            kind,
+            state,
+            value,
+            contact,
+        };
+        log.events.extend([
+            event(
+                3,
+                2,
+                InputKind::Trigger,
+                ButtonState::Released,
+                [0.75, 0.0],
+                0,
+            ),
+            event(
+                3,
+                3,
+                InputKind::MouseDelta,
+                ButtonState::Released,
+                [4.0, -2.5],
+                0,
+            ),
+            event(
+                4,
+                4,
+                InputKind::PointerPosition,
+                ButtonState::Released,
+                [640.0, 360.0],
+                0,
+            ),
+            event(
+                4,
+                5,
+                InputKind::Touch,
+                ButtonState::Pressed,
+                [100.0, 200.0],
+                3,
+            ),
+            event(
+                5,
+                5,
+                InputKind::Touch,
+                ButtonState::Released,
+                [102.0, 201.0],
+                3,
+            ),
+            event(5, 6, InputKind::Key, ButtonState::Pressed, [65.0, 0.0], 0),
+        ]);
+
+        let json = write_json(&log);
+        assert!(json.contains("\"kind\": \"PointerPosition\""));
+        assert!(json.contains("\"contact\": 3"));
+
+        for parsed in [
+            parse_json(&json).unwrap(),
+            parse_json_lines(&write_json_lines(&log)).unwrap(),
+            parse_binary(&write_binary(&log, true)).unwrap(),
+            parse_binary(&write_binary_compressed(&log, true)).unwrap(),
+        ] {
+            assert_eq!(parsed.events, log.events);
+        }
+
+        // Contacts are only written where they are set
+        assert!(!write_json(&create_test_log()).contains("contact"));
+    }
+}
@@ -5,14 +5,30 @@
 
 use eframe::egui;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use crate::core::config::AppSettings;
-use crate::core::error::{self, AppError as DetailedAppError};
+use crate::core::config::{AppSettings, MAX_ROW_HEIGHT, MIN_ROW_HEIGHT, RowDensity};
+use crate::core::csv_io::{self, CsvColumnMapping, CsvField, CsvTable};
+use crate::core::density::{DEFAULT_MAX_BUCKETS, DensityMap};
+use crate::core::edit::{self, EventChange};
+use crate::core::error::{self as detailed_error, AppError as DetailedAppError};
 use crate::core::filter::FilterState;
-use crate::core::log::{Bookmark, ButtonState, InputKind, InputLog};
+use crate::core::format::{self, FormatRegistry};
+use crate::core::history::{Command, History};
+use crate::core::idle::{IdleConfig, IdleKind, IdleReport, detect_idle};
+use crate::core::layout::RowLayout;
+use crate::core::log::{Bookmark, ButtonState, InputKind, InputLog, InputMapping, player_label};
+use crate::core::normalize::{HoldEncoding, normalize};
 use crate::core::parser;
 use crate::core::playback::PlaybackState;
-use crate::core::search::{SearchQuery, SearchResult, find_matches};
+use crate::core::profile::{ApplyReport, MappingProfile, ProfileEntry, ProfileStore};
+use crate::core::search::{SearchQuery, SearchResult, find_annotation_matches, find_matches};
+use crate::core::splice;
+use crate::core::stats::{Histogram, LogStats, StatsConfig, compute_stats};
+use crate::core::stream::{self, Endpoint, StreamReceiver, StreamStatus};
+use crate::core::tail::{LogTail, TailUpdate};
+use crate::core::timing::{TimingConfig, TimingReport, analyze_timing};
+use crate::core::validate::{Diagnostic, Severity, validate};
 
 use super::controls::{ControlAction, ControlsRenderer};
 use super::dialogs::{ErrorDialogAction, ErrorDialogRenderer, ErrorDialogState};
@@ -99,6 +115,8 @@ impl AppState {
 pub enum StatusKind {
     /// Success message (shown in green)
     Success,
+    /// Informational message (shown in the label text color)
+    Info,
     /// Error message (shown in red)
     Error,
 }
@@ -108,7 +126,7 @@ pub enum StatusKind {
 pub struct StatusMessage {
     /// The message text
     pub text: String,
-    /// Kind of message (success/error)
+    /// Kind of message (success/info/error)
     pub kind: StatusKind,
     /// When the message was created (for auto-dismiss)
     pub created_at: std::time::Instant,
@@ -142,6 +160,8 @@ pub struct SearchState {
     pub selected_input_index: usize,
     /// Currently selected button state for searching (0=Any, 1=Pressed, 2=Released)
     pub selected_state_index: usize,
+    /// Text to look for in annotation labels
+    pub annotation_text: String,
     /// Current search results
     pub results: SearchResult,
     /// Whether a search has been performed
@@ -158,6 +178,7 @@ impl SearchState {
     pub fn reset(&mut self) {
         self.selected_input_index = 0;
         self.selected_state_index = 0;
+        self.annotation_text.clear();
         self.results = SearchResult::new();
         self.has_searched = false;
         // Keep dialog_open unchanged so user can continue searching
@@ -181,6 +202,8 @@ pub struct SelectionState {
     pub is_dragging: bool,
     /// Frame where the drag started (used during drag to compute selection).
     pub drag_start_frame: Option<u64>,
+    /// Selection before the current drag started (recorded for undo).
+    pub before_drag: Option<(u64, u64)>,
 }
 
 impl SelectionState {
@@ -204,6 +227,7 @@ impl SelectionState {
 
     /// Start a drag selection from the given frame.
     pub fn start_drag(&mut self, frame: u64) {
+        self.before_drag = self.get_selection();
         self.is_dragging = true;
         self.drag_start_frame = Some(frame);
         // Clear any existing selection when starting a new drag
@@ -233,7 +257,6 @@ impl SelectionState {
     }
 
     /// Set the selection directly.
-    #[allow(dead_code)]
     pub fn set_selection(&mut self, start: u64, end: u64) {
         // Ensure start <= end
         if start <= end {
@@ -251,6 +274,7 @@ impl SelectionState {
         self.end = None;
         self.is_dragging = false;
         self.drag_start_frame = None;
+        self.before_drag = None;
     }
 
     /// Reset the selection state when a new file is loaded.
@@ -401,6 +425,36 @@ impl BookmarkState {
         }
     }
 
+    /// Apply a bookmark command from the undo history.
+    ///
+    /// # Returns
+    /// `false` if the command is not a bookmark command or does not fit the
+    /// current bookmarks (e.g. adding a bookmark that already exists).
+    pub fn apply_command(&mut self, command: &Command) -> bool {
+        // Indices may shift, so stop any label editing in progress
+        self.cancel_editing();
+        match command {
+            Command::AddBookmark(bookmark) => {
+                self.add_bookmark(bookmark.frame, bookmark.label.clone())
+            }
+            Command::RemoveBookmark(bookmark) => self.remove_bookmark(bookmark.frame),
+            Command::RenameBookmark { frame, after, .. } => {
+                match self.bookmarks.iter_mut().find(|b| b.frame == *frame) {
+                    Some(bookmark) => {
+                        bookmark.label = after.clone();
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Command::ReplaceBookmarks { after, .. } => {
+                self.bookmarks = after.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Start editing a bookmark's label.
     pub fn start_editing(&mut self, index: usize) {
         if let Some(bookmark) = self.bookmarks.get(index) {
@@ -426,6 +480,476 @@ impl BookmarkState {
     }
 }
 
+/// State for the idle detection panel and its results.
+#[derive(Debug, Clone)]
+pub struct IdleState {
+    /// Whether the idle detection panel is currently open
+    pub panel_open: bool,
+    /// Detection settings (minimum span length, deadzone)
+    pub config: IdleConfig,
+    /// Result of the last analysis run (None until the analysis has been run)
+    pub report: Option<IdleReport>,
+    /// Whether detected spans are shaded on the timeline
+    pub show_on_timeline: bool,
+}
+
+impl Default for IdleState {
+    fn default() -> Self {
+        Self {
+            panel_open: false,
+            config: IdleConfig::default(),
+            report: None,
+            show_on_timeline: true,
+        }
+    }
+}
+
+impl IdleState {
+    /// Create a new idle detection state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset the results when a new file is loaded.
+    pub fn reset(&mut self) {
+        self.report = None;
+        // Keep panel_open and config unchanged so user can re-run the analysis
+    }
+
+    /// Run the analysis on the given log and store the result.
+    pub fn analyze(&mut self, log: &InputLog) {
+        self.report = Some(detect_idle(log, &self.config));
+    }
+}
+
+/// State for the statistics panel.
+#[derive(Debug, Clone, Default)]
+pub struct StatsState {
+    /// Whether the statistics panel is currently open
+    pub panel_open: bool,
+    /// Threshold and histogram settings
+    pub config: StatsConfig,
+    /// Whether to limit the statistics to the current selection
+    pub use_selection: bool,
+    /// Most recently computed statistics
+    pub report: Option<LogStats>,
+    /// Range and settings the report was computed with (for caching)
+    computed_for: Option<(Option<(u64, u64)>, StatsConfig)>,
+}
+
+impl StatsState {
+    /// Create a new statistics state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset the cached statistics when a new file is loaded.
+    pub fn reset(&mut self) {
+        self.report = None;
+        self.computed_for = None;
+        self.use_selection = false;
+    }
+
+    /// Recompute the statistics if the range or settings changed.
+    pub fn refresh(&mut self, log: &InputLog, selection: Option<(u64, u64)>) {
+        let range = if self.use_selection { selection } else { None };
+        let key = (range, self.config);
+        if self.computed_for != Some(key) {
+            self.report = Some(compute_stats(log, range, &self.config));
+            self.computed_for = Some(key);
+        }
+    }
+
+    /// Force a recomputation on the next refresh (e.g. after events were appended).
+    pub fn invalidate(&mut self) {
+        self.computed_for = None;
+    }
+}
+
+/// State for the log warnings panel.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationState {
+    /// Whether the warnings panel is currently open
+    pub panel_open: bool,
+    /// Diagnostics for the loaded log
+    pub diagnostics: Vec<Diagnostic>,
+    /// Number of leading diagnostics that were reported while loading
+    load_count: usize,
+}
+
+impl ValidationState {
+    /// Create a new validation state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate a newly loaded log.
+    ///
+    /// Problems reported while loading (e.g. entries skipped by lenient
+    /// parsing) are listed first. The panel is opened automatically when
+    /// warnings or errors were found.
+    pub fn run(&mut self, log: &InputLog, load_warnings: &[DetailedAppError]) {
+        self.diagnostics = load_warnings
+            .iter()
+            .map(Diagnostic::from_load_warning)
+            .collect();
+        self.load_count = self.diagnostics.len();
+        self.diagnostics.extend(validate(log));
+        self.panel_open = self.count_at_least(Severity::Warning) > 0;
+    }
+
+    /// Validate the log again after it was edited, keeping the load problems.
+    pub fn revalidate(&mut self, log: &InputLog) {
+        self.diagnostics.truncate(self.load_count);
+        self.diagnostics.extend(validate(log));
+    }
+
+    /// Count diagnostics with at least the given severity.
+    pub fn count_at_least(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity >= severity)
+            .count()
+    }
+}
+
+/// State for editing events on the timeline.
+#[derive(Debug, Clone, Default)]
+pub struct EditState {
+    /// Whether clicking a timeline cell edits it
+    pub enabled: bool,
+    /// Whether the log has edits that are not saved yet
+    pub dirty: bool,
+    /// Cell being edited, as (input ID, frame)
+    pub cell: Option<(u32, u64)>,
+    /// Touch contact being edited (0 for other kinds)
+    pub contact: u16,
+    /// Value entered for an analog cell
+    pub value: [f32; 2],
+}
+
+impl EditState {
+    /// Create a new edit state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset the edit state when a new file is loaded, keeping edit mode on.
+    pub fn reset(&mut self) {
+        self.dirty = false;
+        self.cell = None;
+    }
+}
+
+/// State for mapping profiles and the mapping editor.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileState {
+    /// Saved mapping profiles
+    pub store: ProfileStore,
+    /// Profile suggested for the current log by its source
+    pub suggested: Option<usize>,
+    /// Profile last applied to the current log
+    pub active: Option<usize>,
+    /// Whether the mapping editor is open
+    pub editor_open: bool,
+    /// Name of the profile the editor saves to
+    pub editor_name: String,
+    /// Source text of the profile the editor saves to
+    pub editor_source: String,
+    /// Entries being edited
+    pub editor_entries: Vec<ProfileEntry>,
+}
+
+impl ProfileState {
+    /// Create the profile state with the profiles saved on disk.
+    pub fn load() -> Self {
+        Self {
+            store: ProfileStore::load(),
+            ..Default::default()
+        }
+    }
+
+    /// Suggest a profile for a newly loaded log and apply it if `apply` is set.
+    ///
+    /// # Returns
+    /// The name of the applied profile, if one was applied.
+    pub fn on_log_loaded(&mut self, log: &mut InputLog, apply: bool) -> Option<String> {
+        self.suggested = self.store.suggest(log);
+        self.active = None;
+        let index = self.suggested.filter(|_| apply)?;
+        self.apply(index, log)
+            .map(|_| self.store.profiles[index].name.clone())
+    }
+
+    /// Apply a saved profile to the log and make it the active one.
+    pub fn apply(&mut self, index: usize, log: &mut InputLog) -> Option<ApplyReport> {
+        let profile = self.store.profiles.get(index)?;
+        let report = profile.apply(log);
+        self.active = Some(index);
+        Some(report)
+    }
+
+    /// Apply the active profile again, e.g. after the log was reloaded.
+    pub fn reapply(&self, log: &mut InputLog) {
+        if let Some(profile) = self.active.and_then(|i| self.store.profiles.get(i)) {
+            profile.apply(log);
+        }
+    }
+
+    /// Open the mapping editor for a log.
+    ///
+    /// The editor starts from the active profile, with entries added for the
+    /// log's inputs the profile doesn't cover; without an active profile it
+    /// starts from the log's mappings.
+    pub fn open_editor(&mut self, log: &InputLog) {
+        let from_log =
+            MappingProfile::from_log(log.metadata.source.clone().unwrap_or_default(), log);
+        let profile = match self.active.and_then(|i| self.store.profiles.get(i)) {
+            Some(active) => {
+                let mut profile = active.clone();
+                for entry in from_log.entries {
+                    if !profile.entries.iter().any(|e| e.id == entry.id) {
+                        profile.entries.push(entry);
+                    }
+                }
+                profile.entries.sort_by_key(|e| e.id);
+                profile
+            }
+            None => from_log,
+        };
+        self.editor_name = profile.name;
+        self.editor_source = profile.source.unwrap_or_default();
+        self.editor_entries = profile.entries;
+        self.editor_open = true;
+    }
+
+    /// Build a profile from the editor's fields.
+    pub fn editor_profile(&self) -> MappingProfile {
+        let source = self.editor_source.trim();
+        let mut profile = MappingProfile {
+            name: self.editor_name.trim().to_string(),
+            source: (!source.is_empty()).then(|| source.to_string()),
+            entries: Vec::new(),
+            layout: RowLayout::default(),
+        };
+        profile.set_entries(self.editor_entries.clone());
+        profile
+    }
+
+    /// Row layout of the active profile, or the default layout.
+    pub fn active_layout(&self) -> RowLayout {
+        self.active
+            .and_then(|i| self.store.profiles.get(i))
+            .map(|profile| profile.layout.clone())
+            .unwrap_or_default()
+    }
+
+    /// Store the row layout in the active profile and save the profiles.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The layout was saved
+    /// * `Ok(false)` - There is no active profile to save it in
+    /// * `Err(message)` - Saving the profiles failed
+    pub fn save_layout(&mut self, layout: &RowLayout) -> Result<bool, String> {
+        let Some(profile) = self.active.and_then(|i| self.store.profiles.get_mut(i)) else {
+            return Ok(false);
+        };
+        if profile.layout == *layout {
+            return Ok(true);
+        }
+        profile.layout = layout.clone();
+        self.store.save().map(|_| true)
+    }
+
+    /// Set the group of an input in the active profile, if there is one.
+    ///
+    /// An entry is added from the log's mapping if the profile has none for
+    /// the input.
+    pub fn set_group(&mut self, mapping: &InputMapping, kind: Option<InputKind>) {
+        let Some(profile) = self.active.and_then(|i| self.store.profiles.get_mut(i)) else {
+            return;
+        };
+        match profile.entries.iter_mut().find(|e| e.id == mapping.id) {
+            Some(entry) => entry.group = mapping.group.clone(),
+            None => {
+                let mut entries = profile.entries.clone();
+                entries.push(ProfileEntry::from_mapping(mapping, kind));
+                profile.set_entries(entries);
+            }
+        }
+    }
+
+    /// Write the editor's entries back to the profile with the editor's name.
+    ///
+    /// The profile keeps the given row layout.
+    ///
+    /// # Returns
+    /// The index of the saved profile.
+    pub fn save_editor_profile(&mut self, layout: &RowLayout) -> usize {
+        let mut profile = self.editor_profile();
+        profile.layout = layout.clone();
+        self.editor_entries = profile.entries.clone();
+        let index = self.store.upsert(profile);
+        self.active = Some(index);
+        index
+    }
+}
+
+/// Inputs, range and settings a timing report was computed with.
+type TimingCacheKey = (u32, u32, Option<(u64, u64)>, TimingConfig);
+
+/// State for the A→B input timing panel.
+#[derive(Debug, Clone, Default)]
+pub struct TimingState {
+    /// Whether the timing panel is currently open
+    pub panel_open: bool,
+    /// Leading input (A)
+    pub input_a: Option<u32>,
+    /// Following input (B)
+    pub input_b: Option<u32>,
+    /// Frame-perfect window and press threshold
+    pub config: TimingConfig,
+    /// Most recent analysis result
+    pub report: Option<TimingReport>,
+    /// Inputs, range and settings the report was computed with (for caching)
+    computed_for: Option<TimingCacheKey>,
+}
+
+impl TimingState {
+    /// Create a new timing analysis state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset the chosen inputs and results when a new file is loaded.
+    pub fn reset(&mut self) {
+        self.input_a = None;
+        self.input_b = None;
+        self.report = None;
+        self.computed_for = None;
+    }
+
+    /// Recompute the analysis if the inputs, range or settings changed.
+    ///
+    /// The analysis is limited to the selection when one exists.
+    pub fn refresh(&mut self, log: &InputLog, selection: Option<(u64, u64)>) {
+        let (Some(a), Some(b)) = (self.input_a, self.input_b) else {
+            self.report = None;
+            self.computed_for = None;
+            return;
+        };
+        let key = (a, b, selection, self.config);
+        if self.computed_for != Some(key) {
+            self.report = Some(analyze_timing(log, a, b, selection, &self.config));
+            self.computed_for = Some(key);
+        }
+    }
+
+    /// Force a recomputation on the next refresh (e.g. after events were appended).
+    pub fn invalidate(&mut self) {
+        self.computed_for = None;
+    }
+}
+
+/// How often a followed file is checked for new events.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// State for following a log file that is still being written.
+#[derive(Debug)]
+pub struct FollowState {
+    /// Tail of the followed file
+    pub tail: LogTail,
+    /// When the file was last polled
+    last_poll: Instant,
+}
+
+impl FollowState {
+    /// Start following with the given tail.
+    pub fn new(tail: LogTail) -> Self {
+        Self {
+            tail,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Check whether the poll interval has elapsed, and restart it if so.
+    pub fn poll_due(&mut self, now: Instant) -> bool {
+        if now.duration_since(self.last_poll) >= FOLLOW_POLL_INTERVAL {
+            self.last_poll = now;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// How often the live stream is checked for new events.
+const STREAM_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// State for receiving a live input stream over a local socket.
+pub struct StreamState {
+    /// Whether the stream panel is currently open
+    pub panel_open: bool,
+    /// Endpoint text as entered by the user
+    pub endpoint_input: String,
+    /// Active receiver (Some from Listen until Stop or the next file load)
+    pub receiver: Option<StreamReceiver>,
+    /// Whether the loaded log was recorded from a stream
+    pub log_from_stream: bool,
+}
+
+impl Default for StreamState {
+    fn default() -> Self {
+        Self {
+            panel_open: false,
+            endpoint_input: stream::DEFAULT_ENDPOINT.to_string(),
+            receiver: None,
+            log_from_stream: false,
+        }
+    }
+}
+
+impl StreamState {
+    /// Create a new stream state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a receiver is waiting for or receiving events.
+    pub fn is_active(&self) -> bool {
+        self.receiver.as_ref().is_some_and(|r| r.is_active())
+    }
+}
+
+/// State for the CSV import dialog.
+#[derive(Debug, Clone)]
+pub struct CsvImportState {
+    /// File being imported
+    pub path: PathBuf,
+    /// Parsed CSV content
+    pub table: CsvTable,
+    /// Which column provides each event field
+    pub mapping: CsvColumnMapping,
+    /// Frame rate of the imported log (also used to convert `time_s`)
+    pub target_fps: u32,
+    /// Error from the last import attempt
+    pub error: Option<String>,
+}
+
+impl CsvImportState {
+    /// Create the dialog state for a table, guessing the column mapping.
+    pub fn new(path: PathBuf, table: CsvTable) -> Self {
+        let mapping = CsvColumnMapping::guess(&table.headers);
+        Self {
+            path,
+            table,
+            mapping,
+            target_fps: 60,
+            error: None,
+        }
+    }
+}
+
 /// Main application state and GUI logic.
 pub struct InputLogViewerApp {
     /// Current application state
@@ -452,6 +976,30 @@ pub struct InputLogViewerApp {
     search: SearchState,
     /// Bookmark state for managing frame bookmarks
     bookmarks: BookmarkState,
+    /// Idle gap and dead-input detection state
+    idle: IdleState,
+    /// Per-input statistics panel state
+    stats: StatsState,
+    /// A→B input timing panel state
+    timing: TimingState,
+    /// Log consistency warnings
+    validation: ValidationState,
+    /// Event editing state
+    edit: EditState,
+    /// Mapping profiles and the mapping editor
+    profiles: ProfileState,
+    /// Undo and redo history of user edits
+    history: History,
+    /// Whether the history panel is currently open
+    history_panel_open: bool,
+    /// Event density of the loaded log for the minimap, computed on demand
+    minimap: Option<DensityMap>,
+    /// Live tail state (Some while following the loaded file)
+    follow: Option<FollowState>,
+    /// Live socket stream state
+    stream: StreamState,
+    /// CSV import dialog (Some while the dialog is open)
+    csv_import: Option<CsvImportState>,
     /// Whether to automatically scroll to keep current frame visible during playback
     auto_scroll: bool,
     /// Selection state for frame range selection
@@ -491,6 +1039,17 @@ impl InputLogViewerApp {
             zoom_input_value: 100,
             search: SearchState::new(),
             bookmarks: BookmarkState::new(),
+            idle: IdleState::new(),
+            stats: StatsState::new(),
+            timing: TimingState::new(),
+            validation: ValidationState::new(),
+            edit: EditState::new(),
+            profiles: ProfileState::load(),
+            history: History::default(),
+            history_panel_open: false,
+            follow: None,
+            stream: StreamState::new(),
+            csv_import: None,
             auto_scroll: true,
             selection: SelectionState::new(),
             loop_selection: settings.loop_enabled,
@@ -498,12 +1057,14 @@ impl InputLogViewerApp {
             settings_panel_open: false,
             error_dialog: ErrorDialogState::new(),
             retry_path: None,
+            minimap: None,
         }
     }
 
     /// Handle files dropped onto the application window.
     ///
-    /// Validates the file extension (.ilj or .ilb) and loads the first valid file.
+    /// Checks that a registered format recognizes the file (by content or
+    /// extension) and loads the first valid file.
     /// Multiple dropped files will only load the first one.
     fn handle_dropped_files(&mut self, ctx: &egui::Context) {
         // Only process drops when file operations are allowed
@@ -520,106 +1081,79 @@ impl InputLogViewerApp {
         });
 
         if let Some(path) = dropped_file {
-            // Validate file extension
-            let extension = path
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|s| s.to_lowercase());
-
-            match extension.as_deref() {
-                Some("ilj") | Some("ilb") => {
-                    self.load_file(path);
-                }
-                _ => {
-                    self.show_detailed_error(DetailedAppError::UnsupportedFileType {
-                        path,
-                        expected: vec![".ilj".to_string(), ".ilb".to_string()],
-                    });
-                }
+            let registry = format::registry();
+            if registry.can_open(&path) {
+                self.load_file(path);
+            } else {
+                self.show_detailed_error(registry.unsupported(&path));
             }
         }
     }
 
-    /// Open a file dialog and load the selected input log file (.ilj or .ilb).
+    /// Open a file dialog and load the selected input log file.
+    ///
+    /// Offers one filter per registered format plus "All Files", since the
+    /// format is detected from the content.
     fn open_file_dialog(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("Input Log Files", &["ilj", "ilb"])
-            .add_filter("Input Log JSON", &["ilj"])
-            .add_filter("Input Log Binary", &["ilb"])
-            .set_title("Open Input Log File")
-            .pick_file()
-        {
+        let registry = format::registry();
+        let mut dialog = rfd::FileDialog::new()
+            .add_filter("Input Log Files", &registry.extensions())
+            .set_title("Open Input Log File");
+        for log_format in registry.formats() {
+            dialog = dialog.add_filter(log_format.name(), log_format.extensions());
+        }
+
+        if let Some(path) = dialog.add_filter("All Files", &["*"]).pick_file() {
             self.load_file(path);
         }
     }
 
+    /// Add one save-dialog filter per format that can be written.
+    fn add_save_filters(dialog: rfd::FileDialog, registry: &FormatRegistry) -> rfd::FileDialog {
+        registry
+            .writable_formats()
+            .fold(dialog, |dialog, log_format| {
+                dialog.add_filter(log_format.name(), log_format.extensions())
+            })
+    }
+
     /// Load an input log file from the given path.
     ///
-    /// Auto-detects the file format based on extension:
-    /// - `.ilj` files are parsed as JSON
-    /// - `.ilb` files are parsed as binary
+    /// The format is detected by the format registry from the file content,
+    /// falling back to the extension.
     fn load_file(&mut self, path: PathBuf) {
+        let lenient = self.settings.lenient_json;
+        self.load_file_with(path, lenient);
+    }
+
+    /// Load an input log file, optionally recovering from bad entries.
+    ///
+    /// # Arguments
+    /// * `path` - The file to load
+    /// * `lenient` - Skip or repair bad JSON entries and load the complete
+    ///   events of a truncated binary file, reporting them as warnings
+    fn load_file_with(&mut self, path: PathBuf, lenient: bool) {
         // Store the path for retry functionality
         self.retry_path = Some(path.clone());
+        // Loading a file replaces whatever was being followed or streamed
+        self.follow = None;
 
-        // Determine format from extension
-        let extension = path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .map(|s| s.to_lowercase());
-
-        // First check for unsupported extension
-        if !matches!(extension.as_deref(), Some("ilj") | Some("ilb")) {
-            self.show_detailed_error(DetailedAppError::UnsupportedFileType {
-                path,
-                expected: vec![".ilj".to_string(), ".ilb".to_string()],
-            });
-            return;
-        }
-
-        // Try to read and parse the file
-        let parse_result: Result<InputLog, DetailedAppError> = match extension.as_deref() {
-            Some("ilj") => {
-                // JSON format - read as string
-                match std::fs::read_to_string(&path) {
-                    Ok(content) => match parser::parse_json(&content) {
-                        Ok(log) => Ok(log),
-                        Err(e) => Err(error::from_parse_error(Some(path.clone()), &e)),
-                    },
-                    Err(e) => Err(error::from_io_error(path.clone(), e)),
-                }
-            }
-            Some("ilb") => {
-                // Binary format - read as bytes
-                match std::fs::read(&path) {
-                    Ok(data) => match parser::parse_binary(&data) {
-                        Ok(log) => Ok(log),
-                        Err(e) => Err(error::from_parse_error(Some(path.clone()), &e)),
-                    },
-                    Err(e) => Err(error::from_io_error(path.clone(), e)),
-                }
-            }
-            _ => unreachable!(), // Already handled above
+        // Read and parse the file in whichever format it is written in
+        let registry = format::registry();
+        let parse_result = if lenient {
+            registry.load_lenient(&path)
+        } else {
+            registry.load(&path).map(|log| (log, Vec::new()))
         };
 
         match parse_result {
-            Ok(log) => {
+            Ok((log, load_warnings)) => {
                 let frame_count = log.metadata.frame_count;
                 let event_count = log.events.len();
-                // Initialize filter with all inputs visible
-                self.filter.initialize_from_log(&log);
-                // Reset search state for new file
-                self.search.reset();
-                // Reset bookmarks for new file
-                self.bookmarks.reset();
-                // Reset selection state for new file
-                self.selection.reset();
-                self.loop_selection = false;
-                // Clear playback range when loading new file
-                self.playback.clear_range();
-                self.log = Some(log);
+                self.stream.receiver = None;
+                self.stream.log_from_stream = false;
+                self.install_log(log, &load_warnings);
                 self.loaded_file_path = Some(path.clone());
-                self.state = AppState::Ready;
                 // Clear retry path on success
                 self.retry_path = None;
 
@@ -628,17 +1162,21 @@ impl InputLogViewerApp {
                 // Save settings to persist recent files (ignore errors silently)
                 let _ = self.settings.save();
 
-                self.status_message = Some(StatusMessage::new(
-                    format!(
-                        "Loaded: {} ({} frames, {} events)",
-                        path.file_name()
-                            .map(|n| n.to_string_lossy().to_string())
-                            .unwrap_or_else(|| "file".to_string()),
-                        frame_count,
-                        event_count
-                    ),
-                    StatusKind::Success,
-                ));
+                let mut message = format!(
+                    "Loaded: {} ({} frames, {} events)",
+                    path.file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "file".to_string()),
+                    frame_count,
+                    event_count
+                );
+                let profile_name = |index: usize| &self.profiles.store.profiles[index].name;
+                if let Some(index) = self.profiles.active {
+                    message.push_str(&format!(", profile '{}' applied", profile_name(index)));
+                } else if let Some(index) = self.profiles.suggested {
+                    message.push_str(&format!(", profile '{}' available", profile_name(index)));
+                }
+                self.status_message = Some(StatusMessage::new(message, StatusKind::Success));
             }
             Err(e) => {
                 self.show_detailed_error(e);
@@ -646,6 +1184,45 @@ impl InputLogViewerApp {
         }
     }
 
+    /// Make `log` the current log and reset all per-log state.
+    fn install_log(&mut self, mut log: InputLog, load_warnings: &[DetailedAppError]) {
+        // Label the inputs with the profile matching the log's source
+        self.profiles
+            .on_log_loaded(&mut log, self.settings.apply_profiles_on_load);
+        // Initialize filter with all inputs visible
+        self.filter.initialize_from_log(&log);
+        // Reset search state for new file
+        self.search.reset();
+        // Reset bookmarks for new file
+        self.bookmarks.reset();
+        // Re-run idle detection if the panel is in use, otherwise drop stale results
+        self.idle.reset();
+        if self.idle.panel_open {
+            self.idle.analyze(&log);
+        }
+        // Drop cached statistics for the previous file
+        self.stats.reset();
+        self.minimap = None;
+        self.timing.reset();
+        // Check the new log for consistency problems
+        self.validation.run(&log, load_warnings);
+        // A new file has no edits yet
+        self.edit.reset();
+        self.history.clear();
+        // Reset selection state for new file
+        self.selection.reset();
+        self.loop_selection = false;
+        // Clear playback range when loading new file
+        self.playback.clear_range();
+        self.playback.set_frame(0, log.metadata.frame_count);
+        self.timeline_config.scroll_offset = 0;
+        // Rows are laid out like the applied profile says
+        self.timeline_config.layout = self.profiles.active_layout();
+        self.timeline_config.row_scroll = 0.0;
+        self.log = Some(log);
+        self.state = AppState::Ready;
+    }
+
     /// Show a detailed error in the error dialog.
     fn show_detailed_error(&mut self, error: DetailedAppError) {
         let brief = error.brief_description();
@@ -668,266 +1245,2455 @@ impl InputLogViewerApp {
             self.state = AppState::NoFileLoaded;
         }
     }
-}
 
-impl eframe::App for InputLogViewerApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Handle playback advancement when playing
-        if self.state.is_playing() {
-            if let Some(ref log) = self.log {
-                let target_fps = log.metadata.target_fps;
-                let total_frames = log.metadata.frame_count;
+    /// Start following the loaded file.
+    ///
+    /// The file is loaded again through a `LogTail`, which accepts a partially
+    /// written last event, and the view jumps to the newest frame.
+    fn start_follow(&mut self) {
+        let Some(path) = self.loaded_file_path.clone() else {
+            return;
+        };
 
-                if self.playback.should_advance(target_fps) {
-                    let should_continue = self.playback.advance(total_frames);
-                    if !should_continue {
-                        // Playback ended (loop disabled and reached end)
-                        self.state = AppState::Ready;
-                    }
-                    // Auto-scroll to keep current frame visible (if enabled)
-                    if self.auto_scroll {
-                        self.ensure_frame_visible(total_frames);
-                    }
-                }
+        match LogTail::open(&path) {
+            Ok((tail, mut log)) => {
+                self.profiles.reapply(&mut log);
+                self.filter.initialize_from_log(&log);
+                self.log = Some(log);
+                // The file is read again, so unsaved edits are gone
+                self.edit.reset();
+                self.edit.enabled = false;
+                self.history.clear();
+                self.status_message = Some(StatusMessage::new(
+                    format!("Following {}", tail.path().display()),
+                    StatusKind::Success,
+                ));
+                self.follow = Some(FollowState::new(tail));
+                self.on_log_grew();
             }
-            // Keep requesting repaints while playing
-            ctx.request_repaint();
+            Err(e) => self.show_detailed_error(e),
         }
+    }
 
-        // Handle drag and drop file loading
-        self.handle_dropped_files(ctx);
+    /// Stop following the loaded file, keeping the events read so far.
+    fn stop_follow(&mut self) {
+        if self.follow.take().is_some() {
+            self.status_message =
+                Some(StatusMessage::new("Stopped following", StatusKind::Success));
+        }
+    }
 
-        let total_frames = self
-            .log
-            .as_ref()
-            .map(|l| l.metadata.frame_count)
-            .unwrap_or(0);
-
-        // Handle keyboard shortcuts
-        if let Some(action) = self.handle_keyboard_shortcuts(ctx) {
-            self.handle_control_action(action, total_frames);
+    /// Poll the followed file and append new events to the log.
+    fn poll_follow(&mut self) {
+        let (Some(follow), Some(log)) = (self.follow.as_mut(), self.log.as_mut()) else {
+            return;
+        };
+        if !follow.poll_due(Instant::now()) {
+            return;
         }
 
-        // Sync playback and log state with timeline config for rendering
-        self.timeline_config.current_frame = self.playback.current_frame;
-        self.timeline_config.total_frames = total_frames;
-
-        self.render_toolbar(ctx);
-        self.render_controls(ctx);
-        self.render_timeline(ctx);
+        match follow.tail.poll(log) {
+            Ok(TailUpdate::Unchanged) => {}
+            Ok(TailUpdate::Appended { new_ids, .. }) => {
+                if !new_ids.is_empty() {
+                    self.profiles.reapply(log);
+                }
+                self.filter.show_new_inputs(&new_ids);
+                self.on_log_grew();
+            }
+            Ok(TailUpdate::Reloaded) => {
+                self.profiles.reapply(log);
+                self.filter.initialize_from_log(log);
+                self.bookmarks.reset();
+                self.selection.reset();
+                self.history.clear();
+                self.playback.clear_range();
+                self.on_log_grew();
+            }
+            Err(e) => {
+                self.follow = None;
+                self.show_detailed_error(e);
+            }
+        }
+    }
 
-        // Render drag and drop overlay when files are being hovered
-        self.render_drag_overlay(ctx);
+    /// Start listening for a live stream on the endpoint entered in the panel.
+    ///
+    /// The current log is replaced by an empty one that fills up as events
+    /// arrive.
+    fn start_stream(&mut self) {
+        let receiver = Endpoint::parse(&self.stream.endpoint_input)
+            .and_then(|endpoint| StreamReceiver::listen(&endpoint));
+
+        match receiver {
+            Ok(receiver) => {
+                self.follow = None;
+                self.status_message = Some(StatusMessage::new(
+                    format!("Listening on {}", receiver.endpoint()),
+                    StatusKind::Success,
+                ));
+                self.stream.receiver = Some(receiver);
+                self.stream.log_from_stream = true;
+                self.install_log(InputLog::default(), &[]);
+                self.loaded_file_path = None;
+            }
+            Err(e) => {
+                self.status_message = Some(StatusMessage::new(
+                    format!("Cannot listen: {}", e),
+                    StatusKind::Error,
+                ));
+            }
+        }
+    }
 
-        // Render error dialog (on top of everything else)
-        self.render_error_dialog(ctx);
+    /// Stop receiving, keeping the events received so far.
+    fn stop_stream(&mut self) {
+        if self.stream.receiver.take().is_some() {
+            self.status_message =
+                Some(StatusMessage::new("Stopped listening", StatusKind::Success));
+        }
     }
 
-    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        // Save current settings (loop state, speed, etc.) on exit
-        self.settings.loop_enabled = self.loop_selection;
-        self.settings.set_default_speed(self.playback.speed);
+    /// Move events received from the stream into the log.
+    fn poll_stream(&mut self) {
+        let (Some(receiver), Some(log)) = (self.stream.receiver.as_mut(), self.log.as_mut()) else {
+            return;
+        };
 
-        // Save settings to disk (ignore errors on exit)
-        let _ = self.settings.save();
-    }
-}
+        let previous_status = receiver.status().clone();
+        let update = receiver.poll(log);
+        let status = receiver.status().clone();
+        let event_count = log.events.len();
 
-impl InputLogViewerApp {
-    /// Handle keyboard shortcuts for playback control.
-    ///
-    /// Returns an action if a keyboard shortcut was triggered, None otherwise.
-    /// Shortcuts only work when a file is loaded (controls_enabled).
-    fn handle_keyboard_shortcuts(&mut self, ctx: &egui::Context) -> Option<ControlAction> {
-        // Handle Ctrl+F to open search dialog (works when toolbar is enabled)
-        if self.state.toolbar_enabled() {
-            let open_search = ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::F));
-            if open_search {
-                self.search.dialog_open = true;
-                return None;
-            }
+        if update.events > 0 || !update.new_ids.is_empty() {
+            self.filter.show_new_inputs(&update.new_ids);
+            self.on_log_grew();
         }
 
-        // Only process playback shortcuts when controls are enabled
-        if !self.state.controls_enabled() {
-            return None;
+        if status != previous_status {
+            self.status_message = Some(match status {
+                StreamStatus::Listening => return,
+                StreamStatus::Connected => {
+                    StatusMessage::new("Stream sender connected", StatusKind::Success)
+                }
+                StreamStatus::Finished => StatusMessage::new(
+                    format!("Stream finished ({} events)", event_count),
+                    StatusKind::Success,
+                ),
+                StreamStatus::Failed(reason) => {
+                    StatusMessage::new(format!("Stream failed: {}", reason), StatusKind::Error)
+                }
+            });
         }
+    }
 
-        ctx.input(|i| {
-            // Space: Toggle play/pause
-            if i.key_pressed(egui::Key::Space) {
-                return Some(ControlAction::TogglePlayPause);
-            }
+    /// Save the log recorded from a stream to a file chosen by the user.
+    fn save_stream_dialog(&mut self) {
+        let Some(ref log) = self.log else {
+            return;
+        };
 
-            // Left Arrow: Previous frame
-            if i.key_pressed(egui::Key::ArrowLeft) {
-                return Some(ControlAction::PreviousFrame);
-            }
+        let registry = format::registry();
+        let Some(path) = Self::add_save_filters(rfd::FileDialog::new(), registry)
+            .set_title("Save Stream")
+            .set_file_name("stream.ilj")
+            .save_file()
+        else {
+            return;
+        };
 
-            // Right Arrow: Next frame
-            if i.key_pressed(egui::Key::ArrowRight) {
-                return Some(ControlAction::NextFrame);
+        match registry.save(log, &path, &self.settings.write_options()) {
+            Ok(()) => {
+                self.status_message = Some(StatusMessage::new(
+                    format!("Saved {} events to {}", log.events.len(), path.display()),
+                    StatusKind::Success,
+                ));
             }
+            Err(e) => self.show_detailed_error(e),
+        }
+    }
 
-            // Home: Jump to first frame
-            if i.key_pressed(egui::Key::Home) {
-                return Some(ControlAction::GoToStart);
-            }
+    /// Pick a CSV file and open the import dialog for it.
+    fn open_csv_import_dialog(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_title("Import CSV")
+            .pick_file()
+        else {
+            return;
+        };
 
-            // End: Jump to last frame
-            if i.key_pressed(egui::Key::End) {
-                return Some(ControlAction::GoToEnd);
-            }
+        let table = std::fs::read_to_string(&path)
+            .map_err(|e| detailed_error::from_io_error(path.clone(), e))
+            .and_then(|content| {
+                csv_io::read_csv(&content)
+                    .map_err(|e| detailed_error::from_parse_error(Some(path.clone()), &e))
+            });
 
-            // B: Toggle bookmark at current frame
-            if i.key_pressed(egui::Key::B) {
-                return Some(ControlAction::ToggleBookmark);
-            }
+        match table {
+            Ok(table) => self.csv_import = Some(CsvImportState::new(path, table)),
+            Err(e) => self.show_detailed_error(e),
+        }
+    }
 
-            // Plus (+/=): Increase playback speed
-            if i.key_pressed(egui::Key::Plus) || i.key_pressed(egui::Key::Equals) {
-                return Some(ControlAction::IncreaseSpeed);
-            }
+    /// Import the CSV file of the open import dialog with its column mapping.
+    fn import_csv(&mut self) {
+        let Some(ref mut import) = self.csv_import else {
+            return;
+        };
 
-            // Minus (-): Decrease playback speed
-            if i.key_pressed(egui::Key::Minus) {
-                return Some(ControlAction::DecreaseSpeed);
-            }
+        match csv_io::import_csv(&import.table, &import.mapping, import.target_fps) {
+            Ok(log) => {
+                let file_name = import
+                    .path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "file".to_string());
+                let event_count = log.events.len();
 
-            // Escape: Clear selection
-            if i.key_pressed(egui::Key::Escape) {
-                return Some(ControlAction::ClearSelection);
+                self.csv_import = None;
+                self.follow = None;
+                self.stream.receiver = None;
+                self.stream.log_from_stream = false;
+                self.install_log(log, &[]);
+                self.loaded_file_path = None;
+                self.status_message = Some(StatusMessage::new(
+                    format!("Imported {} events from {}", event_count, file_name),
+                    StatusKind::Success,
+                ));
             }
+            Err(e) => import.error = Some(e.to_string()),
+        }
+    }
 
+    /// Export the visible events (optionally only the selection) to a CSV file.
+    fn export_csv_dialog(&mut self, selection_only: bool) {
+        let Some(ref log) = self.log else {
+            return;
+        };
+        let range = if selection_only {
+            self.selection.get_selection()
+        } else {
             None
-        })
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_title("Export Events as CSV")
+            .set_file_name("events.csv")
+            .save_file()
+        else {
+            return;
+        };
+
+        let csv = csv_io::export_csv(log, &self.filter, range);
+        self.status_message = Some(match std::fs::write(&path, csv) {
+            Ok(()) => StatusMessage::new(
+                format!("Exported events to {}", path.display()),
+                StatusKind::Success,
+            ),
+            Err(e) => {
+                StatusMessage::new(format!("Failed to export events: {}", e), StatusKind::Error)
+            }
+        });
     }
 
-    /// Render the top toolbar section.
+    /// Refresh derived state after events were appended to a followed log.
     ///
-    /// Contains file loading, filter options, and search functionality.
-    fn render_toolbar(&mut self, ctx: &egui::Context) {
-        let can_open = self.state.can_open_file();
-        let toolbar_enabled = self.state.toolbar_enabled();
-
-        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                ui.heading("Input Log Viewer");
-                ui.separator();
+    /// Cached analysis results are recomputed and, when auto-scroll is on,
+    /// the timeline scrolls to the newest frame.
+    fn on_log_grew(&mut self) {
+        let Some(ref log) = self.log else {
+            return;
+        };
+        let total_frames = log.metadata.frame_count;
 
-                // File loading button (enabled based on state)
-                ui.add_enabled_ui(can_open, |ui| {
-                    if ui.button("📂 Open File").clicked() {
-                        self.open_file_dialog();
-                    }
-                });
+        self.stats.invalidate();
+        self.timing.invalidate();
+        self.minimap = None;
+        if self.idle.report.is_some() {
+            self.idle.analyze(log);
+        }
 
-                ui.separator();
+        if self.auto_scroll {
+            // Don't fight the playhead while playing back
+            if !self.state.is_playing() {
+                self.playback
+                    .set_frame(total_frames.saturating_sub(1), total_frames);
+            }
+            self.timeline_config.scroll_offset =
+                total_frames.saturating_sub(self.timeline_config.visible_frames);
+        }
+    }
 
-                // Filter dropdown button (enabled only when file is loaded)
-                ui.add_enabled_ui(toolbar_enabled, |ui| {
-                    let filter_button_text = if self.filter_popup_open {
-                        "Filter ▲"
-                    } else {
-                        "Filter ▼"
-                    };
-                    let filter_response = ui.button(filter_button_text);
-                    if filter_response.clicked() {
-                        self.filter_popup_open = !self.filter_popup_open;
-                    }
+    /// Edit the events of an input on a clicked frame.
+    ///
+    /// Inputs with button states are toggled between up and down right away;
+    /// for analog inputs the cell is selected so the editor can change its value.
+    fn edit_cell(&mut self, id: u32, frame: u64) {
+        let Some(ref log) = self.log else {
+            return;
+        };
+        let kind = edit::input_kind(log, id).unwrap_or(InputKind::Button);
 
-                    // Show filter count indicator (using effective mappings to include unmapped IDs)
-                    if let Some(ref log) = self.log {
-                        let visible_count = self.filter.visible_ids.len();
-                        let total_count = log.get_effective_mappings().len();
-                        if visible_count < total_count {
-                            ui.label(format!("({}/{})", visible_count, total_count));
-                        }
-                    }
-                });
+        self.edit.cell = Some((id, frame));
+        self.edit.contact = edit::contact_at(log, id, frame);
+        if kind.has_button_state() {
+            let change = edit::toggle_button(log, id, kind, self.edit.contact, frame);
+            self.apply_event_change(change);
+        } else {
+            self.edit.value = edit::value_at(log, id, frame).unwrap_or_default();
+        }
+    }
 
-                ui.separator();
+    /// Apply an edit to the loaded log and mark it as modified.
+    fn apply_event_change(&mut self, change: EventChange) {
+        let Some(ref mut log) = self.log else {
+            return;
+        };
+        if change.is_noop() {
+            return;
+        }
+        change.apply(log);
+        self.edit.dirty = true;
+        self.on_log_edited();
+        self.history.push(Command::EditEvents(change));
+    }
 
-                // Search button (enabled only when file is loaded)
-                ui.add_enabled_ui(toolbar_enabled, |ui| {
-                    if ui.button("🔍 Search").clicked() {
-                        self.search.dialog_open = !self.search.dialog_open;
-                    }
-                });
+    /// Save the timeline's row layout in the active mapping profile.
+    fn persist_layout(&mut self) {
+        match self.profiles.save_layout(&self.timeline_config.layout) {
+            Ok(true) => {}
+            Ok(false) => {
+                self.status_message = Some(StatusMessage::new(
+                    "Row layout is kept for this session; save a mapping profile to keep it",
+                    StatusKind::Info,
+                ));
+            }
+            Err(e) => {
+                self.status_message = Some(StatusMessage::new(
+                    format!("Failed to save profiles: {}", e),
+                    StatusKind::Error,
+                ));
+            }
+        }
+    }
 
-                ui.separator();
+    /// Move a timeline row in front of another one, or to the end.
+    fn move_row(&mut self, id: u32, before: Option<u32>) {
+        let Some(ref log) = self.log else {
+            return;
+        };
+        let ids: Vec<u32> = log.get_effective_mappings().iter().map(|m| m.id).collect();
+        self.timeline_config.layout.move_row(&ids, id, before);
+        self.persist_layout();
+    }
 
-                // Bookmarks button (enabled only when file is loaded)
-                ui.add_enabled_ui(toolbar_enabled, |ui| {
-                    let bookmarks_button_text = if self.bookmarks.panel_open {
-                        "★ Bookmarks ▲"
-                    } else {
-                        "★ Bookmarks ▼"
-                    };
-                    if ui.button(bookmarks_button_text).clicked() {
-                        self.bookmarks.panel_open = !self.bookmarks.panel_open;
-                    }
+    /// Put an input into a named group, or take it out of its group.
+    ///
+    /// The group is stored in the log's mapping and, if a profile is active,
+    /// in the profile as well.
+    fn set_row_group(&mut self, id: u32, group: Option<String>) {
+        let Some(ref mut log) = self.log else {
+            return;
+        };
+        let index = match log.mappings.iter().position(|m| m.id == id) {
+            Some(index) => index,
+            None => {
+                log.mappings.push(parser::default_mapping(id));
+                log.mappings.sort_by_key(|m| m.id);
+                log.mappings
+                    .iter()
+                    .position(|m| m.id == id)
+                    .expect("mapping just added")
+            }
+        };
+        log.mappings[index].group = group;
+        let mapping = log.mappings[index].clone();
+        let kind = edit::input_kind(log, id);
+        self.profiles.set_group(&mapping, kind);
+        self.persist_profiles();
+    }
 
-                    // Show bookmark count indicator
-                    if !self.bookmarks.is_empty() {
-                        ui.label(format!("({})", self.bookmarks.bookmarks.len()));
-                    }
-                });
+    /// Remove a named group, leaving its inputs ungrouped.
+    fn ungroup(&mut self, name: &str) {
+        let Some(ref mut log) = self.log else {
+            return;
+        };
+        let ids: Vec<u32> = log
+            .mappings
+            .iter()
+            .filter(|m| m.group.as_deref() == Some(name))
+            .map(|m| m.id)
+            .collect();
+        for id in ids {
+            self.set_row_group(id, None);
+        }
+        if self.timeline_config.layout.collapsed.remove(name) {
+            self.persist_layout();
+        }
+    }
 
-                ui.separator();
+    /// Save the profiles if a profile is active, reporting failures.
+    fn persist_profiles(&mut self) {
+        if self.profiles.active.is_none() {
+            return;
+        }
+        if let Err(e) = self.profiles.store.save() {
+            self.status_message = Some(StatusMessage::new(
+                format!("Failed to save profiles: {}", e),
+                StatusKind::Error,
+            ));
+        }
+    }
 
-                // Settings button (always available)
+    /// Render the row height controls of the timeline.
+    ///
+    /// # Returns
+    /// Whether a setting was changed (a slider only once it is let go).
+    fn render_row_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let rows = &mut self.settings.rows;
+        let mut changed = false;
+        for density in RowDensity::ALL {
+            changed |= ui
+                .radio_value(&mut rows.density, density, density.label())
+                .changed();
+        }
+        ui.add_enabled_ui(rows.density == RowDensity::Normal, |ui| {
+            let response = ui
+                .add(
+                    egui::Slider::new(&mut rows.row_height, MIN_ROW_HEIGHT..=MAX_ROW_HEIGHT)
+                        .suffix(" px")
+                        .text("Row height"),
+                )
+                .on_hover_text("Height of the input rows; compact and fit-all set their own");
+            changed |= response.drag_stopped() || (response.changed() && !response.dragged());
+        });
+        if !self.timeline_config.layout.row_scales.is_empty()
+            && ui
+                .button("Reset row heights")
+                .on_hover_text("Give rows made taller or shorter the normal height again")
+                .clicked()
+        {
+            // Row heights belong to the layout, which is saved with the profile
+            self.timeline_config.layout.row_scales.clear();
+            self.persist_layout();
+        }
+        changed
+    }
+
+    /// Render the contents of the "Normalize" toolbar menu.
+    fn render_normalize_menu(&mut self, ui: &mut egui::Ui) {
+        if let Some(ref log) = self.log {
+            let current = match HoldEncoding::detect(log) {
+                HoldEncoding::Dense => "dense (Held every frame)",
+                HoldEncoding::Sparse => "sparse (edges only)",
+            };
+            ui.weak(format!("Holds are currently {}", current));
+            ui.separator();
+        }
+        if ui
+            .button("Sparse holds (edges only)")
+            .on_hover_text("Keep only the Pressed and Released events of held buttons")
+            .clicked()
+        {
+            ui.close();
+            self.normalize_log(Some(HoldEncoding::Sparse), false);
+        }
+        if ui
+            .button("Dense holds (Held every frame)")
+            .on_hover_text("Add a Held event on every frame a button is down")
+            .clicked()
+        {
+            ui.close();
+            self.normalize_log(Some(HoldEncoding::Dense), false);
+        }
+        ui.separator();
+        if ui
+            .button("Drop repeated axis samples")
+            .on_hover_text("Remove axis and trigger samples that repeat the previous value")
+            .clicked()
+        {
+            ui.close();
+            self.normalize_log(None, true);
+        }
+    }
+
+    /// Normalize the events of the log and record the result as one command.
+    ///
+    /// # Arguments
+    /// * `holds` - Encoding to convert held buttons to, if any
+    /// * `dedup_axes` - Whether to drop axis samples that repeat the previous value
+    fn normalize_log(&mut self, holds: Option<HoldEncoding>, dedup_axes: bool) {
+        let Some(ref mut log) = self.log else {
+            return;
+        };
+        let changes = normalize(log, holds, dedup_axes);
+        if changes.is_empty() {
+            self.status_message = Some(StatusMessage::new(
+                "Log is already normalized".to_string(),
+                StatusKind::Success,
+            ));
+            return;
+        }
+
+        let before = log.events.len();
+        for change in &changes {
+            change.apply(log);
+        }
+        let after = log.events.len();
+        let label = match (holds, dedup_axes) {
+            (Some(HoldEncoding::Dense), _) => "Normalize to dense holds",
+            (Some(HoldEncoding::Sparse), _) => "Normalize to sparse holds",
+            (None, _) => "Drop repeated axis samples",
+        };
+        self.edit.dirty = true;
+        self.on_log_edited();
+        self.status_message = Some(StatusMessage::new(
+            format!(
+                "{}: {} inputs changed, {} → {} events",
+                label,
+                changes.len(),
+                before,
+                after
+            ),
+            StatusKind::Success,
+        ));
+        self.history.push(Command::EditLog {
+            label: label.to_string(),
+            changes,
+        });
+    }
+
+    /// Refresh derived state after the events of the log were edited.
+    fn on_log_edited(&mut self) {
+        let Some(ref log) = self.log else {
+            return;
+        };
+        self.stats.invalidate();
+        self.timing.invalidate();
+        self.minimap = None;
+        if self.idle.report.is_some() {
+            self.idle.analyze(log);
+        }
+        self.validation.revalidate(log);
+    }
+
+    // ========================================================================
+    // Undo / redo
+    // ========================================================================
+
+    /// Undo the most recent command.
+    fn undo(&mut self) {
+        let Some(command) = self.history.undo() else {
+            return;
+        };
+        self.apply_command(&command);
+        if let Some(undone) = self.history.redo_stack().last() {
+            self.status_message = Some(StatusMessage::new(
+                format!("Undo: {}", undone.describe()),
+                StatusKind::Success,
+            ));
+        }
+    }
+
+    /// Redo the most recently undone command.
+    fn redo(&mut self) {
+        let Some(command) = self.history.redo() else {
+            return;
+        };
+        self.apply_command(&command);
+        self.status_message = Some(StatusMessage::new(
+            format!("Redo: {}", command.describe()),
+            StatusKind::Success,
+        ));
+    }
+
+    /// Apply a command taken from the history, without recording it again.
+    fn apply_command(&mut self, command: &Command) {
+        match command {
+            Command::AddBookmark(_)
+            | Command::RemoveBookmark(_)
+            | Command::RenameBookmark { .. }
+            | Command::ReplaceBookmarks { .. } => {
+                self.bookmarks.apply_command(command);
+            }
+            Command::ChangeFilter { after, .. } => {
+                self.filter = (**after).clone();
+            }
+            Command::ChangeSelection { after, .. } => {
+                self.set_selection_range(*after);
+            }
+            Command::EditEvents(change) => {
+                if let Some(ref mut log) = self.log {
+                    change.apply(log);
+                    self.edit.dirty = true;
+                    self.on_log_edited();
+                }
+            }
+            Command::EditLog { changes, .. } => {
+                if let Some(ref mut log) = self.log {
+                    for change in changes {
+                        change.apply(log);
+                    }
+                    self.edit.dirty = true;
+                    self.on_log_edited();
+                }
+            }
+        }
+    }
+
+    /// Select a frame range (or clear the selection) and loop playback in it.
+    fn set_selection_range(&mut self, range: Option<(u64, u64)>) {
+        match range {
+            Some((start, end)) => {
+                self.selection.set_selection(start, end);
+                self.loop_selection = true;
+                self.playback.set_range(Some(start), Some(end));
+            }
+            None => {
+                self.selection.clear();
+                self.loop_selection = false;
+                self.playback.clear_range();
+            }
+        }
+    }
+
+    /// Record a selection change in the history if the selection changed.
+    fn record_selection_change(&mut self, before: Option<(u64, u64)>) {
+        let after = self.selection.get_selection();
+        if before != after {
+            self.history
+                .push(Command::ChangeSelection { before, after });
+        }
+    }
+
+    /// Save the edited log back to its file, or ask for a file if it has none.
+    ///
+    /// Files in a format that cannot be written (e.g. CSV imports) are saved
+    /// through the Save As dialog instead.
+    fn save_log(&mut self) {
+        let registry = format::registry();
+        let writable = self.loaded_file_path.as_ref().filter(|path| {
+            registry
+                .by_extension(path)
+                .is_some_and(|log_format| log_format.can_write())
+        });
+        match writable.cloned() {
+            Some(path) => self.save_log_to(path),
+            None => self.save_log_as_dialog(),
+        }
+    }
+
+    /// Ask for a file and save the log to it.
+    fn save_log_as_dialog(&mut self) {
+        let file_name = self
+            .loaded_file_path
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .map(|stem| format!("{}.ilj", stem.to_string_lossy()))
+            .unwrap_or_else(|| "edited.ilj".to_string());
+        let Some(path) = Self::add_save_filters(rfd::FileDialog::new(), format::registry())
+            .set_title("Save Log")
+            .set_file_name(file_name)
+            .save_file()
+        else {
+            return;
+        };
+        self.save_log_to(path);
+    }
+
+    /// Write the selected frames to a new log file.
+    fn export_selection_dialog(&mut self) {
+        let (Some(log), Some((start, end))) = (self.log.as_ref(), self.selection.get_selection())
+        else {
+            return;
+        };
+        let sliced = match splice::slice(log, start, end) {
+            Ok(sliced) => sliced,
+            Err(e) => {
+                self.status_message = Some(StatusMessage::new(
+                    format!("Failed to export selection: {}", e),
+                    StatusKind::Error,
+                ));
+                return;
+            }
+        };
+
+        let stem = self
+            .loaded_file_path
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "log".to_string());
+        let Some(path) = Self::add_save_filters(rfd::FileDialog::new(), format::registry())
+            .set_title("Export Selection")
+            .set_file_name(format!("{}_F{}-F{}.ilj", stem, start, end))
+            .save_file()
+        else {
+            return;
+        };
+
+        match format::registry().save(&sliced, &path, &self.settings.write_options()) {
+            Ok(()) => {
+                self.status_message = Some(StatusMessage::new(
+                    format!(
+                        "Exported frames {}-{} to {}",
+                        start,
+                        start + sliced.metadata.frame_count - 1,
+                        path.display()
+                    ),
+                    StatusKind::Success,
+                ));
+            }
+            Err(e) => self.show_detailed_error(e),
+        }
+    }
+
+    /// Write the log to a file and clear the modified state.
+    fn save_log_to(&mut self, path: PathBuf) {
+        let Some(ref log) = self.log else {
+            return;
+        };
+        match format::registry().save(log, &path, &self.settings.write_options()) {
+            Ok(()) => {
+                self.status_message = Some(StatusMessage::new(
+                    format!("Saved {} events to {}", log.events.len(), path.display()),
+                    StatusKind::Success,
+                ));
+                self.edit.dirty = false;
+                self.loaded_file_path = Some(path);
+            }
+            Err(e) => self.show_detailed_error(e),
+        }
+    }
+}
+
+impl eframe::App for InputLogViewerApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Handle playback advancement when playing
+        if self.state.is_playing() {
+            if let Some(ref log) = self.log {
+                let total_frames = log.metadata.frame_count;
+                // Follow recorded frame durations; without timestamps this is 1 / target_fps
+                let frame_duration = Duration::from_micros(
+                    log.effective_frame_duration_us(self.playback.current_frame),
+                );
+
+                if self
+                    .playback
+                    .should_advance_after(frame_duration, Instant::now())
+                {
+                    let should_continue = self.playback.advance(total_frames);
+                    if !should_continue {
+                        // Playback ended (loop disabled and reached end)
+                        self.state = AppState::Ready;
+                    }
+                    // Auto-scroll to keep current frame visible (if enabled)
+                    if self.auto_scroll {
+                        self.ensure_frame_visible(total_frames);
+                    }
+                }
+            }
+            // Keep requesting repaints while playing
+            ctx.request_repaint();
+        }
+
+        // Pick up events appended to a followed file
+        if self.follow.is_some() {
+            self.poll_follow();
+            ctx.request_repaint_after(FOLLOW_POLL_INTERVAL);
+        }
+
+        // Pick up events received from a live stream
+        if self.stream.receiver.is_some() {
+            self.poll_stream();
+            if self.stream.is_active() {
+                ctx.request_repaint_after(STREAM_POLL_INTERVAL);
+            }
+        }
+
+        // Handle drag and drop file loading
+        self.handle_dropped_files(ctx);
+
+        let total_frames = self
+            .log
+            .as_ref()
+            .map(|l| l.metadata.frame_count)
+            .unwrap_or(0);
+
+        // Handle keyboard shortcuts
+        if let Some(action) = self.handle_keyboard_shortcuts(ctx) {
+            self.handle_control_action(action, total_frames);
+        }
+
+        // Sync playback and log state with timeline config for rendering
+        self.timeline_config.current_frame = self.playback.current_frame;
+        self.timeline_config.total_frames = total_frames;
+
+        self.render_toolbar(ctx);
+        self.render_controls(ctx);
+        self.render_timeline(ctx);
+
+        // Render drag and drop overlay when files are being hovered
+        self.render_drag_overlay(ctx);
+
+        // Render CSV import dialog if open
+        if self.csv_import.is_some() {
+            self.render_csv_import_dialog(ctx);
+        }
+
+        // Render error dialog (on top of everything else)
+        self.render_error_dialog(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // Save current settings (loop state, speed, etc.) on exit
+        self.settings.loop_enabled = self.loop_selection;
+        self.settings.set_default_speed(self.playback.speed);
+
+        // Save settings to disk (ignore errors on exit)
+        let _ = self.settings.save();
+    }
+}
+
+impl InputLogViewerApp {
+    /// Handle keyboard shortcuts for playback control.
+    ///
+    /// Returns an action if a keyboard shortcut was triggered, None otherwise.
+    /// Shortcuts only work when a file is loaded (controls_enabled).
+    fn handle_keyboard_shortcuts(&mut self, ctx: &egui::Context) -> Option<ControlAction> {
+        // Handle Ctrl+F to open search dialog (works when toolbar is enabled)
+        if self.state.toolbar_enabled() {
+            let open_search = ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::F));
+            if open_search {
+                self.search.dialog_open = true;
+                return None;
+            }
+
+            // Ctrl+S: save edits
+            let save = ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::S));
+            if save && self.edit.dirty {
+                self.save_log();
+                return None;
+            }
+
+            // Ctrl+Z: undo, Ctrl+Shift+Z: redo (text fields keep their own undo)
+            if !ctx.wants_keyboard_input() {
+                let (undo, redo) = ctx.input(|i| {
+                    let z = i.modifiers.ctrl && i.key_pressed(egui::Key::Z);
+                    (z && !i.modifiers.shift, z && i.modifiers.shift)
+                });
+                if undo {
+                    self.undo();
+                    return None;
+                }
+                if redo {
+                    self.redo();
+                    return None;
+                }
+            }
+        }
+
+        // Only process playback shortcuts when controls are enabled
+        if !self.state.controls_enabled() {
+            return None;
+        }
+
+        ctx.input(|i| {
+            // Space: Toggle play/pause
+            if i.key_pressed(egui::Key::Space) {
+                return Some(ControlAction::TogglePlayPause);
+            }
+
+            // Left Arrow: Previous frame
+            if i.key_pressed(egui::Key::ArrowLeft) {
+                return Some(ControlAction::PreviousFrame);
+            }
+
+            // Right Arrow: Next frame
+            if i.key_pressed(egui::Key::ArrowRight) {
+                return Some(ControlAction::NextFrame);
+            }
+
+            // Home: Jump to first frame
+            if i.key_pressed(egui::Key::Home) {
+                return Some(ControlAction::GoToStart);
+            }
+
+            // End: Jump to last frame
+            if i.key_pressed(egui::Key::End) {
+                return Some(ControlAction::GoToEnd);
+            }
+
+            // B: Toggle bookmark at current frame
+            if i.key_pressed(egui::Key::B) {
+                return Some(ControlAction::ToggleBookmark);
+            }
+
+            // Plus (+/=): Increase playback speed
+            if i.key_pressed(egui::Key::Plus) || i.key_pressed(egui::Key::Equals) {
+                return Some(ControlAction::IncreaseSpeed);
+            }
+
+            // Minus (-): Decrease playback speed
+            if i.key_pressed(egui::Key::Minus) {
+                return Some(ControlAction::DecreaseSpeed);
+            }
+
+            // Escape: Clear selection
+            if i.key_pressed(egui::Key::Escape) {
+                return Some(ControlAction::ClearSelection);
+            }
+
+            None
+        })
+    }
+
+    /// Render the top toolbar section.
+    ///
+    /// Contains file loading, filter options, and search functionality.
+    fn render_toolbar(&mut self, ctx: &egui::Context) {
+        let can_open = self.state.can_open_file();
+        let toolbar_enabled = self.state.toolbar_enabled();
+
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Input Log Viewer");
+                ui.separator();
+
+                // File loading button (enabled based on state)
+                ui.add_enabled_ui(can_open, |ui| {
+                    if ui.button("📂 Open File").clicked() {
+                        self.open_file_dialog();
+                    }
+
+                    // Live stream panel (receives logs over a local socket)
+                    let stream_button_text = if self.stream.panel_open {
+                        "🔌 Stream ▲"
+                    } else {
+                        "🔌 Stream ▼"
+                    };
+                    if ui.button(stream_button_text).clicked() {
+                        self.stream.panel_open = !self.stream.panel_open;
+                    }
+                });
+
+                // CSV import/export menu
+                let has_selection = self.selection.has_selection();
+                ui.menu_button("📄 CSV", |ui| {
+                    if ui
+                        .add_enabled(can_open, egui::Button::new("Import…"))
+                        .clicked()
+                    {
+                        ui.close();
+                        self.open_csv_import_dialog();
+                    }
+                    ui.separator();
+                    if ui
+                        .add_enabled(toolbar_enabled, egui::Button::new("Export visible events…"))
+                        .on_hover_text("Events of inputs shown by the current filter")
+                        .clicked()
+                    {
+                        ui.close();
+                        self.export_csv_dialog(false);
+                    }
+                    if ui
+                        .add_enabled(
+                            toolbar_enabled && has_selection,
+                            egui::Button::new("Export selection…"),
+                        )
+                        .clicked()
+                    {
+                        ui.close();
+                        self.export_csv_dialog(true);
+                    }
+                });
+
+                // Mapping profiles menu
+                ui.add_enabled_ui(toolbar_enabled, |ui| {
+                    ui.menu_button("🏷 Profiles", |ui| {
+                        self.render_profiles_menu(ui);
+                    });
+                });
+
+                ui.separator();
+
+                // Filter dropdown button (enabled only when file is loaded)
+                ui.add_enabled_ui(toolbar_enabled, |ui| {
+                    let filter_button_text = if self.filter_popup_open {
+                        "Filter ▲"
+                    } else {
+                        "Filter ▼"
+                    };
+                    let filter_response = ui.button(filter_button_text);
+                    if filter_response.clicked() {
+                        self.filter_popup_open = !self.filter_popup_open;
+                    }
+
+                    // Show filter count indicator (using effective mappings to include unmapped IDs)
+                    if let Some(ref log) = self.log {
+                        let visible_count = self.filter.visible_ids.len();
+                        let total_count = log.get_effective_mappings().len();
+                        if visible_count < total_count {
+                            ui.label(format!("({}/{})", visible_count, total_count));
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                // Search button (enabled only when file is loaded)
+                ui.add_enabled_ui(toolbar_enabled, |ui| {
+                    if ui.button("🔍 Search").clicked() {
+                        self.search.dialog_open = !self.search.dialog_open;
+                    }
+                });
+
+                ui.separator();
+
+                // Bookmarks button (enabled only when file is loaded)
+                ui.add_enabled_ui(toolbar_enabled, |ui| {
+                    let bookmarks_button_text = if self.bookmarks.panel_open {
+                        "★ Bookmarks ▲"
+                    } else {
+                        "★ Bookmarks ▼"
+                    };
+                    if ui.button(bookmarks_button_text).clicked() {
+                        self.bookmarks.panel_open = !self.bookmarks.panel_open;
+                    }
+
+                    // Show bookmark count indicator
+                    if !self.bookmarks.is_empty() {
+                        ui.label(format!("({})", self.bookmarks.bookmarks.len()));
+                    }
+                });
+
+                ui.separator();
+
+                // Edit mode toggle and save (not while the file is followed)
+                ui.add_enabled_ui(toolbar_enabled && self.follow.is_none(), |ui| {
+                    if ui
+                        .selectable_label(self.edit.enabled, "✏ Edit")
+                        .on_hover_text("Click timeline cells to add, remove or change events")
+                        .clicked()
+                    {
+                        self.edit.enabled = !self.edit.enabled;
+                        self.edit.cell = None;
+                    }
+                    if ui
+                        .add_enabled(self.edit.dirty, egui::Button::new("💾 Save"))
+                        .on_hover_text("Save the edited log (Ctrl+S)")
+                        .clicked()
+                    {
+                        self.save_log();
+                    }
+                    ui.menu_button("Normalize", |ui| {
+                        self.render_normalize_menu(ui);
+                    });
+                });
+
+                // Write the selected frames as a new log
+                let can_export = toolbar_enabled && self.selection.has_selection();
+                if ui
+                    .add_enabled(can_export, egui::Button::new("✂ Export selection as…"))
+                    .on_hover_text("Save the selected frames as a new log starting at frame 0")
+                    .clicked()
+                {
+                    self.export_selection_dialog();
+                }
+
+                ui.separator();
+
+                // Row height and density of the timeline
+                ui.menu_button("↕ Rows", |ui| {
+                    if self.render_row_settings(ui) {
+                        let _ = self.settings.save();
+                    }
+                });
+
+                ui.separator();
+
+                // Undo / redo and the history panel
+                ui.add_enabled_ui(toolbar_enabled, |ui| {
+                    if ui
+                        .add_enabled(self.history.can_undo(), egui::Button::new("↶"))
+                        .on_hover_text("Undo (Ctrl+Z)")
+                        .clicked()
+                    {
+                        self.undo();
+                    }
+                    if ui
+                        .add_enabled(self.history.can_redo(), egui::Button::new("↷"))
+                        .on_hover_text("Redo (Ctrl+Shift+Z)")
+                        .clicked()
+                    {
+                        self.redo();
+                    }
+                    let history_button_text = if self.history_panel_open {
+                        "History ▲"
+                    } else {
+                        "History ▼"
+                    };
+                    if ui.button(history_button_text).clicked() {
+                        self.history_panel_open = !self.history_panel_open;
+                    }
+                });
+
+                ui.separator();
+
+                // Follow toggle (enabled only when a file was loaded from disk)
+                ui.add_enabled_ui(toolbar_enabled && self.loaded_file_path.is_some(), |ui| {
+                    let following = self.follow.is_some();
+                    if ui
+                        .selectable_label(following, "📡 Follow")
+                        .on_hover_text("Watch the file and append events as they are written")
+                        .clicked()
+                    {
+                        if following {
+                            self.stop_follow();
+                        } else {
+                            self.start_follow();
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                // Warnings button (only shown when the log has diagnostics)
+                if toolbar_enabled && !self.validation.diagnostics.is_empty() {
+                    let count = self.validation.diagnostics.len();
+                    let warnings_button_text = if self.validation.panel_open {
+                        format!("⚠ Warnings ({}) ▲", count)
+                    } else {
+                        format!("⚠ Warnings ({}) ▼", count)
+                    };
+                    if ui.button(warnings_button_text).clicked() {
+                        self.validation.panel_open = !self.validation.panel_open;
+                    }
+
+                    ui.separator();
+                }
+
+                // Statistics button (enabled only when file is loaded)
+                ui.add_enabled_ui(toolbar_enabled, |ui| {
+                    let stats_button_text = if self.stats.panel_open {
+                        "📊 Stats ▲"
+                    } else {
+                        "📊 Stats ▼"
+                    };
+                    if ui.button(stats_button_text).clicked() {
+                        self.stats.panel_open = !self.stats.panel_open;
+                    }
+                });
+
+                ui.separator();
+
+                // Timing analysis button (enabled only when file is loaded)
+                ui.add_enabled_ui(toolbar_enabled, |ui| {
+                    let timing_button_text = if self.timing.panel_open {
+                        "⏱ Timing ▲"
+                    } else {
+                        "⏱ Timing ▼"
+                    };
+                    if ui.button(timing_button_text).clicked() {
+                        self.timing.panel_open = !self.timing.panel_open;
+                    }
+                });
+
+                ui.separator();
+
+                // Idle detection button (enabled only when file is loaded)
+                ui.add_enabled_ui(toolbar_enabled, |ui| {
+                    let idle_button_text = if self.idle.panel_open {
+                        "⏸ Idle ▲"
+                    } else {
+                        "⏸ Idle ▼"
+                    };
+                    if ui.button(idle_button_text).clicked() {
+                        self.idle.panel_open = !self.idle.panel_open;
+                        // Run the analysis on first open so results are shown immediately
+                        if self.idle.panel_open
+                            && self.idle.report.is_none()
+                            && let Some(ref log) = self.log
+                        {
+                            self.idle.analyze(log);
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                // Settings button (always available)
                 let settings_button_text = if self.settings_panel_open {
                     "⚙ Settings ▲"
                 } else {
-                    "⚙ Settings ▼"
-                };
-                if ui.button(settings_button_text).clicked() {
-                    self.settings_panel_open = !self.settings_panel_open;
+                    "⚙ Settings ▼"
+                };
+                if ui.button(settings_button_text).clicked() {
+                    self.settings_panel_open = !self.settings_panel_open;
+                }
+
+                // Show status message in toolbar (right-aligned)
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    self.render_status_message(ui);
+                });
+            });
+        });
+
+        // Render filter popup panel if open
+        if self.filter_popup_open && toolbar_enabled {
+            self.render_filter_popup(ctx);
+        }
+
+        // Render search dialog if open
+        if self.search.dialog_open && toolbar_enabled {
+            self.render_search_dialog(ctx);
+        }
+
+        // Render the event editor for the selected cell in edit mode
+        if self.edit.enabled && self.edit.cell.is_some() && toolbar_enabled {
+            self.render_edit_panel(ctx);
+        }
+
+        // Render bookmarks panel if open
+        if self.bookmarks.panel_open && toolbar_enabled {
+            self.render_bookmarks_panel(ctx);
+        }
+
+        // Render mapping editor if open
+        if self.profiles.editor_open && toolbar_enabled {
+            self.render_mapping_editor(ctx);
+        }
+
+        // Render undo history panel if open
+        if self.history_panel_open && toolbar_enabled {
+            self.render_history_panel(ctx);
+        }
+
+        // Render warnings panel if open
+        if self.validation.panel_open && toolbar_enabled {
+            self.render_warnings_panel(ctx);
+        }
+
+        // Render statistics panel if open
+        if self.stats.panel_open && toolbar_enabled {
+            self.render_stats_panel(ctx);
+        }
+
+        // Render timing analysis panel if open
+        if self.timing.panel_open && toolbar_enabled {
+            self.render_timing_panel(ctx);
+        }
+
+        // Render idle detection panel if open
+        if self.idle.panel_open && toolbar_enabled {
+            self.render_idle_panel(ctx);
+        }
+
+        // Render live stream panel if open
+        if self.stream.panel_open {
+            self.render_stream_panel(ctx);
+        }
+
+        // Render settings panel if open
+        if self.settings_panel_open {
+            self.render_settings_panel(ctx);
+        }
+    }
+
+    /// Render the CSV import dialog with its column mapping.
+    fn render_csv_import_dialog(&mut self, ctx: &egui::Context) {
+        let mut should_close = false;
+        let mut do_import = false;
+
+        let Some(ref mut import) = self.csv_import else {
+            return;
+        };
+        let file_name = import
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        egui::Window::new("Import CSV")
+            .id(egui::Id::new("csv_import_dialog"))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                // Header with close button
+                ui.horizontal(|ui| {
+                    ui.heading("📄 Import CSV");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+                ui.label(format!(
+                    "{} ({} rows, {} columns)",
+                    file_name,
+                    import.table.rows.len(),
+                    import.table.headers.len()
+                ));
+                ui.separator();
+
+                // Column mapping
+                ui.label("Columns:");
+                egui::Grid::new("csv_mapping_grid")
+                    .num_columns(2)
+                    .spacing([10.0, 4.0])
+                    .show(ui, |ui| {
+                        for field in CsvField::ALL {
+                            ui.label(field.column_name());
+                            let mut column = import.mapping.get(field);
+                            let selected_text = column
+                                .and_then(|c| import.table.headers.get(c))
+                                .map(String::as_str)
+                                .unwrap_or("(none)");
+                            egui::ComboBox::from_id_salt(("csv_column", field as usize))
+                                .selected_text(selected_text)
+                                .width(180.0)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut column, None, "(none)");
+                                    for (i, header) in import.table.headers.iter().enumerate() {
+                                        ui.selectable_value(&mut column, Some(i), header);
+                                    }
+                                });
+                            if column != import.mapping.get(field) {
+                                import.mapping.set(field, column);
+                                import.error = None;
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.small("Needs frame or time_s, and id or name. Kind defaults to Button.");
+
+                ui.horizontal(|ui| {
+                    ui.label("Target FPS:");
+                    ui.add(egui::DragValue::new(&mut import.target_fps).range(1..=1000));
+                });
+
+                // Preview of the first rows
+                ui.separator();
+                ui.label("Preview:");
+                egui::ScrollArea::both().max_height(120.0).show(ui, |ui| {
+                    egui::Grid::new("csv_preview_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for header in &import.table.headers {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+                            for row in import.table.rows.iter().take(5) {
+                                for field in &row.fields {
+                                    ui.label(field);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+
+                if let Some(ref error) = import.error {
+                    ui.separator();
+                    ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            import.mapping.validate().is_ok(),
+                            egui::Button::new("Import"),
+                        )
+                        .clicked()
+                    {
+                        do_import = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        should_close = true;
+                    }
+                });
+            });
+
+        if should_close {
+            self.csv_import = None;
+        } else if do_import {
+            self.import_csv();
+        }
+    }
+
+    /// Render the live stream panel window.
+    fn render_stream_panel(&mut self, ctx: &egui::Context) {
+        let mut should_close = false;
+        let mut start = false;
+        let mut stop = false;
+        let mut save = false;
+
+        let receiving = self.stream.receiver.is_some();
+        let event_count = self.log.as_ref().map(|l| l.events.len()).unwrap_or(0);
+
+        egui::Window::new("Live Stream")
+            .id(egui::Id::new("stream_panel"))
+            .collapsible(false)
+            .resizable(false)
+            .default_width(320.0)
+            .anchor(egui::Align2::LEFT_TOP, egui::vec2(10.0, 80.0))
+            .show(ctx, |ui| {
+                // Header with close button
+                ui.horizontal(|ui| {
+                    ui.heading("🔌 Live Stream");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Endpoint:");
+                    ui.add_enabled(
+                        !receiving,
+                        egui::TextEdit::singleline(&mut self.stream.endpoint_input)
+                            .hint_text(stream::DEFAULT_ENDPOINT)
+                            .desired_width(180.0),
+                    )
+                    .on_hover_text("PORT, HOST:PORT on localhost, or unix:PATH");
+                });
+
+                ui.horizontal(|ui| {
+                    if receiving {
+                        if ui.button("⏹ Stop").clicked() {
+                            stop = true;
+                        }
+                    } else if ui.button("▶ Listen").clicked() {
+                        start = true;
+                    }
+                });
+
+                if let Some(ref receiver) = self.stream.receiver {
+                    ui.label(format!(
+                        "{}: {}",
+                        receiver.endpoint(),
+                        receiver.status().label()
+                    ));
+                }
+
+                if self.stream.log_from_stream {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} events received", event_count));
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui
+                                .add_enabled(event_count > 0, egui::Button::new("💾 Save As…"))
+                                .clicked()
+                            {
+                                save = true;
+                            }
+                        });
+                    });
+                }
+            });
+
+        if should_close {
+            self.stream.panel_open = false;
+        }
+        if start {
+            self.start_stream();
+        }
+        if stop {
+            self.stop_stream();
+        }
+        if save {
+            self.save_stream_dialog();
+        }
+    }
+
+    /// Render the filter popup panel.
+    fn render_filter_popup(&mut self, ctx: &egui::Context) {
+        let mut should_close = false;
+        let filter_before = self.filter.clone();
+
+        egui::Window::new("Input Filter")
+            .id(egui::Id::new("filter_popup"))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(220.0)
+            .anchor(egui::Align2::LEFT_TOP, egui::vec2(160.0, 40.0))
+            .show(ctx, |ui| {
+                // Close button
+                ui.horizontal(|ui| {
+                    ui.heading("Filter Inputs");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+                ui.separator();
+
+                if let Some(ref log) = self.log {
+                    // Input type filters
+                    ui.label("Input Types:");
+
+                    if !log.annotations.is_empty() {
+                        ui.checkbox(
+                            &mut self.filter.show_annotations,
+                            "Event and telemetry tracks",
+                        );
+                        ui.add_space(4.0);
+                    }
+
+                    // Type checkboxes - prevent unchecking the last enabled type
+                    for kind in InputKind::ALL {
+                        let can_toggle = self.filter.can_disable_type(kind);
+                        ui.add_enabled_ui(can_toggle, |ui| {
+                            ui.checkbox(
+                                self.filter.type_flag_mut(kind),
+                                crate::core::writer::input_kind_name(kind),
+                            );
+                        });
+                    }
+
+                    ui.separator();
+
+                    // Player checkboxes hide or show all inputs of a player at once
+                    let players = log.players();
+                    if !players.is_empty() {
+                        ui.label("Players:");
+                        ui.horizontal_wrapped(|ui| {
+                            for player in players {
+                                let mut is_visible = self.filter.is_player_visible(player);
+                                if ui.checkbox(&mut is_visible, player_label(player)).changed() {
+                                    self.filter.set_player_visible(player, is_visible);
+                                }
+                            }
+                        });
+                        ui.separator();
+                    }
+
+                    // Individual input checkboxes (using effective mappings to include unmapped IDs)
+                    ui.label("Inputs:");
+                    let effective_mappings = log.get_effective_mappings();
+                    egui::ScrollArea::vertical()
+                        .max_height(250.0)
+                        .show(ui, |ui| {
+                            for mapping in &effective_mappings {
+                                let mut is_visible = self.filter.visible_ids.contains(&mapping.id);
+
+                                // Show color indicator if available
+                                ui.horizontal(|ui| {
+                                    if let Some(color) = mapping.color {
+                                        let color32 =
+                                            egui::Color32::from_rgb(color[0], color[1], color[2]);
+                                        let (rect, _) = ui.allocate_exact_size(
+                                            egui::vec2(12.0, 12.0),
+                                            egui::Sense::hover(),
+                                        );
+                                        ui.painter().rect_filled(rect, 2.0, color32);
+                                    }
+                                    if ui
+                                        .checkbox(&mut is_visible, mapping.qualified_name())
+                                        .changed()
+                                    {
+                                        self.filter.set_id_visible(mapping.id, is_visible);
+                                    }
+                                });
+                            }
+                        });
+
+                    ui.separator();
+
+                    // Select All / Deselect All buttons
+                    ui.horizontal(|ui| {
+                        if ui.button("All").clicked() {
+                            self.filter.select_all(&effective_mappings);
+                        }
+                        if ui.button("None").clicked() {
+                            self.filter.deselect_all();
+                        }
+                    });
+                } else {
+                    ui.label("No file loaded");
+                }
+            });
+
+        if should_close {
+            self.filter_popup_open = false;
+        }
+
+        if self.filter != filter_before {
+            self.history.push(Command::ChangeFilter {
+                before: Box::new(filter_before),
+                after: Box::new(self.filter.clone()),
+            });
+        }
+    }
+
+    /// Render the search dialog window.
+    fn render_search_dialog(&mut self, ctx: &egui::Context) {
+        let mut should_close = false;
+        let mut seek_to_frame: Option<u64> = None;
+        let mut perform_search = false;
+        let mut perform_annotation_search = false;
+
+        egui::Window::new("Search Input")
+            .id(egui::Id::new("search_dialog"))
+            .collapsible(false)
+            .resizable(false)
+            .default_width(280.0)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .show(ctx, |ui| {
+                // Close button
+                ui.horizontal(|ui| {
+                    ui.heading("Search Input");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+                ui.separator();
+
+                if let Some(ref log) = self.log {
+                    // Get effective mappings to include unmapped IDs
+                    let effective_mappings = log.get_effective_mappings();
+
+                    // Input selector dropdown
+                    ui.horizontal(|ui| {
+                        ui.label("Input:");
+                        ui.add_space(10.0);
+                        let selected_name = if self.search.selected_input_index
+                            < effective_mappings.len()
+                        {
+                            effective_mappings[self.search.selected_input_index].qualified_name()
+                        } else {
+                            "Select input...".to_string()
+                        };
+
+                        egui::ComboBox::from_id_salt("search_input_combo")
+                            .selected_text(selected_name)
+                            .width(160.0)
+                            .show_ui(ui, |ui| {
+                                for (i, mapping) in effective_mappings.iter().enumerate() {
+                                    if ui
+                                        .selectable_label(
+                                            self.search.selected_input_index == i,
+                                            mapping.qualified_name(),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.search.selected_input_index = i;
+                                        // Clear previous results when selection changes
+                                        self.search.clear_results();
+                                    }
+                                }
+                            });
+                    });
+
+                    ui.add_space(4.0);
+
+                    // Get the kind of the selected input to determine if state filter applies
+                    let selected_kind = if self.search.selected_input_index
+                        < effective_mappings.len()
+                    {
+                        let mapping_id = effective_mappings[self.search.selected_input_index].id;
+                        log.events
+                            .iter()
+                            .find(|e| e.id == mapping_id)
+                            .map(|e| e.kind)
+                            .unwrap_or(InputKind::Button)
+                    } else {
+                        InputKind::Button
+                    };
+
+                    // State selector dropdown (only for Button type)
+                    let is_button = selected_kind == InputKind::Button;
+                    ui.add_enabled_ui(is_button, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("State:");
+                            ui.add_space(10.0);
+                            let state_options = ["Any", "Pressed", "Released"];
+                            let selected_state = state_options
+                                .get(self.search.selected_state_index)
+                                .unwrap_or(&"Any");
+
+                            egui::ComboBox::from_id_salt("search_state_combo")
+                                .selected_text(*selected_state)
+                                .width(160.0)
+                                .show_ui(ui, |ui| {
+                                    for (i, state) in state_options.iter().enumerate() {
+                                        if ui
+                                            .selectable_label(
+                                                self.search.selected_state_index == i,
+                                                *state,
+                                            )
+                                            .clicked()
+                                        {
+                                            self.search.selected_state_index = i;
+                                            // Clear previous results when selection changes
+                                            self.search.clear_results();
+                                        }
+                                    }
+                                });
+                        });
+                    });
+
+                    ui.add_space(8.0);
+
+                    // Search button
+                    ui.horizontal(|ui| {
+                        if ui.button("Search").clicked() {
+                            perform_search = true;
+                        }
+                        if self.search.has_searched && ui.button("Clear").clicked() {
+                            self.search.clear_results();
+                        }
+                    });
+
+                    // Game events and telemetry channels are searched by label
+                    if !log.annotations.is_empty() {
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            ui.label("Event:");
+                            ui.add_space(6.0);
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.search.annotation_text)
+                                    .hint_text("e.g. checkpoint")
+                                    .desired_width(120.0),
+                            );
+                            let submitted = response.lost_focus()
+                                && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                            if ui.button("Find").clicked() || submitted {
+                                perform_annotation_search = true;
+                            }
+                        });
+                    }
+
+                    ui.separator();
+
+                    // Results section
+                    if self.search.has_searched {
+                        if self.search.results.is_empty() {
+                            ui.label("No matches found");
+                        } else {
+                            let count = self.search.results.count();
+                            let current = self.search.results.current_position().unwrap_or(0);
+                            ui.label(format!("Results: {} / {} matches", current, count));
+
+                            ui.add_space(4.0);
+
+                            // Navigation buttons
+                            ui.horizontal(|ui| {
+                                if ui.button("< Prev").clicked()
+                                    && let Some(frame) = self.search.results.prev()
+                                {
+                                    seek_to_frame = Some(frame);
+                                }
+                                if ui.button("Next >").clicked()
+                                    && let Some(frame) = self.search.results.next()
+                                {
+                                    seek_to_frame = Some(frame);
+                                }
+                            });
+
+                            // Show current match frame
+                            if let Some(frame) = self.search.results.current_frame() {
+                                ui.add_space(4.0);
+                                ui.label(format!("Current: Frame {}", frame));
+                            }
+                        }
+                    }
+                } else {
+                    ui.label("No file loaded");
+                }
+            });
+
+        if should_close {
+            self.search.dialog_open = false;
+            // Clear search results when closing dialog to remove highlights
+            self.search.clear_results();
+        }
+
+        // Perform search if requested
+        if perform_search {
+            self.perform_search();
+        }
+        if perform_annotation_search {
+            self.perform_annotation_search();
+        }
+
+        // Seek to frame if navigation was used
+        if let Some(frame) = seek_to_frame {
+            let total_frames = self
+                .log
+                .as_ref()
+                .map(|l| l.metadata.frame_count)
+                .unwrap_or(0);
+            self.playback.set_frame(frame, total_frames);
+        }
+    }
+
+    /// Render the editor for the selected timeline cell.
+    fn render_edit_panel(&mut self, ctx: &egui::Context) {
+        let (Some(log), Some((id, frame))) = (self.log.as_ref(), self.edit.cell) else {
+            return;
+        };
+        let kind = edit::input_kind(log, id).unwrap_or(InputKind::Button);
+        let name = log.get_input_name(id);
+        let has_event = log.events.iter().any(|e| e.id == id && e.frame == frame);
+        let contact = self.edit.contact;
+        let is_down = kind.has_button_state() && edit::is_down_at(log, id, contact, frame);
+
+        let mut should_close = false;
+        let mut change: Option<EventChange> = None;
+
+        egui::Window::new("Edit Event")
+            .id(egui::Id::new("edit_event_panel"))
+            .collapsible(false)
+            .resizable(false)
+            .default_width(240.0)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 60.0))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading("Edit Event");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+                ui.separator();
+
+                ui.label(format!("{} · Frame {}", name, frame));
+                ui.add_space(4.0);
+
+                if kind == InputKind::Touch {
+                    ui.horizontal(|ui| {
+                        ui.label("Contact:");
+                        ui.add(egui::DragValue::new(&mut self.edit.contact));
+                    });
+                }
+                if kind.has_button_state() {
+                    ui.label(if is_down { "State: down" } else { "State: up" });
+                    ui.horizontal(|ui| {
+                        let toggle_text = if is_down { "Release" } else { "Press" };
+                        if ui.button(toggle_text).clicked() {
+                            change = Some(edit::toggle_button(log, id, kind, contact, frame));
+                        }
+                        if ui
+                            .add_enabled(is_down, egui::Button::new("Delete press"))
+                            .clicked()
+                        {
+                            change = Some(edit::remove_press(log, id, kind, contact, frame));
+                        }
+                    });
+                    ui.label(
+                        egui::RichText::new("Click cells to extend or shorten a hold")
+                            .small()
+                            .color(self.settings.colors.text_dim_color()),
+                    );
+                } else {
+                    let range = match kind {
+                        InputKind::Trigger => Some(0.0..=1.0),
+                        InputKind::Axis1D | InputKind::Axis2D => Some(-1.0..=1.0),
+                        _ => None,
+                    };
+                    let components = if kind == InputKind::Axis1D || kind == InputKind::Trigger {
+                        1
+                    } else {
+                        2
+                    };
+                    for (i, label) in ["X:", "Y:"].into_iter().take(components).enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(label);
+                            let mut drag = egui::DragValue::new(&mut self.edit.value[i]);
+                            drag = match range.clone() {
+                                Some(range) => drag.range(range).speed(0.01),
+                                None => drag.speed(1.0),
+                            };
+                            ui.add(drag);
+                        });
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Set value").clicked() {
+                            change = Some(edit::set_value(log, id, kind, frame, self.edit.value));
+                        }
+                        if ui
+                            .add_enabled(has_event, egui::Button::new("Remove event"))
+                            .clicked()
+                        {
+                            change = Some(edit::remove_events_at(log, id, frame));
+                        }
+                    });
+                }
+            });
+
+        if let Some(change) = change {
+            self.apply_event_change(change);
+        }
+        if should_close {
+            self.edit.cell = None;
+        }
+    }
+
+    /// Render the bookmarks panel window.
+    fn render_bookmarks_panel(&mut self, ctx: &egui::Context) {
+        let mut should_close = false;
+        let mut seek_to_frame: Option<u64> = None;
+        let mut remove_index: Option<usize> = None;
+        let mut add_bookmark_at_current = false;
+
+        let total_frames = self
+            .log
+            .as_ref()
+            .map(|l| l.metadata.frame_count)
+            .unwrap_or(0);
+        let current_frame = self.playback.current_frame;
+        let current_frame_color = self.settings.colors.current_frame_color();
+
+        egui::Window::new("Bookmarks")
+            .id(egui::Id::new("bookmarks_panel"))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(320.0)
+            .default_height(300.0)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 40.0))
+            .show(ctx, |ui| {
+                // Header with close button
+                ui.horizontal(|ui| {
+                    ui.heading("★ Bookmarks");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+                ui.separator();
+
+                // Add bookmark section
+                ui.horizontal(|ui| {
+                    ui.label("Label:");
+                    let response = ui.text_edit_singleline(&mut self.bookmarks.label_input);
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        add_bookmark_at_current = true;
+                    }
+                    if ui.button("Add at F").clicked() {
+                        add_bookmark_at_current = true;
+                    }
+                    ui.label(format!("{}", current_frame));
+                });
+
+                ui.add_space(4.0);
+
+                // Quick info
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "Current frame: {} | Total bookmarks: {}",
+                        current_frame,
+                        self.bookmarks.bookmarks.len()
+                    ));
+                });
+
+                ui.separator();
+
+                // Bookmark list
+                if self.bookmarks.is_empty() {
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(20.0);
+                        ui.label("No bookmarks yet.");
+                        ui.label("Press 'B' or click 'Add' to create one.");
+                        ui.add_space(20.0);
+                    });
+                } else {
+                    let mut start_editing_index: Option<usize> = None;
+                    let mut finish_editing = false;
+                    let mut cancel_editing = false;
+
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for i in 0..self.bookmarks.bookmarks.len() {
+                                // Copy needed data to avoid borrow issues
+                                let frame = self.bookmarks.bookmarks[i].frame;
+                                let label = self.bookmarks.bookmarks[i].label.clone();
+                                let is_current = frame == current_frame;
+                                let is_editing = self.bookmarks.editing_index == Some(i);
+
+                                ui.horizontal(|ui| {
+                                    // Frame indicator (clickable to jump)
+                                    let frame_text = format!("★ F{}", frame);
+                                    let frame_button = if is_current {
+                                        egui::Button::new(
+                                            egui::RichText::new(&frame_text)
+                                                .color(current_frame_color)
+                                                .strong(),
+                                        )
+                                    } else {
+                                        egui::Button::new(&frame_text)
+                                    };
+
+                                    if ui
+                                        .add(frame_button)
+                                        .on_hover_text("Click to jump")
+                                        .clicked()
+                                    {
+                                        seek_to_frame = Some(frame);
+                                    }
+
+                                    ui.separator();
+
+                                    // Label display/edit
+                                    if is_editing {
+                                        // Editing mode
+                                        let response = ui.text_edit_singleline(
+                                            &mut self.bookmarks.editing_label,
+                                        );
+                                        if response.lost_focus() {
+                                            if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                                finish_editing = true;
+                                            } else if ui.input(|i| i.key_pressed(egui::Key::Escape))
+                                            {
+                                                cancel_editing = true;
+                                            }
+                                        }
+                                        if ui.button("✓").on_hover_text("Save").clicked() {
+                                            finish_editing = true;
+                                        }
+                                        if ui.button("✕").on_hover_text("Cancel").clicked() {
+                                            cancel_editing = true;
+                                        }
+                                    } else {
+                                        // Display mode
+                                        let label_text = label.as_deref().unwrap_or("(no label)");
+                                        let label_color = if label.is_some() {
+                                            egui::Color32::LIGHT_GRAY
+                                        } else {
+                                            egui::Color32::DARK_GRAY
+                                        };
+                                        ui.label(
+                                            egui::RichText::new(label_text).color(label_color),
+                                        );
+
+                                        ui.with_layout(
+                                            egui::Layout::right_to_left(egui::Align::Center),
+                                            |ui| {
+                                                // Delete button
+                                                if ui
+                                                    .button("🗑")
+                                                    .on_hover_text("Delete bookmark")
+                                                    .clicked()
+                                                {
+                                                    remove_index = Some(i);
+                                                }
+                                                // Edit button
+                                                if ui
+                                                    .button("✏")
+                                                    .on_hover_text("Edit label")
+                                                    .clicked()
+                                                {
+                                                    start_editing_index = Some(i);
+                                                }
+                                            },
+                                        );
+                                    }
+                                });
+
+                                ui.add_space(2.0);
+                            }
+                        });
+
+                    // Apply deferred actions
+                    if let Some(idx) = start_editing_index {
+                        self.bookmarks.start_editing(idx);
+                    }
+                    if finish_editing
+                        && let Some(index) = self.bookmarks.editing_index
+                        && let Some(old) = self.bookmarks.get_bookmark(index).cloned()
+                        && self.bookmarks.finish_editing()
+                        && let Some(new) = self.bookmarks.get_bookmark(index)
+                        && new.label != old.label
+                    {
+                        self.history.push(Command::RenameBookmark {
+                            frame: old.frame,
+                            before: old.label,
+                            after: new.label.clone(),
+                        });
+                    }
+                    if cancel_editing {
+                        self.bookmarks.cancel_editing();
+                    }
                 }
 
-                // Show status message in toolbar (right-aligned)
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    self.render_status_message(ui);
+                ui.separator();
+
+                // Bottom actions
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!self.bookmarks.is_empty(), egui::Button::new("Clear All"))
+                        .clicked()
+                    {
+                        let before = std::mem::take(&mut self.bookmarks.bookmarks);
+                        self.history.push(Command::ReplaceBookmarks {
+                            before,
+                            after: Vec::new(),
+                        });
+                        self.status_message = Some(StatusMessage::new(
+                            "All bookmarks cleared",
+                            StatusKind::Success,
+                        ));
+                    }
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label("Tip: Press 'B' to quickly toggle bookmarks");
+                    });
+                });
+            });
+
+        if should_close {
+            self.bookmarks.panel_open = false;
+        }
+
+        // Handle bookmark addition
+        if add_bookmark_at_current {
+            let label = if self.bookmarks.label_input.is_empty() {
+                None
+            } else {
+                Some(std::mem::take(&mut self.bookmarks.label_input))
+            };
+            if self.bookmarks.add_bookmark(current_frame, label.clone()) {
+                self.history.push(Command::AddBookmark(Bookmark {
+                    frame: current_frame,
+                    label,
+                }));
+                self.status_message = Some(StatusMessage::new(
+                    format!("Bookmark added at frame {}", current_frame),
+                    StatusKind::Success,
+                ));
+            } else {
+                self.status_message = Some(StatusMessage::new(
+                    format!("Bookmark already exists at frame {}", current_frame),
+                    StatusKind::Error,
+                ));
+            }
+        }
+
+        // Handle bookmark removal
+        if let Some(index) = remove_index
+            && let Some(bookmark) = self.bookmarks.get_bookmark(index).cloned()
+            && let Some(frame) = self.bookmarks.remove_bookmark_by_index(index)
+        {
+            self.history.push(Command::RemoveBookmark(bookmark));
+            self.status_message = Some(StatusMessage::new(
+                format!("Bookmark removed at frame {}", frame),
+                StatusKind::Success,
+            ));
+        }
+
+        // Handle seeking
+        if let Some(frame) = seek_to_frame {
+            self.playback.set_frame(frame, total_frames);
+        }
+    }
+
+    /// Render the contents of the mapping profiles menu.
+    fn render_profiles_menu(&mut self, ui: &mut egui::Ui) {
+        let mut apply_index: Option<usize> = None;
+
+        if self.profiles.store.profiles.is_empty() {
+            ui.label("No saved profiles");
+        }
+        for (index, profile) in self.profiles.store.profiles.iter().enumerate() {
+            let mut text = profile.name.clone();
+            if self.profiles.suggested == Some(index) {
+                text.push_str("  ★ matches source");
+            }
+            let response = ui.selectable_label(self.profiles.active == Some(index), text);
+            let response = match &profile.source {
+                Some(source) => response.on_hover_text(format!("Source: {}", source)),
+                None => response,
+            };
+            if response.clicked() {
+                apply_index = Some(index);
+                ui.close();
+            }
+        }
+
+        ui.separator();
+        if ui.button("Edit mappings…").clicked() {
+            if let Some(ref log) = self.log {
+                self.profiles.open_editor(log);
+            }
+            ui.close();
+        }
+        if ui
+            .checkbox(
+                &mut self.settings.apply_profiles_on_load,
+                "Apply matching profile on load",
+            )
+            .changed()
+        {
+            let _ = self.settings.save();
+        }
+
+        if let Some(index) = apply_index
+            && let Some(ref mut log) = self.log
+            && let Some(report) = self.profiles.apply(index, log)
+        {
+            let summary = report.summary(&self.profiles.store.profiles[index].name);
+            self.status_message = Some(StatusMessage::new(summary, StatusKind::Success));
+            self.timeline_config.layout = self.profiles.active_layout();
+        }
+    }
+
+    /// Render the mapping editor window.
+    ///
+    /// Rows are edited in place; "Apply" labels the log with them and "Save to
+    /// profile" also writes them back to the profile named at the top.
+    fn render_mapping_editor(&mut self, ctx: &egui::Context) {
+        let mut should_close = false;
+        let mut apply = false;
+        let mut save = false;
+        let mut reload_from_log = false;
+        let mut remove_index: Option<usize> = None;
+
+        let Some(ref log) = self.log else {
+            return;
+        };
+
+        egui::Window::new("Mapping Editor")
+            .id(egui::Id::new("mapping_editor"))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(560.0)
+            .default_height(400.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading("🏷 Mapping Editor");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+                ui.separator();
+
+                egui::Grid::new("mapping_editor_profile")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Profile:");
+                        ui.text_edit_singleline(&mut self.profiles.editor_name);
+                        ui.end_row();
+                        ui.label("Source:");
+                        ui.text_edit_singleline(&mut self.profiles.editor_source)
+                            .on_hover_text("Suggest the profile for logs whose source contains this text");
+                        ui.end_row();
+                    });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(280.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("mapping_editor_entries")
+                            .num_columns(7)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("ID");
+                                ui.strong("Name");
+                                ui.strong("Color");
+                                ui.strong("Group");
+                                ui.strong("Kind");
+                                ui.label("");
+                                ui.label("");
+                                ui.end_row();
+
+                                for (index, entry) in
+                                    self.profiles.editor_entries.iter_mut().enumerate()
+                                {
+                                    ui.add(egui::DragValue::new(&mut entry.id).speed(0.1))
+                                        .on_hover_text("Input ID in the log");
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut entry.name)
+                                            .desired_width(140.0),
+                                    );
+
+                                    ui.horizontal(|ui| {
+                                        let mut has_color = entry.color.is_some();
+                                        if ui.checkbox(&mut has_color, "").changed() {
+                                            entry.color = has_color.then_some([200, 200, 200]);
+                                        }
+                                        if let Some(ref mut color) = entry.color {
+                                            ui.color_edit_button_srgb(color);
+                                        }
+                                    });
+
+                                    let mut group = entry.group.clone().unwrap_or_default();
+                                    if ui
+                                        .add(
+                                            egui::TextEdit::singleline(&mut group)
+                                                .desired_width(90.0),
+                                        )
+                                        .changed()
+                                    {
+                                        let group = group.trim();
+                                        entry.group =
+                                            (!group.is_empty()).then(|| group.to_string());
+                                    }
+
+                                    let kind_text = entry
+                                        .kind
+                                        .map_or("Any", crate::core::writer::input_kind_name);
+                                    egui::ComboBox::from_id_salt(("mapping_kind", index))
+                                        .selected_text(kind_text)
+                                        .show_ui(ui, |ui| {
+                                            ui.selectable_value(&mut entry.kind, None, "Any");
+                                            for kind in InputKind::ALL {
+                                                ui.selectable_value(
+                                                    &mut entry.kind,
+                                                    Some(kind),
+                                                    crate::core::writer::input_kind_name(kind),
+                                                );
+                                            }
+                                        });
+
+                                    // Flag entries that don't fit the loaded log
+                                    match (entry.kind, edit::input_kind(log, entry.id)) {
+                                        (_, None) => {
+                                            ui.label("–").on_hover_text("Not in this log");
+                                        }
+                                        (Some(expected), Some(actual)) if expected != actual => {
+                                            ui.colored_label(egui::Color32::YELLOW, "⚠")
+                                                .on_hover_text(format!(
+                                                    "This log has a {} here; was the input renumbered?",
+                                                    crate::core::writer::input_kind_name(actual)
+                                                ));
+                                        }
+                                        _ => {
+                                            ui.label("✓");
+                                        }
+                                    }
+
+                                    if ui.button("🗑").on_hover_text("Remove entry").clicked() {
+                                        remove_index = Some(index);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.horizontal(|ui| {
+                    if ui.button("+ Add entry").clicked() {
+                        let id = self
+                            .profiles
+                            .editor_entries
+                            .iter()
+                            .map(|e| e.id + 1)
+                            .max()
+                            .unwrap_or(0);
+                        self.profiles.editor_entries.push(ProfileEntry {
+                            id,
+                            name: format!("Input {}", id),
+                            color: None,
+                            group: None,
+                            kind: None,
+                        });
+                    }
+                    if ui
+                        .button("Reset from log")
+                        .on_hover_text("Start over from the log's current mappings")
+                        .clicked()
+                    {
+                        reload_from_log = true;
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Apply")
+                        .on_hover_text("Label the log with these mappings")
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    let can_save = !self.profiles.editor_name.trim().is_empty();
+                    if ui
+                        .add_enabled(can_save, egui::Button::new("💾 Save to profile"))
+                        .on_hover_text("Apply and write the mappings back to the profile")
+                        .clicked()
+                    {
+                        save = true;
+                    }
                 });
             });
-        });
 
-        // Render filter popup panel if open
-        if self.filter_popup_open && toolbar_enabled {
-            self.render_filter_popup(ctx);
+        if let Some(index) = remove_index {
+            self.profiles.editor_entries.remove(index);
         }
-
-        // Render search dialog if open
-        if self.search.dialog_open && toolbar_enabled {
-            self.render_search_dialog(ctx);
+        if should_close {
+            self.profiles.editor_open = false;
         }
-
-        // Render bookmarks panel if open
-        if self.bookmarks.panel_open && toolbar_enabled {
-            self.render_bookmarks_panel(ctx);
+        if reload_from_log && let Some(ref log) = self.log {
+            let active = self.profiles.active.take();
+            self.profiles.open_editor(log);
+            self.profiles.active = active;
         }
 
-        // Render settings panel if open
-        if self.settings_panel_open {
-            self.render_settings_panel(ctx);
+        if save {
+            let index = self
+                .profiles
+                .save_editor_profile(&self.timeline_config.layout);
+            if let Err(e) = self.profiles.store.save() {
+                self.status_message = Some(StatusMessage::new(
+                    format!("Failed to save profiles: {}", e),
+                    StatusKind::Error,
+                ));
+                return;
+            }
+            if let Some(ref mut log) = self.log
+                && let Some(report) = self.profiles.apply(index, log)
+            {
+                let name = &self.profiles.store.profiles[index].name;
+                self.status_message = Some(StatusMessage::new(
+                    format!("Saved profile '{}'. {}", name, report.summary(name)),
+                    StatusKind::Success,
+                ));
+            }
+        } else if apply && let Some(ref mut log) = self.log {
+            let profile = self.profiles.editor_profile();
+            let report = profile.apply(log);
+            self.status_message = Some(StatusMessage::new(
+                report.summary(&profile.name),
+                StatusKind::Success,
+            ));
         }
     }
 
-    /// Render the filter popup panel.
-    fn render_filter_popup(&mut self, ctx: &egui::Context) {
+    /// Render the undo history panel window.
+    ///
+    /// Lists applied commands followed by undone ones; clicking an entry
+    /// undoes or redoes commands until the state after that entry is reached.
+    fn render_history_panel(&mut self, ctx: &egui::Context) {
         let mut should_close = false;
+        // Number of applied commands the user wants to go back or forward to
+        let mut target: Option<usize> = None;
 
-        egui::Window::new("Input Filter")
-            .id(egui::Id::new("filter_popup"))
+        let applied = self.history.undo_stack().len();
+        let current_frame_color = self.settings.colors.current_frame_color();
+
+        egui::Window::new("History")
+            .id(egui::Id::new("history_panel"))
             .collapsible(false)
             .resizable(true)
-            .default_width(220.0)
-            .anchor(egui::Align2::LEFT_TOP, egui::vec2(160.0, 40.0))
+            .default_width(280.0)
+            .default_height(300.0)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-340.0, 40.0))
             .show(ctx, |ui| {
-                // Close button
                 ui.horizontal(|ui| {
-                    ui.heading("Filter Inputs");
+                    ui.heading("↶ History");
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui.button("✕").clicked() {
                             should_close = true;
@@ -936,100 +3702,75 @@ impl InputLogViewerApp {
                 });
                 ui.separator();
 
-                if let Some(ref log) = self.log {
-                    // Input type filters
-                    ui.label("Input Types:");
-
-                    // Button type checkbox - prevent unchecking if it's the last enabled type
-                    let button_can_toggle = self
-                        .filter
-                        .can_disable_type(crate::core::log::InputKind::Button);
-                    ui.add_enabled_ui(button_can_toggle, |ui| {
-                        ui.checkbox(&mut self.filter.show_button, "Button");
-                    });
-
-                    // Axis1D type checkbox - prevent unchecking if it's the last enabled type
-                    let axis1d_can_toggle = self
-                        .filter
-                        .can_disable_type(crate::core::log::InputKind::Axis1D);
-                    ui.add_enabled_ui(axis1d_can_toggle, |ui| {
-                        ui.checkbox(&mut self.filter.show_axis1d, "Axis1D");
-                    });
-
-                    // Axis2D type checkbox - prevent unchecking if it's the last enabled type
-                    let axis2d_can_toggle = self
-                        .filter
-                        .can_disable_type(crate::core::log::InputKind::Axis2D);
-                    ui.add_enabled_ui(axis2d_can_toggle, |ui| {
-                        ui.checkbox(&mut self.filter.show_axis2d, "Axis2D");
-                    });
-
-                    ui.separator();
-
-                    // Individual input checkboxes (using effective mappings to include unmapped IDs)
-                    ui.label("Inputs:");
-                    let effective_mappings = log.get_effective_mappings();
-                    egui::ScrollArea::vertical()
-                        .max_height(250.0)
-                        .show(ui, |ui| {
-                            for mapping in &effective_mappings {
-                                let mut is_visible = self.filter.visible_ids.contains(&mapping.id);
-
-                                // Show color indicator if available
-                                ui.horizontal(|ui| {
-                                    if let Some(color) = mapping.color {
-                                        let color32 =
-                                            egui::Color32::from_rgb(color[0], color[1], color[2]);
-                                        let (rect, _) = ui.allocate_exact_size(
-                                            egui::vec2(12.0, 12.0),
-                                            egui::Sense::hover(),
-                                        );
-                                        ui.painter().rect_filled(rect, 2.0, color32);
-                                    }
-                                    if ui.checkbox(&mut is_visible, &mapping.name).changed() {
-                                        self.filter.set_id_visible(mapping.id, is_visible);
-                                    }
-                                });
+                egui::ScrollArea::vertical()
+                    .max_height(250.0)
+                    .show(ui, |ui| {
+                        let undone = self.history.redo_stack().iter().rev();
+                        let entries = self.history.undo_stack().iter().chain(undone);
+                        let labels = std::iter::once("Initial state".to_string())
+                            .chain(entries.map(|command| command.describe()));
+                        for (position, label) in labels.enumerate() {
+                            let text = if position == applied {
+                                egui::RichText::new(format!("▶ {}", label))
+                                    .color(current_frame_color)
+                                    .strong()
+                            } else if position > applied {
+                                egui::RichText::new(label).color(egui::Color32::DARK_GRAY)
+                            } else {
+                                egui::RichText::new(label)
+                            };
+                            if ui
+                                .selectable_label(position == applied, text)
+                                .on_hover_text("Go back or forward to this point")
+                                .clicked()
+                            {
+                                target = Some(position);
                             }
-                        });
-
-                    ui.separator();
-
-                    // Select All / Deselect All buttons
-                    ui.horizontal(|ui| {
-                        if ui.button("All").clicked() {
-                            self.filter.select_all(&effective_mappings);
-                        }
-                        if ui.button("None").clicked() {
-                            self.filter.deselect_all();
                         }
                     });
-                } else {
-                    ui.label("No file loaded");
-                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Ctrl+Z to undo, Ctrl+Shift+Z to redo");
+                });
             });
 
         if should_close {
-            self.filter_popup_open = false;
+            self.history_panel_open = false;
+        }
+
+        if let Some(target) = target {
+            while self.history.undo_stack().len() > target && self.history.can_undo() {
+                self.undo();
+            }
+            while self.history.undo_stack().len() < target && self.history.can_redo() {
+                self.redo();
+            }
         }
     }
 
-    /// Render the search dialog window.
-    fn render_search_dialog(&mut self, ctx: &egui::Context) {
+    /// Render the log warnings panel window.
+    fn render_warnings_panel(&mut self, ctx: &egui::Context) {
         let mut should_close = false;
         let mut seek_to_frame: Option<u64> = None;
-        let mut perform_search = false;
 
-        egui::Window::new("Search Input")
-            .id(egui::Id::new("search_dialog"))
+        let total_frames = self
+            .log
+            .as_ref()
+            .map(|l| l.metadata.frame_count)
+            .unwrap_or(0);
+
+        egui::Window::new("Warnings")
+            .id(egui::Id::new("warnings_panel"))
             .collapsible(false)
-            .resizable(false)
-            .default_width(280.0)
-            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .resizable(true)
+            .default_width(420.0)
+            .default_height(260.0)
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -60.0))
             .show(ctx, |ui| {
-                // Close button
+                // Header with close button
                 ui.horizontal(|ui| {
-                    ui.heading("Search Input");
+                    ui.heading("⚠ Warnings");
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui.button("✕").clicked() {
                             should_close = true;
@@ -1038,190 +3779,280 @@ impl InputLogViewerApp {
                 });
                 ui.separator();
 
-                if let Some(ref log) = self.log {
-                    // Get effective mappings to include unmapped IDs
-                    let effective_mappings = log.get_effective_mappings();
+                ui.label(format!(
+                    "{} errors, {} warnings",
+                    self.validation
+                        .diagnostics
+                        .iter()
+                        .filter(|d| d.severity == Severity::Error)
+                        .count(),
+                    self.validation
+                        .diagnostics
+                        .iter()
+                        .filter(|d| d.severity == Severity::Warning)
+                        .count()
+                ));
 
-                    // Input selector dropdown
-                    ui.horizontal(|ui| {
-                        ui.label("Input:");
-                        ui.add_space(10.0);
-                        let selected_name =
-                            if self.search.selected_input_index < effective_mappings.len() {
-                                effective_mappings[self.search.selected_input_index]
-                                    .name
-                                    .clone()
-                            } else {
-                                "Select input...".to_string()
-                            };
+                ui.add_space(4.0);
 
-                        egui::ComboBox::from_id_salt("search_input_combo")
-                            .selected_text(selected_name)
-                            .width(160.0)
-                            .show_ui(ui, |ui| {
-                                for (i, mapping) in effective_mappings.iter().enumerate() {
-                                    if ui
-                                        .selectable_label(
-                                            self.search.selected_input_index == i,
-                                            &mapping.name,
-                                        )
-                                        .clicked()
-                                    {
-                                        self.search.selected_input_index = i;
-                                        // Clear previous results when selection changes
-                                        self.search.clear_results();
+                egui::ScrollArea::vertical()
+                    .id_salt("warnings_scroll")
+                    .show(ui, |ui| {
+                        for diagnostic in &self.validation.diagnostics {
+                            ui.horizontal(|ui| {
+                                let (icon, color) = match diagnostic.severity {
+                                    Severity::Error => ("⛔", egui::Color32::from_rgb(220, 80, 80)),
+                                    Severity::Warning => {
+                                        ("⚠", egui::Color32::from_rgb(230, 180, 60))
                                     }
+                                    Severity::Info => ("ℹ", ui.visuals().text_color()),
+                                };
+                                ui.colored_label(color, icon)
+                                    .on_hover_text(diagnostic.severity.label());
+                                if let Some(line) = diagnostic.line {
+                                    ui.weak(match diagnostic.column {
+                                        Some(column) => format!("{}:{}", line, column),
+                                        None => line.to_string(),
+                                    });
+                                }
+                                if let Some(frame) = diagnostic.frame
+                                    && ui
+                                        .small_button(format!("F{}", frame))
+                                        .on_hover_text("Click to jump")
+                                        .clicked()
+                                {
+                                    seek_to_frame = Some(frame);
                                 }
+                                ui.label(&diagnostic.message);
                             });
+                        }
                     });
+            });
 
-                    ui.add_space(4.0);
-
-                    // Get the kind of the selected input to determine if state filter applies
-                    let selected_kind = if self.search.selected_input_index
-                        < effective_mappings.len()
-                    {
-                        let mapping_id = effective_mappings[self.search.selected_input_index].id;
-                        log.events
-                            .iter()
-                            .find(|e| e.id == mapping_id)
-                            .map(|e| e.kind)
-                            .unwrap_or(InputKind::Button)
-                    } else {
-                        InputKind::Button
-                    };
+        if should_close {
+            self.validation.panel_open = false;
+        }
 
-                    // State selector dropdown (only for Button type)
-                    let is_button = selected_kind == InputKind::Button;
-                    ui.add_enabled_ui(is_button, |ui| {
-                        ui.horizontal(|ui| {
-                            ui.label("State:");
-                            ui.add_space(10.0);
-                            let state_options = ["Any", "Pressed", "Released"];
-                            let selected_state = state_options
-                                .get(self.search.selected_state_index)
-                                .unwrap_or(&"Any");
+        if let Some(frame) = seek_to_frame {
+            self.playback.set_frame(frame, total_frames);
+        }
+    }
 
-                            egui::ComboBox::from_id_salt("search_state_combo")
-                                .selected_text(*selected_state)
-                                .width(160.0)
-                                .show_ui(ui, |ui| {
-                                    for (i, state) in state_options.iter().enumerate() {
-                                        if ui
-                                            .selectable_label(
-                                                self.search.selected_state_index == i,
-                                                *state,
-                                            )
-                                            .clicked()
-                                        {
-                                            self.search.selected_state_index = i;
-                                            // Clear previous results when selection changes
-                                            self.search.clear_results();
-                                        }
-                                    }
-                                });
-                        });
-                    });
+    /// Render the per-input statistics panel window.
+    fn render_stats_panel(&mut self, ctx: &egui::Context) {
+        let mut should_close = false;
+        let mut export_json = false;
 
-                    ui.add_space(8.0);
+        let selection = self.selection.get_selection();
+        if selection.is_none() {
+            self.stats.use_selection = false;
+        }
+        if let Some(ref log) = self.log {
+            self.stats.refresh(log, selection);
+        }
 
-                    // Search button
-                    ui.horizontal(|ui| {
-                        if ui.button("Search").clicked() {
-                            perform_search = true;
-                        }
-                        if self.search.has_searched && ui.button("Clear").clicked() {
-                            self.search.clear_results();
+        egui::Window::new("Statistics")
+            .id(egui::Id::new("stats_panel"))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(560.0)
+            .default_height(360.0)
+            .anchor(egui::Align2::LEFT_TOP, egui::vec2(10.0, 80.0))
+            .show(ctx, |ui| {
+                // Header with close button
+                ui.horizontal(|ui| {
+                    ui.heading("📊 Statistics");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").clicked() {
+                            should_close = true;
                         }
                     });
+                });
+                ui.separator();
 
-                    ui.separator();
+                // Range and settings
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.stats.use_selection, false, "Whole log");
+                    ui.add_enabled_ui(selection.is_some(), |ui| {
+                        ui.radio_value(&mut self.stats.use_selection, true, "Selection")
+                            .on_disabled_hover_text("Drag on the timeline to select a range");
+                    });
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Axis threshold:");
+                    ui.add(egui::Slider::new(
+                        &mut self.stats.config.axis_threshold,
+                        0.0..=1.0,
+                    ));
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("📋 Copy JSON").clicked()
+                        && let Some(ref report) = self.stats.report
+                    {
+                        ui.ctx().copy_text(report.to_json());
+                    }
+                    if ui.button("💾 Export JSON...").clicked() {
+                        export_json = true;
+                    }
+                });
 
-                    // Results section
-                    if self.search.has_searched {
-                        if self.search.results.is_empty() {
-                            ui.label("No matches found");
-                        } else {
-                            let count = self.search.results.count();
-                            let current = self.search.results.current_position().unwrap_or(0);
-                            ui.label(format!("Results: {} / {} matches", current, count));
+                ui.separator();
+
+                let Some(ref report) = self.stats.report else {
+                    return;
+                };
 
-                            ui.add_space(4.0);
+                ui.label(format!(
+                    "Frames {}-{} @ {} FPS",
+                    report.start_frame, report.end_frame, report.target_fps
+                ));
+                ui.add_space(4.0);
 
-                            // Navigation buttons
-                            ui.horizontal(|ui| {
-                                if ui.button("< Prev").clicked()
-                                    && let Some(frame) = self.search.results.prev()
-                                {
-                                    seek_to_frame = Some(frame);
-                                }
-                                if ui.button("Next >").clicked()
-                                    && let Some(frame) = self.search.results.next()
-                                {
-                                    seek_to_frame = Some(frame);
+                egui::ScrollArea::both()
+                    .id_salt("stats_scroll")
+                    .show(ui, |ui| {
+                        egui::Grid::new("stats_grid")
+                            .striped(true)
+                            .num_columns(7)
+                            .show(ui, |ui| {
+                                ui.strong("Input");
+                                ui.strong("Presses");
+                                ui.strong("Per min");
+                                ui.strong("Hold avg");
+                                ui.strong("Hold min / max");
+                                ui.strong("Past threshold");
+                                ui.strong("Histogram");
+                                ui.end_row();
+
+                                for input in &report.inputs {
+                                    ui.label(&input.name);
+                                    ui.label(input.press_count.to_string());
+                                    ui.label(format!("{:.1}", input.presses_per_minute));
+                                    match input.hold {
+                                        Some(ref hold) => {
+                                            ui.label(format!(
+                                                "{:.1}f ({:.0}ms)",
+                                                hold.avg_frames, hold.avg_ms
+                                            ));
+                                            ui.label(format!(
+                                                "{}f / {}f",
+                                                hold.min_frames, hold.max_frames
+                                            ));
+                                        }
+                                        None => {
+                                            ui.label("-");
+                                            ui.label("-");
+                                        }
+                                    }
+                                    match input.axis {
+                                        Some(ref axis) => {
+                                            ui.label(format!(
+                                                "{:.0}ms ({:.1}%)",
+                                                axis.ms_past_threshold,
+                                                axis.fraction_past_threshold * 100.0
+                                            ));
+                                            Self::draw_histogram(ui, &axis.histogram);
+                                        }
+                                        None => {
+                                            ui.label("-");
+                                            ui.label("");
+                                        }
+                                    }
+                                    ui.end_row();
                                 }
                             });
-
-                            // Show current match frame
-                            if let Some(frame) = self.search.results.current_frame() {
-                                ui.add_space(4.0);
-                                ui.label(format!("Current: Frame {}", frame));
-                            }
-                        }
-                    }
-                } else {
-                    ui.label("No file loaded");
-                }
+                    });
             });
 
         if should_close {
-            self.search.dialog_open = false;
-            // Clear search results when closing dialog to remove highlights
-            self.search.clear_results();
+            self.stats.panel_open = false;
         }
 
-        // Perform search if requested
-        if perform_search {
-            self.perform_search();
+        if export_json {
+            self.export_stats_json();
         }
+    }
 
-        // Seek to frame if navigation was used
-        if let Some(frame) = seek_to_frame {
-            let total_frames = self
-                .log
-                .as_ref()
-                .map(|l| l.metadata.frame_count)
-                .unwrap_or(0);
-            self.playback.set_frame(frame, total_frames);
+    /// Draw a small bar chart for an axis histogram.
+    fn draw_histogram(ui: &mut egui::Ui, histogram: &Histogram) {
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(100.0, 18.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+
+        let max = histogram.counts.iter().copied().max().unwrap_or(0).max(1);
+        let bar_width = rect.width() / histogram.counts.len() as f32;
+        let color = ui.visuals().selection.bg_fill;
+        for (i, &count) in histogram.counts.iter().enumerate() {
+            let height = rect.height() * count as f32 / max as f32;
+            let x = rect.left() + i as f32 * bar_width;
+            painter.rect_filled(
+                egui::Rect::from_min_max(
+                    egui::pos2(x + 0.5, rect.bottom() - height),
+                    egui::pos2(x + bar_width - 0.5, rect.bottom()),
+                ),
+                0.0,
+                color,
+            );
         }
+
+        response.on_hover_text(format!(
+            "{:.1} to {:.1}: {:?}",
+            histogram.min, histogram.max, histogram.counts
+        ));
     }
 
-    /// Render the bookmarks panel window.
-    fn render_bookmarks_panel(&mut self, ctx: &egui::Context) {
+    /// Ask for a path and write the current statistics as JSON.
+    fn export_stats_json(&mut self) {
+        let Some(ref report) = self.stats.report else {
+            return;
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_title("Export Statistics")
+            .set_file_name("stats.json")
+            .save_file()
+        else {
+            return;
+        };
+
+        self.status_message = Some(match std::fs::write(&path, report.to_json()) {
+            Ok(()) => StatusMessage::new(
+                format!("Exported statistics to {}", path.display()),
+                StatusKind::Success,
+            ),
+            Err(e) => StatusMessage::new(
+                format!("Failed to export statistics: {}", e),
+                StatusKind::Error,
+            ),
+        });
+    }
+
+    /// Render the A→B input timing panel window.
+    fn render_timing_panel(&mut self, ctx: &egui::Context) {
         let mut should_close = false;
         let mut seek_to_frame: Option<u64> = None;
-        let mut remove_index: Option<usize> = None;
-        let mut add_bookmark_at_current = false;
 
-        let total_frames = self
-            .log
-            .as_ref()
-            .map(|l| l.metadata.frame_count)
-            .unwrap_or(0);
-        let current_frame = self.playback.current_frame;
-        let current_frame_color = self.settings.colors.current_frame_color();
+        let Some(ref log) = self.log else {
+            return;
+        };
+        let total_frames = log.metadata.frame_count;
+        let mappings = log.get_effective_mappings();
+        let selection = self.selection.get_selection();
+        self.timing.refresh(log, selection);
 
-        egui::Window::new("Bookmarks")
-            .id(egui::Id::new("bookmarks_panel"))
+        egui::Window::new("Input Timing")
+            .id(egui::Id::new("timing_panel"))
             .collapsible(false)
             .resizable(true)
-            .default_width(320.0)
-            .default_height(300.0)
-            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 40.0))
+            .default_width(360.0)
+            .default_height(420.0)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 120.0))
             .show(ctx, |ui| {
                 // Header with close button
                 ui.horizontal(|ui| {
-                    ui.heading("★ Bookmarks");
+                    ui.heading("⏱ Input Timing");
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui.button("✕").clicked() {
                             should_close = true;
@@ -1230,209 +4061,303 @@ impl InputLogViewerApp {
                 });
                 ui.separator();
 
-                // Add bookmark section
+                // Input pair selection
+                let name_of = |id: Option<u32>| {
+                    id.and_then(|id| mappings.iter().find(|m| m.id == id))
+                        .map(InputMapping::qualified_name)
+                        .unwrap_or_else(|| "Choose...".to_string())
+                };
                 ui.horizontal(|ui| {
-                    ui.label("Label:");
-                    let response = ui.text_edit_singleline(&mut self.bookmarks.label_input);
-                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                        add_bookmark_at_current = true;
-                    }
-                    if ui.button("Add at F").clicked() {
-                        add_bookmark_at_current = true;
-                    }
-                    ui.label(format!("{}", current_frame));
+                    ui.label("A:");
+                    egui::ComboBox::from_id_salt("timing_input_a")
+                        .selected_text(name_of(self.timing.input_a))
+                        .show_ui(ui, |ui| {
+                            for mapping in &mappings {
+                                ui.selectable_value(
+                                    &mut self.timing.input_a,
+                                    Some(mapping.id),
+                                    mapping.qualified_name(),
+                                );
+                            }
+                        });
+                    ui.label("→ B:");
+                    egui::ComboBox::from_id_salt("timing_input_b")
+                        .selected_text(name_of(self.timing.input_b))
+                        .show_ui(ui, |ui| {
+                            for mapping in &mappings {
+                                ui.selectable_value(
+                                    &mut self.timing.input_b,
+                                    Some(mapping.id),
+                                    mapping.qualified_name(),
+                                );
+                            }
+                        });
                 });
-
-                ui.add_space(4.0);
-
-                // Quick info
                 ui.horizontal(|ui| {
-                    ui.label(format!(
-                        "Current frame: {} | Total bookmarks: {}",
-                        current_frame,
-                        self.bookmarks.bookmarks.len()
-                    ));
+                    ui.label("Frame-perfect within:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.timing.config.perfect_window)
+                            .range(0..=60)
+                            .suffix(" f"),
+                    );
                 });
+                match selection {
+                    Some((start, end)) => {
+                        ui.label(format!("Limited to selection F{}-F{}", start, end));
+                    }
+                    None => {
+                        ui.label("Whole log");
+                    }
+                }
 
                 ui.separator();
 
-                // Bookmark list
-                if self.bookmarks.is_empty() {
-                    ui.vertical_centered(|ui| {
-                        ui.add_space(20.0);
-                        ui.label("No bookmarks yet.");
-                        ui.label("Press 'B' or click 'Add' to create one.");
-                        ui.add_space(20.0);
+                let Some(ref report) = self.timing.report else {
+                    ui.label("Choose inputs A and B to analyze.");
+                    return;
+                };
+
+                ui.label(format!(
+                    "Samples: {} | Frame-perfect: {} | Unmatched A: {}",
+                    report.samples.len(),
+                    report.perfect_count(),
+                    report.unmatched
+                ));
+
+                let Some(p) = report.percentiles else {
+                    ui.label("No A press is followed by a B press");
+                    return;
+                };
+
+                ui.label(format!(
+                    "min {} | p50 {} | p90 {} | p95 {} | p99 {} | max {} (mean {:.2}) frames",
+                    p.min, p.p50, p.p90, p.p95, p.p99, p.max, p.mean
+                ));
+
+                ui.add_space(4.0);
+                Self::draw_timing_histogram(ui, report);
+                ui.add_space(4.0);
+
+                egui::ScrollArea::vertical()
+                    .id_salt("timing_samples_scroll")
+                    .show(ui, |ui| {
+                        for sample in &report.samples {
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .button(format!("F{} → F{}", sample.a_frame, sample.b_frame))
+                                    .on_hover_text("Click to jump")
+                                    .clicked()
+                                {
+                                    seek_to_frame = Some(sample.a_frame);
+                                }
+                                let ms = (log.frame_time_secs(sample.b_frame)
+                                    - log.frame_time_secs(sample.a_frame))
+                                    * 1000.0;
+                                ui.label(format!("{} frames ({:.0}ms)", sample.delta, ms));
+                                if sample.perfect {
+                                    ui.colored_label(egui::Color32::GOLD, "★ frame-perfect");
+                                }
+                            });
+                        }
                     });
-                } else {
-                    let mut start_editing_index: Option<usize> = None;
-                    let mut finish_editing = false;
-                    let mut cancel_editing = false;
+            });
 
-                    egui::ScrollArea::vertical()
-                        .max_height(200.0)
-                        .show(ui, |ui| {
-                            for i in 0..self.bookmarks.bookmarks.len() {
-                                // Copy needed data to avoid borrow issues
-                                let frame = self.bookmarks.bookmarks[i].frame;
-                                let label = self.bookmarks.bookmarks[i].label.clone();
-                                let is_current = frame == current_frame;
-                                let is_editing = self.bookmarks.editing_index == Some(i);
+        if should_close {
+            self.timing.panel_open = false;
+        }
 
-                                ui.horizontal(|ui| {
-                                    // Frame indicator (clickable to jump)
-                                    let frame_text = format!("★ F{}", frame);
-                                    let frame_button = if is_current {
-                                        egui::Button::new(
-                                            egui::RichText::new(&frame_text)
-                                                .color(current_frame_color)
-                                                .strong(),
-                                        )
-                                    } else {
-                                        egui::Button::new(&frame_text)
-                                    };
+        if let Some(frame) = seek_to_frame {
+            self.playback.set_frame(frame, total_frames);
+        }
+    }
 
-                                    if ui
-                                        .add(frame_button)
-                                        .on_hover_text("Click to jump")
-                                        .clicked()
-                                    {
-                                        seek_to_frame = Some(frame);
-                                    }
+    /// Draw a bar chart of timing deltas with one bar per delta value.
+    fn draw_timing_histogram(ui: &mut egui::Ui, report: &TimingReport) {
+        let Some((&max_delta, _)) = report.histogram.last_key_value() else {
+            return;
+        };
+        let max_count = report.histogram.values().copied().max().unwrap_or(1).max(1);
+        let bars = max_delta as usize + 1;
+
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), 60.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+
+        let bar_width = rect.width() / bars as f32;
+        for (&delta, &count) in &report.histogram {
+            let height = rect.height() * count as f32 / max_count as f32;
+            let x = rect.left() + delta as f32 * bar_width;
+            let color = if report.samples.iter().any(|s| s.delta == delta && s.perfect) {
+                egui::Color32::GOLD
+            } else {
+                ui.visuals().selection.bg_fill
+            };
+            painter.rect_filled(
+                egui::Rect::from_min_max(
+                    egui::pos2(x + 0.5, rect.bottom() - height),
+                    egui::pos2((x + bar_width - 0.5).max(x + 1.5), rect.bottom()),
+                ),
+                0.0,
+                color,
+            );
+        }
 
-                                    ui.separator();
+        // Show the bucket under the cursor
+        if let Some(pos) = response.hover_pos() {
+            let delta = ((pos.x - rect.left()) / bar_width).floor().max(0.0) as u64;
+            let count = report.histogram.get(&delta).copied().unwrap_or(0);
+            response.on_hover_text(format!("{} frames: {} samples", delta, count));
+        }
+    }
 
-                                    // Label display/edit
-                                    if is_editing {
-                                        // Editing mode
-                                        let response = ui.text_edit_singleline(
-                                            &mut self.bookmarks.editing_label,
-                                        );
-                                        if response.lost_focus() {
-                                            if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                                                finish_editing = true;
-                                            } else if ui.input(|i| i.key_pressed(egui::Key::Escape))
-                                            {
-                                                cancel_editing = true;
-                                            }
-                                        }
-                                        if ui.button("✓").on_hover_text("Save").clicked() {
-                                            finish_editing = true;
-                                        }
-                                        if ui.button("✕").on_hover_text("Cancel").clicked() {
-                                            cancel_editing = true;
-                                        }
-                                    } else {
-                                        // Display mode
-                                        let label_text = label.as_deref().unwrap_or("(no label)");
-                                        let label_color = if label.is_some() {
-                                            egui::Color32::LIGHT_GRAY
-                                        } else {
-                                            egui::Color32::DARK_GRAY
-                                        };
-                                        ui.label(
-                                            egui::RichText::new(label_text).color(label_color),
-                                        );
+    /// Render the idle detection panel window.
+    fn render_idle_panel(&mut self, ctx: &egui::Context) {
+        let mut should_close = false;
+        let mut run_analysis = false;
+        let mut seek_to_frame: Option<u64> = None;
+        let mut select_range: Option<(u64, u64)> = None;
 
-                                        ui.with_layout(
-                                            egui::Layout::right_to_left(egui::Align::Center),
-                                            |ui| {
-                                                // Delete button
-                                                if ui
-                                                    .button("🗑")
-                                                    .on_hover_text("Delete bookmark")
-                                                    .clicked()
-                                                {
-                                                    remove_index = Some(i);
-                                                }
-                                                // Edit button
-                                                if ui
-                                                    .button("✏")
-                                                    .on_hover_text("Edit label")
-                                                    .clicked()
-                                                {
-                                                    start_editing_index = Some(i);
-                                                }
-                                            },
-                                        );
-                                    }
-                                });
+        let (total_frames, target_fps) = self
+            .log
+            .as_ref()
+            .map(|l| (l.metadata.frame_count, l.metadata.target_fps))
+            .unwrap_or((0, 60));
 
-                                ui.add_space(2.0);
-                            }
-                        });
+        egui::Window::new("Idle Detection")
+            .id(egui::Id::new("idle_panel"))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(340.0)
+            .default_height(360.0)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 80.0))
+            .show(ctx, |ui| {
+                // Header with close button
+                ui.horizontal(|ui| {
+                    ui.heading("⏸ Idle Detection");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+                ui.separator();
 
-                    // Apply deferred actions
-                    if let Some(idx) = start_editing_index {
-                        self.bookmarks.start_editing(idx);
+                // Detection settings
+                ui.horizontal(|ui| {
+                    ui.label("Min frames:");
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut self.idle.config.min_frames)
+                                .range(1..=total_frames.max(1)),
+                        )
+                        .changed()
+                    {
+                        run_analysis = true;
                     }
-                    if finish_editing {
-                        self.bookmarks.finish_editing();
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Deadzone:");
+                    if ui
+                        .add(egui::Slider::new(&mut self.idle.config.deadzone, 0.0..=1.0))
+                        .changed()
+                    {
+                        run_analysis = true;
                     }
-                    if cancel_editing {
-                        self.bookmarks.cancel_editing();
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Analyze").clicked() {
+                        run_analysis = true;
                     }
+                    ui.checkbox(&mut self.idle.show_on_timeline, "Shade on timeline");
+                });
+
+                ui.separator();
+
+                let Some(ref report) = self.idle.report else {
+                    ui.label("Click 'Analyze' to detect idle spans.");
+                    return;
+                };
+
+                ui.label(format!(
+                    "Idle spans: {} | Event gaps: {}",
+                    report.count(IdleKind::Idle),
+                    report.count(IdleKind::NoEvents)
+                ));
+
+                ui.add_space(4.0);
+
+                if report.spans.is_empty() {
+                    ui.label("No spans found");
+                } else {
+                    egui::ScrollArea::vertical()
+                        .id_salt("idle_spans_scroll")
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for span in &report.spans {
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .button(format!("F{}-F{}", span.start, span.end))
+                                        .on_hover_text("Click to jump")
+                                        .clicked()
+                                    {
+                                        seek_to_frame = Some(span.start);
+                                    }
+                                    let seconds = span.len() as f32 / target_fps.max(1) as f32;
+                                    ui.label(format!(
+                                        "{} ({} frames, {:.2}s)",
+                                        span.kind.label(),
+                                        span.len(),
+                                        seconds
+                                    ));
+                                    ui.with_layout(
+                                        egui::Layout::right_to_left(egui::Align::Center),
+                                        |ui| {
+                                            if ui
+                                                .small_button("Select")
+                                                .on_hover_text("Select this range")
+                                                .clicked()
+                                            {
+                                                select_range = Some((span.start, span.end));
+                                            }
+                                        },
+                                    );
+                                });
+                            }
+                        });
                 }
 
                 ui.separator();
 
-                // Bottom actions
-                ui.horizontal(|ui| {
-                    if ui
-                        .add_enabled(!self.bookmarks.is_empty(), egui::Button::new("Clear All"))
-                        .clicked()
-                    {
-                        self.bookmarks.bookmarks.clear();
-                        self.status_message = Some(StatusMessage::new(
-                            "All bookmarks cleared",
-                            StatusKind::Success,
-                        ));
+                // Inputs that are mapped but never used
+                ui.label("Unused inputs:");
+                if report.unused_inputs.is_empty() {
+                    ui.label("(none)");
+                } else if let Some(ref log) = self.log {
+                    for &id in &report.unused_inputs {
+                        ui.label(format!("• {} (ID {})", log.get_input_name(id), id));
                     }
-
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.label("Tip: Press 'B' to quickly toggle bookmarks");
-                    });
-                });
+                }
             });
 
         if should_close {
-            self.bookmarks.panel_open = false;
-        }
-
-        // Handle bookmark addition
-        if add_bookmark_at_current {
-            let label = if self.bookmarks.label_input.is_empty() {
-                None
-            } else {
-                Some(std::mem::take(&mut self.bookmarks.label_input))
-            };
-            if self.bookmarks.add_bookmark(current_frame, label) {
-                self.status_message = Some(StatusMessage::new(
-                    format!("Bookmark added at frame {}", current_frame),
-                    StatusKind::Success,
-                ));
-            } else {
-                self.status_message = Some(StatusMessage::new(
-                    format!("Bookmark already exists at frame {}", current_frame),
-                    StatusKind::Error,
-                ));
-            }
+            self.idle.panel_open = false;
         }
 
-        // Handle bookmark removal
-        if let Some(index) = remove_index
-            && let Some(frame) = self.bookmarks.remove_bookmark_by_index(index)
-        {
-            self.status_message = Some(StatusMessage::new(
-                format!("Bookmark removed at frame {}", frame),
-                StatusKind::Success,
-            ));
+        if run_analysis && let Some(ref log) = self.log {
+            self.idle.analyze(log);
         }
 
-        // Handle seeking
         if let Some(frame) = seek_to_frame {
             self.playback.set_frame(frame, total_frames);
         }
+
+        if let Some((start, end)) = select_range {
+            let before = self.selection.get_selection();
+            self.set_selection_range(Some((start, end)));
+            self.playback.set_frame(start, total_frames);
+            self.record_selection_change(before);
+        }
     }
 
     /// Render the settings panel window with color pickers.
@@ -1481,6 +4406,74 @@ impl InputLogViewerApp {
                         ui.horizontal(|ui| {
                             ui.checkbox(&mut self.settings.loop_enabled, "Enable loop by default");
                         });
+
+                        // Hitch threshold for the frame-time track
+                        ui.horizontal(|ui| {
+                            ui.label("Hitch threshold:");
+                            let mut threshold = self.settings.hitch_threshold_ms;
+                            if ui
+                                .add(
+                                    egui::Slider::new(&mut threshold, 1.0..=200.0)
+                                        .suffix(" ms")
+                                        .logarithmic(true),
+                                )
+                                .on_hover_text(
+                                    "Frames that took longer than this are highlighted \
+                                     on the frame-time track of logs with timestamps",
+                                )
+                                .changed()
+                            {
+                                self.settings.set_hitch_threshold_ms(threshold);
+                            }
+                        });
+
+                        ui.checkbox(&mut self.settings.show_minimap, "Show minimap")
+                            .on_hover_text(
+                                "Show an overview of the whole log above the timeline; \
+                                 click or drag it to scroll",
+                            );
+                    });
+
+                    ui.add_space(4.0);
+
+                    // Timeline row height, saved with the other settings
+                    ui.collapsing("Timeline Rows", |ui| {
+                        self.render_row_settings(ui);
+                    });
+
+                    ui.add_space(4.0);
+
+                    // Loading Settings
+                    ui.collapsing("Loading", |ui| {
+                        ui.checkbox(&mut self.settings.lenient_json, "Lenient loading")
+                            .on_hover_text(
+                                "Skip or repair bad JSON events and load the complete events \
+                                 of truncated binary files instead of rejecting them; \
+                                 problems are listed in the Warnings panel",
+                            );
+                    });
+
+                    ui.add_space(4.0);
+
+                    // Saving Settings
+                    ui.collapsing("Saving", |ui| {
+                        ui.checkbox(
+                            &mut self.settings.compress_binary,
+                            "Compress binary logs (.ilb)",
+                        )
+                        .on_hover_text(
+                            "Save .ilb files in the smaller compressed format; \
+                             leave off for files that older readers or follow \
+                             mode need to open",
+                        );
+                        ui.checkbox(
+                            &mut self.settings.checksum_binary,
+                            "Add checksums to binary logs (.ilb)",
+                        )
+                        .on_hover_text(
+                            "End .ilb files with a CRC-32 trailer so that \
+                             corrupted files are detected on load",
+                        );
                     });
 
                     ui.add_space(4.0);
@@ -1490,6 +4483,7 @@ impl InputLogViewerApp {
                         if self.settings.recent_files.is_empty() {
                             ui.label("No recent files");
                         } else {
+                            let registry = format::registry();
                             for path in &self.settings.recent_files {
                                 let file_name = path
                                     .file_name()
@@ -1504,6 +4498,10 @@ impl InputLogViewerApp {
                                     {
                                         load_recent_file = Some(path.clone());
                                     }
+                                    // The format is only detected on open for unknown extensions
+                                    if let Some(log_format) = registry.by_extension(path) {
+                                        ui.weak(log_format.name());
+                                    }
                                 });
                             }
                             ui.add_space(4.0);
@@ -1648,6 +4646,22 @@ impl InputLogViewerApp {
 
                     ui.add_space(4.0);
 
+                    // Analysis Colors
+                    ui.collapsing("Analysis Colors", |ui| {
+                        Self::color_picker_row(
+                            ui,
+                            "Idle Span",
+                            &mut self.settings.colors.idle_span,
+                        );
+                        Self::color_picker_row(
+                            ui,
+                            "Event Gap",
+                            &mut self.settings.colors.event_gap,
+                        );
+                    });
+
+                    ui.add_space(4.0);
+
                     // Control Colors
                     ui.collapsing("Control Colors", |ui| {
                         Self::color_picker_row(
@@ -1772,18 +4786,34 @@ impl InputLogViewerApp {
 
             // Execute the search
             let matches = find_matches(log, &query);
-            self.search.results = SearchResult::from_matches(matches);
-            self.search.has_searched = true;
-
-            // Navigate to the first result closest to current frame
-            if !self.search.results.is_empty() {
-                self.search
-                    .results
-                    .set_closest_to_frame(self.playback.current_frame);
-                if let Some(frame) = self.search.results.current_frame() {
-                    let total_frames = log.metadata.frame_count;
-                    self.playback.set_frame(frame, total_frames);
-                }
+            self.show_search_matches(matches);
+        }
+    }
+
+    /// Search annotation labels for the text entered in the search dialog.
+    fn perform_annotation_search(&mut self) {
+        if let Some(ref log) = self.log {
+            let matches = find_annotation_matches(log, &self.search.annotation_text);
+            self.show_search_matches(matches);
+        }
+    }
+
+    /// Store search matches and seek to the one closest to the current frame.
+    fn show_search_matches(&mut self, matches: Vec<u64>) {
+        self.search.results = SearchResult::from_matches(matches);
+        self.search.has_searched = true;
+
+        if !self.search.results.is_empty() {
+            self.search
+                .results
+                .set_closest_to_frame(self.playback.current_frame);
+            if let Some(frame) = self.search.results.current_frame() {
+                let total_frames = self
+                    .log
+                    .as_ref()
+                    .map(|l| l.metadata.frame_count)
+                    .unwrap_or(0);
+                self.playback.set_frame(frame, total_frames);
             }
         }
     }
@@ -1802,15 +4832,13 @@ impl InputLogViewerApp {
             return;
         }
 
-        // Check if the first hovered file has a valid extension (matches drop behavior)
+        // Check if the first hovered file can be opened (matches drop behavior)
+        let registry = format::registry();
         let has_valid_extension = ctx.input(|i| {
             i.raw.hovered_files.first().is_some_and(|file| {
                 file.path
                     .as_ref()
-                    .and_then(|p| p.extension())
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| ext.to_lowercase())
-                    .is_some_and(|ext| ext == "ilj" || ext == "ilb")
+                    .is_some_and(|path| registry.can_open(path))
             })
         });
 
@@ -1850,9 +4878,9 @@ impl InputLogViewerApp {
 
         // Text message
         let text = if has_valid_extension {
-            "Drop to load file"
+            "Drop to load file".to_string()
         } else {
-            "Invalid file type\n(use .ilj or .ilb)"
+            format!("Invalid file type\n(use {})", registry.extension_list())
         };
         painter.text(
             screen_rect.center(),
@@ -1884,6 +4912,7 @@ impl InputLogViewerApp {
         let msg_info = self.status_message.as_ref().map(|msg| {
             let color = match msg.kind {
                 StatusKind::Success => self.settings.colors.status_success_color(),
+                StatusKind::Info => self.settings.colors.text_label_color(),
                 StatusKind::Error => self.settings.colors.status_error_color(),
             };
             (color, msg.text.clone())
@@ -2020,7 +5049,21 @@ impl InputLogViewerApp {
             }
             ControlAction::ToggleBookmark => {
                 let current_frame = self.playback.current_frame;
+                let existing = self
+                    .bookmarks
+                    .bookmarks
+                    .iter()
+                    .find(|b| b.frame == current_frame)
+                    .cloned();
                 let (added, removed) = self.bookmarks.toggle_bookmark(current_frame);
+                if added {
+                    self.history.push(Command::AddBookmark(Bookmark {
+                        frame: current_frame,
+                        label: None,
+                    }));
+                } else if let Some(bookmark) = existing.filter(|_| removed) {
+                    self.history.push(Command::RemoveBookmark(bookmark));
+                }
                 if added {
                     self.status_message = Some(StatusMessage::new(
                         format!("Bookmark added at frame {}", current_frame),
@@ -2055,9 +5098,10 @@ impl InputLogViewerApp {
                 }
             }
             ControlAction::RemoveBookmark(index) => {
-                if let Some(bookmark) = self.bookmarks.get_bookmark(index) {
+                if let Some(bookmark) = self.bookmarks.get_bookmark(index).cloned() {
                     let frame = bookmark.frame;
                     self.bookmarks.remove_bookmark(frame);
+                    self.history.push(Command::RemoveBookmark(bookmark));
                     self.status_message = Some(StatusMessage::new(
                         format!("Bookmark removed at frame {}", frame),
                         StatusKind::Success,
@@ -2067,7 +5111,11 @@ impl InputLogViewerApp {
             ControlAction::AddBookmarkWithLabel(label) => {
                 let current_frame = self.playback.current_frame;
                 let label = if label.is_empty() { None } else { Some(label) };
-                if self.bookmarks.add_bookmark(current_frame, label) {
+                if self.bookmarks.add_bookmark(current_frame, label.clone()) {
+                    self.history.push(Command::AddBookmark(Bookmark {
+                        frame: current_frame,
+                        label,
+                    }));
                     self.status_message = Some(StatusMessage::new(
                         format!("Bookmark added at frame {}", current_frame),
                         StatusKind::Success,
@@ -2097,9 +5145,11 @@ impl InputLogViewerApp {
                 }
             }
             ControlAction::ClearSelection => {
+                let before = self.selection.get_selection();
                 self.selection.clear();
                 self.loop_selection = false;
                 self.playback.clear_range();
+                self.record_selection_change(before);
             }
         }
     }
@@ -2134,7 +5184,10 @@ impl InputLogViewerApp {
 
             ui.heading("📁 No File Loaded");
             ui.add_space(10.0);
-            ui.label("Drag and drop an input log file (.ilj or .ilb) to get started.");
+            ui.label(format!(
+                "Drag and drop an input log file ({}) to get started.",
+                format::registry().extension_list()
+            ));
             ui.label("Or use the \"Open File\" button in the toolbar.");
 
             ui.add_space(20.0);
@@ -2190,6 +5243,13 @@ impl InputLogViewerApp {
                     ));
                 }
 
+                if self.edit.dirty {
+                    ui.colored_label(
+                        self.settings.colors.status_error_color(),
+                        "● Unsaved changes",
+                    );
+                }
+
                 ui.separator();
                 ui.label(format!(
                     "Frames: {} | FPS: {} | Events: {}",
@@ -2208,6 +5268,16 @@ impl InputLogViewerApp {
 
         // Render the timeline using TimelineRenderer with filter, search results, bookmarks, and selection
         // Handle view action if triggered
+        if self.settings.show_minimap
+            && let Some(ref log) = self.log
+            && self
+                .minimap
+                .as_ref()
+                .is_none_or(|map| map.frame_count() != log.metadata.frame_count)
+        {
+            self.minimap = Some(DensityMap::compute(log, DEFAULT_MAX_BUCKETS));
+        }
+
         let view_action = if let Some(ref log) = self.log {
             let mut renderer = TimelineRenderer::new(
                 log,
@@ -2221,9 +5291,26 @@ impl InputLogViewerApp {
             if !self.bookmarks.is_empty() {
                 renderer = renderer.with_bookmarks(&self.bookmarks.bookmarks);
             }
+            if self.idle.show_on_timeline
+                && let Some(ref report) = self.idle.report
+            {
+                renderer = renderer.with_idle_spans(&report.spans);
+            }
+            if log.has_frame_timestamps() {
+                renderer = renderer.with_frame_times(self.settings.hitch_threshold_us());
+            }
             // Pass selection state to timeline renderer
             renderer =
                 renderer.with_selection(self.selection.get_selection(), self.selection.is_dragging);
+            if self.edit.enabled {
+                renderer = renderer.with_edit_mode(self.edit.cell);
+            }
+            if self.settings.show_minimap
+                && let Some(ref density) = self.minimap
+            {
+                renderer = renderer.with_minimap(density);
+            }
+            renderer = renderer.with_row_settings(&self.settings.rows);
             renderer.render(ui)
         } else {
             None
@@ -2248,7 +5335,9 @@ impl InputLogViewerApp {
                 self.selection.update_drag(frame);
             }
             Some(ViewAction::FinishSelection) => {
+                let before = self.selection.before_drag.take();
                 self.selection.finish_drag();
+                self.record_selection_change(before);
                 // Automatically enable loop selection when selection is made
                 if self.selection.has_selection() {
                     self.loop_selection = true;
@@ -2257,6 +5346,43 @@ impl InputLogViewerApp {
                     }
                 }
             }
+            Some(ViewAction::ToggleGroup { player }) => {
+                self.timeline_config.toggle_group(player);
+            }
+            Some(ViewAction::EditCell { id, frame }) => {
+                self.edit_cell(id, frame);
+            }
+            Some(ViewAction::ScrollRows { row_scroll }) => {
+                self.timeline_config.row_scroll = row_scroll;
+            }
+            Some(ViewAction::StartRowDrag { id }) => {
+                self.timeline_config.dragging_row = Some(id);
+            }
+            Some(ViewAction::MoveRow { id, before }) => {
+                self.timeline_config.dragging_row = None;
+                self.move_row(id, before);
+            }
+            Some(ViewAction::CancelRowDrag) => {
+                self.timeline_config.dragging_row = None;
+            }
+            Some(ViewAction::TogglePin { id }) => {
+                self.timeline_config.layout.toggle_pin(id);
+                self.persist_layout();
+            }
+            Some(ViewAction::ToggleNamedGroup { name }) => {
+                self.timeline_config.layout.toggle_collapsed(&name);
+                self.persist_layout();
+            }
+            Some(ViewAction::SetRowGroup { id, group }) => {
+                self.set_row_group(id, group);
+            }
+            Some(ViewAction::Ungroup { name }) => {
+                self.ungroup(&name);
+            }
+            Some(ViewAction::SetRowScale { id, scale }) => {
+                self.timeline_config.layout.set_scale(id, scale);
+                self.persist_layout();
+            }
             None => {}
         }
     }
@@ -2344,6 +5470,13 @@ impl InputLogViewerApp {
                     self.load_file(path);
                 }
             }
+            Some(ErrorDialogAction::LoadRecoverable) => {
+                // Reload the damaged file, keeping whatever can be recovered
+                if let Some(path) = self.retry_path.take() {
+                    self.clear_error();
+                    self.load_file_with(path, true);
+                }
+            }
             Some(ErrorDialogAction::Close) => {
                 // Clear the error state
                 self.clear_error();
@@ -2578,6 +5711,208 @@ mod tests {
         assert!(state.remove_bookmark_by_index(99).is_none());
     }
 
+    #[test]
+    fn test_bookmark_state_undo_redo() {
+        let mut state = BookmarkState::new();
+        let mut history = History::default();
+
+        state.add_bookmark(10, None);
+        history.push(Command::AddBookmark(Bookmark {
+            frame: 10,
+            label: None,
+        }));
+        state.update_bookmark_label(0, "Start".to_string());
+        history.push(Command::RenameBookmark {
+            frame: 10,
+            before: None,
+            after: Some("Start".to_string()),
+        });
+        let before = std::mem::take(&mut state.bookmarks);
+        history.push(Command::ReplaceBookmarks {
+            before,
+            after: Vec::new(),
+        });
+
+        // Undo the clear, the rename and the add
+        assert!(state.apply_command(&history.undo().unwrap()));
+        assert_eq!(state.bookmarks[0].label.as_deref(), Some("Start"));
+        assert!(state.apply_command(&history.undo().unwrap()));
+        assert_eq!(state.bookmarks[0].label, None);
+        assert!(state.apply_command(&history.undo().unwrap()));
+        assert!(state.is_empty());
+
+        // Redo everything again
+        while let Some(command) = history.redo() {
+            assert!(state.apply_command(&command));
+        }
+        assert!(state.is_empty());
+        assert!(state.apply_command(&history.undo().unwrap()));
+        assert_eq!(state.bookmarks[0].frame, 10);
+        assert_eq!(state.bookmarks[0].label.as_deref(), Some("Start"));
+    }
+
+    #[test]
+    fn test_bookmark_state_apply_command_mismatch() {
+        let mut state = BookmarkState::new();
+        state.add_bookmark(5, None);
+        state.start_editing(0);
+
+        // Adding an existing bookmark or renaming a missing one does nothing
+        let existing = Command::AddBookmark(Bookmark {
+            frame: 5,
+            label: None,
+        });
+        assert!(!state.apply_command(&existing));
+        assert!(!state.apply_command(&Command::RenameBookmark {
+            frame: 7,
+            before: None,
+            after: Some("x".to_string()),
+        }));
+        assert!(!state.apply_command(&Command::ChangeSelection {
+            before: None,
+            after: Some((1, 2)),
+        }));
+        assert_eq!(state.bookmarks.len(), 1);
+        assert_eq!(state.editing_index, None);
+    }
+
+    #[test]
+    fn test_selection_state_remembers_selection_before_drag() {
+        let mut state = SelectionState::new();
+        state.set_selection(8, 2);
+        assert_eq!(state.get_selection(), Some((2, 8)));
+
+        state.start_drag(20);
+        state.update_drag(25);
+        state.finish_drag();
+        assert_eq!(state.before_drag, Some((2, 8)));
+        assert_eq!(state.get_selection(), Some((20, 25)));
+
+        state.clear();
+        assert_eq!(state.before_drag, None);
+    }
+
+    #[test]
+    fn test_profile_state_applies_suggested_profile() {
+        use crate::core::log::{InputEvent, LogMetadata};
+        use crate::core::parser::default_mapping;
+
+        let make_log = || InputLog {
+            metadata: LogMetadata {
+                frame_count: 10,
+                source: Some("Racer 2.1".to_string()),
+                ..Default::default()
+            },
+            mappings: vec![default_mapping(0), default_mapping(1)],
+            events: vec![InputEvent {
+                frame: 0,
+                id: 1,
+                kind: InputKind::Axis1D,
+                state: ButtonState::Released,
+                value: [0.5, 0.0],
+                contact: 0,
+            }],
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
+        };
+
+        let mut state = ProfileState::default();
+        let mut log = make_log();
+        assert_eq!(state.on_log_loaded(&mut log, true), None);
+
+        // Write a profile from the editor
+        state.open_editor(&log);
+        assert!(state.editor_open);
+        assert_eq!(state.editor_name, "Racer 2.1");
+        assert_eq!(state.editor_entries.len(), 2);
+        state.editor_name = "Racer".to_string();
+        state.editor_source = "racer".to_string();
+        state.editor_entries[1].name = "Steer".to_string();
+        let mut layout = RowLayout::default();
+        layout.toggle_pin(1);
+        layout.set_scale(1, 2.0);
+        assert_eq!(state.save_editor_profile(&layout), 0);
+        assert_eq!(state.active, Some(0));
+        assert_eq!(state.active_layout(), layout);
+
+        // A new log of the same game gets the profile on load
+        let mut log = make_log();
+        assert_eq!(
+            state.on_log_loaded(&mut log, true),
+            Some("Racer".to_string())
+        );
+        assert_eq!(log.get_input_name(1), "Steer");
+
+        // Without auto-apply it's only suggested
+        let mut log = make_log();
+        assert_eq!(state.on_log_loaded(&mut log, false), None);
+        assert_eq!(state.suggested, Some(0));
+        assert_eq!(state.active, None);
+        assert_eq!(log.get_input_name(1), "Input 1");
+    }
+
+    #[test]
+    fn test_profile_state_editor_adds_inputs_missing_from_profile() {
+        use crate::core::parser::default_mapping;
+
+        let mut state = ProfileState::default();
+        state.store.upsert(MappingProfile {
+            name: "Racer".to_string(),
+            source: None,
+            entries: vec![ProfileEntry {
+                id: 5,
+                name: "Horn".to_string(),
+                color: None,
+                group: None,
+                kind: None,
+            }],
+            layout: RowLayout::default(),
+        });
+        state.active = Some(0);
+
+        let log = InputLog {
+            mappings: vec![default_mapping(2)],
+            ..Default::default()
+        };
+        state.open_editor(&log);
+        let ids: Vec<u32> = state.editor_entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![2, 5]);
+        assert_eq!(state.editor_name, "Racer");
+    }
+
+    #[test]
+    fn test_profile_state_set_group() {
+        use crate::core::parser::default_mapping;
+
+        let mut state = ProfileState::default();
+        let mut mapping = default_mapping(3);
+        mapping.group = Some("Move".to_string());
+
+        // Without an active profile nothing is stored
+        state.set_group(&mapping, Some(InputKind::Button));
+        assert!(state.store.profiles.is_empty());
+
+        state.store.upsert(MappingProfile {
+            name: "Racer".to_string(),
+            source: None,
+            entries: vec![],
+            layout: RowLayout::default(),
+        });
+        state.active = Some(0);
+        state.set_group(&mapping, Some(InputKind::Button));
+        let entries = &state.store.profiles[0].entries;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].group.as_deref(), Some("Move"));
+
+        // Existing entries only get the new group
+        mapping.group = None;
+        mapping.name = "Renamed".to_string();
+        state.set_group(&mapping, None);
+        let entries = &state.store.profiles[0].entries;
+        assert_eq!(entries[0].group, None);
+        assert_eq!(entries[0].name, "Input 3");
+    }
+
     #[test]
     fn test_bookmark_state_editing() {
         let mut state = BookmarkState::new();
@@ -2612,6 +5947,233 @@ mod tests {
         assert!(!state.finish_editing());
     }
 
+    #[test]
+    fn test_stats_state_refresh_uses_selection() {
+        use crate::core::log::{InputEvent, LogMetadata};
+
+        let log = InputLog {
+            metadata: LogMetadata {
+                frame_count: 100,
+                ..Default::default()
+            },
+            mappings: vec![],
+            events: vec![
+                InputEvent {
+                    frame: 10,
+                    id: 0,
+                    kind: InputKind::Button,
+                    state: ButtonState::Pressed,
+                    value: [0.0, 0.0],
+                    contact: 0,
+                },
+                InputEvent {
+                    frame: 60,
+                    id: 0,
+                    kind: InputKind::Button,
+                    state: ButtonState::Pressed,
+                    value: [0.0, 0.0],
+                    contact: 0,
+                },
+            ],
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
+        };
+
+        let mut state = StatsState::new();
+        state.refresh(&log, Some((50, 99)));
+        // Whole log by default, even with a selection present
+        assert_eq!(state.report.as_ref().unwrap().inputs[0].press_count, 2);
+
+        state.use_selection = true;
+        state.refresh(&log, Some((50, 99)));
+        let report = state.report.as_ref().unwrap();
+        assert_eq!(report.start_frame, 50);
+        assert_eq!(report.inputs[0].press_count, 1);
+
+        state.reset();
+        assert!(state.report.is_none());
+        assert!(!state.use_selection);
+    }
+
+    #[test]
+    fn test_validation_state_opens_on_warnings() {
+        use crate::core::log::{InputEvent, LogMetadata};
+
+        let mut log = InputLog {
+            metadata: LogMetadata {
+                frame_count: 10,
+                ..Default::default()
+            },
+            mappings: vec![],
+            events: vec![],
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
+        };
+
+        let mut state = ValidationState::new();
+        state.run(&log, &[]);
+        assert!(state.diagnostics.is_empty());
+        assert!(!state.panel_open);
+
+        log.events.push(InputEvent {
+            frame: 20,
+            id: 0,
+            kind: InputKind::Axis1D,
+            state: ButtonState::Released,
+            value: [0.0, 0.0],
+            contact: 0,
+        });
+        state.run(&log, &[]);
+        assert_eq!(state.count_at_least(Severity::Warning), 1);
+        assert!(state.panel_open);
+    }
+
+    #[test]
+    fn test_validation_state_revalidate_keeps_load_warnings() {
+        use crate::core::log::{InputEvent, LogMetadata};
+
+        let mut log = InputLog {
+            metadata: LogMetadata {
+                frame_count: 10,
+                ..Default::default()
+            },
+            mappings: vec![],
+            events: vec![],
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
+        };
+        let load_warning = detailed_error::from_parse_warning(
+            None,
+            &crate::core::parser::ParseWarning {
+                message: "Event 3 was skipped".to_string(),
+                event_index: Some(3),
+                line: 1,
+                column: 1,
+            },
+        );
+
+        let mut state = ValidationState::new();
+        state.run(&log, &[load_warning]);
+        assert_eq!(state.diagnostics.len(), 1);
+
+        // An edit adds an out-of-range event, then the edit is undone
+        log.events.push(InputEvent {
+            frame: 20,
+            id: 0,
+            kind: InputKind::Axis1D,
+            state: ButtonState::Released,
+            value: [0.0, 0.0],
+            contact: 0,
+        });
+        state.revalidate(&log);
+        assert_eq!(state.diagnostics.len(), 2);
+        log.events.clear();
+        state.revalidate(&log);
+        assert_eq!(state.diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_timing_state_refresh() {
+        use crate::core::log::{InputEvent, LogMetadata};
+
+        let press = |frame: u64, id: u32| InputEvent {
+            frame,
+            id,
+            kind: InputKind::Button,
+            state: ButtonState::Pressed,
+            value: [0.0, 0.0],
+            contact: 0,
+        };
+        let log = InputLog {
+            metadata: LogMetadata {
+                frame_count: 100,
+                ..Default::default()
+            },
+            mappings: vec![],
+            events: vec![press(10, 0), press(13, 1), press(60, 0), press(61, 1)],
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
+        };
+
+        let mut state = TimingState::new();
+        state.refresh(&log, None);
+        assert!(state.report.is_none());
+
+        state.input_a = Some(0);
+        state.input_b = Some(1);
+        state.refresh(&log, None);
+        assert_eq!(state.report.as_ref().unwrap().samples.len(), 2);
+
+        // Limited to the selection when one exists
+        state.refresh(&log, Some((50, 99)));
+        let report = state.report.as_ref().unwrap();
+        assert_eq!(report.samples.len(), 1);
+        assert_eq!(report.samples[0].delta, 1);
+
+        state.reset();
+        assert!(state.input_a.is_none());
+        assert!(state.report.is_none());
+    }
+
+    #[test]
+    fn test_follow_state_poll_interval() {
+        let path = std::env::temp_dir().join(format!("ilv_follow_{}.ilj", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{"version": 1, "metadata": {"target_fps": 60, "frame_count": 0}, "mappings": [], "events": ["#,
+        )
+        .unwrap();
+        let (tail, _) = LogTail::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let mut follow = FollowState::new(tail);
+        let start = follow.last_poll;
+        assert!(!follow.poll_due(start));
+        assert!(follow.poll_due(start + FOLLOW_POLL_INTERVAL));
+        // The interval restarts after a due poll
+        assert!(!follow.poll_due(start + FOLLOW_POLL_INTERVAL));
+    }
+
+    #[test]
+    fn test_idle_state_analyze_and_reset() {
+        use crate::core::log::{InputEvent, LogMetadata};
+
+        let log = InputLog {
+            metadata: LogMetadata {
+                frame_count: 100,
+                ..Default::default()
+            },
+            mappings: vec![],
+            events: vec![InputEvent {
+                frame: 50,
+                id: 0,
+                kind: InputKind::Button,
+                state: ButtonState::Pressed,
+                value: [1.0, 0.0],
+                contact: 0,
+            }],
+            frame_timestamps: Vec::new(),
+            annotations: Vec::new(),
+        };
+
+        let mut state = IdleState::new();
+        assert!(state.show_on_timeline);
+        assert!(state.report.is_none());
+
+        state.config.min_frames = 10;
+        state.analyze(&log);
+        let report = state.report.as_ref().unwrap();
+        assert_eq!(report.count(IdleKind::Idle), 1);
+        assert_eq!(report.count(IdleKind::NoEvents), 2);
+
+        state.panel_open = true;
+        state.reset();
+        assert!(state.report.is_none());
+        // panel_open and config should remain unchanged
+        assert!(state.panel_open);
+        assert_eq!(state.config.min_frames, 10);
+    }
+
     #[test]
     fn test_selection_state_new() {
         let state = SelectionState::new();
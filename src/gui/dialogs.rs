@@ -15,6 +15,8 @@ use crate::core::error::AppError;
 pub enum ErrorDialogAction {
     /// User wants to retry the failed operation
     Retry,
+    /// User wants to load the events that survived in a damaged file
+    LoadRecoverable,
     /// User dismissed the dialog
     Close,
 }
@@ -125,6 +127,7 @@ impl<'a> ErrorDialogRenderer<'a> {
         let brief = error.brief_description();
         let detailed = error.detailed_info();
         let supports_retry = error.supports_retry();
+        let recoverable_events = error.recoverable_events();
         let is_recoverable = error.is_recoverable();
 
         // Create modal overlay
@@ -238,6 +241,17 @@ impl<'a> ErrorDialogRenderer<'a> {
                             should_close = true;
                             action = Some(ErrorDialogAction::Retry);
                         }
+
+                        // Load anyway (only for damaged files with complete events left)
+                        if let Some(count) = recoverable_events
+                            && ui
+                                .button(format!("📥 Load {} events", count))
+                                .on_hover_text("Load the complete events before the damage")
+                                .clicked()
+                        {
+                            should_close = true;
+                            action = Some(ErrorDialogAction::LoadRecoverable);
+                        }
                     });
                 });
 
@@ -3,12 +3,20 @@
 //! This module handles the visualization of input events over frames,
 //! including drawing the frame grid, input rows, and event representations.
 
-use eframe::egui::{self, Color32, Painter, Pos2, Rect, Stroke};
-use std::collections::HashMap;
+use eframe::egui::{self, Color32, Painter, Pos2, Rangef, Rect, Stroke};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
 
-use crate::core::config::ColorSettings;
+use crate::core::config::{ColorSettings, DEFAULT_ROW_HEIGHT, RowDensity, RowSettings};
+use crate::core::density::DensityMap;
+use crate::core::edit::down_ranges;
 use crate::core::filter::FilterState;
-use crate::core::log::{Bookmark, ButtonState, InputEvent, InputKind, InputLog, InputMapping};
+use crate::core::idle::{IdleKind, IdleSpan};
+use crate::core::layout::{LayoutRow, ROW_SCALES, RowLayout};
+use crate::core::log::{
+    Bookmark, ButtonState, InputEvent, InputKind, InputLog, InputMapping, TelemetryChannel,
+    player_label,
+};
 use crate::core::search::SearchResult;
 
 /// Default number of visible frames in the timeline.
@@ -23,17 +31,17 @@ pub const MAX_VISIBLE_FRAMES: u64 = 10000;
 /// Zoom factor applied when scrolling (multiplier per scroll step).
 const ZOOM_FACTOR: f32 = 1.15;
 
-/// Height of each input row in pixels.
-const ROW_HEIGHT: f32 = 32.0;
+/// Row heights below which headers and cell padding shrink as well.
+const COMPACT_THRESHOLD: f32 = 20.0;
 
-/// Width of the label column on the left side.
-const LABEL_WIDTH: f32 = 120.0;
+/// Height of the game event track in pixels.
+const ANNOTATION_TRACK_HEIGHT: f32 = 22.0;
 
-/// Padding inside cells.
-const CELL_PADDING: f32 = 2.0;
+/// Height of each telemetry channel track in pixels.
+const TELEMETRY_TRACK_HEIGHT: f32 = 28.0;
 
-/// Height of the frame number header.
-const HEADER_HEIGHT: f32 = 20.0;
+/// Width of the label column on the left side.
+const LABEL_WIDTH: f32 = 120.0;
 
 /// Height of the legend area at the bottom.
 const LEGEND_HEIGHT: f32 = 30.0;
@@ -41,6 +49,15 @@ const LEGEND_HEIGHT: f32 = 30.0;
 /// Height of the scrollbar area.
 const SCROLLBAR_HEIGHT: f32 = 16.0;
 
+/// Height of the minimap strip above the frame header.
+const MINIMAP_HEIGHT: f32 = 40.0;
+
+/// Height of the frame-time track shown for logs with timestamps.
+const FRAME_TIME_TRACK_HEIGHT: f32 = 28.0;
+
+/// Width of the vertical scrollbar of the rows.
+const ROW_SCROLLBAR_WIDTH: f32 = 8.0;
+
 /// Scroll speed in frames per scroll step.
 const SCROLL_SPEED: f32 = 10.0;
 
@@ -60,6 +77,28 @@ pub enum ViewAction {
     UpdateSelection { frame: u64 },
     /// Finish the range selection.
     FinishSelection,
+    /// Collapse or expand the rows of a player (None for inputs without a player).
+    ToggleGroup { player: Option<u32> },
+    /// Edit the events of an input on a frame (edit mode only).
+    EditCell { id: u32, frame: u64 },
+    /// Scroll the rows vertically, in pixels from the top.
+    ScrollRows { row_scroll: f32 },
+    /// Start dragging the row of an input by its label.
+    StartRowDrag { id: u32 },
+    /// Drop the dragged row in front of another input's row, or at the end.
+    MoveRow { id: u32, before: Option<u32> },
+    /// Stop dragging a row without moving it.
+    CancelRowDrag,
+    /// Pin the row of an input to the top, or unpin it.
+    TogglePin { id: u32 },
+    /// Collapse or expand a named group.
+    ToggleNamedGroup { name: String },
+    /// Put an input into a named group, or take it out of its group.
+    SetRowGroup { id: u32, group: Option<String> },
+    /// Take all inputs out of a named group.
+    Ungroup { name: String },
+    /// Change the height multiplier of an input row.
+    SetRowScale { id: u32, scale: f32 },
 }
 
 /// Configuration for timeline rendering.
@@ -72,6 +111,14 @@ pub struct TimelineConfig {
     pub current_frame: u64,
     /// Total number of frames in the log
     pub total_frames: u64,
+    /// Player groups whose rows are collapsed (None for inputs without a player)
+    pub collapsed_groups: HashSet<Option<u32>>,
+    /// Order, pinned rows and collapsed named groups
+    pub layout: RowLayout,
+    /// Vertical scroll of the rows below the pinned ones, in pixels
+    pub row_scroll: f32,
+    /// Input whose row is being dragged to a new position
+    pub dragging_row: Option<u32>,
 }
 
 impl Default for TimelineConfig {
@@ -81,6 +128,10 @@ impl Default for TimelineConfig {
             visible_frames: DEFAULT_VISIBLE_FRAMES.max(1),
             current_frame: 0,
             total_frames: 0,
+            collapsed_groups: HashSet::new(),
+            layout: RowLayout::default(),
+            row_scroll: 0.0,
+            dragging_row: None,
         }
     }
 }
@@ -90,6 +141,152 @@ impl TimelineConfig {
     pub fn zoom_percentage(&self) -> f32 {
         (DEFAULT_VISIBLE_FRAMES as f32 / self.visible_frames as f32) * 100.0
     }
+
+    /// Collapse or expand a player group.
+    pub fn toggle_group(&mut self, player: Option<u32>) {
+        if !self.collapsed_groups.remove(&player) {
+            self.collapsed_groups.insert(player);
+        }
+    }
+}
+
+/// A row of the timeline.
+enum TimelineRow {
+    /// Collapsible header above the inputs of a player
+    Group {
+        /// The player, or None for inputs without a player
+        player: Option<u32>,
+        /// Number of visible inputs in the group
+        inputs: usize,
+        /// Whether the inputs are hidden
+        collapsed: bool,
+    },
+    /// Collapsible header above the inputs of a named group
+    NamedGroup {
+        /// Group name
+        name: String,
+        /// Number of visible inputs in the group
+        inputs: usize,
+        /// Whether the inputs are hidden
+        collapsed: bool,
+    },
+    /// An input, as an index into `effective_mappings`
+    Input(usize),
+    /// The game events (annotations without a value)
+    Annotations,
+    /// A telemetry channel, as an index into `telemetry`
+    Telemetry(usize),
+}
+
+impl TimelineRow {
+    /// Height of the row in pixels.
+    ///
+    /// # Arguments
+    /// * `metrics` - Sizes for the current row height
+    /// * `scale` - Height multiplier of an input row
+    fn height(&self, metrics: &RowMetrics, scale: f32) -> f32 {
+        match self {
+            Self::Group { .. } | Self::NamedGroup { .. } => metrics.group_header_height,
+            Self::Input(_) => metrics.row_height * scale,
+            Self::Annotations => ANNOTATION_TRACK_HEIGHT,
+            Self::Telemetry(_) => TELEMETRY_TRACK_HEIGHT,
+        }
+    }
+}
+
+/// Sizes of the parts of the timeline that follow the row height.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RowMetrics {
+    /// Height of an input row at a height multiplier of 1
+    row_height: f32,
+    /// Height of a group header row
+    group_header_height: f32,
+    /// Height of the frame number header
+    header_height: f32,
+    /// Padding inside cells
+    cell_padding: f32,
+}
+
+impl RowMetrics {
+    /// Metrics for a row height; short rows get compact headers and padding.
+    fn for_row_height(row_height: f32) -> Self {
+        if row_height < COMPACT_THRESHOLD {
+            Self {
+                row_height,
+                group_header_height: 14.0,
+                header_height: 16.0,
+                cell_padding: 1.0,
+            }
+        } else {
+            Self {
+                row_height,
+                group_header_height: 20.0,
+                header_height: 20.0,
+                cell_padding: 2.0,
+            }
+        }
+    }
+}
+
+/// Row a context menu was opened on.
+#[derive(Clone)]
+enum RowMenuTarget {
+    /// The row of an input, by ID
+    Input(u32),
+    /// The header of a named group
+    Group(String),
+}
+
+/// A press of an input with button states, however its holds are encoded.
+struct HoldSpan {
+    /// Index in `log.events` of the event that started the press
+    press: usize,
+    /// Frames the input is down for
+    frames: Range<u64>,
+}
+
+/// Find the presses of all inputs with button states.
+///
+/// The events of each input and touch contact are taken in frame order and
+/// split into presses by `edit::down_ranges`, so unsorted logs draw the same
+/// as sorted ones.
+///
+/// # Arguments
+/// * `log` - The log
+/// * `frame_order` - Indices into `log.events` in frame order
+fn hold_spans(log: &InputLog, frame_order: &[usize]) -> Vec<HoldSpan> {
+    let mut streams: BTreeMap<(u32, u16), Vec<usize>> = BTreeMap::new();
+    for &index in frame_order {
+        let event = &log.events[index];
+        if event.kind.has_button_state() {
+            streams
+                .entry((event.id, event.contact))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    let mut spans = Vec::new();
+    for indices in streams.values() {
+        let events: Vec<InputEvent> = indices.iter().map(|&i| log.events[i].clone()).collect();
+        for frames in down_ranges(&events, log.metadata.frame_count) {
+            // The Pressed or Held event the press starts with
+            let start = events
+                .iter()
+                .position(|e| e.frame == frames.start && e.state != ButtonState::Released)
+                .unwrap_or(0);
+            spans.push(HoldSpan {
+                press: indices[start],
+                frames,
+            });
+        }
+    }
+    spans
+}
+
+/// Whether an analog event is at rest, drawn only as a faint indicator.
+fn is_resting(event: &InputEvent) -> bool {
+    event.value[0].abs() < 0.01 && event.value[1].abs() < 0.01
 }
 
 /// Timeline renderer that draws input events over frames.
@@ -110,14 +307,50 @@ pub struct TimelineRenderer<'a> {
     selection: Option<(u64, u64)>,
     /// Whether a selection drag is currently in progress
     selection_dragging: bool,
+    /// Idle and event-gap spans to shade (optional)
+    idle_spans: Option<&'a [IdleSpan]>,
+    /// Hitch threshold in microseconds; enables the frame-time track (optional)
+    hitch_threshold_us: Option<u64>,
+    /// Whether clicking a cell edits it
+    edit_mode: bool,
+    /// Cell being edited, as (input ID, frame)
+    edit_cell: Option<(u32, u64)>,
+    /// Event density of the whole log; enables the minimap (optional)
+    minimap: Option<&'a DensityMap>,
     /// Effective mappings including fallback entries for unmapped IDs
     effective_mappings: Vec<InputMapping>,
-    /// Visible mappings based on current filter (indices into effective_mappings)
-    visible_mapping_indices: Vec<usize>,
+    /// Rows to draw: annotation tracks, then visible inputs, grouped per
+    /// player in multi-player logs
+    rows: Vec<TimelineRow>,
+    /// Telemetry channels of the log, empty if the tracks are hidden
+    telemetry: Vec<TelemetryChannel>,
+    /// Row height settings; rows are the default height without them (optional)
+    row_settings: Option<&'a RowSettings>,
+    /// Sizes that follow the row height
+    metrics: RowMetrics,
+    /// Height of each row
+    row_heights: Vec<f32>,
+    /// Top of each row relative to the bottom of the frame header, without
+    /// the vertical scroll
+    row_tops: Vec<f32>,
+    /// Number of rows at the top that don't scroll vertically: annotation
+    /// tracks and pinned rows
+    fixed_rows: usize,
     /// Map from input ID to row index (among visible rows)
     id_to_row: HashMap<u32, usize>,
     /// Map from input ID to index in effective_mappings (for name and color)
     id_to_mapping_index: HashMap<u32, usize>,
+    /// Indices into `log.events` in frame order; the log itself may be unsorted
+    frame_order: Vec<usize>,
+    /// Pointer position of each event (accumulated for mouse deltas), indexed like `log.events`
+    pointer_positions: Vec<Option<[f32; 2]>>,
+    /// Range covered by each pointer input, used to place positions inside a cell
+    pointer_bounds: HashMap<u32, Rect>,
+    /// Presses of inputs with button states, so dense and sparse holds draw alike
+    hold_spans: Vec<HoldSpan>,
+    /// Samples of axis and trigger inputs that change the value, as indices
+    /// into `log.events` in frame order, per input ID
+    analog_samples: HashMap<u32, Vec<usize>>,
 }
 
 impl<'a> TimelineRenderer<'a> {
@@ -155,14 +388,136 @@ impl<'a> TimelineRenderer<'a> {
             .map(|(i, _)| i)
             .collect();
 
-        // Build ID to row mapping based on visible mappings order
-        let id_to_row: HashMap<u32, usize> = visible_mapping_indices
+        // Order rows and gather named groups as the layout says
+        let layout = &config.layout;
+        let arrange = |inputs: &[usize]| -> Vec<TimelineRow> {
+            let items: Vec<(u32, Option<&str>)> = inputs
+                .iter()
+                .map(|&idx| {
+                    let mapping = &effective_mappings[idx];
+                    (mapping.id, mapping.group.as_deref())
+                })
+                .collect();
+            layout
+                .arrange(&items)
+                .into_iter()
+                .map(|row| match row {
+                    LayoutRow::Group {
+                        name,
+                        inputs,
+                        collapsed,
+                    } => TimelineRow::NamedGroup {
+                        name,
+                        inputs,
+                        collapsed,
+                    },
+                    LayoutRow::Input(id) => TimelineRow::Input(id_to_mapping_index[&id]),
+                })
+                .collect()
+        };
+
+        // Pinned rows stay at the top while the other rows scroll
+        let pinned_rows: Vec<TimelineRow> = layout
+            .pinned
+            .iter()
+            .filter_map(|id| id_to_mapping_index.get(id).copied())
+            .filter(|idx| visible_mapping_indices.contains(idx))
+            .map(TimelineRow::Input)
+            .collect();
+
+        // Group rows per player when the log has players; inputs without one come last
+        let players = log.players();
+        let input_rows: Vec<TimelineRow> = if players.is_empty() {
+            arrange(&visible_mapping_indices)
+        } else {
+            let groups = players
+                .into_iter()
+                .map(Some)
+                .chain(std::iter::once(None))
+                .filter(|player| player.is_none_or(|p| filter.is_player_visible(p)));
+            let mut rows = Vec::new();
+            for player in groups {
+                let inputs: Vec<usize> = visible_mapping_indices
+                    .iter()
+                    .copied()
+                    .filter(|&idx| effective_mappings[idx].player == player)
+                    .collect();
+                if inputs.is_empty() {
+                    continue;
+                }
+                let collapsed = config.collapsed_groups.contains(&player);
+                rows.push(TimelineRow::Group {
+                    player,
+                    inputs: inputs.len(),
+                    collapsed,
+                });
+                if !collapsed {
+                    rows.extend(arrange(&inputs));
+                }
+            }
+            rows
+        };
+
+        // Game events and telemetry go above the inputs
+        let telemetry = if filter.show_annotations {
+            log.telemetry_channels()
+        } else {
+            Vec::new()
+        };
+        let mut rows = Vec::new();
+        if filter.show_annotations && log.markers().next().is_some() {
+            rows.push(TimelineRow::Annotations);
+        }
+        rows.extend((0..telemetry.len()).map(TimelineRow::Telemetry));
+        rows.extend(pinned_rows);
+        let fixed_rows = rows.len();
+        rows.extend(input_rows);
+
+        // Build ID to row mapping based on visible rows order
+        let id_to_row: HashMap<u32, usize> = rows
             .iter()
             .enumerate()
-            .map(|(row, &idx)| (effective_mappings[idx].id, row))
+            .filter_map(|(row, entry)| match entry {
+                TimelineRow::Input(idx) => Some((effective_mappings[*idx].id, row)),
+                _ => None,
+            })
             .collect();
 
-        Self {
+        // Stable, so events on the same frame keep their order
+        let mut frame_order: Vec<usize> = (0..log.events.len()).collect();
+        frame_order.sort_by_key(|&index| log.events[index].frame);
+
+        let pointer_positions = log.pointer_positions();
+        let mut pointer_bounds: HashMap<u32, Rect> = HashMap::new();
+        for (event, position) in log.events.iter().zip(&pointer_positions) {
+            if let Some([px, py]) = *position {
+                let point = Pos2::new(px, py);
+                pointer_bounds
+                    .entry(event.id)
+                    .and_modify(|bounds| bounds.extend_with(point))
+                    .or_insert_with(|| Rect::from_min_max(point, point));
+            }
+        }
+
+        // Repeated samples draw the same as the value held from an earlier one
+        let mut analog_samples: HashMap<u32, Vec<usize>> = HashMap::new();
+        for &index in &frame_order {
+            let event = &log.events[index];
+            if !event.kind.is_sampled() {
+                continue;
+            }
+            let samples = analog_samples.entry(event.id).or_default();
+            if samples
+                .last()
+                .is_none_or(|&last| log.events[last].value != event.value)
+            {
+                samples.push(index);
+            }
+        }
+
+        let hold_spans = hold_spans(log, &frame_order);
+
+        let mut renderer = Self {
             log,
             config,
             filter,
@@ -171,11 +526,29 @@ impl<'a> TimelineRenderer<'a> {
             bookmarks: None,
             selection: None,
             selection_dragging: false,
+            idle_spans: None,
+            hitch_threshold_us: None,
+            edit_mode: false,
+            edit_cell: None,
+            minimap: None,
             effective_mappings,
-            visible_mapping_indices,
+            rows,
+            telemetry,
+            row_settings: None,
+            metrics: RowMetrics::for_row_height(DEFAULT_ROW_HEIGHT),
+            row_heights: Vec::new(),
+            row_tops: Vec::new(),
+            fixed_rows,
             id_to_row,
             id_to_mapping_index,
-        }
+            frame_order,
+            pointer_positions,
+            pointer_bounds,
+            hold_spans,
+            analog_samples,
+        };
+        renderer.layout_rows();
+        renderer
     }
 
     /// Set search results for highlighting matching frames.
@@ -197,6 +570,108 @@ impl<'a> TimelineRenderer<'a> {
         self
     }
 
+    /// Set idle detection spans for shading.
+    pub fn with_idle_spans(mut self, spans: &'a [IdleSpan]) -> Self {
+        self.idle_spans = Some(spans);
+        self
+    }
+
+    /// Show the frame-time track, highlighting frames longer than the threshold.
+    ///
+    /// The track is only drawn if the log has frame timestamps.
+    pub fn with_frame_times(mut self, hitch_threshold_us: u64) -> Self {
+        self.hitch_threshold_us = Some(hitch_threshold_us.max(1));
+        self
+    }
+
+    /// Enable edit mode, in which clicking an input cell requests an edit.
+    ///
+    /// The cell being edited, if any, is outlined.
+    pub fn with_edit_mode(mut self, edit_cell: Option<(u32, u64)>) -> Self {
+        self.edit_mode = true;
+        self.edit_cell = edit_cell;
+        self
+    }
+
+    /// Size rows by the row height settings.
+    pub fn with_row_settings(mut self, settings: &'a RowSettings) -> Self {
+        self.row_settings = Some(settings);
+        self.metrics = RowMetrics::for_row_height(settings.base_height());
+        self.layout_rows();
+        self
+    }
+
+    /// Height multiplier of a row; 1.0 for rows other than inputs.
+    fn row_scale(&self, row: &TimelineRow) -> f32 {
+        match *row {
+            TimelineRow::Input(idx) => self.config.layout.scale(self.effective_mappings[idx].id),
+            _ => 1.0,
+        }
+    }
+
+    /// Compute the height and top of every row from the metrics.
+    fn layout_rows(&mut self) {
+        self.row_heights = self
+            .rows
+            .iter()
+            .map(|row| row.height(&self.metrics, self.row_scale(row)))
+            .collect();
+        self.row_tops = self
+            .row_heights
+            .iter()
+            .scan(0.0, |top, height| {
+                let row_top = *top;
+                *top += height;
+                Some(row_top)
+            })
+            .collect();
+    }
+
+    /// Size input rows so that all rows fit in the given height.
+    fn fit_rows(&mut self, height: f32) {
+        let chrome = self.minimap_height()
+            + self.metrics.header_height
+            + self.frame_time_track_height()
+            + SCROLLBAR_HEIGHT
+            + LEGEND_HEIGHT;
+        let mut other_rows = 0.0;
+        let mut total_scale = 0.0;
+        for (row, &row_height) in self.rows.iter().zip(&self.row_heights) {
+            if matches!(row, TimelineRow::Input(_)) {
+                total_scale += self.row_scale(row);
+            } else {
+                other_rows += row_height;
+            }
+        }
+        let row_height = RowSettings::fit_height(total_scale, height - chrome - other_rows);
+        self.metrics = RowMetrics::for_row_height(row_height);
+        self.layout_rows();
+    }
+
+    /// Show a minimap of the whole log above the timeline.
+    pub fn with_minimap(mut self, density: &'a DensityMap) -> Self {
+        self.minimap = Some(density);
+        self
+    }
+
+    /// Height of the minimap, or 0 if it is not shown.
+    fn minimap_height(&self) -> f32 {
+        if self.minimap.is_some() {
+            MINIMAP_HEIGHT
+        } else {
+            0.0
+        }
+    }
+
+    /// Height of the frame-time track, or 0 if it is not shown.
+    fn frame_time_track_height(&self) -> f32 {
+        if self.hitch_threshold_us.is_some() && self.log.has_frame_timestamps() {
+            FRAME_TIME_TRACK_HEIGHT
+        } else {
+            0.0
+        }
+    }
+
     /// Get the color for an input ID, or a default color if not mapped.
     fn get_color(&self, id: u32) -> Color32 {
         self.id_to_mapping_index
@@ -211,16 +686,135 @@ impl<'a> TimelineRenderer<'a> {
         self.id_to_row.get(&id).copied()
     }
 
+    /// Total height of all rows, at least one input row high.
+    fn rows_height(&self) -> f32 {
+        let height: f32 = self.row_heights.iter().sum();
+        height.max(self.metrics.row_height)
+    }
+
+    /// Height of the rows that don't scroll vertically.
+    fn fixed_rows_height(&self) -> f32 {
+        self.row_heights[..self.fixed_rows].iter().sum()
+    }
+
+    /// Largest vertical scroll that still fills the rows area.
+    fn max_row_scroll(&self, timeline_rect: Rect) -> f32 {
+        (self.rows_height() - timeline_rect.height()).max(0.0)
+    }
+
+    /// Vertical scroll of the scrolling rows, limited to the rows area.
+    fn row_scroll(&self, timeline_rect: Rect) -> f32 {
+        self.config
+            .row_scroll
+            .clamp(0.0, self.max_row_scroll(timeline_rect))
+    }
+
+    /// Top of a row on screen.
+    fn row_top(&self, row: usize, timeline_rect: Rect) -> f32 {
+        let top = timeline_rect.top() + self.row_tops[row];
+        if row < self.fixed_rows {
+            top
+        } else {
+            top - self.row_scroll(timeline_rect)
+        }
+    }
+
+    /// Area a row may be drawn in across the full width, including the label
+    /// column: the fixed rows at the top, or the scrolling area below them.
+    fn row_clip(&self, row: usize, timeline_rect: Rect) -> Rect {
+        let fixed_bottom = timeline_rect.top() + self.fixed_rows_height();
+        let (top, bottom) = if row < self.fixed_rows {
+            (timeline_rect.top(), fixed_bottom)
+        } else {
+            (fixed_bottom, timeline_rect.bottom())
+        };
+        Rect::from_min_max(
+            Pos2::new(timeline_rect.left() - LABEL_WIDTH, top),
+            Pos2::new(timeline_rect.right(), bottom.max(top)),
+        )
+    }
+
+    /// Screen rectangle of a row across the full width, clipped to its area.
+    fn row_rect(&self, row: usize, timeline_rect: Rect) -> Rect {
+        let top = self.row_top(row, timeline_rect);
+        Rect::from_min_max(
+            Pos2::new(timeline_rect.left() - LABEL_WIDTH, top),
+            Pos2::new(timeline_rect.right(), top + self.row_heights[row]),
+        )
+        .intersect(self.row_clip(row, timeline_rect))
+    }
+
+    /// Vertical extent of a row on screen, before clipping.
+    fn row_y(&self, row: usize, timeline_rect: Rect) -> Rangef {
+        let top = self.row_top(row, timeline_rect);
+        Rangef::new(top, top + self.row_heights[row])
+    }
+
+    /// Whether any part of a row is on screen.
+    fn is_row_visible(&self, row: usize, timeline_rect: Rect) -> bool {
+        self.row_rect(row, timeline_rect).is_positive()
+    }
+
+    /// Painter that clips drawing to the area of a row.
+    fn row_painter(&self, painter: &Painter, row: usize, timeline_rect: Rect) -> Painter {
+        painter.with_clip_rect(self.row_clip(row, timeline_rect))
+    }
+
+    /// Find the row at a vertical position.
+    fn row_at(&self, y: f32, timeline_rect: Rect) -> Option<usize> {
+        (0..self.rows.len()).find(|&row| {
+            let rect = self.row_rect(row, timeline_rect);
+            y >= rect.top() && y < rect.bottom()
+        })
+    }
+
+    /// Where a dragged row would be dropped for a pointer position.
+    ///
+    /// # Returns
+    /// The input to move the row in front of (`None` for the end) and the
+    /// vertical position of the insertion line.
+    fn row_drop_target(&self, y: f32, timeline_rect: Rect) -> Option<(Option<u32>, f32)> {
+        let row = self.row_at(y, timeline_rect)?;
+        let top = self.row_top(row, timeline_rect);
+        let upper_half = y < top + self.row_heights[row] / 2.0;
+        let first = if matches!(self.rows[row], TimelineRow::Input(_)) && upper_half {
+            row
+        } else {
+            row + 1
+        };
+        let line_row = if upper_half { row } else { row + 1 };
+        let line_y = match line_row {
+            r if r < self.rows.len() => self.row_top(r, timeline_rect),
+            _ => top + self.row_heights[row],
+        };
+        let before = self.rows[first.min(self.rows.len())..]
+            .iter()
+            .find_map(|row| match row {
+                TimelineRow::Input(idx) => Some(self.effective_mappings[*idx].id),
+                _ => None,
+            });
+        Some((before, line_y))
+    }
+
     /// Calculate the total height needed for the timeline.
     pub fn calculate_height(&self) -> f32 {
-        let num_rows = self.visible_mapping_indices.len().max(1);
-        HEADER_HEIGHT + (num_rows as f32 * ROW_HEIGHT) + SCROLLBAR_HEIGHT + LEGEND_HEIGHT
+        self.minimap_height()
+            + self.metrics.header_height
+            + self.rows_height()
+            + self.frame_time_track_height()
+            + SCROLLBAR_HEIGHT
+            + LEGEND_HEIGHT
     }
 
     /// Render the complete timeline and return any view actions triggered by user interaction.
-    pub fn render(&self, ui: &mut egui::Ui) -> Option<ViewAction> {
+    pub fn render(&mut self, ui: &mut egui::Ui) -> Option<ViewAction> {
         let available_size = ui.available_size();
-        let num_rows = self.visible_mapping_indices.len().max(1);
+        if self
+            .row_settings
+            .is_some_and(|settings| settings.density == RowDensity::FitAll)
+        {
+            self.fit_rows(available_size.y - 10.0);
+        }
         let grid_height = self.calculate_height().min(available_size.y - 10.0);
 
         let (response, painter) = ui.allocate_painter(
@@ -228,16 +822,32 @@ impl<'a> TimelineRenderer<'a> {
             egui::Sense::click_and_drag(),
         );
 
-        let rect = response.rect;
+        // The minimap goes above everything else
+        self.draw_background(&painter, response.rect);
+        let minimap_rect = Rect::from_min_max(
+            Pos2::new(response.rect.left() + LABEL_WIDTH, response.rect.top()),
+            Pos2::new(
+                response.rect.right(),
+                response.rect.top() + self.minimap_height(),
+            ),
+        );
+        let rect = Rect::from_min_max(
+            Pos2::new(response.rect.left(), minimap_rect.bottom()),
+            response.rect.max,
+        );
 
         // Calculate layout areas from bottom to top
         let legend_top = rect.bottom() - LEGEND_HEIGHT;
         let scrollbar_top = legend_top - SCROLLBAR_HEIGHT;
-        let content_bottom = scrollbar_top;
+        let track_top = scrollbar_top - self.frame_time_track_height();
+        let content_bottom = track_top;
 
         // Calculate timeline area (excluding label column, scrollbar and legend)
         let timeline_rect = Rect::from_min_max(
-            Pos2::new(rect.left() + LABEL_WIDTH, rect.top() + HEADER_HEIGHT),
+            Pos2::new(
+                rect.left() + LABEL_WIDTH,
+                rect.top() + self.metrics.header_height,
+            ),
             Pos2::new(rect.right(), content_bottom),
         );
 
@@ -250,25 +860,313 @@ impl<'a> TimelineRenderer<'a> {
             Pos2::new(rect.right(), legend_top),
         );
 
+        // Calculate frame-time track rect (full width, empty if not shown)
+        let track_rect = Rect::from_min_max(
+            Pos2::new(rect.left(), track_top),
+            Pos2::new(rect.right(), scrollbar_top),
+        );
+
         // Calculate legend area rect
         let legend_area_rect = Rect::from_min_max(Pos2::new(rect.left(), legend_top), rect.max);
 
         // Draw components
-        self.draw_background(&painter, rect);
+        self.draw_minimap(&painter, minimap_rect);
         self.draw_frame_header(&painter, content_rect, timeline_rect);
-        self.draw_row_labels(&painter, content_rect, num_rows);
-        self.draw_grid(&painter, content_rect, timeline_rect, num_rows);
+        self.draw_row_labels(&painter, content_rect, timeline_rect);
+        self.draw_grid(&painter, content_rect, timeline_rect);
+        self.draw_idle_spans(&painter, content_rect, timeline_rect);
         self.draw_selection_highlight(&painter, content_rect, timeline_rect);
         self.draw_search_highlights(&painter, content_rect, timeline_rect);
         self.draw_bookmark_markers(&painter, rect, timeline_rect);
         self.draw_events(&painter, timeline_rect);
+        self.draw_annotation_tracks(&painter, timeline_rect);
+        self.draw_edit_cell(&painter, timeline_rect);
         self.draw_current_frame_indicator(&painter, content_rect, timeline_rect);
+        self.draw_row_scrollbar(&painter, timeline_rect);
+        self.draw_row_drop_indicator(ui, &painter, content_rect, timeline_rect);
+        self.draw_frame_time_track(&painter, track_rect, timeline_rect);
         self.draw_scrollbar(&painter, scrollbar_rect);
         self.draw_button_state_legend(&painter, legend_area_rect);
         self.draw_zoom_indicator(&painter, legend_area_rect);
 
         // Handle mouse interactions (scroll, zoom, scrollbar drag, selection)
-        self.handle_mouse_interaction(ui, &response, timeline_rect, scrollbar_rect)
+        let action = self.handle_mouse_interaction(
+            ui,
+            &response,
+            timeline_rect,
+            scrollbar_rect,
+            minimap_rect,
+        );
+        let menu_action = self.row_context_menu(ui, &response, timeline_rect);
+        action.or(menu_action)
+    }
+
+    /// Toggle a player group when its header is clicked.
+    fn handle_group_header_click(
+        &self,
+        response: &egui::Response,
+        timeline_rect: Rect,
+    ) -> Option<ViewAction> {
+        if !response.clicked() {
+            return None;
+        }
+        let pos = response.interact_pointer_pos()?;
+        match self.rows.get(self.row_at(pos.y, timeline_rect)?)? {
+            TimelineRow::Group { player, .. } => Some(ViewAction::ToggleGroup { player: *player }),
+            TimelineRow::NamedGroup { name, .. } => {
+                Some(ViewAction::ToggleNamedGroup { name: name.clone() })
+            }
+            _ => None,
+        }
+    }
+
+    /// Handle dragging a row by its label to a new position.
+    fn handle_row_drag(
+        &self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        timeline_rect: Rect,
+    ) -> Option<ViewAction> {
+        let label_column = Rect::from_min_max(
+            Pos2::new(timeline_rect.left() - LABEL_WIDTH, timeline_rect.top()),
+            Pos2::new(timeline_rect.left(), timeline_rect.bottom()),
+        );
+
+        if let Some(id) = self.config.dragging_row {
+            if !response.drag_stopped() {
+                return None;
+            }
+            let pointer = ui.ctx().input(|i| i.pointer.latest_pos());
+            return Some(
+                match pointer.and_then(|pos| self.row_drop_target(pos.y, timeline_rect)) {
+                    Some((before, _)) => ViewAction::MoveRow { id, before },
+                    None => ViewAction::CancelRowDrag,
+                },
+            );
+        }
+
+        if !response.drag_started() {
+            return None;
+        }
+        let origin = ui.ctx().input(|i| i.pointer.press_origin())?;
+        if !label_column.contains(origin) {
+            return None;
+        }
+        match self.rows.get(self.row_at(origin.y, timeline_rect)?)? {
+            TimelineRow::Input(idx) => Some(ViewAction::StartRowDrag {
+                id: self.effective_mappings[*idx].id,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Track of the vertical scrollbar of the rows, if they don't all fit.
+    fn row_scrollbar_rect(&self, timeline_rect: Rect) -> Option<Rect> {
+        if self.max_row_scroll(timeline_rect) <= 0.0 {
+            return None;
+        }
+        Some(Rect::from_min_max(
+            Pos2::new(
+                timeline_rect.right() - ROW_SCROLLBAR_WIDTH,
+                timeline_rect.top() + self.fixed_rows_height(),
+            ),
+            timeline_rect.max,
+        ))
+    }
+
+    /// Thumb of the vertical scrollbar within its track.
+    fn row_scrollbar_thumb(&self, track: Rect, timeline_rect: Rect) -> Rect {
+        let scrolling_height = self.rows_height() - self.fixed_rows_height();
+        let visible = (track.height() / scrolling_height).clamp(0.05, 1.0);
+        let thumb_height = track.height() * visible;
+        let max_scroll = self.max_row_scroll(timeline_rect);
+        let ratio = self.row_scroll(timeline_rect) / max_scroll;
+        let top = track.top() + (track.height() - thumb_height) * ratio;
+        Rect::from_min_size(
+            Pos2::new(track.left(), top),
+            egui::vec2(track.width(), thumb_height),
+        )
+    }
+
+    /// Handle dragging the vertical scrollbar of the rows.
+    fn handle_row_scrollbar_drag(
+        &self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        timeline_rect: Rect,
+    ) -> Option<ViewAction> {
+        let track = self.row_scrollbar_rect(timeline_rect)?;
+        if !response.dragged() {
+            return None;
+        }
+        let origin = ui.ctx().input(|i| i.pointer.press_origin())?;
+        if !track.contains(origin) {
+            return None;
+        }
+        let pointer = response.interact_pointer_pos()?;
+        let thumb = self.row_scrollbar_thumb(track, timeline_rect);
+        let usable = (track.height() - thumb.height()).max(1.0);
+        let ratio = ((pointer.y - track.top() - thumb.height() / 2.0) / usable).clamp(0.0, 1.0);
+        Some(ViewAction::ScrollRows {
+            row_scroll: ratio * self.max_row_scroll(timeline_rect),
+        })
+    }
+
+    /// Show the context menu of a row: pin it, or move it into a named group.
+    fn row_context_menu(
+        &self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        timeline_rect: Rect,
+    ) -> Option<ViewAction> {
+        let menu_id = response.id.with("row_menu");
+        let new_group_id = menu_id.with("new_group");
+        let ctx = ui.ctx();
+        if response.secondary_clicked() {
+            let row = response
+                .interact_pointer_pos()
+                .filter(|pos| timeline_rect.contains(*pos) || pos.x < timeline_rect.left())
+                .and_then(|pos| self.row_at(pos.y, timeline_rect));
+            let target = row.and_then(|row| match &self.rows[row] {
+                TimelineRow::Input(idx) => {
+                    Some(RowMenuTarget::Input(self.effective_mappings[*idx].id))
+                }
+                TimelineRow::NamedGroup { name, .. } => Some(RowMenuTarget::Group(name.clone())),
+                _ => None,
+            });
+            ctx.data_mut(|d| {
+                d.insert_temp(menu_id, target);
+                d.insert_temp(new_group_id, String::new());
+            });
+        }
+
+        let mut action = None;
+        response.context_menu(|ui| {
+            let target: Option<RowMenuTarget> = ctx.data(|d| d.get_temp(menu_id)).flatten();
+            match target {
+                Some(RowMenuTarget::Input(id)) => {
+                    let mapping = self
+                        .id_to_mapping_index
+                        .get(&id)
+                        .map(|&idx| &self.effective_mappings[idx]);
+                    let group = mapping.and_then(|m| m.group.clone());
+                    ui.weak(mapping.map(|m| m.qualified_name()).unwrap_or_default());
+                    let pin_text = if self.config.layout.is_pinned(id) {
+                        "Unpin"
+                    } else {
+                        "📌 Pin to top"
+                    };
+                    if ui.button(pin_text).clicked() {
+                        action = Some(ViewAction::TogglePin { id });
+                        ui.close();
+                    }
+                    ui.menu_button("Row height", |ui| {
+                        let current = self.config.layout.scale(id);
+                        for scale in ROW_SCALES {
+                            if ui.radio(current == scale, format!("{}×", scale)).clicked() {
+                                action = Some(ViewAction::SetRowScale { id, scale });
+                                ui.close();
+                            }
+                        }
+                    });
+                    ui.separator();
+
+                    let mut groups: Vec<&str> = self
+                        .effective_mappings
+                        .iter()
+                        .filter_map(|m| m.group.as_deref())
+                        .filter(|name| Some(*name) != group.as_deref())
+                        .collect();
+                    groups.sort_unstable();
+                    groups.dedup();
+                    for name in groups {
+                        if ui.button(format!("Move to group \"{}\"", name)).clicked() {
+                            action = Some(ViewAction::SetRowGroup {
+                                id,
+                                group: Some(name.to_string()),
+                            });
+                            ui.close();
+                        }
+                    }
+                    ui.horizontal(|ui| {
+                        let mut name: String =
+                            ctx.data(|d| d.get_temp(new_group_id)).unwrap_or_default();
+                        ui.add(
+                            egui::TextEdit::singleline(&mut name)
+                                .hint_text("New group")
+                                .desired_width(100.0),
+                        );
+                        let trimmed = name.trim();
+                        if ui
+                            .add_enabled(!trimmed.is_empty(), egui::Button::new("Create"))
+                            .clicked()
+                        {
+                            action = Some(ViewAction::SetRowGroup {
+                                id,
+                                group: Some(trimmed.to_string()),
+                            });
+                            ui.close();
+                        }
+                        ctx.data_mut(|d| d.insert_temp(new_group_id, name));
+                    });
+                    if let Some(group) = group
+                        && ui
+                            .button(format!("Remove from group \"{}\"", group))
+                            .clicked()
+                    {
+                        action = Some(ViewAction::SetRowGroup { id, group: None });
+                        ui.close();
+                    }
+                }
+                Some(RowMenuTarget::Group(name)) => {
+                    let toggle_text = if self.config.layout.is_collapsed(&name) {
+                        "Expand"
+                    } else {
+                        "Collapse"
+                    };
+                    if ui.button(toggle_text).clicked() {
+                        action = Some(ViewAction::ToggleNamedGroup { name: name.clone() });
+                        ui.close();
+                    }
+                    if ui.button("Ungroup").clicked() {
+                        action = Some(ViewAction::Ungroup { name });
+                        ui.close();
+                    }
+                }
+                None => ui.close(),
+            }
+        });
+        action
+    }
+
+    /// Request an edit of the clicked input cell in edit mode.
+    fn handle_edit_click(
+        &self,
+        response: &egui::Response,
+        timeline_rect: Rect,
+    ) -> Option<ViewAction> {
+        if !self.edit_mode || !response.clicked() {
+            return None;
+        }
+        let pos = response.interact_pointer_pos()?;
+        if !timeline_rect.contains(pos) {
+            return None;
+        }
+        let TimelineRow::Input(mapping_idx) = *self.rows.get(self.row_at(pos.y, timeline_rect)?)?
+        else {
+            return None;
+        };
+
+        let frame_width = timeline_rect.width() / self.config.visible_frames as f32;
+        let frame_offset = ((pos.x - timeline_rect.left()) / frame_width) as u64;
+        let frame = self.config.scroll_offset + frame_offset;
+        if frame >= self.config.total_frames {
+            return None;
+        }
+        Some(ViewAction::EditCell {
+            id: self.effective_mappings[mapping_idx].id,
+            frame,
+        })
     }
 
     /// Handle mouse interactions: wheel scroll, Ctrl+wheel zoom, scrollbar drag, and timeline drag-to-pan.
@@ -278,9 +1176,38 @@ impl<'a> TimelineRenderer<'a> {
         response: &egui::Response,
         timeline_rect: Rect,
         scrollbar_rect: Rect,
+        minimap_rect: Rect,
     ) -> Option<ViewAction> {
         let ctx = ui.ctx();
 
+        // Describe the hovered cell
+        self.show_hover_tooltip(response, timeline_rect);
+
+        // Handle dragging rows by their label
+        if let Some(action) = self.handle_row_drag(ui, response, timeline_rect) {
+            return Some(action);
+        }
+
+        // Handle the vertical scrollbar of the rows
+        if let Some(action) = self.handle_row_scrollbar_drag(ui, response, timeline_rect) {
+            return Some(action);
+        }
+
+        // Handle clicks on player group headers
+        if let Some(action) = self.handle_group_header_click(response, timeline_rect) {
+            return Some(action);
+        }
+
+        // Handle clicks on input cells in edit mode
+        if let Some(action) = self.handle_edit_click(response, timeline_rect) {
+            return Some(action);
+        }
+
+        // Handle clicks and drags on the minimap
+        if let Some(action) = self.handle_minimap_drag(ui, response, minimap_rect) {
+            return Some(action);
+        }
+
         // Handle scrollbar drag
         if let Some(action) = self.handle_scrollbar_drag(response, scrollbar_rect) {
             return Some(action);
@@ -296,6 +1223,19 @@ impl<'a> TimelineRenderer<'a> {
         let over_timeline = timeline_rect.contains(pointer_pos);
         let over_scrollbar = scrollbar_rect.contains(pointer_pos);
 
+        // Wheel over the row labels scrolls the rows vertically
+        let over_labels = pointer_pos.x < timeline_rect.left()
+            && pointer_pos.y >= timeline_rect.top()
+            && pointer_pos.y < timeline_rect.bottom();
+        if over_labels && self.max_row_scroll(timeline_rect) > 0.0 {
+            let scroll_delta_y = ctx.input(|i| i.raw_scroll_delta.y);
+            if scroll_delta_y != 0.0 {
+                let row_scroll = (self.row_scroll(timeline_rect) - scroll_delta_y)
+                    .clamp(0.0, self.max_row_scroll(timeline_rect));
+                return Some(ViewAction::ScrollRows { row_scroll });
+            }
+        }
+
         if !over_timeline && !over_scrollbar {
             return None;
         }
@@ -372,6 +1312,205 @@ impl<'a> TimelineRenderer<'a> {
         })
     }
 
+    /// Show a tooltip with the details of the hovered cell.
+    fn show_hover_tooltip(&self, response: &egui::Response, timeline_rect: Rect) {
+        if response.dragged() {
+            return;
+        }
+        let Some(pos) = response.hover_pos() else {
+            return;
+        };
+        if !timeline_rect.contains(pos) {
+            return;
+        }
+        let frame_width = timeline_rect.width() / self.config.visible_frames as f32;
+        let frame =
+            self.config.scroll_offset + ((pos.x - timeline_rect.left()) / frame_width) as u64;
+        if frame >= self.config.total_frames {
+            return;
+        }
+        let Some(row) = self.row_at(pos.y, timeline_rect) else {
+            return;
+        };
+        let Some((title, lines)) = self.cell_details(row, frame) else {
+            return;
+        };
+
+        let header = format!("Frame {} · {:.3} s", frame, self.log.frame_time_secs(frame));
+        response.clone().on_hover_ui_at_pointer(|ui| {
+            ui.weak(header);
+            ui.strong(title);
+            for line in lines {
+                ui.label(line);
+            }
+        });
+    }
+
+    /// Describe the contents of a cell for its tooltip.
+    ///
+    /// # Returns
+    /// The name of the row and one line per event or value, or `None` for
+    /// rows without details.
+    fn cell_details(&self, row: usize, frame: u64) -> Option<(String, Vec<String>)> {
+        match self.rows.get(row)? {
+            TimelineRow::Input(idx) => {
+                let mapping = &self.effective_mappings[*idx];
+                Some((
+                    mapping.qualified_name(),
+                    self.input_details(mapping.id, frame),
+                ))
+            }
+            TimelineRow::Annotations => {
+                let lines: Vec<String> = self
+                    .log
+                    .markers()
+                    .filter(|a| a.frame == frame)
+                    .map(|a| a.label.clone())
+                    .collect();
+                (!lines.is_empty()).then(|| ("Game events".to_string(), lines))
+            }
+            TimelineRow::Telemetry(index) => {
+                let channel = &self.telemetry[*index];
+                let value = channel.value_at(frame)?;
+                Some((channel.name.clone(), vec![format!("{}", value)]))
+            }
+            TimelineRow::Group { .. } | TimelineRow::NamedGroup { .. } => None,
+        }
+    }
+
+    /// Describe the events of an input on a frame, what is held or in effect
+    /// there, and for how long it has been held.
+    fn input_details(&self, id: u32, frame: u64) -> Vec<String> {
+        let events = &self.log.events;
+        let first = self
+            .frame_order
+            .partition_point(|&index| events[index].frame < frame);
+        let cell: Vec<(usize, &InputEvent)> = self.frame_order[first..]
+            .iter()
+            .map(|&index| (index, &events[index]))
+            .take_while(|(_, e)| e.frame == frame)
+            .filter(|(_, e)| e.id == id)
+            .collect();
+        let mut lines: Vec<String> = cell
+            .iter()
+            .map(|&(index, e)| self.describe_event(index, e))
+            .collect();
+
+        // Presses covering the frame, one per touch contact
+        for span in &self.hold_spans {
+            let press = &events[span.press];
+            if press.id != id || !span.frames.contains(&frame) {
+                continue;
+            }
+            let held = frame - span.frames.start + 1;
+            let secs = self.log.frame_time_secs(frame)
+                + self.log.effective_frame_duration_us(frame) as f64 / 1_000_000.0
+                - self.log.frame_time_secs(span.frames.start);
+            let contact = match press.kind {
+                InputKind::Touch => format!(" (contact {})", press.contact),
+                _ => String::new(),
+            };
+            // Sparse logs have no Held event to describe
+            if !cell.iter().any(|(_, e)| e.contact == press.contact) {
+                lines.push(format!("Held{}", contact));
+            }
+            lines.push(format!(
+                "Down for {} frame{} ({:.0} ms) since frame {}{}",
+                held,
+                if held == 1 { "" } else { "s" },
+                secs * 1000.0,
+                span.frames.start,
+                contact
+            ));
+        }
+
+        // Value held from an earlier sample of an axis or trigger
+        if lines.is_empty()
+            && let Some(samples) = self.analog_samples.get(&id)
+        {
+            let seen = samples.partition_point(|&i| events[i].frame <= frame);
+            if let Some(&index) = seen.checked_sub(1).and_then(|i| samples.get(i)) {
+                let event = &events[index];
+                lines.push(format!(
+                    "{} (sampled at frame {})",
+                    self.describe_event(index, event),
+                    event.frame
+                ));
+            }
+        }
+        lines
+    }
+
+    /// Describe one event: its state for inputs with button states, its values otherwise.
+    fn describe_event(&self, index: usize, event: &InputEvent) -> String {
+        match event.kind {
+            InputKind::Button => format!("{:?}", event.state),
+            InputKind::Touch => format!(
+                "{:?} (contact {}) at ({:.2}, {:.2})",
+                event.state, event.contact, event.value[0], event.value[1]
+            ),
+            InputKind::Key => match event.value[0] as u32 {
+                code @ 0x21..=0x7e => {
+                    format!(
+                        "{:?} '{}' (key {})",
+                        event.state,
+                        char::from(code as u8),
+                        code
+                    )
+                }
+                code => format!("{:?} (key {})", event.state, code),
+            },
+            InputKind::Axis1D | InputKind::Trigger => format!("Value {:.3}", event.value[0]),
+            InputKind::Axis2D => format!("X {:.3}, Y {:.3}", event.value[0], event.value[1]),
+            InputKind::MouseDelta => {
+                let mut line = format!("Delta ({:.1}, {:.1})", event.value[0], event.value[1]);
+                if let Some([x, y]) = self.pointer_positions.get(index).copied().flatten() {
+                    line.push_str(&format!(" → ({:.1}, {:.1})", x, y));
+                }
+                line
+            }
+            InputKind::PointerPosition => {
+                format!("Position ({:.1}, {:.1})", event.value[0], event.value[1])
+            }
+        }
+    }
+
+    /// Handle clicks and drags on the minimap.
+    ///
+    /// Dragging the viewport rectangle moves it along; clicking or dragging
+    /// anywhere else centers the view on the pointer.
+    fn handle_minimap_drag(
+        &self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        minimap_rect: Rect,
+    ) -> Option<ViewAction> {
+        if self.minimap.is_none() || !(response.dragged() || response.clicked()) {
+            return None;
+        }
+        let origin = ui.ctx().input(|i| i.pointer.press_origin())?;
+        if !minimap_rect.contains(origin) || minimap_rect.width() <= 0.0 {
+            return None;
+        }
+
+        let total_frames = self.config.total_frames.max(1);
+        let frames_per_pixel = total_frames as f32 / minimap_rect.width();
+        let max_scroll = total_frames.saturating_sub(self.config.visible_frames);
+        let scroll_offset = if self.minimap_viewport(minimap_rect).contains(origin) {
+            let delta = response.drag_delta().x * frames_per_pixel;
+            (self.config.scroll_offset as f32 + delta).max(0.0) as u64
+        } else {
+            let pointer = response.interact_pointer_pos()?;
+            let frame = (pointer.x - minimap_rect.left()).max(0.0) * frames_per_pixel;
+            (frame as u64).saturating_sub(self.config.visible_frames / 2)
+        };
+
+        // Also returned when unchanged, so the drag doesn't pan the timeline
+        Some(ViewAction::Scroll {
+            scroll_offset: scroll_offset.min(max_scroll),
+        })
+    }
+
     /// Handle drag-to-pan interaction on the timeline area.
     /// Without Shift: pans the timeline.
     /// With Shift: selects a range.
@@ -593,6 +1732,192 @@ impl<'a> TimelineRenderer<'a> {
         );
     }
 
+    /// Horizontal position of a frame on the minimap.
+    fn minimap_x(&self, minimap_rect: Rect, frame: u64) -> f32 {
+        let total_frames = self.config.total_frames.max(1);
+        minimap_rect.left()
+            + minimap_rect.width() * frame.min(total_frames) as f32 / total_frames as f32
+    }
+
+    /// The rectangle of the minimap matching the visible frames.
+    fn minimap_viewport(&self, minimap_rect: Rect) -> Rect {
+        let start = self.config.scroll_offset;
+        let end = start + self.config.visible_frames;
+        let left = self.minimap_x(minimap_rect, start);
+        // At least a few pixels wide so it can be grabbed when zoomed in
+        let right = self.minimap_x(minimap_rect, end).max(left + 4.0);
+        Rect::from_min_max(
+            Pos2::new(left, minimap_rect.top() + 1.0),
+            Pos2::new(right, minimap_rect.bottom() - 1.0),
+        )
+    }
+
+    /// Draw the minimap: a heat strip of event density per input row over
+    /// the whole log, with the selection, search hits, bookmarks, current
+    /// frame and the visible frames marked on it.
+    fn draw_minimap(&self, painter: &Painter, minimap_rect: Rect) {
+        let Some(density) = self.minimap else {
+            return;
+        };
+        let label_rect = Rect::from_min_max(
+            Pos2::new(minimap_rect.left() - LABEL_WIDTH, minimap_rect.top()),
+            Pos2::new(minimap_rect.left(), minimap_rect.bottom()),
+        );
+        painter.rect_filled(label_rect, 0.0, self.colors.label_background_color());
+        painter.text(
+            Pos2::new(label_rect.left() + 8.0, label_rect.center().y),
+            egui::Align2::LEFT_CENTER,
+            "Overview",
+            egui::FontId::proportional(11.0),
+            self.colors.text_dim_color(),
+        );
+        painter.rect_filled(minimap_rect, 0.0, self.colors.header_background_color());
+
+        // Selection below everything else
+        if let Some((start, end)) = self.selection {
+            let selection_rect = Rect::from_min_max(
+                Pos2::new(self.minimap_x(minimap_rect, start), minimap_rect.top()),
+                Pos2::new(self.minimap_x(minimap_rect, end + 1), minimap_rect.bottom()),
+            );
+            painter.rect_filled(selection_rect, 0.0, self.colors.selection_color_alpha(60));
+        }
+
+        // One thin strip per visible input row
+        let strip_rect = minimap_rect.shrink2(egui::vec2(0.0, 2.0));
+        let ids: Vec<u32> = self
+            .rows
+            .iter()
+            .filter_map(|row| match row {
+                TimelineRow::Input(idx) => Some(self.effective_mappings[*idx].id),
+                _ => None,
+            })
+            .collect();
+        if !ids.is_empty() {
+            let row_height = strip_rect.height() / ids.len() as f32;
+            let bucket_frames = density.frames_per_bucket();
+            for (i, &id) in ids.iter().enumerate() {
+                let Some(row) = density.row(id) else {
+                    continue;
+                };
+                let color = self.get_color(id);
+                let top = strip_rect.top() + i as f32 * row_height;
+                for (bucket, &fraction) in row.iter().enumerate() {
+                    if fraction <= 0.0 {
+                        continue;
+                    }
+                    let start = bucket as u64 * bucket_frames;
+                    let left = self.minimap_x(strip_rect, start);
+                    let right = self
+                        .minimap_x(strip_rect, start + bucket_frames)
+                        .max(left + 1.0);
+                    // Square root so sparse activity stays visible
+                    let intensity = 0.2 + 0.8 * fraction.sqrt();
+                    painter.rect_filled(
+                        Rect::from_min_max(
+                            Pos2::new(left, top),
+                            Pos2::new(right, top + row_height.max(1.0)),
+                        ),
+                        0.0,
+                        color.gamma_multiply(intensity),
+                    );
+                }
+            }
+        }
+
+        // Search hits and bookmarks as ticks
+        if let Some(results) = self.search_results {
+            let current = results.current_frame();
+            for &frame in &results.matches {
+                let x = self.minimap_x(minimap_rect, frame);
+                let color = if current == Some(frame) {
+                    self.colors.search_current_color()
+                } else {
+                    self.colors.search_other_color_alpha(160)
+                };
+                painter.line_segment(
+                    [
+                        Pos2::new(x, minimap_rect.top()),
+                        Pos2::new(x, minimap_rect.top() + 6.0),
+                    ],
+                    Stroke::new(1.5, color),
+                );
+            }
+        }
+        if let Some(bookmarks) = self.bookmarks {
+            for bookmark in bookmarks {
+                let x = self.minimap_x(minimap_rect, bookmark.frame);
+                painter.line_segment(
+                    [
+                        Pos2::new(x, minimap_rect.bottom() - 6.0),
+                        Pos2::new(x, minimap_rect.bottom()),
+                    ],
+                    Stroke::new(1.5, self.colors.bookmark_color()),
+                );
+            }
+        }
+
+        let x = self.minimap_x(minimap_rect, self.config.current_frame);
+        painter.line_segment(
+            [
+                Pos2::new(x, minimap_rect.top()),
+                Pos2::new(x, minimap_rect.bottom()),
+            ],
+            Stroke::new(1.0, self.colors.current_frame_color()),
+        );
+
+        // The visible frames
+        let viewport = self.minimap_viewport(minimap_rect);
+        painter.rect_filled(
+            viewport,
+            2.0,
+            self.colors.scrollbar_thumb_color().gamma_multiply(0.3),
+        );
+        painter.rect_stroke(
+            viewport,
+            2.0,
+            Stroke::new(1.5, self.colors.scrollbar_border_color()),
+            egui::StrokeKind::Inside,
+        );
+    }
+
+    /// Draw the vertical scrollbar of the rows, if they don't all fit.
+    fn draw_row_scrollbar(&self, painter: &Painter, timeline_rect: Rect) {
+        let Some(track) = self.row_scrollbar_rect(timeline_rect) else {
+            return;
+        };
+        painter.rect_filled(track, 2.0, self.colors.scrollbar_track_color());
+        let thumb = self.row_scrollbar_thumb(track, timeline_rect);
+        painter.rect_filled(thumb, 4.0, self.colors.scrollbar_thumb_color());
+        painter.rect_stroke(
+            thumb,
+            4.0,
+            Stroke::new(1.0, self.colors.scrollbar_border_color()),
+            egui::StrokeKind::Inside,
+        );
+    }
+
+    /// Draw where the row being dragged would be dropped.
+    fn draw_row_drop_indicator(
+        &self,
+        ui: &egui::Ui,
+        painter: &Painter,
+        rect: Rect,
+        timeline_rect: Rect,
+    ) {
+        if self.config.dragging_row.is_none() {
+            return;
+        }
+        let Some(pointer) = ui.ctx().input(|i| i.pointer.latest_pos()) else {
+            return;
+        };
+        if let Some((_, y)) = self.row_drop_target(pointer.y, timeline_rect) {
+            painter.line_segment(
+                [Pos2::new(rect.left(), y), Pos2::new(rect.right(), y)],
+                Stroke::new(2.0, self.colors.current_frame_color()),
+            );
+        }
+    }
+
     /// Draw the background and border.
     fn draw_background(&self, painter: &Painter, rect: Rect) {
         // Fill background
@@ -612,7 +1937,7 @@ impl<'a> TimelineRenderer<'a> {
         let frame_width = timeline_rect.width() / self.config.visible_frames as f32;
         let header_rect = Rect::from_min_max(
             Pos2::new(rect.left() + LABEL_WIDTH, rect.top()),
-            Pos2::new(rect.right(), rect.top() + HEADER_HEIGHT),
+            Pos2::new(rect.right(), rect.top() + self.metrics.header_height),
         );
 
         // Draw header background
@@ -634,7 +1959,7 @@ impl<'a> TimelineRenderer<'a> {
 
             if x >= timeline_rect.left() && x <= timeline_rect.right() {
                 painter.text(
-                    Pos2::new(x, rect.top() + HEADER_HEIGHT / 2.0),
+                    Pos2::new(x, rect.top() + self.metrics.header_height / 2.0),
                     egui::Align2::CENTER_CENTER,
                     format!("{}", frame),
                     egui::FontId::proportional(10.0),
@@ -648,8 +1973,8 @@ impl<'a> TimelineRenderer<'a> {
         // Draw separator line below header
         painter.line_segment(
             [
-                Pos2::new(rect.left(), rect.top() + HEADER_HEIGHT),
-                Pos2::new(rect.right(), rect.top() + HEADER_HEIGHT),
+                Pos2::new(rect.left(), rect.top() + self.metrics.header_height),
+                Pos2::new(rect.right(), rect.top() + self.metrics.header_height),
             ],
             Stroke::new(1.0, self.colors.grid_color()),
         );
@@ -675,9 +2000,9 @@ impl<'a> TimelineRenderer<'a> {
     }
 
     /// Draw the row labels on the left side.
-    fn draw_row_labels(&self, painter: &Painter, rect: Rect, num_rows: usize) {
+    fn draw_row_labels(&self, painter: &Painter, rect: Rect, timeline_rect: Rect) {
         let label_rect = Rect::from_min_max(
-            Pos2::new(rect.left(), rect.top() + HEADER_HEIGHT),
+            Pos2::new(rect.left(), rect.top() + self.metrics.header_height),
             Pos2::new(rect.left() + LABEL_WIDTH, rect.bottom()),
         );
 
@@ -693,52 +2018,184 @@ impl<'a> TimelineRenderer<'a> {
             Stroke::new(1.0, self.colors.grid_color()),
         );
 
-        // Draw each row label (using filtered visible mappings)
-        for i in 0..num_rows {
-            let row_top = rect.top() + HEADER_HEIGHT + (i as f32 * ROW_HEIGHT);
-            let row_center_y = row_top + ROW_HEIGHT / 2.0;
-
-            if i < self.visible_mapping_indices.len() {
-                let mapping_idx = self.visible_mapping_indices[i];
-                let mapping = &self.effective_mappings[mapping_idx];
-                let color = mapping
-                    .color
-                    .map(|c| Color32::from_rgb(c[0], c[1], c[2]))
-                    .unwrap_or(self.colors.text_label_color());
-
-                // Draw color indicator
-                let indicator_rect = Rect::from_min_size(
-                    Pos2::new(rect.left() + 4.0, row_center_y - 4.0),
-                    egui::vec2(8.0, 8.0),
-                );
-                painter.rect_filled(indicator_rect, 2.0, color);
+        // Draw each row label (using filtered visible mappings)
+        for (index, row) in self.rows.iter().enumerate() {
+            if !self.is_row_visible(index, timeline_rect) {
+                continue;
+            }
+            let painter = &self.row_painter(painter, index, timeline_rect);
+            let row_top = self.row_top(index, timeline_rect);
+            let row_height = self.row_heights[index];
+            let row_center_y = row_top + row_height / 2.0;
+            let header_rect = Rect::from_min_max(
+                Pos2::new(rect.left(), row_top),
+                Pos2::new(rect.right(), row_top + self.metrics.group_header_height),
+            );
+
+            match *row {
+                TimelineRow::Group {
+                    player,
+                    inputs,
+                    collapsed,
+                } => {
+                    let label = self.player_group_label(player);
+                    self.draw_group_header(painter, header_rect, label, inputs, collapsed);
+                }
+                TimelineRow::NamedGroup {
+                    ref name,
+                    inputs,
+                    collapsed,
+                } => {
+                    // Indented below a player header
+                    let indented = header_rect.with_min_x(header_rect.left() + 8.0);
+                    self.draw_group_header(painter, indented, name.clone(), inputs, collapsed);
+                }
+                TimelineRow::Input(mapping_idx) => {
+                    let mapping = &self.effective_mappings[mapping_idx];
+                    let color = mapping
+                        .color
+                        .map(|c| Color32::from_rgb(c[0], c[1], c[2]))
+                        .unwrap_or(self.colors.text_label_color());
+
+                    // Draw color indicator, shrunk to fit short rows
+                    let indicator_size = (row_height - 4.0).clamp(4.0, 8.0);
+                    let indicator_rect = Rect::from_center_size(
+                        Pos2::new(rect.left() + 8.0, row_center_y),
+                        egui::vec2(indicator_size, indicator_size),
+                    );
+                    painter.rect_filled(indicator_rect, 2.0, color);
+
+                    // Draw label text (uses mapping name which includes fallback)
+                    painter.text(
+                        Pos2::new(rect.left() + 16.0, row_center_y),
+                        egui::Align2::LEFT_CENTER,
+                        &mapping.name,
+                        egui::FontId::proportional((row_height - 2.0).clamp(7.0, 12.0)),
+                        self.colors.text_label_color(),
+                    );
+
+                    if self.config.layout.is_pinned(mapping.id) {
+                        painter.text(
+                            Pos2::new(rect.left() + LABEL_WIDTH - 4.0, row_center_y),
+                            egui::Align2::RIGHT_CENTER,
+                            "📌",
+                            egui::FontId::proportional(10.0),
+                            self.colors.text_dim_color(),
+                        );
+                    }
+                    // Highlight the row being dragged
+                    if self.config.dragging_row == Some(mapping.id) {
+                        painter.rect_filled(
+                            Rect::from_min_size(
+                                Pos2::new(rect.left(), row_top),
+                                egui::vec2(LABEL_WIDTH, row_height),
+                            ),
+                            0.0,
+                            self.colors.selection_color_alpha(60),
+                        );
+                    }
+                }
+                TimelineRow::Annotations => {
+                    painter.text(
+                        Pos2::new(rect.left() + 4.0, row_center_y),
+                        egui::Align2::LEFT_CENTER,
+                        "Events",
+                        egui::FontId::proportional(11.0),
+                        self.colors.text_header_color(),
+                    );
+                }
+                TimelineRow::Telemetry(index) => {
+                    let channel = &self.telemetry[index];
+                    painter.text(
+                        Pos2::new(rect.left() + 4.0, row_center_y - 5.0),
+                        egui::Align2::LEFT_CENTER,
+                        &channel.name,
+                        egui::FontId::proportional(11.0),
+                        self.colors.text_header_color(),
+                    );
+                    painter.text(
+                        Pos2::new(rect.left() + 4.0, row_center_y + 7.0),
+                        egui::Align2::LEFT_CENTER,
+                        format!("{} – {}", channel.min, channel.max),
+                        egui::FontId::proportional(9.0),
+                        self.colors.text_dim_color(),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Label of the header of a player group.
+    fn player_group_label(&self, player: Option<u32>) -> String {
+        let mut label = match player {
+            Some(player) => player_label(player),
+            None => "Other inputs".to_string(),
+        };
+        // Name the device if all of the player's inputs come from the same one
+        let mut devices = self
+            .effective_mappings
+            .iter()
+            .filter(|m| m.player == player)
+            .map(|m| m.device);
+        if let Some(Some(device)) = devices.next()
+            && devices.all(|d| d == Some(device))
+        {
+            label = format!("{} · {:?}", label, device);
+        }
+        label
+    }
+
+    /// Draw the collapsible header of a player or named group across the full width.
+    fn draw_group_header(
+        &self,
+        painter: &Painter,
+        header_rect: Rect,
+        mut label: String,
+        inputs: usize,
+        collapsed: bool,
+    ) {
+        painter.rect_filled(header_rect, 0.0, self.colors.header_background_color());
 
-                // Draw label text (uses mapping name which includes fallback)
-                painter.text(
-                    Pos2::new(rect.left() + 16.0, row_center_y),
-                    egui::Align2::LEFT_CENTER,
-                    &mapping.name,
-                    egui::FontId::proportional(12.0),
-                    self.colors.text_label_color(),
-                );
-            }
+        if collapsed {
+            label = format!("{} ({} inputs)", label, inputs);
         }
+
+        let arrow = if collapsed { "▶" } else { "▼" };
+        painter.text(
+            Pos2::new(header_rect.left() + 4.0, header_rect.center().y),
+            egui::Align2::LEFT_CENTER,
+            format!("{} {}", arrow, label),
+            egui::FontId::proportional(11.0),
+            self.colors.text_header_color(),
+        );
     }
 
     /// Draw the grid lines.
-    fn draw_grid(&self, painter: &Painter, rect: Rect, timeline_rect: Rect, num_rows: usize) {
+    fn draw_grid(&self, painter: &Painter, rect: Rect, timeline_rect: Rect) {
         let frame_width = timeline_rect.width() / self.config.visible_frames as f32;
         let grid_color = self.colors.grid_color();
 
         // Draw horizontal row separators
-        for i in 1..num_rows {
-            let y = rect.top() + HEADER_HEIGHT + (i as f32 * ROW_HEIGHT);
-            painter.line_segment(
+        for row in 1..self.rows.len() {
+            if !self.is_row_visible(row, timeline_rect) {
+                continue;
+            }
+            let y = self.row_top(row, timeline_rect);
+            self.row_painter(painter, row, timeline_rect).line_segment(
                 [Pos2::new(rect.left(), y), Pos2::new(rect.right(), y)],
                 Stroke::new(0.5, grid_color),
             );
         }
 
+        // Set the pinned rows apart from the rows scrolling below them
+        if self.fixed_rows > 0 && self.fixed_rows < self.rows.len() {
+            let y = timeline_rect.top() + self.fixed_rows_height();
+            painter.line_segment(
+                [Pos2::new(rect.left(), y), Pos2::new(rect.right(), y)],
+                Stroke::new(1.5, grid_color),
+            );
+        }
+
         // Draw vertical frame lines at major intervals
         let interval = self.calculate_frame_interval();
         let start_frame = self.config.scroll_offset;
@@ -754,7 +2211,7 @@ impl<'a> TimelineRenderer<'a> {
             if x >= timeline_rect.left() && x <= timeline_rect.right() {
                 painter.line_segment(
                     [
-                        Pos2::new(x, rect.top() + HEADER_HEIGHT),
+                        Pos2::new(x, rect.top() + self.metrics.header_height),
                         Pos2::new(x, rect.bottom()),
                     ],
                     Stroke::new(0.5, grid_color),
@@ -771,13 +2228,23 @@ impl<'a> TimelineRenderer<'a> {
         let start_frame = self.config.scroll_offset;
         let end_frame = start_frame + self.config.visible_frames;
 
+        self.draw_holds(painter, timeline_rect, frame_width);
+        self.draw_analog_samples(painter, timeline_rect, frame_width);
+
         // Filter events to visible range and draw them
-        for event in &self.log.events {
+        for (index, event) in self.log.events.iter().enumerate() {
             // Skip events outside visible range
             if event.frame < start_frame || event.frame >= end_frame {
                 continue;
             }
 
+            // Holds and analog values are drawn from the precomputed spans and samples
+            if (event.state == ButtonState::Held && event.kind.has_button_state())
+                || event.kind.is_sampled()
+            {
+                continue;
+            }
+
             // Skip events that are filtered out by type or ID filter
             if !self.filter.is_visible(event.id, event.kind) {
                 continue;
@@ -789,19 +2256,123 @@ impl<'a> TimelineRenderer<'a> {
                 None => continue,
             };
 
+            // Skip rows scrolled out of view
+            if !self.is_row_visible(row, timeline_rect) {
+                continue;
+            }
+            let painter = &self.row_painter(painter, row, timeline_rect);
+
             let color = self.get_color(event.id);
             let x = timeline_rect.left() + ((event.frame - start_frame) as f32 * frame_width);
-            let row_top = timeline_rect.top() + (row as f32 * ROW_HEIGHT);
+            let row_y = self.row_y(row, timeline_rect);
 
             match event.kind {
                 InputKind::Button => {
-                    self.draw_button_event(painter, x, row_top, frame_width, color, event.state);
+                    self.draw_button_event(painter, x, row_y, frame_width, color, event.state);
+                }
+                InputKind::Axis1D | InputKind::Axis2D | InputKind::Trigger => {}
+                InputKind::MouseDelta | InputKind::PointerPosition => {
+                    let position = self.pointer_positions.get(index).copied().flatten();
+                    self.draw_pointer_event(painter, event, position, x, row_y, frame_width, color);
+                }
+                InputKind::Touch | InputKind::Key => {
+                    self.draw_button_event(painter, x, row_y, frame_width, color, event.state);
+                    self.draw_event_label(painter, event, x, row_y, frame_width);
+                }
+            }
+        }
+    }
+
+    /// Fill the frames each press is held for.
+    ///
+    /// The Pressed and Released edges are drawn from the events themselves;
+    /// a press without a Pressed event (starting with Held) is filled from
+    /// its first frame.
+    fn draw_holds(&self, painter: &Painter, timeline_rect: Rect, frame_width: f32) {
+        let start_frame = self.config.scroll_offset;
+        let end_frame = start_frame + self.config.visible_frames;
+
+        for span in &self.hold_spans {
+            let press = &self.log.events[span.press];
+            let fill_start = match press.state {
+                ButtonState::Pressed => span.frames.start + 1,
+                _ => span.frames.start,
+            };
+            let frames = fill_start.max(start_frame)..span.frames.end.min(end_frame);
+            if frames.is_empty() || !self.filter.is_visible(press.id, press.kind) {
+                continue;
+            }
+            let Some(row) = self.get_row(press.id) else {
+                continue;
+            };
+            if !self.is_row_visible(row, timeline_rect) {
+                continue;
+            }
+            let painter = &self.row_painter(painter, row, timeline_rect);
+
+            let color = self.get_color(press.id);
+            let row_y = self.row_y(row, timeline_rect);
+            for frame in frames {
+                let x = timeline_rect.left() + ((frame - start_frame) as f32 * frame_width);
+                self.draw_button_event(painter, x, row_y, frame_width, color, ButtonState::Held);
+                self.draw_event_label(painter, press, x, row_y, frame_width);
+            }
+        }
+    }
+
+    /// Draw axis and trigger inputs on every frame from the value in effect.
+    ///
+    /// A value is held until the next sample, so it is drawn on the frames in
+    /// between as well; the faint indicator of a value at rest is only drawn
+    /// on the frame it was sampled.
+    fn draw_analog_samples(&self, painter: &Painter, timeline_rect: Rect, frame_width: f32) {
+        let start_frame = self.config.scroll_offset;
+        let end_frame = (start_frame + self.config.visible_frames)
+            .min(self.log.metadata.frame_count.max(start_frame));
+
+        for (&id, samples) in &self.analog_samples {
+            let kind = self.log.events[samples[0]].kind;
+            if !self.filter.is_visible(id, kind) {
+                continue;
+            }
+            let Some(row) = self.get_row(id) else {
+                continue;
+            };
+            if !self.is_row_visible(row, timeline_rect) {
+                continue;
+            }
+            let painter = &self.row_painter(painter, row, timeline_rect);
+
+            let color = self.get_color(id);
+            let row_y = self.row_y(row, timeline_rect);
+            // Number of samples at or before the current frame
+            let mut seen = samples.partition_point(|&i| self.log.events[i].frame < start_frame);
+            for frame in start_frame..end_frame {
+                while samples
+                    .get(seen)
+                    .is_some_and(|&i| self.log.events[i].frame <= frame)
+                {
+                    seen += 1;
                 }
-                InputKind::Axis1D => {
-                    self.draw_axis1d_event(painter, event, x, row_top, frame_width, color);
+                let Some(&index) = seen.checked_sub(1).and_then(|i| samples.get(i)) else {
+                    continue;
+                };
+                let event = &self.log.events[index];
+                if event.frame != frame && is_resting(event) {
+                    continue;
                 }
-                InputKind::Axis2D => {
-                    self.draw_axis2d_event(painter, event, x, row_top, frame_width, color);
+
+                let x = timeline_rect.left() + ((frame - start_frame) as f32 * frame_width);
+                match kind {
+                    InputKind::Axis1D => {
+                        self.draw_axis1d_event(painter, event, x, row_y, frame_width, color);
+                    }
+                    InputKind::Axis2D => {
+                        self.draw_axis2d_event(painter, event, x, row_y, frame_width, color);
+                    }
+                    _ => {
+                        self.draw_trigger_event(painter, event, x, row_y, frame_width, color);
+                    }
                 }
             }
         }
@@ -817,16 +2388,20 @@ impl<'a> TimelineRenderer<'a> {
         &self,
         painter: &Painter,
         x: f32,
-        row_top: f32,
+        row_y: Rangef,
         frame_width: f32,
         color: Color32,
         state: ButtonState,
     ) {
+        let (row_top, row_height) = (row_y.min, row_y.span());
         let cell_rect = Rect::from_min_size(
-            Pos2::new(x + CELL_PADDING, row_top + CELL_PADDING),
+            Pos2::new(
+                x + self.metrics.cell_padding,
+                row_top + self.metrics.cell_padding,
+            ),
             egui::vec2(
-                frame_width - CELL_PADDING * 2.0,
-                ROW_HEIGHT - CELL_PADDING * 2.0,
+                frame_width - self.metrics.cell_padding * 2.0,
+                row_height - self.metrics.cell_padding * 2.0,
             ),
         );
 
@@ -873,16 +2448,17 @@ impl<'a> TimelineRenderer<'a> {
         painter: &Painter,
         event: &InputEvent,
         x: f32,
-        row_top: f32,
+        row_y: Rangef,
         frame_width: f32,
         color: Color32,
     ) {
+        let (row_top, row_height) = (row_y.min, row_y.span());
         let value = event.value[0];
         let abs_value = value.abs();
 
         if abs_value < 0.01 {
             // Draw minimal indicator for zero value
-            let center_y = row_top + ROW_HEIGHT / 2.0;
+            let center_y = row_top + row_height / 2.0;
             let indicator_rect = Rect::from_min_size(
                 Pos2::new(x + frame_width / 2.0 - 1.0, center_y - 1.0),
                 egui::vec2(2.0, 2.0),
@@ -891,13 +2467,13 @@ impl<'a> TimelineRenderer<'a> {
             return;
         }
 
-        let cell_height = ROW_HEIGHT - CELL_PADDING * 2.0;
+        let cell_height = row_height - self.metrics.cell_padding * 2.0;
         let bar_height = cell_height * 0.6;
-        let center_y = row_top + ROW_HEIGHT / 2.0;
+        let center_y = row_top + row_height / 2.0;
 
         // Calculate bar dimensions using explicit half-cell width
         // This ensures magnitude 1.0 fills from center to cell edge
-        let max_half_width = (frame_width - CELL_PADDING * 2.0) / 2.0;
+        let max_half_width = (frame_width - self.metrics.cell_padding * 2.0) / 2.0;
         let half_width = max_half_width * abs_value;
 
         // Center point of the cell
@@ -925,8 +2501,11 @@ impl<'a> TimelineRenderer<'a> {
         // Draw center line indicator
         painter.line_segment(
             [
-                Pos2::new(cell_center_x, row_top + CELL_PADDING),
-                Pos2::new(cell_center_x, row_top + ROW_HEIGHT - CELL_PADDING),
+                Pos2::new(cell_center_x, row_top + self.metrics.cell_padding),
+                Pos2::new(
+                    cell_center_x,
+                    row_top + row_height - self.metrics.cell_padding,
+                ),
             ],
             Stroke::new(0.5, self.colors.axis_center_color()),
         );
@@ -938,17 +2517,18 @@ impl<'a> TimelineRenderer<'a> {
         painter: &Painter,
         event: &InputEvent,
         x: f32,
-        row_top: f32,
+        row_y: Rangef,
         frame_width: f32,
         color: Color32,
     ) {
+        let (row_top, row_height) = (row_y.min, row_y.span());
         let value_x = event.value[0];
         let value_y = event.value[1];
         let magnitude = (value_x * value_x + value_y * value_y).sqrt().min(1.0);
 
         if magnitude < 0.01 {
             // Draw minimal indicator for zero value
-            let center_y = row_top + ROW_HEIGHT / 2.0;
+            let center_y = row_top + row_height / 2.0;
             let center_x = x + frame_width / 2.0;
             let indicator_rect = Rect::from_min_size(
                 Pos2::new(center_x - 1.0, center_y - 1.0),
@@ -958,9 +2538,10 @@ impl<'a> TimelineRenderer<'a> {
             return;
         }
 
-        let cell_size = (frame_width - CELL_PADDING * 2.0).min(ROW_HEIGHT - CELL_PADDING * 2.0);
+        let cell_size = (frame_width - self.metrics.cell_padding * 2.0)
+            .min(row_height - self.metrics.cell_padding * 2.0);
         let center_x = x + frame_width / 2.0;
-        let center_y = row_top + ROW_HEIGHT / 2.0;
+        let center_y = row_top + row_height / 2.0;
 
         // Draw a small circle with a direction indicator
         let circle_radius = cell_size / 2.0 * 0.8;
@@ -985,6 +2566,424 @@ impl<'a> TimelineRenderer<'a> {
         );
     }
 
+    /// Outline the cell being edited.
+    fn draw_edit_cell(&self, painter: &Painter, timeline_rect: Rect) {
+        let Some((id, frame)) = self.edit_cell else {
+            return;
+        };
+        let view_start = self.config.scroll_offset;
+        if frame < view_start || frame >= view_start + self.config.visible_frames {
+            return;
+        }
+        let Some(row) = self.get_row(id) else {
+            return;
+        };
+
+        let frame_width = timeline_rect.width() / self.config.visible_frames as f32;
+        let cell_rect = Rect::from_min_size(
+            Pos2::new(
+                timeline_rect.left() + (frame - view_start) as f32 * frame_width,
+                self.row_top(row, timeline_rect),
+            ),
+            egui::vec2(frame_width, self.row_heights[row]),
+        );
+        self.row_painter(painter, row, timeline_rect).rect_stroke(
+            cell_rect,
+            1.0,
+            Stroke::new(2.0, self.colors.current_frame_color()),
+            egui::StrokeKind::Outside,
+        );
+    }
+
+    /// Draw the game event and telemetry tracks above the input rows.
+    fn draw_annotation_tracks(&self, painter: &Painter, timeline_rect: Rect) {
+        // Annotation tracks are always among the fixed rows at the top
+        for (index, row) in self.rows.iter().enumerate().take(self.fixed_rows) {
+            let track_rect = Rect::from_min_size(
+                Pos2::new(timeline_rect.left(), self.row_top(index, timeline_rect)),
+                egui::vec2(timeline_rect.width(), self.row_heights[index]),
+            );
+            match *row {
+                TimelineRow::Annotations => self.draw_marker_track(painter, track_rect),
+                TimelineRow::Telemetry(index) => {
+                    self.draw_telemetry_track(painter, track_rect, &self.telemetry[index]);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Draw game events as ticks with their label.
+    ///
+    /// Labels are clipped to the track and left out when the next event is too
+    /// close to leave room for them.
+    fn draw_marker_track(&self, painter: &Painter, track_rect: Rect) {
+        let frame_width = track_rect.width() / self.config.visible_frames as f32;
+        let view_start = self.config.scroll_offset;
+        let view_end = view_start + self.config.visible_frames;
+        let painter = painter.with_clip_rect(track_rect);
+        let color = self.colors.text_header_color();
+
+        let markers: Vec<_> = self
+            .log
+            .markers()
+            .filter(|a| a.frame >= view_start && a.frame < view_end)
+            .collect();
+        for (i, marker) in markers.iter().enumerate() {
+            let x = track_rect.left() + (marker.frame - view_start) as f32 * frame_width;
+            painter.line_segment(
+                [
+                    Pos2::new(x, track_rect.top() + 2.0),
+                    Pos2::new(x, track_rect.bottom() - 2.0),
+                ],
+                Stroke::new(1.5, color),
+            );
+
+            let next_x = markers
+                .get(i + 1)
+                .map(|next| track_rect.left() + (next.frame - view_start) as f32 * frame_width)
+                .unwrap_or(track_rect.right());
+            if next_x - x >= 30.0 {
+                painter.text(
+                    Pos2::new(x + 3.0, track_rect.center().y),
+                    egui::Align2::LEFT_CENTER,
+                    &marker.label,
+                    egui::FontId::proportional(10.0),
+                    color,
+                );
+            }
+        }
+    }
+
+    /// Draw a telemetry channel as a step line scaled to its value range.
+    fn draw_telemetry_track(
+        &self,
+        painter: &Painter,
+        track_rect: Rect,
+        channel: &TelemetryChannel,
+    ) {
+        let frame_width = track_rect.width() / self.config.visible_frames as f32;
+        let view_start = self.config.scroll_offset;
+        let view_end = view_start + self.config.visible_frames;
+        let range = channel.max - channel.min;
+        let plot_top = track_rect.top() + self.metrics.cell_padding;
+        let plot_height = track_rect.height() - self.metrics.cell_padding * 2.0;
+        let value_y = |value: f32| {
+            // A constant channel is drawn through the middle
+            let fraction = if range > 0.0 {
+                (value - channel.min) / range
+            } else {
+                0.5
+            };
+            plot_top + plot_height * (1.0 - fraction)
+        };
+        let frame_x = |frame: u64| {
+            let frame = frame.clamp(view_start, view_end);
+            track_rect.left() + (frame - view_start) as f32 * frame_width
+        };
+
+        // Start from the sample in effect at the left edge, if any
+        let first = channel
+            .samples
+            .partition_point(|&(frame, _)| frame <= view_start)
+            .saturating_sub(1);
+        let mut points: Vec<Pos2> = Vec::new();
+        for &(frame, value) in &channel.samples[first..] {
+            if frame >= view_end {
+                break;
+            }
+            let x = frame_x(frame);
+            let y = value_y(value);
+            if let Some(&last) = points.last() {
+                points.push(Pos2::new(x, last.y));
+            }
+            points.push(Pos2::new(x, y));
+        }
+        if let Some(&last) = points.last() {
+            points.push(Pos2::new(track_rect.right(), last.y));
+        }
+        if points.len() >= 2 {
+            painter.add(egui::Shape::line(
+                points,
+                Stroke::new(1.5, self.colors.axis1d_color()),
+            ));
+        }
+    }
+
+    /// Draw a Trigger event as a bar rising from the bottom of the cell.
+    fn draw_trigger_event(
+        &self,
+        painter: &Painter,
+        event: &InputEvent,
+        x: f32,
+        row_y: Rangef,
+        frame_width: f32,
+        color: Color32,
+    ) {
+        let (row_top, row_height) = (row_y.min, row_y.span());
+        let value = event.value[0].clamp(0.0, 1.0);
+        let cell_bottom = row_top + row_height - self.metrics.cell_padding;
+
+        // Baseline so released triggers still show an event
+        painter.line_segment(
+            [
+                Pos2::new(x + self.metrics.cell_padding, cell_bottom),
+                Pos2::new(x + frame_width - self.metrics.cell_padding, cell_bottom),
+            ],
+            Stroke::new(1.0, color.gamma_multiply(0.3)),
+        );
+        if value < 0.01 {
+            return;
+        }
+
+        let bar_height = (row_height - self.metrics.cell_padding * 2.0) * value;
+        let bar_rect = Rect::from_min_max(
+            Pos2::new(x + self.metrics.cell_padding, cell_bottom - bar_height),
+            Pos2::new(x + frame_width - self.metrics.cell_padding, cell_bottom),
+        );
+        let intensity = 0.3 + (value * 0.7);
+        painter.rect_filled(bar_rect, 1.0, color.gamma_multiply(intensity));
+    }
+
+    /// Draw a MouseDelta or PointerPosition event.
+    ///
+    /// The pointer position is scaled into the cell using the range the input
+    /// covers over the whole log, so the dot shows where the pointer is
+    /// relative to its travel. Mouse deltas also get an arrow pointing in the
+    /// direction of movement.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_pointer_event(
+        &self,
+        painter: &Painter,
+        event: &InputEvent,
+        position: Option<[f32; 2]>,
+        x: f32,
+        row_y: Rangef,
+        frame_width: f32,
+        color: Color32,
+    ) {
+        let (row_top, row_height) = (row_y.min, row_y.span());
+        let cell_rect = Rect::from_min_max(
+            Pos2::new(
+                x + self.metrics.cell_padding,
+                row_top + self.metrics.cell_padding,
+            ),
+            Pos2::new(
+                x + frame_width - self.metrics.cell_padding,
+                row_top + row_height - self.metrics.cell_padding,
+            ),
+        );
+        painter.rect_stroke(
+            cell_rect,
+            1.0,
+            Stroke::new(0.5, color.gamma_multiply(0.3)),
+            egui::StrokeKind::Inside,
+        );
+
+        let bounds = self.pointer_bounds.get(&event.id);
+        let dot = match (position, bounds) {
+            (Some([px, py]), Some(bounds)) => {
+                // Degenerate ranges (a pointer that never moves) map to the center
+                let fx = if bounds.width() > 0.0 {
+                    (px - bounds.left()) / bounds.width()
+                } else {
+                    0.5
+                };
+                let fy = if bounds.height() > 0.0 {
+                    (py - bounds.top()) / bounds.height()
+                } else {
+                    0.5
+                };
+                // Screen space has Y pointing down, like the painter
+                Pos2::new(
+                    cell_rect.left() + fx * cell_rect.width(),
+                    cell_rect.top() + fy * cell_rect.height(),
+                )
+            }
+            _ => cell_rect.center(),
+        };
+
+        if event.kind == InputKind::MouseDelta {
+            let delta = egui::vec2(event.value[0], event.value[1]);
+            if delta.length() > f32::EPSILON {
+                let arrow_length = cell_rect.width().min(cell_rect.height()) * 0.4;
+                let tip = dot + delta.normalized() * arrow_length;
+                painter.line_segment([dot, tip], Stroke::new(1.0, color));
+            }
+        }
+        painter.circle_filled(dot, 2.0, color);
+    }
+
+    /// Label a Touch or Key cell with its contact ID or key character.
+    ///
+    /// Nothing is drawn when the cell is too narrow for the text.
+    fn draw_event_label(
+        &self,
+        painter: &Painter,
+        event: &InputEvent,
+        x: f32,
+        row_y: Rangef,
+        frame_width: f32,
+    ) {
+        let (row_top, row_height) = (row_y.min, row_y.span());
+        if frame_width < 10.0 || event.state == ButtonState::Released {
+            return;
+        }
+        let label = match event.kind {
+            InputKind::Touch => event.contact.to_string(),
+            // Key codes in the printable ASCII range are shown as the character
+            InputKind::Key => match event.value[0] as u32 {
+                code @ 0x21..=0x7e => char::from(code as u8).to_string(),
+                _ => return,
+            },
+            _ => return,
+        };
+        painter.text(
+            Pos2::new(x + frame_width / 2.0, row_top + row_height / 2.0),
+            egui::Align2::CENTER_CENTER,
+            label,
+            egui::FontId::monospace(9.0),
+            self.colors.text_label_color(),
+        );
+    }
+
+    /// Draw shaded ranges for idle spans and event gaps.
+    ///
+    /// Idle spans shade the full content height. Event gaps additionally get a
+    /// strip along the bottom of the header so they stay visible when they
+    /// overlap an idle span.
+    fn draw_idle_spans(&self, painter: &Painter, rect: Rect, timeline_rect: Rect) {
+        let spans = match self.idle_spans {
+            Some(s) if !s.is_empty() => s,
+            _ => return,
+        };
+
+        let view_start = self.config.scroll_offset;
+        let view_end = view_start + self.config.visible_frames;
+        let frame_width = timeline_rect.width() / self.config.visible_frames as f32;
+
+        for span in spans {
+            // Skip spans outside the visible range
+            if span.end < view_start || span.start >= view_end {
+                continue;
+            }
+
+            let visible_start = span.start.max(view_start);
+            let visible_end = span.end.min(view_end.saturating_sub(1));
+            let x_start =
+                timeline_rect.left() + ((visible_start - view_start) as f32 * frame_width);
+            let x_end =
+                timeline_rect.left() + ((visible_end - view_start + 1) as f32 * frame_width);
+
+            match span.kind {
+                IdleKind::Idle => {
+                    let shade_rect = Rect::from_min_max(
+                        Pos2::new(x_start, rect.top() + self.metrics.header_height),
+                        Pos2::new(x_end, timeline_rect.bottom()),
+                    );
+                    painter.rect_filled(shade_rect, 0.0, self.colors.idle_span_color_alpha(35));
+                }
+                IdleKind::NoEvents => {
+                    let shade_rect = Rect::from_min_max(
+                        Pos2::new(x_start, rect.top() + self.metrics.header_height),
+                        Pos2::new(x_end, timeline_rect.bottom()),
+                    );
+                    painter.rect_filled(shade_rect, 0.0, self.colors.event_gap_color_alpha(20));
+
+                    let strip_rect = Rect::from_min_max(
+                        Pos2::new(x_start, rect.top() + self.metrics.header_height - 3.0),
+                        Pos2::new(x_end, rect.top() + self.metrics.header_height),
+                    );
+                    painter.rect_filled(strip_rect, 0.0, self.colors.event_gap_color_alpha(200));
+                }
+            }
+        }
+    }
+
+    /// Draw the frame-time track below the input rows.
+    ///
+    /// Each frame gets a bar proportional to its recorded duration, with the
+    /// hitch threshold at half the track height; frames over the threshold
+    /// are drawn in the error color and reach the top of the track.
+    fn draw_frame_time_track(&self, painter: &Painter, track_rect: Rect, timeline_rect: Rect) {
+        let Some(threshold_us) = self.hitch_threshold_us else {
+            return;
+        };
+        if track_rect.height() <= 0.0 {
+            return;
+        }
+
+        // Label column and track background
+        let label_rect = Rect::from_min_max(
+            track_rect.min,
+            Pos2::new(track_rect.left() + LABEL_WIDTH, track_rect.bottom()),
+        );
+        painter.rect_filled(label_rect, 0.0, self.colors.label_background_color());
+        painter.text(
+            Pos2::new(label_rect.left() + 16.0, label_rect.center().y),
+            egui::Align2::LEFT_CENTER,
+            "Frame time",
+            egui::FontId::proportional(12.0),
+            self.colors.text_label_color(),
+        );
+        painter.line_segment(
+            [
+                Pos2::new(track_rect.left(), track_rect.top()),
+                Pos2::new(track_rect.right(), track_rect.top()),
+            ],
+            Stroke::new(1.0, self.colors.grid_color()),
+        );
+
+        let bar_area_top = track_rect.top() + self.metrics.cell_padding;
+        let bar_area_height = track_rect.bottom() - self.metrics.cell_padding - bar_area_top;
+        let threshold_y = track_rect.bottom() - self.metrics.cell_padding - bar_area_height / 2.0;
+
+        // Threshold line with its value in the label column
+        painter.line_segment(
+            [
+                Pos2::new(timeline_rect.left(), threshold_y),
+                Pos2::new(timeline_rect.right(), threshold_y),
+            ],
+            Stroke::new(1.0, self.colors.status_error_color().gamma_multiply(0.4)),
+        );
+        painter.text(
+            Pos2::new(label_rect.right() - 4.0, threshold_y),
+            egui::Align2::RIGHT_CENTER,
+            format!("{:.0} ms", threshold_us as f64 / 1000.0),
+            egui::FontId::proportional(9.0),
+            self.colors.text_dim_color(),
+        );
+
+        let view_start = self.config.scroll_offset;
+        let view_end = (view_start + self.config.visible_frames).min(self.log.metadata.frame_count);
+        let frame_width = timeline_rect.width() / self.config.visible_frames as f32;
+
+        for frame in view_start..view_end {
+            let Some(duration_us) = self.log.frame_duration_us(frame) else {
+                continue;
+            };
+            let hitch = duration_us > threshold_us;
+            let fraction = (duration_us as f32 / (2 * threshold_us) as f32).min(1.0);
+            let x = timeline_rect.left() + (frame - view_start) as f32 * frame_width;
+            let bar_rect = Rect::from_min_max(
+                Pos2::new(
+                    x + (frame_width * 0.1).min(1.0),
+                    track_rect.bottom() - self.metrics.cell_padding - bar_area_height * fraction,
+                ),
+                Pos2::new(
+                    x + frame_width,
+                    track_rect.bottom() - self.metrics.cell_padding,
+                ),
+            );
+            let color = if hitch {
+                self.colors.status_error_color()
+            } else {
+                self.colors.axis1d_color()
+            };
+            painter.rect_filled(bar_rect, 0.0, color);
+        }
+    }
+
     /// Draw highlight for selected frame range.
     fn draw_selection_highlight(&self, painter: &Painter, rect: Rect, timeline_rect: Rect) {
         let (sel_start, sel_end) = match self.selection {
@@ -1028,7 +3027,7 @@ impl<'a> TimelineRenderer<'a> {
 
         // Draw the selection highlight rectangle
         let highlight_rect = Rect::from_min_max(
-            Pos2::new(x_start, rect.top() + HEADER_HEIGHT),
+            Pos2::new(x_start, rect.top() + self.metrics.header_height),
             Pos2::new(x_end, timeline_rect.bottom()),
         );
         painter.rect_filled(highlight_rect, 0.0, fill_color);
@@ -1042,7 +3041,7 @@ impl<'a> TimelineRenderer<'a> {
         // Draw selection range text in the header area
         let selection_text = format!("F{}-F{}", sel_start, sel_end);
         let text_x = (x_start + x_end) / 2.0;
-        let text_y = rect.top() + HEADER_HEIGHT / 2.0;
+        let text_y = rect.top() + self.metrics.header_height / 2.0;
 
         // Only draw text if there's enough space
         if x_end - x_start > 40.0 {
@@ -1118,8 +3117,8 @@ impl<'a> TimelineRenderer<'a> {
 
             // Draw a vertical highlight bar for the frame column
             let highlight_rect = Rect::from_min_size(
-                Pos2::new(x, rect.top() + HEADER_HEIGHT),
-                egui::vec2(frame_width, rect.height() - HEADER_HEIGHT),
+                Pos2::new(x, rect.top() + self.metrics.header_height),
+                egui::vec2(frame_width, rect.height() - self.metrics.header_height),
             );
             painter.rect_filled(highlight_rect, 0.0, fill_color);
             painter.rect_stroke(
@@ -1167,7 +3166,7 @@ impl<'a> TimelineRenderer<'a> {
 
             // Draw star marker at the top of the header
             painter.text(
-                Pos2::new(x, rect.top() + HEADER_HEIGHT / 2.0 - 1.0),
+                Pos2::new(x, rect.top() + self.metrics.header_height / 2.0 - 1.0),
                 egui::Align2::CENTER_CENTER,
                 "★",
                 egui::FontId::proportional(12.0),
@@ -1178,7 +3177,7 @@ impl<'a> TimelineRenderer<'a> {
             let line_color = self.colors.bookmark_color_alpha(80);
             painter.line_segment(
                 [
-                    Pos2::new(x, rect.top() + HEADER_HEIGHT),
+                    Pos2::new(x, rect.top() + self.metrics.header_height),
                     Pos2::new(x, timeline_rect.bottom()),
                 ],
                 Stroke::new(1.0, line_color),
@@ -1330,3 +3329,65 @@ impl<'a> TimelineRenderer<'a> {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_util::{event, log_with};
+
+    fn touch(frame: u64, contact: u16, state: ButtonState) -> InputEvent {
+        InputEvent {
+            contact,
+            ..event(frame, 0, InputKind::Touch, state)
+        }
+    }
+
+    #[test]
+    fn test_input_details_in_unsorted_log() {
+        let log = log_with(
+            20,
+            vec![
+                touch(5, 0, ButtonState::Pressed),
+                touch(9, 0, ButtonState::Released),
+                touch(5, 1, ButtonState::Pressed),
+                touch(1, 2, ButtonState::Pressed),
+                touch(2, 2, ButtonState::Released),
+            ],
+        );
+        let config = TimelineConfig::default();
+        let filter = FilterState::default();
+        let colors = ColorSettings::default();
+        let renderer = TimelineRenderer::new(&log, &config, &filter, &colors);
+
+        let details = renderer.input_details(0, 5);
+        let pressed: Vec<&String> = details
+            .iter()
+            .filter(|line| line.starts_with("Pressed"))
+            .collect();
+        assert_eq!(pressed.len(), 2, "{:?}", details);
+        assert!(pressed[0].contains("contact 0"));
+        assert!(pressed[1].contains("contact 1"));
+    }
+
+    #[test]
+    fn test_hold_spans_in_unsorted_log() {
+        let log = log_with(
+            20,
+            vec![
+                touch(9, 0, ButtonState::Released),
+                touch(12, 1, ButtonState::Pressed),
+                touch(5, 0, ButtonState::Pressed),
+                touch(1, 0, ButtonState::Pressed),
+                touch(3, 0, ButtonState::Released),
+            ],
+        );
+        let frame_order = vec![3, 4, 2, 0, 1];
+
+        let mut spans: Vec<(usize, Range<u64>)> = hold_spans(&log, &frame_order)
+            .into_iter()
+            .map(|span| (span.press, span.frames))
+            .collect();
+        spans.sort_by_key(|(_, frames)| frames.start);
+        assert_eq!(spans, vec![(3, 1..3), (2, 5..9), (1, 12..20)]);
+    }
+}
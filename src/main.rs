@@ -1,11 +1,18 @@
 //! Input Log Viewer - A lightweight input log viewer for game developers.
 
+mod cli;
 mod core;
 mod gui;
 
 use gui::InputLogViewerApp;
 
 fn main() -> eframe::Result<()> {
+    // Headless subcommands (e.g. `stats`) run without opening a window
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     let options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
             .with_inner_size([1024.0, 768.0])